@@ -0,0 +1,179 @@
+//! Saved logins: auth.json with named profiles, and token expiry helpers.
+
+use crate::paths::{config_file_path, writable, write_private};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Auth {
+    pub token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Unix timestamp (seconds) at which the token expires, if the server told us.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// Server this token belongs to; set for profiles created with `auth login --profile`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// On-disk layout of auth.json: named profiles plus the active one.
+/// Older releases stored a bare `Auth` object, which is read as the "default" profile.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuthStore {
+    pub active: String,
+    pub profiles: BTreeMap<String, Auth>,
+    /// Keys for other services, by the config.toml setting they belong to
+    /// (e.g. `scrobble.listenbrainz_token`), so that file holds no secrets.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, String>,
+}
+
+impl Default for AuthStore {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: BTreeMap::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+/// Response header set by the server when the bearer token is about to expire
+/// and should be rotated via `/webhook/auth/refresh`.
+pub const TOKEN_EXPIRING_HEADER: &str = "x-token-expiring";
+
+/// How long before a token expires `auth info` and the TUI start warning.
+pub const TOKEN_EXPIRY_WARNING_SECS: i64 = 3 * 24 * 60 * 60;
+
+/// Seconds left on a token expiring at `expires_at`, if that is within
+/// `TOKEN_EXPIRY_WARNING_SECS` (zero or less once it has expired).
+pub fn expires_soon(expires_at: Option<i64>) -> Option<i64> {
+    let left = expires_at? - chrono::Utc::now().timestamp();
+    (left <= TOKEN_EXPIRY_WARNING_SECS).then_some(left)
+}
+
+/// `secs` as the two largest units, e.g. `2d 5h` or `14m`.
+pub fn format_remaining(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (days, hours, mins) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins.max(1))
+    }
+}
+
+pub fn save_token(token: &str, avatar_url: Option<&str>, username: Option<&str>) -> Result<()> {
+    let auth = Auth {
+        token: token.trim().to_string(),
+        avatar_url: avatar_url.map(|s| s.to_string()),
+        username: username.map(|s| s.to_string()),
+        expires_at: None,
+        // Keep the server a profile was pinned to when re-logging into it.
+        base_url: load_auth().and_then(|a| a.base_url),
+    };
+    save_auth(&auth)
+}
+
+/// Save `auth` into the active profile.
+pub fn save_auth(auth: &Auth) -> Result<()> {
+    let mut store = load_auth_store();
+    store.profiles.insert(store.active.clone(), auth.clone());
+    save_auth_store(&store)
+}
+
+/// Save `auth` under `name` and make it the active profile.
+pub fn save_profile(name: &str, auth: &Auth) -> Result<()> {
+    let mut store = load_auth_store();
+    store.active = name.to_string();
+    store.profiles.insert(name.to_string(), auth.clone());
+    save_auth_store(&store)
+}
+
+/// The saved logins, or none when there is no auth.json (or in stateless
+/// mode). A file that can't be read or parsed is an error.
+pub fn read_auth_store() -> Result<AuthStore> {
+    let Some(path) = config_file_path() else {
+        return Ok(AuthStore::default());
+    };
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AuthStore::default()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    if let Ok(store) = serde_json::from_str::<AuthStore>(&contents) {
+        return Ok(store);
+    }
+    let auth = serde_json::from_str::<Auth>(&contents).with_context(|| format!("parsing {}", path.display()))?;
+    let mut store = AuthStore::default();
+    store.profiles.insert(DEFAULT_PROFILE.to_string(), auth);
+    Ok(store)
+}
+
+/// The saved logins, with none when auth.json is missing or broken
+/// (`read_auth_store` says why).
+pub fn load_auth_store() -> AuthStore {
+    read_auth_store().unwrap_or_default()
+}
+
+pub fn save_auth_store(store: &AuthStore) -> Result<()> {
+    let path = writable(config_file_path(), "auth")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("creating config directory")?;
+    }
+    // Never replace logins that were only unreadable with an empty store.
+    if let Err(e) = read_auth_store() {
+        bail!("{:#}; fix or remove it first", e);
+    }
+
+    let json = serde_json::to_string_pretty(store).context("serializing auth")?;
+    write_private(&path, &json).context("writing auth file")?;
+    Ok(())
+}
+
+/// Remove the active profile, deleting auth.json once nothing else is left in it.
+/// Returns the removed profile name, if there was one.
+pub fn remove_active_profile() -> Result<Option<String>> {
+    let mut store = load_auth_store();
+    let name = store.active.clone();
+    if store.profiles.remove(&name).is_none() {
+        return Ok(None);
+    }
+    if let Some(next) = store.profiles.keys().next().cloned() {
+        store.active = next;
+        save_auth_store(&store)?;
+    } else if !store.keys.is_empty() {
+        save_auth_store(&store)?;
+    } else if let Some(path) = config_file_path()
+        && path.exists()
+    {
+        fs::remove_file(&path).context("removing auth file")?;
+    }
+    Ok(Some(name))
+}
+
+/// Replace the saved token after a refresh, keeping username/avatar intact.
+pub fn update_saved_token(token: &str, expires_at: Option<i64>) -> Result<()> {
+    let mut auth = load_auth().context("no saved auth to update")?;
+    auth.token = token.trim().to_string();
+    auth.expires_at = expires_at;
+    save_auth(&auth)
+}
+
+pub fn load_auth() -> Option<Auth> {
+    let mut store = load_auth_store();
+    store.profiles.remove(&store.active)
+}
+
+pub fn load_token() -> Option<String> {
+    load_auth().map(|a| a.token)
+}
@@ -0,0 +1,160 @@
+//! A typed client for programs that drive Jorik directly.
+
+use crate::http::{REQUEST_ID_HEADER, build_url, http_client, new_request_id};
+use crate::payload::{PlayPayload, QueuePayload, SimplePayload};
+use crate::response::{ErrorResponse, NowPlaying, NowPlayingResponse, QueueResponse, Track};
+use crate::shape;
+use crate::ws::{self, WsStream};
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// The server answered, but with an error status or an `error` body. Every
+/// `JorikClient` call fails with this for a refused request; get at it with
+/// `err.downcast_ref::<Rejected>()`.
+#[derive(Debug)]
+pub struct Rejected {
+    pub status: StatusCode,
+    /// The reply, when it was the usual `{"error", "message"}` object
+    pub error: Option<ErrorResponse>,
+}
+
+impl Rejected {
+    /// 401/403 or an `unauthorized` body: the token is missing, invalid or expired.
+    pub fn is_auth(&self) -> bool {
+        self.status == StatusCode::UNAUTHORIZED
+            || self.status == StatusCode::FORBIDDEN
+            || self.error.as_ref().is_some_and(|e| e.error == "unauthorized")
+    }
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            Some(ErrorResponse { message: Some(message), .. }) => write!(f, "{}: {}", self.status, message),
+            Some(ErrorResponse { error, .. }) => write!(f, "{}: {}", self.status, error),
+            None => write!(f, "server returned {}", self.status),
+        }
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+/// One server and the token used with it. Clones share a connection pool,
+/// so clone one client rather than building several.
+#[derive(Clone)]
+pub struct JorikClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl JorikClient {
+    /// A client for `base_url` (e.g. `DEFAULT_BASE_URL`) with its own pool,
+    /// built by `http_client` so `tls::configure` applies.
+    pub fn new(base_url: impl Into<String>, token: Option<String>) -> Result<Self> {
+        let http = http_client("jorik-core").context("building HTTP client")?;
+        Ok(Self::with_http(http, base_url, token))
+    }
+
+    /// A client on an existing `reqwest::Client`.
+    pub fn with_http(http: reqwest::Client, base_url: impl Into<String>, token: Option<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into(),
+            token,
+        }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Send any action body to `/webhook/audio` and return the reply as-is.
+    /// Fails with `Rejected` when the server refuses it.
+    pub async fn audio<T: Serialize + ?Sized>(&self, payload: &T) -> Result<Value> {
+        let url = build_url(&self.base_url, "/webhook/audio");
+        let mut req = self
+            .http
+            .post(&url)
+            .header(REQUEST_ID_HEADER, new_request_id())
+            .json(payload);
+        if let Some(token) = &self.token {
+            req = req.bearer_auth(token);
+        }
+        let resp = req.send().await.with_context(|| format!("POST {url}"))?;
+        let status = resp.status();
+        let text = resp.text().await.context("reading response body")?;
+        let json = serde_json::from_str::<Value>(&text).ok();
+        let error = json
+            .as_ref()
+            .filter(|j| j.get("error").is_some_and(|e| !e.is_null()))
+            .and_then(|j| ErrorResponse::deserialize(j).ok());
+        if !status.is_success() || error.is_some() {
+            return Err(Rejected { status, error }.into());
+        }
+        json.context("reply is not JSON")
+    }
+
+    /// Enqueue `query`, a URL or a search, in `guild_id`, joining
+    /// `channel_id` first if given. Returns the tracks that were added.
+    pub async fn play(&self, guild_id: &str, channel_id: Option<&str>, query: &str) -> Result<Vec<Track>> {
+        let reply = self
+            .audio(&PlayPayload {
+                action: "play",
+                guild_id: Some(guild_id.to_string()),
+                channel_id: channel_id.map(str::to_string),
+                query: crate::payload::clean_query(query),
+                user_id: None,
+                requested_by: None,
+                avatar_url: None,
+            })
+            .await?;
+        let tracks = shape::parse::<Option<Vec<Track>>>(reply.get("tracks").unwrap_or(&Value::Null))
+            .map_err(|e| e.within("tracks"));
+        Ok(tracks.context("parsing play reply")?.unwrap_or_default())
+    }
+
+    /// `skip`, `pause`, `resume`, `stop` or another action that only needs
+    /// the guild.
+    pub async fn simple(&self, action: &'static str, guild_id: &str) -> Result<Value> {
+        self.audio(&SimplePayload {
+            action,
+            guild_id: Some(guild_id.to_string()),
+            user_id: None,
+        })
+        .await
+    }
+
+    /// `limit` upcoming tracks from 0-based `offset`, plus the current one.
+    pub async fn queue(&self, guild_id: &str, offset: usize, limit: usize) -> Result<QueueResponse> {
+        let reply = self
+            .audio(&QueuePayload {
+                action: "queue",
+                guild_id: Some(guild_id.to_string()),
+                user_id: None,
+                limit,
+                offset,
+            })
+            .await?;
+        QueueResponse::from_json(&reply).context("parsing queue reply")
+    }
+
+    /// What is playing in `guild_id`, or `None` when idle.
+    pub async fn now_playing(&self, guild_id: &str) -> Result<Option<NowPlaying>> {
+        let reply = self.simple("nowplaying", guild_id).await?;
+        let reply: NowPlayingResponse = shape::parse(&reply).context("parsing nowplaying reply")?;
+        Ok(reply.now_playing)
+    }
+
+    /// The live events of `guild_id`: a stream of text frames, each a
+    /// `WsEvent`. Needs a token.
+    pub async fn events(&self, guild_id: &str) -> Result<WsStream> {
+        let token = self.token.as_deref().context("the event stream needs a token")?;
+        let mut stream = ws::connect(&self.base_url, token).await?;
+        ws::subscribe(&mut stream, guild_id).await?;
+        Ok(stream)
+    }
+}
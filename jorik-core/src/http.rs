@@ -0,0 +1,39 @@
+//! The shared HTTP client and request IDs.
+
+/// The HTTP client for the whole process. Clones share one connection pool,
+/// so hand this one around instead of building another: back-to-back
+/// commands then reuse a warm connection rather than paying for a new TCP
+/// and TLS handshake each time.
+pub fn http_client(user_agent: &'static str) -> reqwest::Result<reqwest::Client> {
+    crate::tls::apply(reqwest::Client::builder())
+        .user_agent(user_agent)
+        .timeout(std::time::Duration::from_secs(10))
+        .connect_timeout(std::time::Duration::from_secs(5))
+        // The TUI fires bursts of requests at one host; keep enough of them warm.
+        .pool_max_idle_per_host(8)
+        .pool_idle_timeout(std::time::Duration::from_secs(120))
+        // Stops NAT boxes and proxies from silently dropping idle connections.
+        .tcp_keepalive(std::time::Duration::from_secs(30))
+        .http2_keep_alive_interval(std::time::Duration::from_secs(30))
+        .http2_keep_alive_while_idle(true)
+        .build()
+}
+
+/// Sent with every request so a failure can be found in the server's logs.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The ID the server filed a request under, when it reports one.
+pub fn server_request_id(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    [REQUEST_ID_HEADER, "request-id", "x-correlation-id"]
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok())
+        .map(str::to_string)
+}
+
+pub fn build_url(base: &str, path: &str) -> String {
+    format!("{}{}", base.trim_end_matches('/'), path)
+}
@@ -0,0 +1,46 @@
+//! Talk to a Jorik music bot server: the request and reply types, saved
+//! logins, the shared HTTP client and the `/ws` event stream. `jorik-cli` is
+//! a CLI/TUI on top of this crate; other programs (bots, GUIs) start from
+//! [`JorikClient`].
+//!
+//! ```no_run
+//! # async fn demo() -> anyhow::Result<()> {
+//! use futures_util::StreamExt;
+//! use jorik_core::JorikClient;
+//!
+//! // The login saved by `jorik auth login`, if there is one.
+//! let client = JorikClient::new(jorik_core::DEFAULT_BASE_URL, jorik_core::load_token())?;
+//! client.play("123456789", None, "never gonna give you up").await?;
+//! let queue = client.queue("123456789", 0, 10).await?;
+//! println!("{} upcoming", queue.upcoming.unwrap_or_default().len());
+//!
+//! let mut events = client.events("123456789").await?;
+//! while let Some(frame) = events.next().await {
+//!     println!("{}", frame?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod auth;
+pub mod client;
+pub mod http;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod paths;
+pub mod payload;
+pub mod response;
+pub mod shape;
+pub mod tls;
+pub mod ws;
+
+pub use auth::*;
+pub use client::{JorikClient, Rejected};
+pub use http::*;
+pub use paths::*;
+pub use payload::*;
+pub use response::*;
+pub use shape::ShapeError;
+pub use ws::{WsAction, WsSubscribe, build_ws_request, build_ws_url};
+
+pub const DEFAULT_BASE_URL: &str = "https://jorik.xserv.pp.ua";
@@ -0,0 +1,402 @@
+//! A stand-in Jorik server for tests, behind the `mock` feature: canned
+//! replies for the audio, auth and `/ws` endpoints, and a record of every
+//! request so a test can check what was sent.
+//!
+//! It is plain HTTP/1.1 with one request per connection, which is all
+//! reqwest and tungstenite need; anything fancier belongs on a real server.
+
+use futures_util::{SinkExt, StreamExt};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::{Message, Role};
+
+/// A token every endpoint refuses with 401, for testing auth failures.
+pub const BAD_TOKEN: &str = "bad-token";
+
+/// One request the server received. `/ws` frames are recorded too, with
+/// method `WS` and the frame as `body`.
+#[derive(Clone, Debug)]
+pub struct Recorded {
+    pub method: String,
+    pub path: String,
+    pub authorization: Option<String>,
+    pub request_id: Option<String>,
+    pub body: Value,
+}
+
+impl Recorded {
+    /// The `action` of an audio request or WS frame.
+    pub fn action(&self) -> Option<&str> {
+        self.body.get("action").and_then(Value::as_str)
+    }
+}
+
+struct State {
+    requests: Vec<Recorded>,
+    /// Replies set with `MockServer::reply`, by action or path
+    replies: HashMap<String, (StatusCode, Value)>,
+    /// Titles `queue` pages through
+    upcoming: Vec<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            requests: Vec::new(),
+            replies: HashMap::new(),
+            upcoming: vec!["Next Song".to_string(), "Later Song".to_string()],
+        }
+    }
+}
+
+/// A mock server on a random local port, running on its own thread for as
+/// long as the process lives.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    /// Start listening; requests are accepted once this returns. Usable from
+    /// plain `#[test]` functions as well as from inside a runtime.
+    pub fn start() -> Self {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("binding mock server");
+        listener.set_nonblocking(true).expect("configuring mock server");
+        let addr = listener.local_addr().expect("mock server address");
+        let state = Arc::new(Mutex::new(State::default()));
+        let shared = state.clone();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("mock server runtime");
+            runtime.block_on(async move {
+                let listener = TcpListener::from_std(listener).expect("mock server listener");
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, shared.clone()));
+                }
+            });
+        });
+        Self { addr, state }
+    }
+
+    /// Base URL to pass as `--base-url` or to `JorikClient::new`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Everything received so far, oldest first.
+    pub fn requests(&self) -> Vec<Recorded> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Received audio requests and WS frames with this `action`.
+    pub fn actions(&self, action: &str) -> Vec<Recorded> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.action() == Some(action))
+            .collect()
+    }
+
+    /// Answer `key` with `status` and `body` from now on instead of the
+    /// canned reply. `key` is an audio action (`"queue"`) or a path
+    /// (`"/webhook/auth/whoami"`).
+    pub fn reply(&self, key: &str, status: u16, body: Value) {
+        let status = StatusCode::from_u16(status).expect("valid status code");
+        self.state.lock().unwrap().replies.insert(key.to_string(), (status, body));
+    }
+
+    /// Make the canned `queue` reply page through these upcoming titles,
+    /// honouring `limit` and `offset`.
+    pub fn set_queue(&self, upcoming: impl IntoIterator<Item = impl Into<String>>) {
+        self.state.lock().unwrap().upcoming = upcoming.into_iter().map(Into::into).collect();
+    }
+}
+
+async fn serve(mut stream: TcpStream, state: Arc<Mutex<State>>) {
+    let Some(request) = read_request(&mut stream).await else {
+        return;
+    };
+    if request.path.split('?').next() == Some("/ws") && request.header("upgrade").is_some() {
+        return serve_ws(stream, request, state).await;
+    }
+    let body: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+    let recorded = Recorded {
+        method: request.method.clone(),
+        path: request.path.clone(),
+        authorization: request.header("authorization").map(str::to_string),
+        request_id: request.header("x-request-id").map(str::to_string),
+        body,
+    };
+    let (status, reply) = respond(&recorded, &state);
+    state.lock().unwrap().requests.push(recorded);
+
+    let text = reply.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-Request-Id: mock-{}\r\nConnection: close\r\n\r\n",
+        status.as_u16(),
+        status.canonical_reason().unwrap_or(""),
+        text.len(),
+        request.header("x-request-id").unwrap_or("none"),
+    );
+    stream.write_all(head.as_bytes()).await.ok();
+    stream.write_all(text.as_bytes()).await.ok();
+    stream.shutdown().await.ok();
+}
+
+/// The reply `MockServer::reply` set for this request, or the canned one.
+fn respond(request: &Recorded, state: &Mutex<State>) -> (StatusCode, Value) {
+    let path = request.path.split('?').next().unwrap_or("");
+    if request.authorization.as_deref() == Some(&format!("Bearer {BAD_TOKEN}")) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            json!({"error": "unauthorized", "message": "invalid token"}),
+        );
+    }
+    let key = match path {
+        "/webhook/audio" => request.action().unwrap_or(""),
+        _ => path,
+    };
+    let state = state.lock().unwrap();
+    if let Some(reply) = state.replies.get(key) {
+        return reply.clone();
+    }
+    if key == "queue" {
+        return (StatusCode::OK, queue_reply(&request.body, &state.upcoming));
+    }
+    match canned(key, &request.body) {
+        Some(body) => (StatusCode::OK, body),
+        None => (
+            StatusCode::NOT_FOUND,
+            json!({"error": "not_found", "message": format!("no mock for {key}")}),
+        ),
+    }
+}
+
+fn track(title: &str) -> Value {
+    json!({
+        "title": title,
+        "author": "Mock Artist",
+        "uri": format!("https://example.com/{}", title.replace(' ', "-")),
+        "durationMs": 180_000,
+        "requestedBy": "mockuser",
+    })
+}
+
+/// The `limit` tracks of `upcoming` from `offset` in the request.
+fn queue_reply(body: &Value, upcoming: &[String]) -> Value {
+    let field = |name, default| body.get(name).and_then(Value::as_u64).map_or(default, |n| n as usize);
+    let offset = field("offset", 0).min(upcoming.len());
+    let limit = field("limit", 10);
+    let page: Vec<Value> = upcoming[offset..].iter().take(limit).map(|t| track(t)).collect();
+    json!({
+        "action": "queue",
+        "current": track("Current Song"),
+        "upcoming": page,
+        "total_upcoming": upcoming.len(),
+        "loopMode": "off",
+        "twentyFourSeven": false,
+        "shuffle": false,
+    })
+}
+
+/// Default replies, shaped like a current server's.
+fn canned(key: &str, body: &Value) -> Option<Value> {
+    let in_a_month = chrono::Utc::now().timestamp() + 30 * 24 * 60 * 60;
+    let reply = match key {
+        "/health" => json!({"status": "ok"}),
+        "/webhook/auth/introspect" => {
+            json!({"active": true, "scopes": ["player", "queue"], "expires_at": in_a_month})
+        }
+        "/webhook/auth/whoami" => json!({
+            "user_id": "42",
+            "username": "mockuser",
+            "avatar_url": "https://example.com/avatar.png",
+            "guilds": [{"id": "1", "name": "Mock Guild"}],
+        }),
+        "/webhook/auth/refresh" => json!({"token": "refreshed-token", "expires_at": in_a_month}),
+        "/webhook/auth/revoke" => json!({"ok": true}),
+        "/webhook/auth/device" => json!({
+            "device_code": "mock-device-code",
+            "user_code": "MOCK-1234",
+            "verification_uri": "https://example.com/device",
+            "interval": 1,
+            "expires_in": 30,
+        }),
+        "/webhook/auth/device/token" => json!({
+            "token": "device-token",
+            "username": "mockuser",
+            "avatar_url": "https://example.com/avatar.png",
+            "expires_at": in_a_month,
+        }),
+        "play" => {
+            let query = body.get("query").and_then(Value::as_str).unwrap_or("");
+            json!({"action": "play", "tracks": [track(query)]})
+        }
+        "play_bulk" => {
+            let tracks: Vec<Value> = body
+                .get("queries")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(track)
+                .collect();
+            json!({"action": "play_bulk", "tracks": tracks})
+        }
+        "skip" => json!({"action": "skip", "skipped": track("Current Song")}),
+        "stop" => json!({"action": "stop"}),
+        "pause" => json!({"action": "pause", "state": "paused"}),
+        "history" => json!({"action": "history", "tracks": [track("Old Song")]}),
+        "clear" => json!({"action": "clear", "removed": 2}),
+        "nowplaying" => json!({
+            "action": "nowplaying",
+            "now_playing": {"track": track("Current Song"), "elapsedMs": 60_000, "durationMs": 180_000},
+        }),
+        "loop" => json!({"action": "loop", "mode": body.get("loop_mode").cloned().unwrap_or(json!("off"))}),
+        "247" => json!({"action": "247", "enabled": body.get("enabled").cloned().unwrap_or(json!(true))}),
+        "shuffle" => json!({"action": "shuffle"}),
+        "filter" => json!({"action": "filter", "message": "Filters applied"}),
+        "lyrics" => json!({
+            "action": "lyrics",
+            "data": {"text": "Mock lyrics line one\nMock lyrics line two", "sourceName": "mock"},
+        }),
+        _ => return None,
+    };
+    Some(reply)
+}
+
+/// Answer a `/ws` upgrade. Each `subscribe` gets an `initial_state` and a
+/// `track_start` for its guild; each `action` gets an `action_response`
+/// carrying the canned reply.
+async fn serve_ws(mut stream: TcpStream, request: Request, state: Arc<Mutex<State>>) {
+    let authorization = request.header("authorization").map(str::to_string);
+    if authorization.as_deref() == Some(&format!("Bearer {BAD_TOKEN}")) {
+        let head = "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        stream.write_all(head.as_bytes()).await.ok();
+        return;
+    }
+    let Some(key) = request.header("sec-websocket-key") else {
+        return;
+    };
+    let head = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        derive_accept_key(key.as_bytes())
+    );
+    if stream.write_all(head.as_bytes()).await.is_err() {
+        return;
+    }
+    let mut ws = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+    while let Some(Ok(message)) = ws.next().await {
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let frame: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+        let recorded = Recorded {
+            method: "WS".to_string(),
+            path: "/ws".to_string(),
+            authorization: authorization.clone(),
+            request_id: None,
+            body: frame.clone(),
+        };
+        let guild_id = frame
+            .get("guildId")
+            .or_else(|| frame.get("guild_id"))
+            .cloned()
+            .unwrap_or(Value::Null);
+        let replies = match frame.get("type").and_then(Value::as_str) {
+            Some("subscribe") => {
+                let queue = queue_reply(&Value::Null, &state.lock().unwrap().upcoming);
+                vec![
+                    json!({"type": "initial_state", "guildId": guild_id, "data": queue}),
+                    json!({"type": "track_start", "guildId": guild_id, "data": track("Current Song")}),
+                ]
+            }
+            Some("action") => {
+                let (status, data) = respond(&recorded, &state);
+                vec![json!({
+                    "type": "action_response",
+                    "id": frame.get("id"),
+                    "guildId": guild_id,
+                    "success": status.is_success(),
+                    "data": data,
+                })]
+            }
+            _ => Vec::new(),
+        };
+        state.lock().unwrap().requests.push(recorded);
+        for reply in replies {
+            if ws.send(Message::Text(reply.to_string().into())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    /// Lowercased names
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = Vec::new();
+    let head_end = loop {
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i;
+        }
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    let length: usize = headers
+        .iter()
+        .find(|(n, _)| n == "content-length")
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = buf[head_end + 4..].to_vec();
+    while body.len() < length {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    Some(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
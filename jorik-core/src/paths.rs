@@ -0,0 +1,67 @@
+//! Where auth.json and the other config files live.
+
+use anyhow::{Context, Result, bail};
+use dirs::config_dir;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once from `--no-config` / `JORIK_STATELESS`; no config file is read or written.
+static STATELESS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_stateless(stateless: bool) {
+    STATELESS.store(stateless, Ordering::Relaxed);
+}
+
+pub fn is_stateless() -> bool {
+    STATELESS.load(Ordering::Relaxed)
+}
+
+/// Set once from `--config-dir` / `JORIK_CONFIG_DIR`.
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn set_config_dir(dir: Option<PathBuf>) {
+    if let Some(dir) = dir {
+        CONFIG_DIR_OVERRIDE.set(dir).ok();
+    }
+}
+
+/// Directory holding auth.json and config.toml, or `None` in stateless mode.
+/// An override is used as-is; the platform default gets a `jorik-cli` subdirectory.
+pub fn config_root() -> Option<PathBuf> {
+    if is_stateless() {
+        return None;
+    }
+    if let Some(dir) = CONFIG_DIR_OVERRIDE.get() {
+        return Some(dir.clone());
+    }
+    config_dir().map(|p| p.join("jorik-cli"))
+}
+
+pub fn config_file_path() -> Option<PathBuf> {
+    config_root().map(|p| p.join("auth.json"))
+}
+
+/// Resolve a config path for writing, with a clear error in stateless mode.
+pub fn writable(path: Option<PathBuf>, what: &str) -> Result<PathBuf> {
+    if is_stateless() {
+        bail!("not saving {what}: running with --no-config (JORIK_STATELESS); pass --token and flags instead");
+    }
+    path.with_context(|| format!("cannot determine {what} path"))
+}
+
+/// Write `contents` to `path` readable by its owner only, for files that
+/// hold tokens.
+pub fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    // The mode above only applies to a new file.
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+}
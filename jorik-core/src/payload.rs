@@ -0,0 +1,259 @@
+//! Request bodies for `POST /webhook/audio`, one per action.
+
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Clone)]
+pub struct PlayPayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub query: String,
+    pub user_id: Option<String>,
+    pub requested_by: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Several tracks in one request, for servers that understand `play_bulk`.
+/// They are queued in the order given.
+#[derive(Serialize, Clone)]
+pub struct BulkPlayPayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub queries: Vec<String>,
+    pub user_id: Option<String>,
+    pub requested_by: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Most tracks sent in one `play_bulk` request; longer lists go in chunks.
+pub const BULK_PLAY_MAX: usize = 50;
+
+impl BulkPlayPayload {
+    /// `queries` with the IDs and requester of `single`.
+    pub fn like(single: &PlayPayload, queries: &[String]) -> Self {
+        Self {
+            action: "play_bulk",
+            guild_id: single.guild_id.clone(),
+            channel_id: single.channel_id.clone(),
+            queries: queries.to_vec(),
+            user_id: single.user_id.clone(),
+            requested_by: single.requested_by.clone(),
+            avatar_url: single.avatar_url.clone(),
+        }
+    }
+}
+
+/// Whether a reply to `play_bulk` means the server doesn't know that action,
+/// so the tracks have to go one `play` at a time instead.
+pub fn bulk_unsupported(status: reqwest::StatusCode, error: Option<&str>) -> bool {
+    matches!(status.as_u16(), 404 | 405 | 501)
+        || (status == reqwest::StatusCode::BAD_REQUEST
+            && matches!(error, Some("unknown_action" | "invalid_action" | "unsupported_action")))
+}
+
+#[derive(Serialize, Clone)]
+pub struct SimplePayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct QueuePayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+/// An action on one upcoming track, addressed by its 1-based queue position.
+#[derive(Serialize, Clone)]
+pub struct QueueItemPayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    pub position: usize,
+}
+
+/// Move an upcoming track from one 1-based queue position to another.
+#[derive(Serialize, Clone)]
+pub struct MovePayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SeekPayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    pub position_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LoopPayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    pub loop_mode: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct TwentyFourSevenPayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct FilterPayload {
+    pub action: &'static str,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    pub filters: AudioFilters,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LyricsPayload {
+    pub action: String,
+    pub guild_id: Option<String>,
+    pub user_id: Option<String>,
+    /// Free-form "artist - title" lookup; when set the server resolves lyrics
+    /// for this song instead of the current track.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct AudioFilters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub equalizer: Option<Vec<EqualizerBand>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub karaoke: Option<KaraokeOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timescale: Option<TimescaleOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tremolo: Option<TremoloOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vibrato: Option<VibratoOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<RotationOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distortion: Option<DistortionOptions>,
+    #[serde(rename = "channelMix", skip_serializing_if = "Option::is_none")]
+    pub channel_mix: Option<ChannelMixOptions>,
+    #[serde(rename = "lowPass", skip_serializing_if = "Option::is_none")]
+    pub low_pass: Option<LowPassOptions>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EqualizerBand {
+    pub band: i32,
+    pub gain: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KaraokeOptions {
+    pub level: Option<f32>,
+    #[serde(rename = "monoLevel")]
+    pub mono_level: Option<f32>,
+    #[serde(rename = "filterBand")]
+    pub filter_band: Option<f32>,
+    #[serde(rename = "filterWidth")]
+    pub filter_width: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimescaleOptions {
+    pub speed: Option<f32>,
+    pub pitch: Option<f32>,
+    pub rate: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TremoloOptions {
+    pub frequency: Option<f32>,
+    pub depth: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VibratoOptions {
+    pub frequency: Option<f32>,
+    pub depth: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RotationOptions {
+    #[serde(rename = "rotationHz")]
+    pub rotation_hz: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DistortionOptions {
+    #[serde(rename = "sinOffset")]
+    pub sin_offset: Option<f32>,
+    #[serde(rename = "sinScale")]
+    pub sin_scale: Option<f32>,
+    #[serde(rename = "cosOffset")]
+    pub cos_offset: Option<f32>,
+    #[serde(rename = "cosScale")]
+    pub cos_scale: Option<f32>,
+    #[serde(rename = "tanOffset")]
+    pub tan_offset: Option<f32>,
+    #[serde(rename = "tanScale")]
+    pub tan_scale: Option<f32>,
+    pub offset: Option<f32>,
+    pub scale: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChannelMixOptions {
+    #[serde(rename = "leftToLeft")]
+    pub left_to_left: Option<f32>,
+    #[serde(rename = "leftToRight")]
+    pub left_to_right: Option<f32>,
+    #[serde(rename = "rightToLeft")]
+    pub right_to_left: Option<f32>,
+    #[serde(rename = "rightToRight")]
+    pub right_to_right: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LowPassOptions {
+    pub smoothing: Option<f32>,
+}
+
+pub fn clean_query(input: &str) -> String {
+    if let Ok(mut url) = Url::parse(input) {
+        if url.cannot_be_a_base() || url.query().is_none() {
+            return input.to_string();
+        }
+
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(k, _)| k != "si")
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+
+        if pairs.is_empty() {
+            url.set_query(None);
+        } else {
+            let mut serializer = url.query_pairs_mut();
+            serializer.clear();
+            for (k, v) in pairs {
+                serializer.append_pair(&k, &v);
+            }
+        }
+        return url.to_string();
+    }
+    input.to_string()
+}
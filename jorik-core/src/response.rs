@@ -0,0 +1,241 @@
+//! Replies from the server, deserialized leniently: server versions disagree
+//! on key names, so fields accept every spelling seen so far.
+
+use crate::shape::{self, ShapeError};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Reply to `GET /webhook/auth/introspect` for the bearer token.
+#[derive(Deserialize, Debug, Default)]
+pub struct TokenInfo {
+    #[serde(default, alias = "valid")]
+    pub active: bool,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RefreshResponse {
+    pub token: String,
+    pub expires_at: Option<i64>,
+}
+
+/// Reply to `POST /webhook/auth/device`: a code for the user to enter on
+/// another device, and the one the CLI polls with.
+#[derive(Deserialize, Debug)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    #[serde(alias = "verification_uri")]
+    pub verification_url: String,
+    /// Same page with the code already filled in, when the server offers one.
+    #[serde(default, alias = "verification_uri_complete")]
+    pub verification_url_complete: Option<String>,
+    /// Seconds to wait between polls.
+    #[serde(default = "default_device_interval")]
+    pub interval: u64,
+    /// Seconds until the code stops working.
+    #[serde(default = "default_device_expiry")]
+    pub expires_in: u64,
+}
+
+fn default_device_interval() -> u64 {
+    5
+}
+
+fn default_device_expiry() -> u64 {
+    600
+}
+
+/// Reply to polling `POST /webhook/auth/device/token`. Until the code is
+/// approved only `error` is set (`authorization_pending`, `slow_down`,
+/// `access_denied` or `expired_token`).
+#[derive(Deserialize, Debug, Default)]
+pub struct DeviceToken {
+    pub token: Option<String>,
+    #[serde(alias = "avatar")]
+    pub avatar_url: Option<String>,
+    pub username: Option<String>,
+    pub expires_at: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GuildInfo {
+    pub id: String,
+    pub name: Option<String>,
+}
+
+/// A voice channel as listed by `/webhook/guilds/{id}/channels`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ChannelInfo {
+    pub id: String,
+    pub name: Option<String>,
+    /// People in the channel right now, if the server reports it.
+    #[serde(default, alias = "memberCount")]
+    pub members: Option<u32>,
+}
+
+/// Identity of the token owner as reported by `/webhook/auth/whoami`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct WhoamiResponse {
+    pub user_id: Option<String>,
+    pub username: Option<String>,
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub guilds: Vec<GuildInfo>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WsEvent {
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(rename = "guildId")]
+    pub guild_id: Option<String>,
+    pub data: Option<Value>,
+    pub playback: Option<PlaybackState>,
+    pub success: Option<bool>,
+    pub id: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PlaybackState {
+    #[serde(rename = "elapsedMs")]
+    pub elapsed_ms: u64,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+    pub paused: bool,
+    pub spectrogram: Option<Vec<Vec<u8>>>,
+}
+
+/// A track as the server describes it. Server versions disagree on key
+/// names, so each field takes every spelling seen so far.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct Track {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    #[serde(alias = "url")]
+    pub uri: Option<String>,
+    #[serde(rename = "durationMs", alias = "duration_ms", alias = "length", alias = "duration")]
+    pub duration_ms: Option<u64>,
+    #[serde(rename = "artworkUrl", alias = "artwork_url", alias = "thumbnail")]
+    pub artwork_url: Option<String>,
+    #[serde(rename = "requestedBy", alias = "requested_by", alias = "requester")]
+    pub requester: Option<Requester>,
+    #[serde(rename = "requesterAvatar", alias = "requester_avatar")]
+    pub requester_avatar: Option<String>,
+}
+
+impl Track {
+    /// Who queued the track, by the friendliest name the server gave.
+    pub fn requester_name(&self) -> Option<&str> {
+        match self.requester.as_ref()? {
+            Requester::Name(name) => Some(name),
+            Requester::User(user) => user
+                .display_name
+                .as_deref()
+                .or(user.username.as_deref())
+                .or(user.name.as_deref()),
+            Requester::Other(_) => None,
+        }
+    }
+
+    pub fn requester_avatar(&self) -> Option<&str> {
+        self.requester_avatar.as_deref().or(match &self.requester {
+            Some(Requester::User(user)) => user.avatar.as_deref().or(user.avatar_url.as_deref()),
+            _ => None,
+        })
+    }
+}
+
+/// Who queued a track: a plain name or a user object. Anything else (a bare
+/// ID, say) is accepted and ignored rather than failing the whole reply.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Requester {
+    Name(String),
+    User(RequesterUser),
+    Other(serde::de::IgnoredAny),
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct RequesterUser {
+    #[serde(alias = "displayName")]
+    pub display_name: Option<String>,
+    pub username: Option<String>,
+    pub name: Option<String>,
+    pub avatar: Option<String>,
+    #[serde(alias = "avatarUrl")]
+    pub avatar_url: Option<String>,
+}
+
+/// Reply to `queue`, and the `data` of WS state and queue updates.
+#[derive(Deserialize, Debug, Default)]
+pub struct QueueResponse {
+    #[serde(alias = "guildId")]
+    pub guild_id: Option<String>,
+    #[serde(alias = "guildName")]
+    pub guild_name: Option<String>,
+    /// `Some(None)` when the reply says nothing is playing, `None` when it
+    /// doesn't mention the current track at all.
+    #[serde(default, deserialize_with = "present")]
+    pub current: Option<Option<Track>>,
+    pub upcoming: Option<Vec<Track>>,
+    #[serde(alias = "totalUpcoming", alias = "total")]
+    pub total_upcoming: Option<usize>,
+}
+
+impl QueueResponse {
+    /// Parse a reply, lifting the lists out of the `queue` object some
+    /// servers nest them in.
+    pub fn from_json(json: &Value) -> Result<Self, ShapeError> {
+        let mut response: Self = shape::parse(json)?;
+        if let Some(nested) = json.get("queue") {
+            let nested: Self = shape::parse(nested).map_err(|e| e.within("queue"))?;
+            response.current = nested.current;
+            response.upcoming = nested.upcoming;
+            response.total_upcoming = nested.total_upcoming;
+        }
+        Ok(response)
+    }
+
+    /// Whether the reply describes the queue, as opposed to a partial update.
+    pub fn has_queue(&self) -> bool {
+        self.current.is_some() || self.upcoming.is_some()
+    }
+}
+
+/// Reply to `nowplaying`; `now_playing` is missing or null when idle.
+#[derive(Deserialize, Debug)]
+pub struct NowPlayingResponse {
+    pub now_playing: Option<NowPlaying>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct NowPlaying {
+    pub track: Option<Track>,
+    #[serde(default, rename = "elapsedMs")]
+    pub elapsed_ms: u64,
+    #[serde(default, rename = "durationMs")]
+    pub duration_ms: u64,
+    /// Only sent by servers that know; false otherwise
+    #[serde(default)]
+    pub paused: bool,
+}
+
+/// The body of a rejected request.
+#[derive(Deserialize, Debug)]
+pub struct ErrorResponse {
+    pub error: String,
+    pub message: Option<String>,
+}
+
+/// Tell a key set to `null` apart from one that is missing.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
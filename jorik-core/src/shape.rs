@@ -0,0 +1,152 @@
+//! Pinpoint what is wrong with a reply that doesn't deserialize, so the
+//! error names the field to look at instead of serde's bare "invalid type".
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fmt;
+
+/// A reply without the shape this version expects.
+#[derive(Debug)]
+pub struct ShapeError {
+    /// Where in the reply, e.g. `upcoming[2].durationMs`; empty for the reply itself
+    pub path: String,
+    /// What is wrong there, e.g. `invalid type: string "x", expected u64`
+    pub problem: String,
+}
+
+impl ShapeError {
+    pub fn new(path: impl Into<String>, problem: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            problem: problem.into(),
+        }
+    }
+
+    /// The same error for a value that sits under `key` of a larger reply.
+    pub fn within(mut self, key: &str) -> Self {
+        self.path = match self.path.as_str() {
+            "" => key.to_string(),
+            path if path.starts_with('[') => format!("{key}{path}"),
+            path => format!("{key}.{path}"),
+        };
+        self
+    }
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.problem)
+        } else {
+            write!(f, "`{}`: {}", self.path, self.problem)
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// Deserialize `json` as `T`, finding the offending field when it fails.
+pub fn parse<T: DeserializeOwned>(json: &Value) -> Result<T, ShapeError> {
+    T::deserialize(json).map_err(|e| ShapeError {
+        path: render(&locate(json, |trial| T::deserialize(trial).is_ok())),
+        problem: e.to_string(),
+    })
+}
+
+#[derive(Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// Stand-ins tried for a value that can't simply be left out (a required
+/// field); whichever parses shows that this is the field at fault.
+fn placeholders() -> [Value; 6] {
+    [
+        Value::Null,
+        Value::Bool(false),
+        Value::from(0),
+        Value::from(""),
+        Value::Array(Vec::new()),
+        Value::Object(Default::default()),
+    ]
+}
+
+/// Walk down from the root one step at a time, each time into the child
+/// whose removal (or replacement) makes the reply parse. Stops at the
+/// deepest value that still explains the failure.
+fn locate(root: &Value, parses: impl Fn(&Value) -> bool) -> Vec<Step> {
+    let mut path = Vec::new();
+    loop {
+        let steps: Vec<Step> = match at(root, &path) {
+            Some(Value::Object(map)) => map.keys().cloned().map(Step::Key).collect(),
+            Some(Value::Array(items)) => (0..items.len()).map(Step::Index).collect(),
+            _ => break,
+        };
+        let fixes = |step: &Step, with: Option<Value>| {
+            let mut trial = root.clone();
+            replace(&mut trial, &path, step, with);
+            parses(&trial)
+        };
+        let culprit = steps
+            .iter()
+            .find(|step| fixes(step, None))
+            .or_else(|| steps.iter().find(|step| placeholders().into_iter().any(|p| fixes(step, Some(p)))));
+        match culprit {
+            Some(step) => path.push(step.clone()),
+            None => break,
+        }
+    }
+    path
+}
+
+fn at<'a>(root: &'a Value, path: &[Step]) -> Option<&'a Value> {
+    path.iter().try_fold(root, |node, step| match step {
+        Step::Key(key) => node.get(key),
+        Step::Index(i) => node.get(i),
+    })
+}
+
+/// Remove `step` below `path`, or put `with` in its place.
+fn replace(root: &mut Value, path: &[Step], step: &Step, with: Option<Value>) {
+    let mut node = root;
+    for s in path {
+        let next = match (s, node) {
+            (Step::Key(key), Value::Object(map)) => map.get_mut(key),
+            (Step::Index(i), Value::Array(items)) => items.get_mut(*i),
+            _ => None,
+        };
+        let Some(next) = next else {
+            return;
+        };
+        node = next;
+    }
+    match (step, node, with) {
+        (Step::Key(key), Value::Object(map), None) => {
+            map.remove(key);
+        }
+        (Step::Key(key), Value::Object(map), Some(with)) => {
+            map.insert(key.clone(), with);
+        }
+        (Step::Index(i), Value::Array(items), None) => {
+            items.remove(*i);
+        }
+        (Step::Index(i), Value::Array(items), Some(with)) => items[*i] = with,
+        _ => {}
+    }
+}
+
+fn render(path: &[Step]) -> String {
+    let mut out = String::new();
+    for step in path {
+        match step {
+            Step::Key(key) if out.is_empty() => out.push_str(key),
+            Step::Key(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            Step::Index(i) => out.push_str(&format!("[{i}]")),
+        }
+    }
+    out
+}
@@ -0,0 +1,134 @@
+use anyhow::{Context, Result, bail};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{CryptoProvider, WebPkiSupportedAlgorithms};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, handshake::client::Response};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+/// Trust settings from `--cacert` and `--insecure`, applied to both the
+/// HTTP client and the WebSocket connector.
+#[derive(Default)]
+struct Options {
+    /// Trusted on top of the bundled web roots.
+    extra_roots: Vec<CertificateDer<'static>>,
+    insecure: bool,
+}
+
+static OPTIONS: OnceLock<Options> = OnceLock::new();
+
+/// Read the `--cacert` bundle and remember both flags for `apply` and
+/// `connect_ws`. Call once, before any client is built.
+pub fn configure(cacert: Option<&Path>, insecure: bool) -> Result<()> {
+    let mut options = Options {
+        insecure,
+        ..Default::default()
+    };
+    if let Some(path) = cacert {
+        options.extra_roots = CertificateDer::pem_file_iter(path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .with_context(|| format!("reading certificates from {}", path.display()))?;
+        if options.extra_roots.is_empty() {
+            bail!("no PEM certificates found in {}", path.display());
+        }
+    }
+    OPTIONS.set(options).ok();
+    Ok(())
+}
+
+pub fn insecure() -> bool {
+    OPTIONS.get().is_some_and(|o| o.insecure)
+}
+
+/// `builder` with the configured trust settings.
+pub fn apply(mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    let Some(options) = OPTIONS.get() else {
+        return builder;
+    };
+    for cert in &options.extra_roots {
+        if let Ok(cert) = reqwest::Certificate::from_der(cert) {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    builder.danger_accept_invalid_certs(options.insecure)
+}
+
+/// `connect_async` with the configured trust settings.
+pub async fn connect_ws(
+    request: impl IntoClientRequest + Unpin,
+) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response), tungstenite::Error> {
+    tokio_tungstenite::connect_async_tls_with_config(request, None, false, ws_connector()).await
+}
+
+/// The connector for `connect_ws`, or `None` for tungstenite's default when
+/// nothing was configured.
+fn ws_connector() -> Option<Connector> {
+    let options = OPTIONS.get().filter(|o| o.insecure || !o.extra_roots.is_empty())?;
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .ok()?;
+    let config = if options.insecure {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCert(provider)))
+            .with_no_client_auth()
+    } else {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        roots.add_parsable_certificates(options.extra_roots.iter().cloned());
+        builder.with_root_certificates(roots).with_no_client_auth()
+    };
+    Some(Connector::Rustls(Arc::new(config)))
+}
+
+/// `--insecure`: any certificate is taken at face value. Handshake
+/// signatures are still checked, so the connection is at least consistent
+/// with the certificate that was presented.
+#[derive(Debug)]
+struct AcceptAnyCert(Arc<CryptoProvider>);
+
+impl AcceptAnyCert {
+    fn algorithms(&self) -> &WebPkiSupportedAlgorithms {
+        &self.0.signature_verification_algorithms
+    }
+}
+
+impl ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, self.algorithms())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, self.algorithms())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms().supported_schemes()
+    }
+}
@@ -0,0 +1,74 @@
+//! The `/ws` event stream.
+
+use anyhow::{Context, Result};
+use futures_util::SinkExt;
+use reqwest::Url;
+use serde::Serialize;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, handshake::client::Request, http::HeaderValue};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+#[derive(Serialize)]
+pub struct WsSubscribe {
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    #[serde(rename = "guildId")]
+    pub guild_id: String,
+}
+
+#[derive(Serialize)]
+pub struct WsAction<T> {
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    pub id: String,
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+/// Derive the WebSocket endpoint (`/ws`) from the HTTP base URL.
+pub fn build_ws_url(base: &str, token: &str) -> Result<Url> {
+    let mut url = Url::parse(base).context("parsing base URL")?;
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme).ok();
+    url.set_path("/ws");
+    url.query_pairs_mut().append_pair("token", token);
+    Ok(url)
+}
+
+/// Build the WebSocket handshake request with the headers the server expects.
+pub fn build_ws_request(base: &str, ws_url: &Url, token: &str) -> Result<Request> {
+    let mut req = ws_url.as_str().into_client_request().context("building WS request")?;
+    let headers = req.headers_mut();
+    headers.insert("User-Agent", HeaderValue::from_static("jorik-cli"));
+    headers.insert("Origin", HeaderValue::from_str(base).unwrap_or_else(|_| HeaderValue::from_static("jorik-cli")));
+    if let Some(host) = ws_url.host_str() {
+        headers.insert("Host", HeaderValue::from_str(host).unwrap_or_else(|_| HeaderValue::from_static("localhost")));
+    }
+    headers.insert("Authorization", HeaderValue::from_str(&format!("Bearer {}", token)).unwrap_or_else(|_| HeaderValue::from_static("")));
+    Ok(req)
+}
+
+pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Open the event stream of `base` with `token`, using the TLS settings from
+/// `tls::configure`. A rejected token shows up as a
+/// `tungstenite::Error::Http` with a 401 or 403 response in the error chain.
+pub async fn connect(base: &str, token: &str) -> Result<WsStream> {
+    let url = build_ws_url(base, token)?;
+    let request = build_ws_request(base, &url, token)?;
+    let (stream, _) = crate::tls::connect_ws(request).await?;
+    Ok(stream)
+}
+
+/// Ask for the events of `guild_id` on `stream`. Events start arriving as
+/// text frames holding a `WsEvent`.
+pub async fn subscribe(stream: &mut WsStream, guild_id: &str) -> Result<()> {
+    let json = serde_json::to_string(&WsSubscribe {
+        event_type: "subscribe",
+        guild_id: guild_id.to_string(),
+    })
+    .context("serializing subscribe")?;
+    stream.send(Message::Text(json.into())).await?;
+    Ok(())
+}
@@ -1,330 +1,564 @@
-use anyhow::{Context, Result};
+//! The CLI and TUI settings in config.toml, on top of everything shared with
+//! other programs in `jorik_core`.
+
+use anyhow::{Context, Result, bail};
 pub use crate::GiteaAsset;
-use dirs::config_dir;
-use reqwest::Url;
+pub use jorik_core::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
-#[derive(Serialize, Clone)]
-pub struct PlayPayload {
-    pub action: &'static str,
-    pub guild_id: Option<String>,
-    pub channel_id: Option<String>,
-    pub query: String,
-    pub user_id: Option<String>,
-    pub requested_by: Option<String>,
-    pub avatar_url: Option<String>,
-}
-
-#[derive(Serialize, Clone)]
-pub struct SimplePayload {
-    pub action: &'static str,
-    pub guild_id: Option<String>,
-    pub user_id: Option<String>,
-}
-
-#[derive(Serialize, Clone)]
-pub struct QueuePayload {
-    pub action: &'static str,
-    pub guild_id: Option<String>,
-    pub user_id: Option<String>,
-    pub limit: usize,
-    pub offset: usize,
-}
-
-#[derive(Serialize, Clone)]
-pub struct LoopPayload {
-    pub action: &'static str,
-    pub guild_id: Option<String>,
-    pub user_id: Option<String>,
-    pub loop_mode: String,
+/// What `jorik config export` writes and `import` reads back: the settings
+/// plus, unless left out, the saved login profiles and service keys.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConfigBundle {
+    /// Release that wrote the bundle
+    pub version: String,
+    pub settings: Settings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthStore>,
 }
 
-#[derive(Serialize, Clone)]
-pub struct TwentyFourSevenPayload {
-    pub action: &'static str,
-    pub guild_id: Option<String>,
-    pub user_id: Option<String>,
-    pub enabled: Option<bool>,
-}
-
-#[derive(Serialize, Clone)]
-pub struct FilterPayload {
-    pub action: &'static str,
-    pub guild_id: Option<String>,
-    pub user_id: Option<String>,
-    pub filters: AudioFilters,
-}
-
-#[derive(Serialize, Clone)]
-pub struct LyricsPayload {
-    pub action: String,
-    pub guild_id: Option<String>,
-    pub user_id: Option<String>,
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Settings {
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    #[serde(default = "default_offset")]
+    pub visualizer_offset: i64,
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    #[serde(default = "default_viz")]
+    pub visualizer_style: String,
+    #[serde(default = "default_layout")]
+    pub layout: String,
+    /// Check GitHub for a newer release after commands and on TUI start
+    #[serde(default = "default_true")]
+    pub update_check: bool,
+    /// How many times to retry a request after a network error or 5xx
+    #[serde(default)]
+    pub retries: u32,
+    /// Save play commands to pending.jsonl when the server can't be reached,
+    /// instead of failing; they're replayed by `jorik pending flush`
+    #[serde(default)]
+    pub offline_queue: bool,
+    /// Seconds between pings on the TUI's event stream; it reconnects after
+    /// two intervals without hearing back. 0 turns the pings off
+    #[serde(default = "default_ws_heartbeat_secs")]
+    pub ws_heartbeat_secs: u64,
+    /// Used by every command when `--guild-id` is not given
+    #[serde(default)]
+    pub default_guild_id: Option<String>,
+    /// Used by every command when `--user-id` is not given
+    #[serde(default)]
+    pub default_user_id: Option<String>,
+    /// Used by play commands when `--channel-id` is not given
+    #[serde(default)]
+    pub default_channel_id: Option<String>,
+    /// UI language code ("en", "ru"), or "auto" to follow JORIK_LANG / the system locale
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Ask before Stop, Clear Queue and removing tracks in the TUI
+    #[serde(default = "default_true")]
+    pub confirm_destructive: bool,
+    /// TUI panels toggled with Alt+V, Alt+L and Alt+Q
+    #[serde(default = "default_true")]
+    pub show_visualizer: bool,
+    #[serde(default = "default_true")]
+    pub show_logo: bool,
+    #[serde(default = "default_true")]
+    pub show_queue: bool,
+    /// Single-column TUI for small terminals: "Auto" (by terminal size), "On" or "Off"
+    #[serde(default = "default_compact_mode")]
+    pub compact_mode: String,
+    /// Where the TUI saves spectrograms and debug logs; the Desktop when unset
+    #[serde(default)]
+    pub export_dir: Option<String>,
+    /// Minutes with nothing playing before the TUI shows its screensaver; 0 turns it off
+    #[serde(default = "default_screensaver_minutes")]
+    pub screensaver_minutes: u64,
+    /// Desktop notification from the TUI whenever a new track starts
+    #[serde(default)]
+    pub notify_track_start: bool,
+    /// Discord application to show the TUI's track as on your Discord
+    /// profile (Rich Presence), through the Discord client running locally
+    #[serde(default)]
+    pub discord_client_id: Option<String>,
+    /// TUI redraws per second while the visualizer animates
+    #[serde(default = "default_frame_rate")]
+    pub frame_rate: u32,
+    /// TUI for screen readers and limited fonts: ASCII only, no visualizer, state spelled out
+    #[serde(default)]
+    pub accessible: bool,
+    // Tables stay last: TOML needs every plain value before the first one.
+    /// User-defined TUI themes by name, selectable next to the built-in ones
+    #[serde(default)]
+    pub custom_themes: BTreeMap<String, CustomTheme>,
+    /// Filter presets saved from the TUI filter editor, by name
+    #[serde(default)]
+    pub filter_presets: BTreeMap<String, AudioFilters>,
+    /// Overrides for individual guilds, by guild ID
+    #[serde(default)]
+    pub guilds: BTreeMap<String, GuildSettings>,
+    /// Stream URLs for `jorik radio`, by station name
+    #[serde(default)]
+    pub radio: BTreeMap<String, String>,
+    /// Where the TUI scrobbles to, set up by `jorik scrobble enable`
+    #[serde(default)]
+    pub scrobble: ScrobbleSettings,
+    /// App keys `jorik play --expand` reads playlists and albums with
+    #[serde(default)]
+    pub spotify: SpotifySettings,
+    /// API key `jorik play --pick` reads YouTube playlists with
+    #[serde(default)]
+    pub youtube: YoutubeSettings,
+    /// Commands run on playback events
+    #[serde(default)]
+    pub hooks: HookSettings,
+}
+
+/// Defaults for one guild, from `[guilds.<id>]` in config.toml. They apply
+/// whenever a command or the TUI targets that guild.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct GuildSettings {
+    /// Voice channel for play commands, ahead of `default_channel_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+    /// Replaces `visualizer_offset` while the TUI shows this guild
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visualizer_offset: Option<i64>,
+    /// Filter style or saved preset the TUI applies when it opens the guild,
+    /// and `jorik filter` uses when no style is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<String>,
 }
 
-#[derive(Serialize, Default, Clone)]
-pub struct AudioFilters {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub volume: Option<f32>,
+/// `[scrobble]`: the service the TUI submits what it plays to, and the
+/// keys it needs there. Scrobbling is off while `service` is unset. The
+/// secret and session keys and the token are kept in auth.json.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ScrobbleSettings {
+    /// "lastfm" or "listenbrainz"
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub equalizer: Option<Vec<EqualizerBand>>,
+    pub service: Option<String>,
+    /// Account the keys belong to, as the service reported it
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub karaoke: Option<KaraokeOptions>,
+    pub username: Option<String>,
+    /// API root of a server other than the service's own, e.g. a self-hosted
+    /// ListenBrainz or Libre.fm for the Last.fm API
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub timescale: Option<TimescaleOptions>,
+    pub api_url: Option<String>,
+    /// API account from last.fm/api/account/create
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub tremolo: Option<TremoloOptions>,
+    pub lastfm_api_key: Option<String>,
+    #[serde(skip_serializing)]
+    pub lastfm_api_secret: Option<String>,
+    /// Session the user granted that account in the browser
+    #[serde(skip_serializing)]
+    pub lastfm_session_key: Option<String>,
+    /// User token from listenbrainz.org/settings
+    #[serde(skip_serializing)]
+    pub listenbrainz_token: Option<String>,
+}
+
+/// `[spotify]`: a Spotify app from developer.spotify.com/dashboard, for
+/// expanding playlist and album links the server can't. Its secret is kept
+/// in auth.json.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SpotifySettings {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub vibrato: Option<VibratoOptions>,
+    pub client_id: Option<String>,
+    #[serde(skip_serializing)]
+    pub client_secret: Option<String>,
+    /// API roots other than Spotify's own, e.g. a caching proxy
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub rotation: Option<RotationOptions>,
+    pub api_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub distortion: Option<DistortionOptions>,
-    #[serde(rename = "channelMix", skip_serializing_if = "Option::is_none")]
-    pub channel_mix: Option<ChannelMixOptions>,
-    #[serde(rename = "lowPass", skip_serializing_if = "Option::is_none")]
-    pub low_pass: Option<LowPassOptions>,
+    pub accounts_url: Option<String>,
 }
 
-#[derive(Serialize, Clone)]
-pub struct EqualizerBand {
-    pub band: i32,
-    pub gain: f32,
-}
-
-#[derive(Serialize, Clone)]
-pub struct KaraokeOptions {
-    pub level: Option<f32>,
-    #[serde(rename = "monoLevel")]
-    pub mono_level: Option<f32>,
-    #[serde(rename = "filterBand")]
-    pub filter_band: Option<f32>,
-    #[serde(rename = "filterWidth")]
-    pub filter_width: Option<f32>,
-}
-
-#[derive(Serialize, Clone)]
-pub struct TimescaleOptions {
-    pub speed: Option<f32>,
-    pub pitch: Option<f32>,
-    pub rate: Option<f32>,
+/// `[youtube]`: a YouTube Data API key from console.cloud.google.com, for
+/// listing a playlist's videos to pick from. The key is kept in auth.json.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct YoutubeSettings {
+    #[serde(skip_serializing)]
+    pub api_key: Option<String>,
+    /// API root other than Google's own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
 }
 
-#[derive(Serialize, Clone)]
-pub struct TremoloOptions {
-    pub frequency: Option<f32>,
-    pub depth: Option<f32>,
+/// `[hooks]`: shell commands run when something happens in a guild being
+/// followed, with the track in `JORIK_TITLE`, `JORIK_AUTHOR` and the like.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct HookSettings {
+    /// A new track starts playing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_track_start: Option<String>,
+    /// The last track ends with nothing queued after it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_queue_empty: Option<String>,
+    /// Playback is paused
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_pause: Option<String>,
 }
 
-#[derive(Serialize, Clone)]
-pub struct VibratoOptions {
-    pub frequency: Option<f32>,
-    pub depth: Option<f32>,
+impl HookSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
-#[derive(Serialize, Clone)]
-pub struct RotationOptions {
-    #[serde(rename = "rotationHz")]
-    pub rotation_hz: Option<f32>,
+impl ScrobbleSettings {
+    /// Whether any key is saved, which an export would give away.
+    pub fn has_secrets(&self) -> bool {
+        self.lastfm_api_secret.is_some() || self.lastfm_session_key.is_some() || self.listenbrainz_token.is_some()
+    }
 }
 
-#[derive(Serialize, Clone)]
-pub struct DistortionOptions {
-    #[serde(rename = "sinOffset")]
-    pub sin_offset: Option<f32>,
-    #[serde(rename = "sinScale")]
-    pub sin_scale: Option<f32>,
-    #[serde(rename = "cosOffset")]
-    pub cos_offset: Option<f32>,
-    #[serde(rename = "cosScale")]
-    pub cos_scale: Option<f32>,
-    #[serde(rename = "tanOffset")]
-    pub tan_offset: Option<f32>,
-    #[serde(rename = "tanScale")]
-    pub tan_scale: Option<f32>,
-    pub offset: Option<f32>,
-    pub scale: Option<f32>,
-}
+impl Settings {
+    /// The `[guilds.<id>]` overrides for `guild_id`, if there are any.
+    pub fn guild(&self, guild_id: Option<&str>) -> Option<&GuildSettings> {
+        self.guilds.get(guild_id?)
+    }
 
-#[derive(Serialize, Clone)]
-pub struct ChannelMixOptions {
-    #[serde(rename = "leftToLeft")]
-    pub left_to_left: Option<f32>,
-    #[serde(rename = "leftToRight")]
-    pub left_to_right: Option<f32>,
-    #[serde(rename = "rightToLeft")]
-    pub right_to_left: Option<f32>,
-    #[serde(rename = "rightToRight")]
-    pub right_to_right: Option<f32>,
-}
+    /// The settings kept in auth.json's `keys` instead of config.toml, by
+    /// the name they have there.
+    fn keys_mut(&mut self) -> [(&'static str, &mut Option<String>); 5] {
+        [
+            ("scrobble.lastfm_api_secret", &mut self.scrobble.lastfm_api_secret),
+            ("scrobble.lastfm_session_key", &mut self.scrobble.lastfm_session_key),
+            ("scrobble.listenbrainz_token", &mut self.scrobble.listenbrainz_token),
+            ("spotify.client_secret", &mut self.spotify.client_secret),
+            ("youtube.api_key", &mut self.youtube.api_key),
+        ]
+    }
 
-#[derive(Serialize, Clone)]
-pub struct LowPassOptions {
-    pub smoothing: Option<f32>,
+    /// The keys among those that are set, for auth.json.
+    pub fn keys(&self) -> BTreeMap<String, String> {
+        let mut settings = self.clone();
+        settings
+            .keys_mut()
+            .into_iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.take()?)))
+            .collect()
+    }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Auth {
-    pub token: String,
+/// A TUI theme from settings. Colours are `#rrggbb`; any that are missing or
+/// unparsable come from the `base` built-in theme.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CustomTheme {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub avatar_url: Option<String>,
+    pub base: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub username: Option<String>,
+    pub background: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_secondary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visualizer_low: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visualizer_mid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visualizer_peak: Option<String>,
+    /// Blend the visualizer colours by bar height; `false` colours whole columns
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visualizer_gradient: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_bar: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            base_url: default_base_url(),
+            visualizer_offset: default_offset(),
+            theme: default_theme(),
+            visualizer_style: default_viz(),
+            layout: default_layout(),
+            update_check: true,
+            retries: 0,
+            offline_queue: false,
+            ws_heartbeat_secs: default_ws_heartbeat_secs(),
+            default_guild_id: None,
+            default_user_id: None,
+            default_channel_id: None,
+            language: default_language(),
+            confirm_destructive: true,
+            show_visualizer: true,
+            show_logo: true,
+            show_queue: true,
+            compact_mode: default_compact_mode(),
+            export_dir: None,
+            screensaver_minutes: default_screensaver_minutes(),
+            notify_track_start: false,
+            discord_client_id: None,
+            frame_rate: default_frame_rate(),
+            accessible: false,
+            custom_themes: BTreeMap::new(),
+            filter_presets: BTreeMap::new(),
+            guilds: BTreeMap::new(),
+            radio: BTreeMap::new(),
+            scrobble: ScrobbleSettings::default(),
+            spotify: SpotifySettings::default(),
+            youtube: YoutubeSettings::default(),
+            hooks: HookSettings::default(),
+        }
+    }
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct WsEvent {
-    #[serde(rename = "type")]
-    pub event_type: String,
-    #[serde(rename = "guildId")]
-    pub guild_id: Option<String>,
-    pub data: Option<Value>,
-    pub playback: Option<PlaybackState>,
-    pub success: Option<bool>,
-    pub id: Option<String>,
-}
+fn default_base_url() -> String { DEFAULT_BASE_URL.to_string() }
+fn default_offset() -> i64 { 0 }
+fn default_theme() -> String { "Default".to_string() }
+fn default_viz() -> String { "Bars".to_string() }
+fn default_layout() -> String { "Standard".to_string() }
+fn default_compact_mode() -> String { "Auto".to_string() }
+fn default_screensaver_minutes() -> u64 { 5 }
+fn default_frame_rate() -> u32 { 60 }
+fn default_ws_heartbeat_secs() -> u64 { 30 }
+fn default_true() -> bool { true }
+fn default_language() -> String { "auto".to_string() }
 
-#[derive(Deserialize, Debug, Clone)]
-pub struct PlaybackState {
-    #[serde(rename = "elapsedMs")]
-    pub elapsed_ms: u64,
-    #[serde(rename = "durationMs")]
-    pub duration_ms: u64,
-    pub paused: bool,
-    pub spectrogram: Option<Vec<Vec<u8>>>,
+pub fn settings_file_path() -> Option<PathBuf> {
+    config_root().map(|p| p.join("config.toml"))
 }
 
-#[derive(Serialize)]
-pub struct WsSubscribe {
-    #[serde(rename = "type")]
-    pub event_type: &'static str,
-    #[serde(rename = "guildId")]
-    pub guild_id: String,
+/// Where releases before config.toml kept settings; moved over on first load.
+fn legacy_settings_path() -> Option<PathBuf> {
+    config_root().map(|p| p.join("settings.json"))
 }
 
-#[derive(Serialize)]
-pub struct WsAction<T> {
-    #[serde(rename = "type")]
-    pub event_type: &'static str,
-    pub id: String,
-    #[serde(flatten)]
-    pub payload: T,
-}
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Settings {
-    pub base_url: String,
-    #[serde(default = "default_offset")]
-    pub visualizer_offset: i64,
-    #[serde(default = "default_theme")]
-    pub theme: String,
-    #[serde(default = "default_viz")]
-    pub visualizer_style: String,
-    #[serde(default = "default_layout")]
-    pub layout: String,
+/// Settings from config.toml, or `None` when there is no config file yet.
+/// A leftover settings.json is converted (and kept as settings.json.bak).
+pub fn read_settings() -> Result<Option<Settings>> {
+    let Some(path) = settings_file_path() else {
+        return Ok(None);
+    };
+    let settings = match fs::read_to_string(&path) {
+        Ok(contents) => toml::from_str(&contents).with_context(|| format!("parsing {}", path.display()))?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => match migrate_legacy_settings()? {
+            Some(settings) => settings,
+            None => return Ok(None),
+        },
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    Ok(Some(with_keys(settings)))
+}
+
+/// `settings` with the keys saved in auth.json filled in. Keys still written
+/// in config.toml, by an older release or by hand, are moved over first.
+fn with_keys(mut settings: Settings) -> Settings {
+    let mut store = load_auth_store();
+    let in_file = settings.keys();
+    let moved = !in_file.is_empty();
+    store.keys.extend(in_file);
+    // Best effort: the keys still work from config.toml if this fails.
+    if moved && save_auth_store(&store).is_ok() {
+        let _ = save_settings(&settings);
+    }
+    for (name, value) in settings.keys_mut() {
+        *value = store.keys.get(name).cloned();
+    }
+    settings
 }
 
-fn default_offset() -> i64 { 0 }
-fn default_theme() -> String { "Default".to_string() }
-fn default_viz() -> String { "Bars".to_string() }
-fn default_layout() -> String { "Standard".to_string() }
-
-pub fn config_file_path() -> Option<PathBuf> {
-    config_dir().map(|p| p.join("jorik-cli").join("auth.json"))
+/// Save the keys in `settings` to auth.json, and forget the unset ones.
+pub fn save_keys(settings: &Settings) -> Result<()> {
+    let mut store = load_auth_store();
+    for (name, _) in settings.clone().keys_mut() {
+        store.keys.remove(name);
+    }
+    store.keys.extend(settings.keys());
+    save_auth_store(&store)
+}
+
+/// Forget the keys auth.json holds for the `[table]` setting, when it is
+/// reset with `jorik config unset`.
+pub fn forget_keys(table: &str) -> Result<()> {
+    let mut store = load_auth_store();
+    let before = store.keys.len();
+    store.keys.retain(|name, _| name.split_once('.').is_none_or(|(t, _)| t != table));
+    if store.keys.len() == before {
+        return Ok(());
+    }
+    save_auth_store(&store)
 }
 
-pub fn settings_file_path() -> Option<PathBuf> {
-    config_dir().map(|p| p.join("jorik-cli").join("settings.json"))
+fn migrate_legacy_settings() -> Result<Option<Settings>> {
+    let Some(legacy) = legacy_settings_path() else {
+        return Ok(None);
+    };
+    let Ok(contents) = fs::read_to_string(&legacy) else {
+        return Ok(None);
+    };
+    let settings: Settings = serde_json::from_str(&contents)
+        .with_context(|| format!("parsing {}", legacy.display()))?;
+    save_settings(&settings)?;
+    fs::rename(&legacy, legacy.with_extension("json.bak")).context("moving settings.json aside")?;
+    Ok(Some(settings))
 }
 
+/// Settings from the config file, falling back to defaults when it is
+/// missing or broken (`read_settings` says why).
 pub fn load_settings() -> Settings {
-    if let Some(path) = settings_file_path() {
-        if let Ok(contents) = fs::read_to_string(&path) {
-            if let Ok(settings) = serde_json::from_str::<Settings>(&contents) {
-                return settings;
-            }
-        }
-    }
-    Settings {
-        base_url: "https://jorik.xserv.pp.ua".to_string(),
-        visualizer_offset: 0,
-        theme: "Default".to_string(),
-        visualizer_style: "Bars".to_string(),
-        layout: "Standard".to_string(),
-    }
+    read_settings().ok().flatten().unwrap_or_default()
 }
 
 pub fn save_settings(settings: &Settings) -> Result<()> {
-    let path = settings_file_path().context("cannot determine settings path")?;
+    let path = writable(settings_file_path(), "settings")?;
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).context("creating config directory")?;
     }
-    let json = serde_json::to_string_pretty(settings).context("serializing settings")?;
-    fs::write(&path, json).context("writing settings file")?;
-    Ok(())
-}
-
-pub fn save_token(token: &str, avatar_url: Option<&str>, username: Option<&str>) -> Result<()> {
-    let path = config_file_path().context("cannot determine config path")?;
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).context("creating config directory")?;
+    // Never replace a hand-edited file that has a mistake in it with defaults.
+    if let Ok(contents) = fs::read_to_string(&path)
+        && let Err(e) = toml::from_str::<Settings>(&contents)
+    {
+        bail!("{} has an error, fix it first: {}", path.display(), e);
     }
-
-    let auth = Auth {
-        token: token.trim().to_string(),
-        avatar_url: avatar_url.map(|s| s.to_string()),
-        username: username.map(|s| s.to_string()),
-    };
-
-    let json = serde_json::to_string_pretty(&auth).context("serializing auth")?;
-    fs::write(&path, json).context("writing auth file")?;
+    fs::write(&path, settings_toml(settings)?).context("writing settings file")?;
     Ok(())
 }
 
-pub fn load_auth() -> Option<Auth> {
-    if let Some(path) = config_file_path() {
-        if let Ok(contents) = fs::read_to_string(&path) {
-            if let Ok(auth) = serde_json::from_str::<Auth>(&contents) {
-                return Some(auth);
+/// What each setting does, written above it in config.toml.
+pub const SETTING_DOCS: &[(&str, &str)] = &[
+    ("base_url", "Webhook server to talk to"),
+    ("visualizer_offset", "Visualizer delay in milliseconds, to line it up with what you hear"),
+    ("theme", "TUI theme: a built-in name or one from [custom_themes]"),
+    ("visualizer_style", "TUI visualizer style"),
+    ("layout", "TUI layout: Standard, Sidebar, Studio, Zen or Lyrics"),
+    ("update_check", "Check GitHub for a newer release after commands and on TUI start"),
+    ("retries", "How many times to retry a request after a network error or 5xx"),
+    ("offline_queue", "Save play commands for later when the server can't be reached; see `jorik pending`"),
+    ("ws_heartbeat_secs", "Seconds between pings on the TUI's event stream; it reconnects after two unanswered intervals. 0 turns pings off"),
+    ("default_guild_id", "Used by every command when --guild-id is not given"),
+    ("default_user_id", "Used by every command when --user-id is not given"),
+    ("default_channel_id", "Used by play commands when --channel-id is not given"),
+    ("language", "UI language code (\"en\", \"ru\"), or \"auto\" to follow JORIK_LANG / the system locale"),
+    ("confirm_destructive", "Ask before Stop, Clear Queue and removing tracks in the TUI"),
+    ("show_visualizer", "Show the TUI visualizer panel (toggled with Alt+V)"),
+    ("show_logo", "Show the TUI logo panel (toggled with Alt+L)"),
+    ("show_queue", "Show the TUI queue panel (toggled with Alt+Q)"),
+    ("compact_mode", "Single-column TUI for small terminals: \"Auto\" (by terminal size), \"On\" or \"Off\""),
+    ("export_dir", "Where the TUI saves spectrograms and debug logs; the Desktop when unset"),
+    ("screensaver_minutes", "Minutes with nothing playing before the TUI shows its screensaver; 0 turns it off"),
+    ("notify_track_start", "Desktop notification from the TUI whenever a new track starts"),
+    ("discord_client_id", "Application ID from the Discord developer portal; when set, the TUI shows what's playing on your Discord profile through the local Discord client"),
+    ("frame_rate", "TUI redraws per second while the visualizer animates"),
+    ("accessible", "TUI for screen readers and limited fonts: ASCII only, no visualizer, state spelled out"),
+    ("custom_themes", "User-defined TUI themes by name; colours are \"#rrggbb\" and missing ones come from `base`"),
+    ("filter_presets", "Filter presets saved from the TUI filter editor, by name"),
+    ("guilds", "Overrides for one guild as [guilds.<guild id>]: channel_id, visualizer_offset and filters (a style or preset name)"),
+    ("radio", "Stations for `jorik radio`, as name = \"stream URL\"; they add to the built-in ones and replace those of the same name"),
+    ("scrobble", "Last.fm or ListenBrainz account the TUI scrobbles to (its keys are in auth.json); set up with `jorik scrobble enable`"),
+    ("spotify", "Spotify app (client_id and client_secret from developer.spotify.com/dashboard) that `jorik play --expand` reads playlists and albums with"),
+    ("youtube", "YouTube Data API key (api_key, from console.cloud.google.com) that `jorik play --pick` lists playlists with"),
+    ("hooks", "Shell commands run on_track_start, on_queue_empty and on_pause by the TUI, `jorik events` and `jorik daemon`; the track is in JORIK_TITLE, JORIK_AUTHOR, JORIK_URI, JORIK_DURATION_MS and JORIK_REQUESTER"),
+];
+
+const SETTINGS_HEADER: &str = "\
+# Jorik CLI configuration.
+#
+# Each setting can be overridden for one run with a JORIK_<NAME> environment
+# variable (e.g. JORIK_THEME=Nord), and command-line flags beat both.
+# Tokens are not kept here but in auth.json next to this file.
+# Edit by hand or with `jorik config set <name> <value>`.
+";
+
+/// config.toml text for `settings`: the header, a comment above each
+/// setting, and unset optional ones listed commented out.
+fn settings_toml(settings: &Settings) -> Result<String> {
+    let value = toml::Value::try_from(settings).context("serializing settings")?;
+    let body = toml::to_string(settings).context("serializing settings")?;
+    let doc = |key: &str| SETTING_DOCS.iter().find(|(k, _)| *k == key).map(|(_, d)| *d);
+    let table = value.as_table();
+    let unset: Vec<&str> = SETTING_DOCS
+        .iter()
+        .map(|(k, _)| *k)
+        .filter(|k| table.is_some_and(|t| !t.contains_key(*k)))
+        .collect();
+
+    let mut out = String::from(SETTINGS_HEADER);
+    let mut unset_written = false;
+    let mut documented = std::collections::BTreeSet::new();
+    for line in body.lines() {
+        let is_table = line.starts_with('[');
+        if is_table && !unset_written {
+            for key in &unset {
+                out.push_str(&format!("\n# {}\n# {} = \"\"\n", doc(key).unwrap_or_default(), key));
             }
+            unset_written = true;
         }
+        let key = line
+            .trim_start_matches('[')
+            .split(['.', ' ', ']', '='])
+            .next()
+            .unwrap_or_default();
+        if !key.is_empty() && documented.insert(key.to_string())
+            && let Some(doc) = doc(key)
+        {
+            out.push_str(&format!("\n# {}\n", doc));
+        }
+        out.push_str(line);
+        out.push('\n');
     }
-    None
-}
-
-pub fn load_token() -> Option<String> {
-    load_auth().map(|a| a.token)
-}
-
-pub fn build_url(base: &str, path: &str) -> String {
-    format!("{}{}", base.trim_end_matches('/'), path)
-}
-
-pub fn clean_query(input: &str) -> String {
-    if let Ok(mut url) = Url::parse(input) {
-        if url.cannot_be_a_base() || url.query().is_none() {
-            return input.to_string();
+    if !unset_written {
+        for key in &unset {
+            out.push_str(&format!("\n# {}\n# {} = \"\"\n", doc(key).unwrap_or_default(), key));
         }
+    }
+    Ok(out)
+}
 
-        let pairs: Vec<(String, String)> = url
-            .query_pairs()
-            .filter(|(k, _)| k != "si")
-            .map(|(k, v)| (k.into_owned(), v.into_owned()))
-            .collect();
-
-        if pairs.is_empty() {
-            url.set_query(None);
-        } else {
-            let mut serializer = url.query_pairs_mut();
-            serializer.clear();
-            for (k, v) in pairs {
-                serializer.append_pair(&k, &v);
-            }
-        }
-        return url.to_string();
+/// `settings` with `key` set from text, spelled the way `jorik config set`
+/// and `JORIK_*` variables take it: a plain string (IDs, URLs) or JSON
+/// (numbers, booleans, tables). `None` if the key is unknown or the value
+/// does not fit.
+pub fn with_setting(settings: &Settings, key: &str, raw: &str) -> Option<Settings> {
+    let Ok(Value::Object(map)) = serde_json::to_value(settings) else {
+        return None;
+    };
+    if !map.contains_key(key) {
+        return None;
+    }
+    [Some(Value::from(raw)), serde_json::from_str::<Value>(raw).ok()]
+        .into_iter()
+        .flatten()
+        .find_map(|v| {
+            let mut map = map.clone();
+            map.insert(key.to_string(), v);
+            serde_json::from_value::<Settings>(Value::Object(map)).ok()
+        })
+}
+
+/// Environment variable that overrides `key`, e.g. `JORIK_DEFAULT_GUILD_ID`.
+pub fn setting_env_var(key: &str) -> String {
+    format!("JORIK_{}", key.to_uppercase())
+}
+
+/// Layer `JORIK_<NAME>` variables over settings read from the file.
+pub fn apply_env_overrides(mut settings: Settings) -> Result<Settings> {
+    for (key, _) in SETTING_DOCS {
+        let var = setting_env_var(key);
+        let Ok(raw) = std::env::var(&var) else {
+            continue;
+        };
+        settings = with_setting(&settings, key, &raw)
+            .with_context(|| format!("{var}: invalid value '{raw}'"))?;
     }
-    input.to_string()
+    Ok(settings)
 }
+
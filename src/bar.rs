@@ -0,0 +1,148 @@
+//! `jorik status --bar`: the current track in the shapes status bars read
+//! from a custom-script module.
+
+use crate::api::{NowPlaying, PlaybackState, QueueResponse, Track, WsEvent};
+use crate::format;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// Which status bar's script protocol to speak.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bar {
+    /// One JSON object per line (`"return-type": "json"`)
+    Waybar,
+    /// One line of text per update
+    Polybar,
+    /// A full_text and a short_text line, or one line per update with --follow
+    I3blocks,
+}
+
+/// The player as the bar shows it; the default is nothing playing.
+#[derive(Default)]
+pub struct Snapshot {
+    pub track: Option<Track>,
+    pub paused: bool,
+    /// As of the last reply or event that said
+    pub elapsed_ms: Option<u64>,
+    pub duration_ms: Option<u64>,
+}
+
+impl Snapshot {
+    pub fn from_now_playing(np: Option<NowPlaying>) -> Self {
+        let Some(np) = np else {
+            return Self::default();
+        };
+        Self {
+            track: np.track,
+            paused: np.paused,
+            elapsed_ms: Some(np.elapsed_ms),
+            duration_ms: Some(np.duration_ms).filter(|&ms| ms > 0),
+        }
+    }
+
+    /// Take in what a `/ws` event says about the player.
+    pub fn apply(&mut self, event: &Value) {
+        let Ok(event) = WsEvent::deserialize(event) else {
+            return;
+        };
+        match event.event_type.as_str() {
+            "initial_state" | "state_update" => {
+                if let Some(data) = &event.data
+                    && let Ok(queue) = QueueResponse::from_json(data)
+                    && let Some(current) = queue.current
+                {
+                    if current.is_none() {
+                        self.elapsed_ms = None;
+                        self.duration_ms = None;
+                    }
+                    self.track = current;
+                }
+                // Some servers nest it in the data rather than beside it.
+                let playback = event.playback.or_else(|| {
+                    let playback = event.data.as_ref()?.get("playback")?;
+                    PlaybackState::deserialize(playback).ok()
+                });
+                if let Some(playback) = playback {
+                    self.paused = playback.paused;
+                    self.elapsed_ms = Some(playback.elapsed_ms);
+                    self.duration_ms = Some(playback.duration_ms).filter(|&ms| ms > 0);
+                }
+            }
+            "track_start" => {
+                let track = event
+                    .data
+                    .as_ref()
+                    .and_then(|d| Track::deserialize(d.get("track").unwrap_or(d)).ok())
+                    .filter(|t| t.title.is_some());
+                // A bare track_start still means the track changed; the next
+                // state_update says to what.
+                self.duration_ms = track.as_ref().and_then(|t| t.duration_ms);
+                self.track = track;
+                self.paused = false;
+                self.elapsed_ms = Some(0);
+            }
+            _ => {}
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match (&self.track, self.paused) {
+            (None, _) => "stopped",
+            (Some(_), true) => "paused",
+            (Some(_), false) => "playing",
+        }
+    }
+
+    /// The track as `--format` has it, or "title by author".
+    fn label(&self, track: &Track) -> String {
+        match format::template() {
+            Some(template) => format::render(
+                template,
+                &format::TrackFields {
+                    track: Some(track),
+                    duration_ms: self.duration_ms,
+                    elapsed_ms: self.elapsed_ms,
+                    position: None,
+                },
+            ),
+            None => crate::display_title(track, crate::i18n::tr("Unknown")),
+        }
+    }
+
+    /// `self` as one update for `bar`; idle is an empty line (or empty
+    /// text), which the bars take as a cue to hide the module.
+    pub fn render(&self, bar: Bar, follow: bool) -> String {
+        let Some(track) = &self.track else {
+            return match bar {
+                Bar::Waybar => json!({"text": "", "class": "stopped", "alt": "stopped"}).to_string(),
+                Bar::Polybar | Bar::I3blocks => String::new(),
+            };
+        };
+        let icon = if self.paused { "⏸" } else { "▶" };
+        let text = format!("{icon} {}", self.label(track));
+        match bar {
+            Bar::Waybar => {
+                let title = track.title.as_deref().unwrap_or(crate::i18n::tr("Unknown"));
+                let tooltip = match &track.author {
+                    Some(author) => format!("{title}\n{author}"),
+                    None => title.to_string(),
+                };
+                // Waybar reads both as Pango markup.
+                json!({
+                    "text": crate::escape_html(&text),
+                    "tooltip": crate::escape_html(&tooltip),
+                    "class": self.class(),
+                    "alt": self.class(),
+                })
+                .to_string()
+            }
+            Bar::Polybar => text,
+            // In persist mode i3blocks takes every line as a new full_text.
+            Bar::I3blocks if follow => text,
+            Bar::I3blocks => {
+                let short = track.title.as_deref().unwrap_or(crate::i18n::tr("Unknown"));
+                format!("{text}\n{icon} {short}")
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! The checklist `jorik play --pick` chooses tracks with: arrows and Space
+//! in a terminal, or a line of numbers ("1-3,7") when stdin is piped.
+
+use crate::exit::{self, ExitCode};
+use crate::{i18n, t};
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, queue, style, terminal};
+use std::io::{self, BufRead, Write};
+
+/// Which of `labels` to keep, by index in order. Everything starts ticked.
+pub fn pick(labels: &[String]) -> Result<Vec<usize>> {
+    if atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stdout) {
+        interactive(labels)
+    } else {
+        from_numbers(labels)
+    }
+}
+
+fn from_numbers(labels: &[String]) -> Result<Vec<usize>> {
+    let mut stderr = io::stderr().lock();
+    for (i, label) in labels.iter().enumerate() {
+        writeln!(stderr, "{:>3}. {}", i + 1, label)?;
+    }
+    write!(stderr, "{}", i18n::tr("Tracks to enqueue (e.g. 1-3,7; Enter for all): "))?;
+    stderr.flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    writeln!(stderr)?;
+    parse_numbers(line.trim(), labels.len())
+        .ok_or_else(|| exit::fail(ExitCode::Usage, t!("'{}' is not a list of track numbers", line.trim())))
+}
+
+/// "1-3,7" as indices; blank is all of them.
+fn parse_numbers(text: &str, count: usize) -> Option<Vec<usize>> {
+    if text.is_empty() {
+        return Some((0..count).collect());
+    }
+    let mut picked = vec![false; count];
+    for part in text.split([',', ' ']).filter(|p| !p.is_empty()) {
+        let (from, to) = match part.split_once('-') {
+            Some((from, to)) => (from.trim().parse::<usize>().ok()?, to.trim().parse::<usize>().ok()?),
+            None => {
+                let n = part.parse::<usize>().ok()?;
+                (n, n)
+            }
+        };
+        if from == 0 || from > to || to > count {
+            return None;
+        }
+        picked[from - 1..to].iter_mut().for_each(|p| *p = true);
+    }
+    Some((0..count).filter(|&i| picked[i]).collect())
+}
+
+fn interactive(labels: &[String]) -> Result<Vec<usize>> {
+    let (width, height) = terminal::size().unwrap_or((80, 24));
+    // Room for the help line, with the rest of the screen left alone.
+    let rows = usize::from(height).saturating_sub(3).clamp(1, 20).min(labels.len());
+    let mut list = Checklist {
+        checked: vec![true; labels.len()],
+        cursor: 0,
+        top: 0,
+        rows,
+        width: usize::from(width).saturating_sub(1),
+    };
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    queue!(stdout, cursor::Hide)?;
+    let result = (|| {
+        list.draw(&mut stdout, labels, true)?;
+        loop {
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => list.move_by(-1),
+                KeyCode::Down | KeyCode::Char('j') => list.move_by(1),
+                KeyCode::PageUp => list.move_by(-(rows as isize)),
+                KeyCode::PageDown => list.move_by(rows as isize),
+                KeyCode::Home => list.move_by(-(labels.len() as isize)),
+                KeyCode::End => list.move_by(labels.len() as isize),
+                KeyCode::Char(' ') => list.checked[list.cursor] ^= true,
+                KeyCode::Char('a') => {
+                    let all = list.checked.iter().all(|&c| c);
+                    list.checked.iter_mut().for_each(|c| *c = !all);
+                }
+                KeyCode::Enter => break Ok((0..labels.len()).filter(|&i| list.checked[i]).collect()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(exit::fail(ExitCode::Interrupted, t!("Cancelled")));
+                }
+                KeyCode::Esc | KeyCode::Char('q') => break Err(exit::fail(ExitCode::Interrupted, t!("Cancelled"))),
+                _ => continue,
+            }
+            list.draw(&mut stdout, labels, false)?;
+        }
+    })();
+    queue!(stdout, cursor::Show, style::Print("\r\n"))?;
+    stdout.flush()?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+struct Checklist {
+    checked: Vec<bool>,
+    cursor: usize,
+    /// First label on screen
+    top: usize,
+    rows: usize,
+    /// Lines are cut to this, since a wrapped one would throw the redraw off
+    width: usize,
+}
+
+impl Checklist {
+    fn move_by(&mut self, by: isize) {
+        let last = self.checked.len() - 1;
+        self.cursor = self.cursor.saturating_add_signed(by).min(last);
+        if self.cursor < self.top {
+            self.top = self.cursor;
+        } else if self.cursor >= self.top + self.rows {
+            self.top = self.cursor + 1 - self.rows;
+        }
+    }
+
+    /// The help line and the visible rows, over the previous drawing
+    /// unless `first`.
+    fn draw(&self, out: &mut impl Write, labels: &[String], first: bool) -> io::Result<()> {
+        if !first {
+            queue!(out, cursor::MoveToPreviousLine(self.rows as u16))?;
+        }
+        let picked = self.checked.iter().filter(|&&c| c).count();
+        let help = t!(
+            "↑/↓ move · Space tick · a all · Enter enqueue ({} of {}) · Esc cancel",
+            picked,
+            labels.len()
+        );
+        queue!(
+            out,
+            cursor::MoveToColumn(0),
+            terminal::Clear(terminal::ClearType::CurrentLine),
+            style::Print(cut(&help, self.width)),
+        )?;
+        let shown = labels.iter().zip(&self.checked).enumerate().skip(self.top).take(self.rows);
+        for (i, (label, &checked)) in shown {
+            let pointer = if i == self.cursor { '>' } else { ' ' };
+            let tick = if checked { 'x' } else { ' ' };
+            let line = format!("{pointer} [{tick}] {:>3}. {}", i + 1, label);
+            queue!(
+                out,
+                style::Print("\r\n"),
+                terminal::Clear(terminal::ClearType::CurrentLine),
+                style::Print(cut(&line, self.width)),
+            )?;
+        }
+        out.flush()
+    }
+}
+
+fn cut(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
@@ -0,0 +1,90 @@
+use anyhow::{Context, Result, bail};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64_STD;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+type Tool = (&'static str, &'static [&'static str]);
+
+/// Programs that print the clipboard, tried in order. There is no clipboard
+/// crate in the tree, so this leans on whatever the desktop already ships.
+fn paste_commands() -> &'static [Tool] {
+    if cfg!(target_os = "windows") {
+        &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])]
+    } else if cfg!(target_os = "macos") {
+        &[("pbpaste", &[])]
+    } else {
+        &[
+            ("wl-paste", &["--no-newline"]),
+            ("xclip", &["-o", "-selection", "clipboard"]),
+            ("xsel", &["--output", "--clipboard"]),
+        ]
+    }
+}
+
+/// Programs that take new clipboard text on stdin, tried in order.
+fn copy_commands() -> &'static [Tool] {
+    if cfg!(target_os = "windows") {
+        &[("clip", &[])]
+    } else if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--input", "--clipboard"]),
+        ]
+    }
+}
+
+/// Current clipboard text.
+pub fn paste() -> Result<String> {
+    for (program, args) in paste_commands() {
+        let Ok(output) = Command::new(program).args(*args).output() else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        return String::from_utf8(output.stdout).context("clipboard does not hold text");
+    }
+    bail!("no clipboard tool found (install wl-clipboard, xclip or xsel)")
+}
+
+/// Put `text` on the clipboard with the first copy tool that works, and say
+/// which. Over SSH the local machine's clipboard is the one that matters, so
+/// there this leaves it to `copy_to_terminal`, as it does when no tool is
+/// installed.
+pub fn copy_with_tool(text: &str) -> Option<&'static str> {
+    let remote = std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some();
+    if remote {
+        return None;
+    }
+    copy_commands()
+        .iter()
+        .find(|(program, args)| run_copy(program, args, text).is_ok())
+        .map(|(program, _)| *program)
+}
+
+/// Ask the terminal to set its clipboard via an OSC 52 sequence; whether it
+/// lands depends on the terminal allowing clipboard writes.
+pub fn copy_to_terminal(text: &str) -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    write!(out, "\x1b]52;c;{}\x07", BASE64_STD.encode(text))?;
+    out.flush()?;
+    Ok(())
+}
+
+fn run_copy(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    child.stdin.take().context("no stdin")?.write_all(text.as_bytes())?;
+    if !child.wait()?.success() {
+        bail!("{} failed", program);
+    }
+    Ok(())
+}
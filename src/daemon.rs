@@ -0,0 +1,157 @@
+//! `jorik daemon`: a small REST API on localhost that forwards to the Jorik
+//! server with the saved login, so scripts on the machine need no token.
+//!
+//! | Request            | Does                                              |
+//! |--------------------|---------------------------------------------------|
+//! | `GET /now-playing` | the `nowplaying` reply                            |
+//! | `GET /queue`       | the `queue` reply; `?limit=` and `?offset=`       |
+//! | `POST /play`       | enqueue `{"query": …}` (or `?query=`)             |
+//! | `POST /skip`       | skip the current track                            |
+//!
+//! Every one takes `?guild_id=` (and `/play` `?channel_id=`) over the
+//! defaults. Replies are the server's JSON as-is, with its status; a refused
+//! request keeps its `{"error", "message"}` body.
+
+use crate::api::{self, JorikClient, PlayPayload, QueuePayload, Rejected, SimplePayload};
+use crate::httpd::{self, Request};
+use anyhow::Result;
+use serde_json::{Value, json};
+use tokio::net::{TcpListener, TcpStream};
+
+/// What requests are forwarded with.
+pub struct Daemon {
+    pub http: reqwest::Client,
+    pub base_url: String,
+    /// `--token` if given. Otherwise the saved login is read for each
+    /// request, so logging in again takes effect without a restart.
+    pub token: Option<String>,
+    pub guild_id: Option<String>,
+    pub channel_id: Option<String>,
+    pub user_id: Option<String>,
+    /// Whether to insist on a loopback `Host`
+    pub loopback: bool,
+}
+
+/// A reply: status and JSON body.
+type Reply = (u16, Value);
+
+fn error(status: u16, error: &str, message: impl Into<String>) -> Reply {
+    (status, json!({"error": error, "message": message.into()}))
+}
+
+impl Daemon {
+    /// Answer requests on `listener` until the process ends.
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        let daemon = std::sync::Arc::new(self);
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let daemon = daemon.clone();
+            tokio::spawn(async move {
+                // A client that hangs up mid-request is no concern of the others.
+                daemon.serve_client(stream).await.ok();
+            });
+        }
+    }
+
+    async fn serve_client(&self, mut stream: TcpStream) -> Result<()> {
+        let (status, body) = match httpd::read_request(&mut stream).await {
+            Ok(request) => self.handle(&request).await,
+            Err(e) => error(e.status, e.error, e.message),
+        };
+        httpd::respond(&mut stream, status, "application/json", body.to_string().as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn handle(&self, request: &Request) -> Reply {
+        // Browsers send an Origin with any request a page makes to another
+        // site; scripts and apps don't. Pages have no business here.
+        if request.header("origin").is_some() {
+            return error(403, "forbidden", "requests from web pages are not accepted");
+        }
+        if self.loopback && !request.for_loopback_host() {
+            return error(403, "forbidden", "the Host header must name this machine");
+        }
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/now-playing" | "/queue") | ("POST", "/play" | "/skip") => {}
+            (_, "/now-playing" | "/queue" | "/play" | "/skip") => {
+                return error(405, "method_not_allowed", format!("{} is not allowed here", request.method));
+            }
+            _ => return error(404, "not_found", format!("no such endpoint: {}", request.path)),
+        }
+        let param = |name: &str| request.query.get(name).cloned();
+        let Some(guild_id) = param("guild_id").or_else(|| self.guild_id.clone()) else {
+            return error(400, "no_guild", "pass ?guild_id= or set default_guild_id");
+        };
+        let payload = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/now-playing") => serde_json::to_value(SimplePayload {
+                action: "nowplaying",
+                guild_id: Some(guild_id),
+                user_id: self.user_id.clone(),
+            }),
+            ("GET", "/queue") => {
+                let number = |name: &str, default: usize| match param(name) {
+                    Some(n) => n.parse().map_err(|_| error(400, "bad_request", format!("{name} must be a number"))),
+                    None => Ok(default),
+                };
+                let (limit, offset) = match (number("limit", 20), number("offset", 0)) {
+                    (Ok(limit), Ok(offset)) => (limit, offset),
+                    (Err(reply), _) | (_, Err(reply)) => return reply,
+                };
+                serde_json::to_value(QueuePayload {
+                    action: "queue",
+                    guild_id: Some(guild_id),
+                    user_id: self.user_id.clone(),
+                    limit,
+                    offset,
+                })
+            }
+            ("POST", "/play") => {
+                let body: Value = if request.body.is_empty() {
+                    Value::Null
+                } else {
+                    match serde_json::from_slice(&request.body) {
+                        Ok(body) => body,
+                        Err(e) => return error(400, "bad_request", format!("body is not JSON: {e}")),
+                    }
+                };
+                let field = |name: &str| body.get(name).and_then(Value::as_str).map(str::to_string).or_else(|| param(name));
+                let Some(query) = field("query").filter(|q| !q.trim().is_empty()) else {
+                    return error(400, "bad_request", "nothing to play; send {\"query\": …}");
+                };
+                let saved = api::load_auth();
+                serde_json::to_value(PlayPayload {
+                    action: "play",
+                    guild_id: Some(guild_id),
+                    channel_id: field("channel_id").or_else(|| self.channel_id.clone()),
+                    query: api::clean_query(&query),
+                    user_id: self.user_id.clone(),
+                    requested_by: saved.as_ref().and_then(|a| a.username.clone()),
+                    avatar_url: saved.and_then(|a| a.avatar_url),
+                })
+            }
+            _ => serde_json::to_value(SimplePayload {
+                action: "skip",
+                guild_id: Some(guild_id),
+                user_id: self.user_id.clone(),
+            }),
+        };
+        let Ok(payload) = payload else {
+            return error(500, "internal", "could not build the request");
+        };
+        let token = self.token.clone().or_else(api::load_token);
+        let client = JorikClient::with_http(self.http.clone(), self.base_url.clone(), token);
+        match client.audio(&payload).await {
+            Ok(reply) => (200, reply),
+            Err(e) => match e.downcast_ref::<Rejected>() {
+                Some(rejected) => {
+                    let body = match &rejected.error {
+                        Some(err) => json!({"error": err.error, "message": err.message}),
+                        None => json!({"error": "rejected", "message": rejected.to_string()}),
+                    };
+                    (rejected.status.as_u16(), body)
+                }
+                None => error(502, "unreachable", format!("{e:#}")),
+            },
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::fmt;
+
+/// Process exit codes, so shell scripts can branch on why a command failed.
+///
+/// `2` matches what clap already uses for argument parsing errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Not a failure: `--dry-run` printed the request in place of sending it
+    DryRun = 0,
+    /// Anything not covered below
+    Failure = 1,
+    /// Bad arguments or an unknown value (e.g. filter style)
+    Usage = 2,
+    /// Missing, invalid or expired token
+    Auth = 3,
+    /// The server could not be reached
+    Network = 4,
+    /// The server answered but refused the request
+    Rejected = 5,
+    /// The server stopped accepting the token mid-session and no new login
+    /// happened (non-interactive, or the offer was declined)
+    LoginRequired = 6,
+    /// Stopped with Ctrl-C; 128 + SIGINT, as a shell reports it
+    Interrupted = 130,
+}
+
+/// An error carrying an explicit exit code. When `message` is `None` the
+/// failure has already been printed and `main` only sets the exit status.
+#[derive(Debug)]
+pub struct Failure {
+    pub code: ExitCode,
+    pub message: Option<String>,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.message {
+            Some(msg) => f.write_str(msg),
+            None => write!(f, "command failed ({:?})", self.code),
+        }
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Build an error that exits with `code` and prints `msg`.
+pub fn fail(code: ExitCode, msg: impl Into<String>) -> anyhow::Error {
+    Failure {
+        code,
+        message: Some(msg.into()),
+    }
+    .into()
+}
+
+/// Build an error for a failure that has already been shown to the user.
+pub fn reported(code: ExitCode) -> anyhow::Error {
+    Failure {
+        code,
+        message: None,
+    }
+    .into()
+}
+
+/// Pick the exit code for an error bubbled up to `main`.
+pub fn code_for(err: &anyhow::Error) -> ExitCode {
+    for cause in err.chain() {
+        if let Some(failure) = cause.downcast_ref::<Failure>() {
+            return failure.code;
+        }
+        if cause.is::<reqwest::Error>()
+            || cause.is::<tokio_tungstenite::tungstenite::Error>()
+            || cause.is::<tokio::time::error::Elapsed>()
+        {
+            return ExitCode::Network;
+        }
+    }
+    ExitCode::Failure
+}
+
+/// Whether the error was already printed and only the exit status is left.
+pub fn is_reported(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<Failure>()
+        .is_some_and(|f| f.message.is_none())
+}
+
+/// Whether the error is `--dry-run` stopping a command after printing its
+/// request, which is not a failure.
+pub fn is_dry_run(err: &anyhow::Error) -> bool {
+    code_for(err) == ExitCode::DryRun
+}
+
+/// Exit code for a server reply: 401/403 (or an `unauthorized` body) is an
+/// auth problem, anything else unsuccessful is a rejection.
+pub fn for_status(status: reqwest::StatusCode, error: Option<&str>) -> ExitCode {
+    if status == reqwest::StatusCode::UNAUTHORIZED
+        || status == reqwest::StatusCode::FORBIDDEN
+        || error == Some("unauthorized")
+    {
+        ExitCode::Auth
+    } else {
+        ExitCode::Rejected
+    }
+}
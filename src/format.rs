@@ -0,0 +1,158 @@
+use crate::api::Track;
+use std::sync::OnceLock;
+
+/// Set once from `--format`; consulted when summarizing track responses.
+static TEMPLATE: OnceLock<String> = OnceLock::new();
+
+pub fn set_template(template: Option<String>) {
+    if let Some(t) = template {
+        TEMPLATE.set(t).ok();
+    }
+}
+
+pub fn template() -> Option<&'static str> {
+    TEMPLATE.get().map(String::as_str)
+}
+
+/// Placeholders understood by `--format`, shown in `--help`.
+pub const PLACEHOLDERS: &str =
+    "{title} {author} {duration} {elapsed} {position} {uri} {requester}; use {{ and }} for literal braces";
+
+/// Values available to a template for one track.
+#[derive(Default)]
+pub struct TrackFields<'a> {
+    pub track: Option<&'a Track>,
+    pub duration_ms: Option<u64>,
+    pub elapsed_ms: Option<u64>,
+    pub position: Option<usize>,
+}
+
+impl<'a> TrackFields<'a> {
+    pub fn of(track: &'a Track) -> Self {
+        Self {
+            track: Some(track),
+            ..Default::default()
+        }
+    }
+
+    fn str_field(&self, get: impl Fn(&'a Track) -> Option<&'a str>) -> String {
+        self.track.and_then(get).unwrap_or("").to_string()
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "title" => self.str_field(|t| t.title.as_deref()),
+            "author" | "artist" => self.str_field(|t| t.author.as_deref()),
+            "uri" | "url" => self.str_field(|t| t.uri.as_deref()),
+            "requester" => self.str_field(Track::requester_name),
+            "duration" => self
+                .duration_ms
+                .or_else(|| self.track?.duration_ms)
+                .map(format_ms)
+                .unwrap_or_default(),
+            "elapsed" => self.elapsed_ms.map(format_ms).unwrap_or_default(),
+            "position" => self.position.map(|p| p.to_string()).unwrap_or_default(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// `mm:ss`, or `h:mm:ss` for anything an hour or longer.
+pub fn format_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Expand `{name}` placeholders. Unknown placeholders are kept verbatim so a
+/// typo shows up in the output instead of silently vanishing.
+pub fn render(template: &str, fields: &TrackFields) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+        } else if tail.starts_with('{')
+            && let Some(end) = tail.find('}')
+            && let Some(value) = fields.lookup(&tail[1..end])
+        {
+            out.push_str(&value);
+            rest = &tail[end + 1..];
+        } else {
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Tabular output for track lists (`--output`).
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Table {
+    Tsv,
+    Csv,
+}
+
+/// Set from a command's `--output`; consulted when summarizing track lists.
+static TABLE: OnceLock<Table> = OnceLock::new();
+
+pub fn set_table(table: Option<Table>) {
+    if let Some(t) = table {
+        TABLE.set(t).ok();
+    }
+}
+
+pub fn table() -> Option<Table> {
+    TABLE.get().copied()
+}
+
+/// Column order is part of the interface; only ever append new columns.
+const COLUMNS: [&str; 6] = ["position", "title", "author", "duration_ms", "uri", "requester"];
+
+/// One header row plus one row per `(position, track)`.
+pub fn render_table<'a>(
+    table: Table,
+    tracks: impl IntoIterator<Item = (usize, &'a Track)>,
+) -> String {
+    let mut rows = vec![COLUMNS.iter().map(|c| c.to_string()).collect::<Vec<_>>()];
+    for (position, track) in tracks {
+        let fields = TrackFields::of(track);
+        rows.push(vec![
+            position.to_string(),
+            fields.str_field(|t| t.title.as_deref()),
+            fields.str_field(|t| t.author.as_deref()),
+            track.duration_ms.map(|d| d.to_string()).unwrap_or_default(),
+            fields.str_field(|t| t.uri.as_deref()),
+            fields.str_field(Track::requester_name),
+        ]);
+    }
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = row.iter().map(|cell| escape_cell(table, cell)).collect();
+            cells.join(match table {
+                Table::Tsv => "\t",
+                Table::Csv => ",",
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape_cell(table: Table, cell: &str) -> String {
+    match table {
+        // TSV has no quoting; flatten the separators instead.
+        Table::Tsv => cell.replace(['\t', '\n', '\r'], " "),
+        Table::Csv if cell.contains([',', '"', '\n', '\r']) => {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        }
+        Table::Csv => cell.to_string(),
+    }
+}
@@ -0,0 +1,194 @@
+//! `[hooks]`: the user's own commands, run on playback events seen on a
+//! guild's event stream. Each gets the event and track in its environment:
+//!
+//! | Variable            | Value                                       |
+//! |---------------------|---------------------------------------------|
+//! | `JORIK_EVENT`       | `track_start`, `queue_empty` or `pause`     |
+//! | `JORIK_GUILD_ID`    | the guild it happened in                    |
+//! | `JORIK_TITLE`       | the track, when known; unset otherwise      |
+//! | `JORIK_AUTHOR`      |                                             |
+//! | `JORIK_URI`         |                                             |
+//! | `JORIK_DURATION_MS` |                                             |
+//! | `JORIK_REQUESTER`   | who queued it                               |
+
+use crate::api::{HookSettings, PlaybackState, QueueResponse, Track, WsEvent};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hook {
+    TrackStart,
+    QueueEmpty,
+    Pause,
+}
+
+impl Hook {
+    /// `JORIK_EVENT` for it.
+    pub fn event(self) -> &'static str {
+        match self {
+            Self::TrackStart => "track_start",
+            Self::QueueEmpty => "queue_empty",
+            Self::Pause => "pause",
+        }
+    }
+
+    fn command(self, settings: &HookSettings) -> Option<&str> {
+        match self {
+            Self::TrackStart => settings.on_track_start.as_deref(),
+            Self::QueueEmpty => settings.on_queue_empty.as_deref(),
+            Self::Pause => settings.on_pause.as_deref(),
+        }
+    }
+}
+
+/// What the stream has said about one guild so far. `None` is not known
+/// yet; a hook only runs on a change from a known state.
+#[derive(Default)]
+struct GuildState {
+    track: Option<Track>,
+    playing: Option<bool>,
+    paused: Option<bool>,
+}
+
+/// Follows events and runs the hooks they trigger.
+pub struct Hooks {
+    settings: HookSettings,
+    guilds: HashMap<String, GuildState>,
+    /// Pass the commands' output on (stdout to our stderr, which keeps
+    /// `jorik events` output clean); off under the TUI, which owns the screen
+    show_output: bool,
+}
+
+impl Hooks {
+    /// `None` when no hook is set.
+    pub fn new(settings: &HookSettings, show_output: bool) -> Option<Self> {
+        (!settings.is_empty()).then(|| Self {
+            settings: settings.clone(),
+            guilds: HashMap::new(),
+            show_output,
+        })
+    }
+
+    /// Take in a `/ws` event, running whichever hooks it triggers. Returns
+    /// a message for each command that could not be started.
+    pub fn observe(&mut self, event: &Value) -> Vec<String> {
+        let Ok(event) = WsEvent::deserialize(event) else {
+            return Vec::new();
+        };
+        let Some(guild_id) = event.guild_id.clone() else {
+            return Vec::new();
+        };
+        let state = self.guilds.entry(guild_id.clone()).or_default();
+        let mut fired = Vec::new();
+        match event.event_type.as_str() {
+            "track_start" => {
+                let track = event
+                    .data
+                    .as_ref()
+                    .and_then(|d| Track::deserialize(d.get("track").unwrap_or(d)).ok())
+                    .filter(|t| t.title.is_some());
+                state.track = track;
+                state.playing = Some(true);
+                state.paused = Some(false);
+                fired.push(Hook::TrackStart);
+            }
+            "queue_end" | "queue_empty" => {
+                if state.playing != Some(false) {
+                    fired.push(Hook::QueueEmpty);
+                }
+                state.playing = Some(false);
+            }
+            "initial_state" | "state_update" | "queue_update" | "player_update" => {
+                if let Some(data) = &event.data
+                    && let Ok(queue) = QueueResponse::from_json(data)
+                    && let Some(current) = queue.current
+                {
+                    let playing = current.is_some();
+                    if !playing && state.playing == Some(true) && queue.upcoming.is_none_or(|u| u.is_empty()) {
+                        fired.push(Hook::QueueEmpty);
+                    }
+                    if current.is_some() {
+                        state.track = current;
+                    }
+                    state.playing = Some(playing);
+                }
+                // Beside the data or, from some servers, inside it.
+                let paused = event
+                    .playback
+                    .map(|p| p.paused)
+                    .or_else(|| {
+                        let data = event.data.as_ref()?;
+                        match data.get("playback") {
+                            Some(playback) => PlaybackState::deserialize(playback).ok().map(|p| p.paused),
+                            None => data.get("paused")?.as_bool(),
+                        }
+                    });
+                if let Some(paused) = paused {
+                    if paused && state.paused == Some(false) && state.playing != Some(false) {
+                        fired.push(Hook::Pause);
+                    }
+                    state.paused = Some(paused);
+                }
+            }
+            _ => {}
+        }
+
+        let track = state.track.clone();
+        fired
+            .into_iter()
+            .filter_map(|hook| {
+                let command = hook.command(&self.settings)?;
+                self.run(command, hook, &guild_id, track.as_ref())
+                    .err()
+                    .map(|e| format!("{} hook `{}` did not start: {}", hook.event(), command, e))
+            })
+            .collect()
+    }
+
+    /// Start `command` in the background; nothing waits for it to finish.
+    fn run(&self, command: &str, hook: Hook, guild_id: &str, track: Option<&Track>) -> std::io::Result<()> {
+        let mut cmd = shell(command);
+        cmd.env("JORIK_EVENT", hook.event()).env("JORIK_GUILD_ID", guild_id);
+        let duration = track.and_then(|t| t.duration_ms).map(|ms| ms.to_string());
+        let fields = [
+            ("JORIK_TITLE", track.and_then(|t| t.title.as_deref())),
+            ("JORIK_AUTHOR", track.and_then(|t| t.author.as_deref())),
+            ("JORIK_URI", track.and_then(|t| t.uri.as_deref())),
+            ("JORIK_DURATION_MS", duration.as_deref()),
+            ("JORIK_REQUESTER", track.and_then(Track::requester_name)),
+        ];
+        for (name, value) in fields {
+            // Unset rather than inherited from whatever started jorik.
+            match value {
+                Some(value) => cmd.env(name, value),
+                None => cmd.env_remove(name),
+            };
+        }
+        cmd.stdin(Stdio::null());
+        if self.show_output {
+            cmd.stdout(std::io::stderr());
+        } else {
+            cmd.stdout(Stdio::null()).stderr(Stdio::null());
+        }
+        let mut child = cmd.spawn()?;
+        // Reaped off to the side so slow hooks hold nothing up.
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Message catalogs, keyed by the English source string (gettext style), so an
+/// untranslated message simply falls back to English.
+const CATALOGS: &[(&str, &str)] = &[("ru", include_str!("../locales/ru.po"))];
+
+/// Translations for the active language; empty for English.
+static CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Pick the language from the `language` setting, `JORIK_LANG`, or the usual
+/// POSIX locale variables, in that order. `auto` (the default) skips the setting.
+pub fn init(setting: &str) {
+    let requested = Some(setting.to_string())
+        .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("auto"))
+        .or_else(|| std::env::var("JORIK_LANG").ok())
+        .or_else(|| {
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|v| std::env::var(v).ok())
+                .find(|v| !v.is_empty())
+        })
+        .unwrap_or_default();
+    let code = language_code(&requested);
+    let catalog = CATALOGS
+        .iter()
+        .find(|(lang, _)| *lang == code)
+        .map(|(_, po)| parse_po(po))
+        .unwrap_or_default();
+    CATALOG.set(catalog).ok();
+}
+
+/// Languages with a catalog, plus English.
+pub fn available() -> impl Iterator<Item = &'static str> {
+    std::iter::once("en").chain(CATALOGS.iter().map(|(lang, _)| *lang))
+}
+
+/// `ru_RU.UTF-8` -> `ru`
+fn language_code(locale: &str) -> String {
+    locale
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Translate `msgid`, or return it unchanged when there is no translation.
+pub fn tr(msgid: &'static str) -> &'static str {
+    CATALOG
+        .get()
+        .and_then(|c| c.get(msgid))
+        .map(String::as_str)
+        .unwrap_or(msgid)
+}
+
+/// Substitute `{}` placeholders in order; `{{` and `}}` are literal braces.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('}')) => {
+                chars.next();
+                if let Some(arg) = args.next() {
+                    out.push_str(&arg.to_string());
+                }
+            }
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Minimal PO reader: single-line `msgid`/`msgstr` pairs with C escapes.
+fn parse_po(po: &str) -> HashMap<String, String> {
+    let mut catalog = HashMap::new();
+    let mut msgid = None;
+    for line in po.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("msgid ") {
+            msgid = Some(unquote(rest));
+        } else if let Some(rest) = line.strip_prefix("msgstr ")
+            && let Some(id) = msgid.take()
+        {
+            let msgstr = unquote(rest);
+            if !id.is_empty() && !msgstr.is_empty() {
+                catalog.insert(id, msgstr);
+            }
+        }
+    }
+    catalog
+}
+
+fn unquote(s: &str) -> String {
+    let inner = s.trim().trim_start_matches('"').trim_end_matches('"');
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// `format!`-like helper over a translated template: `t!("Skipped {}", title)`.
+#[macro_export]
+macro_rules! t {
+    ($msgid:literal $(,)?) => {
+        $crate::i18n::tr($msgid).to_string()
+    };
+    ($msgid:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::tr($msgid),
+            &[$(&$arg as &dyn ::std::fmt::Display),+],
+        )
+    };
+}
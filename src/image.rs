@@ -3,7 +3,8 @@
 //! This module contains all of the logic related to detecting terminal image
 //! protocols (iTerm2, Kitty, Sixel), encoding the embedded `logo.png` into
 //! the appropriate sequence, and best-effort printing of the logo when the
-//! CLI is invoked with `-V` / `--version`.
+//! CLI is invoked with `-V` / `--version`. It also loads track artwork for
+//! the TUI.
 
 use ::image::{DynamicImage, imageops::FilterType};
 use anyhow::{Context, Result};
@@ -289,6 +290,56 @@ pub fn try_print_logo() -> Result<bool> {
     }
 }
 
+/// Picker used to draw images inside the TUI.
+///
+/// Must be called after ratatui has entered the alternate screen and before
+/// events are read. The terminal is only queried when the environment hints
+/// at graphics support: a terminal that never answers stalls startup and
+/// swallows the first keypress. Everything else gets coloured half-blocks.
+pub fn tui_picker() -> Picker {
+    if !(detect_iterm2() || detect_kitty() || detect_sixel()) {
+        return Picker::halfblocks();
+    }
+    Picker::from_query_stdio().unwrap_or_else(|_| Picker::halfblocks())
+}
+
+/// Download and decode a track's artwork image.
+pub async fn fetch_artwork(client: &reqwest::Client, url: &str) -> Result<DynamicImage> {
+    let bytes = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    ::image::load_from_memory(&bytes).context("decoding artwork")
+}
+
+/// Heatmap colour stops from silence to full level, inferno style.
+const HEATMAP: [[u8; 3]; 5] = [[0, 0, 4], [87, 16, 110], [188, 55, 84], [249, 142, 9], [252, 255, 164]];
+/// Pixel rows per frequency bin, so the usual 64 bins give a readable height.
+const HEATMAP_BIN_PX: u32 = 4;
+
+/// Render spectrogram frames as a heatmap: one column per frame, low
+/// frequencies at the bottom.
+pub fn spectrogram_heatmap(frames: &[Vec<u8>]) -> ::image::RgbImage {
+    let bins = frames.iter().map(Vec::len).max().unwrap_or(0) as u32;
+    let height = bins * HEATMAP_BIN_PX;
+    ::image::RgbImage::from_fn(frames.len() as u32, height, |x, y| {
+        let bin = (bins - 1 - y / HEATMAP_BIN_PX) as usize;
+        let level = frames[x as usize].get(bin).copied().unwrap_or(0) as f32 / 255.0;
+        ::image::Rgb(heatmap_color(level))
+    })
+}
+
+fn heatmap_color(level: f32) -> [u8; 3] {
+    let pos = level.clamp(0.0, 1.0) * (HEATMAP.len() - 1) as f32;
+    let i = (pos as usize).min(HEATMAP.len() - 2);
+    let t = pos - i as f32;
+    let (a, b) = (HEATMAP[i], HEATMAP[i + 1]);
+    [0, 1, 2].map(|c| (a[c] as f32 + (b[c] as f32 - a[c] as f32) * t).round() as u8)
+}
+
 /// Print image using iTerm2 inline image escape sequence (base64 PNG).
 fn encode_iterm2(img: &DynamicImage) -> Result<String> {
     // Re-encode the (possibly downscaled) image to PNG before sending to terminal.
@@ -0,0 +1,72 @@
+use crate::exit::ExitCode;
+use crate::i18n;
+use colored::Colorize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// What a running command wants said or undone if Ctrl-C cuts it short.
+struct Partial {
+    /// How far it got, e.g. `40 of 120 tracks queued`
+    progress: Option<String>,
+    /// A file still being written, useless if left half-done
+    file: Option<PathBuf>,
+}
+
+static PARTIAL: Mutex<Partial> = Mutex::new(Partial {
+    progress: None,
+    file: None,
+});
+
+/// Set while the TUI has the terminal in raw mode on the alternate screen.
+static TERMINAL_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Watch for Ctrl-C for the rest of the process. It runs on its own task so
+/// a command blocked on a prompt or child process can still be stopped.
+pub fn install() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            exit();
+        }
+    });
+}
+
+pub fn set_progress(progress: Option<String>) {
+    if let Ok(mut partial) = PARTIAL.lock() {
+        partial.progress = progress;
+    }
+}
+
+pub fn set_partial_file(file: Option<PathBuf>) {
+    if let Ok(mut partial) = PARTIAL.lock() {
+        partial.file = file;
+    }
+}
+
+pub fn set_terminal_taken(taken: bool) {
+    TERMINAL_TAKEN.store(taken, Ordering::Relaxed);
+}
+
+/// Put the terminal back, drop the half-written file, say how far the
+/// command got and exit with `ExitCode::Interrupted`. Requests still in
+/// flight go with the process.
+fn exit() -> ! {
+    if TERMINAL_TAKEN.load(Ordering::Relaxed) {
+        crate::tui::restore_terminal();
+    }
+    crate::progress::halt();
+    let (progress, file) = match PARTIAL.lock() {
+        Ok(mut partial) => (partial.progress.take(), partial.file.take()),
+        Err(_) => (None, None),
+    };
+    if let Some(file) = &file {
+        std::fs::remove_file(file).ok();
+    }
+    let message = match &progress {
+        Some(progress) => format!("{} ({})", i18n::tr("Interrupted"), progress),
+        None => i18n::tr("Interrupted").to_string(),
+    };
+    eprintln!("\n{}", message.yellow());
+    crate::logfile::event("interrupted", serde_json::json!({"progress": progress}));
+    std::process::exit(ExitCode::Interrupted as i32);
+}
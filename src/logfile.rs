@@ -0,0 +1,62 @@
+use serde_json::{Value, json};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Rotate once the log grows past this size.
+const MAX_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept next to the log: `jorik.log.1` (newest) .. `jorik.log.3`.
+const KEEP: usize = 3;
+
+/// Open log file, set once from `--log-file`.
+static LOG: OnceLock<Mutex<File>> = OnceLock::new();
+
+/// Default location when `--log-file` is given without a path.
+pub fn default_path() -> Option<PathBuf> {
+    crate::api::config_root().map(|p| p.join("jorik.log"))
+}
+
+/// Start appending JSON lines to `path`, rotating it first if it is too big.
+pub fn init(path: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_BYTES) {
+        rotate(path);
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    LOG.set(Mutex::new(file)).ok();
+    Ok(())
+}
+
+fn rotate(path: &Path) {
+    let numbered = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+    for n in (1..KEEP).rev() {
+        fs::rename(numbered(n), numbered(n + 1)).ok();
+    }
+    fs::rename(path, numbered(1)).ok();
+}
+
+pub fn enabled() -> bool {
+    LOG.get().is_some()
+}
+
+/// Append one `{"ts", "kind", ...fields}` line. Logging never fails the caller.
+pub fn event(kind: &str, fields: Value) {
+    let Some(log) = LOG.get() else {
+        return;
+    };
+    let mut line = json!({
+        "ts": chrono::Local::now().to_rfc3339(),
+        "kind": kind,
+    });
+    if let (Some(line), Value::Object(fields)) = (line.as_object_mut(), fields) {
+        line.extend(fields);
+    }
+    if let Ok(mut file) = log.lock() {
+        writeln!(file, "{}", line).ok();
+    }
+}
@@ -1,36 +1,117 @@
 use anyhow::{Context, Result, bail};
-use clap::{Parser, Subcommand};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use colored::Colorize;
-use dirs::config_dir;
+use futures_util::{SinkExt, StreamExt};
 use open::that;
 use reqwest::{Client, Url};
 use semver::Version;
 use serde_json::Value;
-use std::fs::{self, File};
+use std::fs::File;
 use std::io::{self, Write};
 use std::process::Command;
-use std::time::Duration;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
-use tokio::time::timeout;
+use tokio::time::{timeout, timeout_at};
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+/// Set once from `--quiet`; read by the output helpers below.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Set once from `--verbose`; makes `send` trace requests and responses to stderr.
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Spinners only make sense when nothing else is being written to stderr.
+fn spinners_enabled() -> bool {
+    !is_quiet() && !VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Set once from `--dry-run`; makes `send` print the request instead.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Set once from `--raw`; response bodies are printed verbatim, skipping `summarize`.
+static RAW: AtomicBool = AtomicBool::new(false);
+
+/// Set once from stdin being a terminal and no `--yes`; lets a 401 offer a fresh login.
+static INTERACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Set once from `--retries` or the `retries` setting; used by `send_with_retries`.
+static RETRIES: AtomicU32 = AtomicU32::new(0);
+
+/// Like `t!`, prefixed with a decorative icon that `--quiet` drops.
+macro_rules! deco {
+    ($icon:expr, $($arg:tt)*) => {{
+        let text = t!($($arg)*);
+        if is_quiet() {
+            text
+        } else {
+            format!("{} {}", $icon, text)
+        }
+    }};
+}
+
+/// Like `println!` over a translated template, prefixed with a decorative icon that `--quiet` drops.
+macro_rules! status {
+    ($icon:expr, $($arg:tt)*) => {
+        println!("{}", deco!($icon, $($arg)*))
+    };
+}
 
 mod api;
 mod ascii;
+mod bar;
+mod checklist;
+mod clipboard;
+mod daemon;
+mod exit;
+mod format;
+mod httpd;
+mod hooks;
+mod i18n;
 mod image;
+mod interrupt;
+mod logfile;
+mod mpd;
+mod notify;
+mod overlay;
+mod pending;
+mod playlist;
+mod presence;
+mod progress;
+mod qr;
+mod radio;
+mod scrobble;
+mod spotify;
 mod tui;
+mod youtube;
 
 use api::*;
+use jorik_core::tls;
+use exit::ExitCode;
 
 /// CLI to interact with the Jorik webhook server.
 #[derive(Parser, Debug)]
-#[command(name = "jorik CLI", author, version, about)]
+#[command(
+    name = "jorik CLI",
+    author,
+    version,
+    about,
+    after_help = "Exit codes: 0 ok, 1 failure, 2 bad arguments, 3 auth error, 4 network error, 5 rejected by server, 6 login required (token no longer accepted)"
+)]
 struct Cli {
     /// Base URL of the webhook server
     #[arg(
         long,
         global = true,
         env = "JORIK_BASE_URL",
-        default_value = "https://jorik.xserv.pp.ua"
+        default_value = DEFAULT_BASE_URL
     )]
     base_url: String,
 
@@ -38,6 +119,77 @@ struct Cli {
     #[arg(long, global = true, env = "JORIK_TOKEN")]
     token: Option<String>,
 
+    /// Print only the essential result: no emoji, hints or update prompts
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Template for track output of nowplaying, queue, history and status,
+    /// e.g. "{title} — {author} ({duration})"
+    #[arg(long, global = true, value_name = "TEMPLATE", long_help = format!(
+        "Template for track output of nowplaying, queue, history and status, e.g. \"{{title}} — {{author}} ({{duration}})\".\nPlaceholders: {}",
+        format::PLACEHOLDERS
+    ))]
+    format: Option<String>,
+
+    /// Never prompt; take the default answer (also implied when stdin is not a terminal)
+    #[arg(short = 'y', long, visible_alias = "non-interactive", global = true)]
+    yes: bool,
+
+    /// Don't check GitHub for a newer release (see also `update_check` in config.toml)
+    #[arg(long, global = true)]
+    no_update_check: bool,
+
+    /// Trace HTTP requests and responses (token redacted) to stderr
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Print the response body exactly as received (status goes to stderr)
+    #[arg(long, global = true, conflicts_with = "format")]
+    raw: bool,
+
+    /// Print the request that would be sent (without the token) and exit
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Directory for auth.json and config.toml instead of the platform default
+    #[arg(long, global = true, env = "JORIK_CONFIG_DIR", value_name = "DIR")]
+    config_dir: Option<std::path::PathBuf>,
+
+    /// Never read or write auth.json/config.toml; rely only on flags and env vars
+    #[arg(
+        long,
+        global = true,
+        env = "JORIK_STATELESS",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    no_config: bool,
+
+    /// Append JSON-lines logs (requests, WS events, errors) to PATH [default: jorik.log in the config dir]
+    ///
+    /// Pass the path as `--log-file=PATH`; a bare `--log-file` uses the default location.
+    #[arg(
+        long,
+        global = true,
+        env = "JORIK_LOG_FILE",
+        value_name = "PATH",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ""
+    )]
+    log_file: Option<String>,
+
+    /// Retry transient failures (network errors, 5xx) this many times [default: `retries` in config.toml]. Rate limits (429) are always waited out
+    #[arg(long, global = true, value_name = "N")]
+    retries: Option<u32>,
+
+    /// Also trust the CA certificates in this PEM file, e.g. for a self-hosted server with an internal CA
+    #[arg(long, global = true, env = "JORIK_CACERT", value_name = "PEM")]
+    cacert: Option<std::path::PathBuf>,
+
+    /// Don't verify TLS certificates at all. Unsafe: anyone on the network path can read your token
+    #[arg(long, global = true)]
+    insecure: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -46,11 +198,50 @@ struct Cli {
 enum Commands {
     /// Check server health
     Health,
+    /// Measure HTTP and WebSocket latency to the server
+    Ping {
+        /// Number of samples to average
+        #[arg(long, short = 'n', default_value = "5")]
+        count: usize,
+        /// Guild ID to subscribe to when timing the WebSocket (optional)
+        #[arg(long)]
+        guild_id: Option<String>,
+    },
+    /// Stream a guild's live events (track_start, queue_update, state_update, ...) as JSON lines
+    #[command(after_help = "Each line is one event exactly as the server sent it, e.g.\n  jorik events --type track_start | jq -r '.data.title'")]
+    Events {
+        /// Guild ID to follow [default: `default_guild_id` in config.toml]
+        #[arg(long)]
+        guild_id: Option<String>,
+        /// Only pass on these event types (comma-separated or repeated)
+        #[arg(long = "type", value_name = "TYPE", value_delimiter = ',')]
+        types: Vec<String>,
+        /// Exit when the connection drops instead of reconnecting
+        #[arg(long)]
+        no_reconnect: bool,
+        /// Don't run the commands in `[hooks]`
+        #[arg(long)]
+        no_hooks: bool,
+    },
     /// Enqueue audio to play
     Play {
         /// Query/URL to play
         #[arg(num_args = 1..)]
         query: Vec<String>,
+        /// Enqueue one track per line of FILE ("-" for stdin) instead, in a single
+        /// batch when the server supports it
+        #[arg(long, value_name = "FILE", conflicts_with = "query")]
+        from: Option<std::path::PathBuf>,
+        /// Read a Spotify playlist or album link's tracks here and enqueue each
+        /// as a search, for servers that only take single tracks; needs
+        /// client_id and client_secret under [spotify] in config.toml
+        #[arg(long, conflicts_with = "from")]
+        expand: bool,
+        /// Choose which videos of a YouTube playlist link (or, with --expand, tracks
+        /// of a Spotify one) to enqueue from a checklist; needs api_key under
+        /// [youtube] in config.toml. With stdin piped, reads track numbers ("1-3,7")
+        #[arg(long, conflicts_with = "from")]
+        pick: bool,
         /// Guild ID (optional)
         #[arg(long)]
         guild_id: Option<String>,
@@ -85,6 +276,31 @@ enum Commands {
         #[arg(long)]
         avatar_url: Option<String>,
     },
+    /// Play an internet radio station by name, or any stream URL
+    #[command(after_help = "Add your own stations to config.toml:\n  [radio]\n  mystation = \"https://example.com/stream.mp3\"")]
+    Radio {
+        /// Station name (see --list) or stream URL
+        #[arg(required_unless_present = "list")]
+        station: Option<String>,
+        /// List the stations instead
+        #[arg(long, conflicts_with = "station")]
+        list: bool,
+        /// Play the URL without first checking that it is an audio stream
+        #[arg(long)]
+        no_check: bool,
+        /// Also turn on 24/7 mode, so the bot stays in the channel if the stream drops
+        #[arg(long)]
+        stay: bool,
+        /// Guild ID (optional)
+        #[arg(long)]
+        guild_id: Option<String>,
+        /// Voice channel ID (optional)
+        #[arg(long)]
+        channel_id: Option<String>,
+        /// User ID (optional)
+        #[arg(long)]
+        user_id: Option<String>,
+    },
     /// Skip the current track
     Skip {
         #[arg(long)]
@@ -116,6 +332,26 @@ enum Commands {
         limit: usize,
         #[arg(long, default_value = "0")]
         offset: usize,
+        /// Fetch the whole queue, page by page, instead of one --limit window
+        #[arg(long, conflicts_with_all = ["limit", "offset"])]
+        all: bool,
+        /// Print one row per track (with a header row) instead of a list
+        #[arg(long, value_enum, conflicts_with = "format")]
+        output: Option<format::Table>,
+    },
+    /// Show recently played tracks
+    History {
+        #[arg(long)]
+        guild_id: Option<String>,
+        #[arg(long)]
+        user_id: Option<String>,
+        #[arg(long, default_value = "10")]
+        limit: usize,
+        #[arg(long, default_value = "0")]
+        offset: usize,
+        /// Print one row per track (with a header row) instead of a list
+        #[arg(long, value_enum, conflicts_with = "format")]
+        output: Option<format::Table>,
     },
     /// Clear the queue
     Clear {
@@ -131,6 +367,65 @@ enum Commands {
         #[arg(long)]
         user_id: Option<String>,
     },
+    /// Serve a small REST API on localhost that forwards to the server with the saved login
+    #[command(after_help = "Endpoints: GET /now-playing, GET /queue?limit=&offset=, POST /play {\"query\": ...}, POST /skip; each takes ?guild_id=. e.g.\n  curl -X POST localhost:6680/play -d '{\"query\": \"never gonna give you up\"}'")]
+    Daemon {
+        /// Address to listen on; anyone who can reach it acts as you
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:6680")]
+        listen: String,
+        /// Guild ID for requests without ?guild_id= [default: `default_guild_id` in config.toml]
+        #[arg(long)]
+        guild_id: Option<String>,
+        /// Voice channel to join on /play [default: the guild's `channel_id` or `default_channel_id`]
+        #[arg(long)]
+        channel_id: Option<String>,
+        /// User ID sent with each request [default: `default_user_id` in config.toml]
+        #[arg(long)]
+        user_id: Option<String>,
+        /// Don't follow the guild's events to run the commands in `[hooks]`
+        #[arg(long)]
+        no_hooks: bool,
+    },
+    /// Speak the MPD protocol locally so MPD clients (mpc, ncmpcpp, ...) can control a guild's player
+    #[command(after_help = "The playlist is the current track followed by the queue. Try it with\n  jorik mpd-bridge --guild-id 123 &\n  mpc status")]
+    MpdBridge {
+        /// Address to listen on; anyone who can reach it controls the player
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:6600")]
+        listen: String,
+        /// Guild ID to control [default: `default_guild_id` in config.toml]
+        #[arg(long)]
+        guild_id: Option<String>,
+    },
+    /// Serve a now-playing page (track, artwork, progress) to add to OBS as a browser source
+    #[command(after_help = "In OBS, add a Browser source with URL http://localhost:8090/ (width 480, height 120).\nThe page's background is transparent; restyle it with the source's Custom CSS.")]
+    Overlay {
+        /// Port to serve the page on
+        #[arg(long, default_value = "8090")]
+        port: u16,
+        /// Address to bind; the page shows what's playing to anyone who can reach it
+        #[arg(long, value_name = "IP", default_value = "127.0.0.1")]
+        bind: std::net::IpAddr,
+        /// Guild ID to show [default: `default_guild_id` in config.toml]
+        #[arg(long)]
+        guild_id: Option<String>,
+    },
+    /// Print the current track for a status bar (waybar, polybar, i3blocks)
+    #[command(after_help = "Waybar (~/.config/waybar/config):\n  \"custom/jorik\": {\"exec\": \"jorik status --bar waybar --follow\", \"return-type\": \"json\"}\n\
+Polybar:\n  [module/jorik]\n  type = custom/script\n  exec = jorik status --bar polybar --follow\n  tail = true\n\
+i3blocks:\n  [jorik]\n  command=jorik status --bar i3blocks --follow\n  interval=persist")]
+    Status {
+        /// Which bar's output to print
+        #[arg(long, value_enum)]
+        bar: bar::Bar,
+        /// Keep running, printing a new line each time the track or its state changes
+        #[arg(long)]
+        follow: bool,
+        /// Guild ID [default: `default_guild_id` in config.toml]
+        #[arg(long)]
+        guild_id: Option<String>,
+        #[arg(long)]
+        user_id: Option<String>,
+    },
     /// Set loop mode (off, track, queue)
     Loop {
         mode: String,
@@ -156,10 +451,10 @@ enum Commands {
         #[arg(long)]
         user_id: Option<String>,
     },
-    /// Apply audio filters (clear, bassboost, nightcore, vaporwave, 8d, soft, tremolo, vibrato, karaoke)
+    /// Apply audio filters (clear, bassboost, nightcore, vaporwave, 8d, soft, tremolo, vibrato, karaoke, or a saved preset)
     Filter {
-        /// Filter style
-        style: String,
+        /// Filter style [default: `filters` for the guild in config.toml]
+        style: Option<String>,
         #[arg(long)]
         guild_id: Option<String>,
         #[arg(long)]
@@ -170,8 +465,28 @@ enum Commands {
         #[command(subcommand)]
         command: AuthSubcommand,
     },
+    /// Ask the server who the current token belongs to
+    Whoami,
+    /// View or change config.toml (default IDs, retries, update check, ...)
+    Config {
+        #[command(subcommand)]
+        command: ConfigSubcommand,
+    },
+    /// Play commands saved while the server was unreachable (`offline_queue` in config.toml)
+    Pending {
+        #[command(subcommand)]
+        command: PendingSubcommand,
+    },
+    /// Scrobble what the TUI plays to Last.fm or ListenBrainz
+    Scrobble {
+        #[command(subcommand)]
+        command: ScrobbleSubcommand,
+    },
     /// Get lyrics for current track
     Lyrics {
+        /// Look up lyrics for any song ("artist - title") instead of the current track
+        #[arg(long)]
+        query: Option<String>,
         #[arg(long)]
         guild_id: Option<String>,
         #[arg(long)]
@@ -181,6 +496,9 @@ enum Commands {
     Tui {
         #[arg(long)]
         guild_id: Option<String>,
+        /// Also open a tab for this guild (repeatable); switch tabs with [ and ] or Alt+1-9
+        #[arg(long = "tab", value_name = "GUILD_ID")]
+        tabs: Vec<String>,
         #[arg(long)]
         user_id: Option<String>,
     },
@@ -189,11 +507,224 @@ enum Commands {
 #[derive(Subcommand, Debug)]
 enum AuthSubcommand {
     /// Login via browser and capture token, username and avatar
-    Login,
+    Login {
+        /// Save the login as a named profile (bound to the current base URL) and make it active
+        #[arg(long)]
+        profile: Option<String>,
+        /// Print a code to enter on another device instead of waiting for a browser callback (for SSH and headless machines)
+        #[arg(long, conflicts_with = "manual")]
+        device: bool,
+        /// Paste a token by hand; it is checked against the server before it is saved
+        #[arg(long)]
+        manual: bool,
+        /// Wait for a browser callback even where no local browser was detected (over SSH, no display)
+        #[arg(long, conflicts_with_all = ["device", "manual"])]
+        browser: bool,
+        /// Listen for the browser callback on this port instead of a random one
+        #[arg(long, conflicts_with_all = ["device", "manual"])]
+        port: Option<u16>,
+        /// Host the browser is redirected to for the callback (e.g. a forwarded hostname); the listener stays on loopback unless --bind says otherwise
+        #[arg(long, value_name = "HOST", conflicts_with_all = ["device", "manual"])]
+        callback_host: Option<String>,
+        /// Address the callback listener binds [default: loopback]; anything else accepts connections from other machines
+        #[arg(long, value_name = "IP", conflicts_with_all = ["device", "manual"])]
+        bind: Option<std::net::IpAddr>,
+    },
     /// Sign out and remove the saved auth data from device
     Signout,
     /// Show current saved auth info
     Info,
+    /// Exchange the saved token for a fresh one
+    Refresh,
+    /// Switch the active auth profile
+    Switch {
+        /// Profile name (see `jorik auth profiles`)
+        name: String,
+    },
+    /// List saved auth profiles
+    Profiles,
+    /// Print the raw bearer token for use in scripts
+    Token {
+        /// Actually print the token (required to avoid leaking it by accident)
+        #[arg(long)]
+        show: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigSubcommand {
+    /// Print all settings
+    Show,
+    /// Print one setting
+    Get { key: String },
+    /// Change a setting, e.g. `jorik config set default_guild_id 1234`
+    Set { key: String, value: String },
+    /// Reset a setting to its default
+    Unset { key: String },
+    /// Print every effective setting and where its value came from
+    Sources,
+    /// Write settings and saved logins to FILE ("-" for stdout) to set up another machine
+    Export {
+        file: std::path::PathBuf,
+        /// Leave the saved tokens, scrobbling keys, Spotify client secret and YouTube API key out
+        #[arg(long)]
+        no_secrets: bool,
+    },
+    /// Replace settings with those from an exported FILE and add its logins, keeping any saved under the same name
+    Import {
+        file: std::path::PathBuf,
+        /// Skip the logins in the file, importing only settings
+        #[arg(long)]
+        no_secrets: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum PendingSubcommand {
+    /// Show saved commands, oldest first
+    List,
+    /// Send saved commands to the server now, in order
+    Flush,
+    /// Throw away every saved command
+    Clear,
+}
+
+#[derive(Subcommand, Debug)]
+enum ScrobbleSubcommand {
+    /// Connect an account and scrobble to it from now on
+    Enable {
+        #[command(subcommand)]
+        service: ScrobbleService,
+    },
+    /// Show which account scrobbles go to
+    Status,
+    /// Stop scrobbling and remove the saved keys
+    Disable,
+}
+
+#[derive(Subcommand, Debug)]
+enum ScrobbleService {
+    /// Last.fm, through your own API account (last.fm/api/account/create);
+    /// asks for its shared secret, then for approval in the browser
+    Lastfm {
+        /// The API account's key
+        #[arg(long)]
+        api_key: String,
+        /// Another server with the Last.fm API, e.g. https://libre.fm/2.0/
+        #[arg(long, value_name = "URL")]
+        api_url: Option<String>,
+    },
+    /// ListenBrainz; asks for the user token from listenbrainz.org/settings
+    Listenbrainz {
+        /// API root of a self-hosted server
+        #[arg(long, value_name = "URL")]
+        api_url: Option<String>,
+    },
+}
+
+impl Commands {
+    /// Whether `--dry-run` can describe the command. Ones that work over the
+    /// WebSocket, a browser or the terminal rather than requests to the
+    /// server would run for real.
+    fn dry_runnable(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Ping { .. }
+                | Commands::Events { .. }
+                | Commands::Daemon { .. }
+                | Commands::MpdBridge { .. }
+                | Commands::Overlay { .. }
+                | Commands::Status { follow: true, .. }
+                | Commands::Tui { .. }
+                | Commands::Auth {
+                    command: AuthSubcommand::Login { device: false, manual: false, .. }
+                }
+        )
+    }
+
+    /// Fill ID flags the user left out from the defaults in settings.
+    fn apply_id_defaults(&mut self, settings: &Settings) {
+        let (guild_id, channel_id, user_id) = match self {
+            Commands::Play {
+                guild_id,
+                channel_id,
+                user_id,
+                ..
+            }
+            | Commands::Turip {
+                guild_id,
+                channel_id,
+                user_id,
+                ..
+            }
+            | Commands::Radio {
+                guild_id,
+                channel_id,
+                user_id,
+                ..
+            }
+            | Commands::Daemon {
+                guild_id,
+                channel_id,
+                user_id,
+                ..
+            } => (Some(guild_id), Some(channel_id), Some(user_id)),
+            Commands::Skip { guild_id, user_id }
+            | Commands::Stop { guild_id, user_id }
+            | Commands::Pause { guild_id, user_id }
+            | Commands::Clear { guild_id, user_id }
+            | Commands::NowPlaying { guild_id, user_id }
+            | Commands::Status {
+                guild_id, user_id, ..
+            }
+            | Commands::Shuffle { guild_id, user_id }
+            | Commands::Tui { guild_id, user_id, .. }
+            | Commands::Queue {
+                guild_id, user_id, ..
+            }
+            | Commands::History {
+                guild_id, user_id, ..
+            }
+            | Commands::Loop {
+                guild_id, user_id, ..
+            }
+            | Commands::TwentyFourSeven {
+                guild_id, user_id, ..
+            }
+            | Commands::Filter {
+                guild_id, user_id, ..
+            }
+            | Commands::Lyrics {
+                guild_id, user_id, ..
+            } => (Some(guild_id), None, Some(user_id)),
+            Commands::Ping { guild_id, .. }
+            | Commands::Events { guild_id, .. }
+            | Commands::MpdBridge { guild_id, .. }
+            | Commands::Overlay { guild_id, .. } => (Some(guild_id), None, None),
+            _ => return,
+        };
+        let guild_id = guild_id.map(|arg| {
+            if arg.is_none() {
+                arg.clone_from(&settings.default_guild_id);
+            }
+            &*arg
+        });
+        // A guild's own channel beats the global default.
+        let guild = settings.guild(guild_id.and_then(|g| g.as_deref()));
+        let default_channel = guild
+            .and_then(|g| g.channel_id.as_ref())
+            .or(settings.default_channel_id.as_ref());
+        for (arg, default) in [
+            (channel_id, default_channel),
+            (user_id, settings.default_user_id.as_ref()),
+        ] {
+            if let Some(arg) = arg
+                && arg.is_none()
+            {
+                *arg = default.cloned();
+            }
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -250,7 +781,28 @@ pub async fn check_for_updates(client: &Client) -> Option<(String, Vec<GiteaAsse
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    interrupt::install();
+    if let Err(err) = run().await {
+        if exit::is_dry_run(&err) {
+            return;
+        }
+        let code = exit::code_for(&err);
+        logfile::event(
+            "error",
+            serde_json::json!({"message": format!("{:#}", err), "exit_code": code as i32}),
+        );
+        if !exit::is_reported(&err) {
+            eprintln!("{} {:#}", i18n::tr("Error:").red().bold(), err);
+        }
+        if let Some(ids) = FAILED_REQUEST.lock().ok().and_then(|ids| ids.clone()) {
+            eprintln!("{}", ids.to_string().dimmed());
+        }
+        std::process::exit(code as i32);
+    }
+}
+
+async fn run() -> Result<()> {
     {
         let args: Vec<_> = std::env::args_os().collect();
         let mut want_version = false;
@@ -280,25 +832,88 @@ async fn main() -> Result<()> {
         }
     }
 
-    let mut cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // A --base-url or JORIK_BASE_URL wins even when it names the default server.
+    let base_url_given = matches.value_source("base_url").is_some_and(|s| s != ValueSource::DefaultValue);
+    QUIET.store(cli.quiet, Ordering::Relaxed);
+    VERBOSE.store(cli.verbose, Ordering::Relaxed);
+    DRY_RUN.store(cli.dry_run, Ordering::Relaxed);
+    RAW.store(cli.raw, Ordering::Relaxed);
+    format::set_template(cli.format.clone());
+    api::set_stateless(cli.no_config);
+    api::set_config_dir(cli.config_dir.clone());
+    if let Some(path) = &cli.log_file {
+        let path = if path.is_empty() {
+            logfile::default_path().context("no config directory for the log file; pass a path")?
+        } else {
+            std::path::PathBuf::from(path)
+        };
+        logfile::init(&path).with_context(|| format!("opening log file {}", path.display()))?;
+        logfile::event(
+            "start",
+            serde_json::json!({"version": env!("CARGO_PKG_VERSION"), "command": format!("{:?}", cli.command)}),
+        );
+    }
     
-    let settings = api::load_settings();
+    let file_settings = api::read_settings();
+    let mut settings = api::apply_env_overrides(file_settings.as_ref().ok().cloned().flatten().unwrap_or_default())
+        .map_err(|e| exit::fail(ExitCode::Usage, format!("{:#}", e)))?;
+    i18n::init(&settings.language);
+    if cli.dry_run && !cli.command.dry_runnable() {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            t!("--dry-run only describes requests to the server; this command would run for real"),
+        ));
+    }
+    // clap only sees the conflict when --format comes after the subcommand.
+    if cli.format.is_some()
+        && matches!(
+            cli.command,
+            Commands::Queue { output: Some(_), .. } | Commands::History { output: Some(_), .. }
+        )
+    {
+        return Err(exit::fail(ExitCode::Usage, t!("--output and --format can't be used together")));
+    }
+    if let Err(e) = &file_settings {
+        eprintln!("{}", deco!("⚠️".yellow(), "Ignoring the config file: {}", format!("{:#}", e)));
+    }
+    if let Err(e) = api::read_auth_store() {
+        eprintln!("{}", deco!("⚠️".yellow(), "Ignoring the saved logins: {}", format!("{:#}", e)));
+    }
+    settings.update_check &= !cli.no_update_check;
+    cli.command.apply_id_defaults(&settings);
+    RETRIES.store(cli.retries.unwrap_or(settings.retries), Ordering::Relaxed);
+    tls::configure(cli.cacert.as_deref(), cli.insecure)
+        .map_err(|e| exit::fail(ExitCode::Usage, format!("{:#}", e)))?;
+    if cli.insecure {
+        // Not silenced by --quiet: this should never be on by accident.
+        eprintln!(
+            "{}",
+            i18n::tr("⚠️ TLS certificate verification is disabled (--insecure). Your token and traffic can be read or changed by anyone on the network path.")
+                .red()
+                .bold()
+        );
+    }
     
-    if cli.base_url == "https://jorik.xserv.pp.ua" && settings.base_url != "https://jorik.xserv.pp.ua" {
-        cli.base_url = settings.base_url.clone();
+    if !base_url_given {
+        if let Some(profile_url) = load_auth().and_then(|a| a.base_url) {
+            cli.base_url = profile_url;
+        } else if settings.base_url != DEFAULT_BASE_URL {
+            cli.base_url = settings.base_url.clone();
+        }
     }
     
-    let client = Client::builder()
-        .user_agent("jorik-cli")
-        .timeout(Duration::from_secs(10))
-        .build()
-        .context("building HTTP client")?;
+    let user_agent = if matches!(cli.command, Commands::Tui { .. }) { "jorik-cli-tui" } else { "jorik-cli" };
+    let client = api::http_client(user_agent).context("building HTTP client")?;
 
-    if let Commands::Tui { guild_id, user_id } = cli.command {
+    if let Commands::Tui { guild_id, tabs, user_id } = cli.command {
         if let Some((latest, assets)) = tui::run(
+            client.clone(),
             settings,
             cli.token.or_else(load_token),
             guild_id,
+            tabs,
             user_id
         ).await? {
              return trigger_update(&client, &latest, &assets).await;
@@ -306,15 +921,49 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Commands whose stdout is meant to be captured by scripts skip the update notice,
+    // as does anything piped or redirected.
+    let wants_update_check = settings.update_check
+        && !cli.quiet
+        && atty::is(atty::Stream::Stdout)
+        && !matches!(
+            cli.command,
+            Commands::Auth {
+                command: AuthSubcommand::Token { .. }
+            } | Commands::Events { .. }
+        );
     let update_client = client.clone();
-    let update_check = tokio::spawn(async move { check_for_updates(&update_client).await });
+    let update_check = tokio::spawn(async move {
+        if wants_update_check {
+            check_for_updates(&update_client).await
+        } else {
+            None
+        }
+    });
 
     let token = cli.token.clone().or_else(load_token);
+    let interactive = !cli.yes && atty::is(atty::Stream::Stdin);
+    INTERACTIVE.store(interactive, Ordering::Relaxed);
 
     match cli.command {
         Commands::Health => health(&client, &cli.base_url).await?,
+        Commands::Ping { count, guild_id } => {
+            ping(&client, &cli.base_url, token.as_deref(), guild_id, count).await?
+        }
+        Commands::Events {
+            guild_id,
+            types,
+            no_reconnect,
+            no_hooks,
+        } => {
+            let hooks = hooks::Hooks::new(&settings.hooks, true).filter(|_| !no_hooks);
+            events(&cli.base_url, token.as_deref(), guild_id, &types, !no_reconnect, hooks).await?
+        }
         Commands::Play {
             query,
+            from,
+            expand,
+            pick,
             guild_id,
             channel_id,
             user_id,
@@ -334,7 +983,29 @@ async fn main() -> Result<()> {
                 requested_by,
                 avatar_url: avatar,
             };
-            post_audio(&client, &cli.base_url, token.as_deref(), &payload).await?;
+            if let Some(from) = from {
+                let queries = read_batch(&from)?;
+                play_batch(&client, &cli.base_url, token.as_deref(), &payload, &queries, settings.offline_queue)
+                    .await?;
+            } else if let Some(tracks) = expanded_tracks(&client, &settings, &payload.query, expand, pick).await? {
+                let queries = if pick {
+                    let labels: Vec<String> = tracks.iter().map(|(label, _)| label.clone()).collect();
+                    let picked = checklist::pick(&labels)?;
+                    picked.into_iter().map(|i| tracks[i].1.clone()).collect()
+                } else {
+                    tracks.into_iter().map(|(_, query)| query).collect::<Vec<_>>()
+                };
+                if queries.is_empty() {
+                    status!("ℹ️".blue(), "Nothing picked, so nothing was enqueued");
+                } else {
+                    play_batch(&client, &cli.base_url, token.as_deref(), &payload, &queries, settings.offline_queue)
+                        .await?;
+                }
+            } else {
+                let label = t!("Enqueueing {}…", payload.query);
+                post_play(&client, &cli.base_url, token.as_deref(), &payload, &label, settings.offline_queue)
+                    .await?;
+            }
         }
         Commands::Turip {
             guild_id,
@@ -356,7 +1027,65 @@ async fn main() -> Result<()> {
                 requested_by,
                 avatar_url: avatar,
             };
-            post_audio(&client, &cli.base_url, token.as_deref(), &payload).await?;
+            let label = t!("Enqueueing {}…", payload.query);
+            post_play(&client, &cli.base_url, token.as_deref(), &payload, &label, settings.offline_queue)
+                .await?;
+        }
+        Commands::Radio {
+            station,
+            list,
+            no_check,
+            stay,
+            guild_id,
+            channel_id,
+            user_id,
+        } => {
+            if list {
+                radio_list(&settings.radio);
+                return Ok(());
+            }
+            let station = station.unwrap_or_default();
+            let Some(url) = radio::resolve(&station, &settings.radio) else {
+                return Err(exit::fail(
+                    ExitCode::Usage,
+                    t!("No station named '{}'; `jorik radio --list` shows them", station),
+                ));
+            };
+            let url = if no_check {
+                url
+            } else {
+                let spinner = progress::Spinner::start(t!("Checking {}…", url), spinners_enabled());
+                let checked = radio::check(&client, &url).await;
+                drop(spinner);
+                checked?
+            };
+            let saved = load_auth();
+            let payload = PlayPayload {
+                action: "play",
+                guild_id: guild_id.clone(),
+                channel_id,
+                query: url,
+                user_id: user_id.clone(),
+                requested_by: saved.as_ref().and_then(|a| a.username.clone()),
+                avatar_url: saved.and_then(|a| a.avatar_url),
+            };
+            let label = t!("Tuning in to {}…", station);
+            post_play(&client, &cli.base_url, token.as_deref(), &payload, &label, settings.offline_queue)
+                .await?;
+            if stay {
+                let payload = TwentyFourSevenPayload {
+                    action: "247",
+                    guild_id,
+                    user_id,
+                    enabled: Some(true),
+                };
+                post_audio(&client, &cli.base_url, token.as_deref(), &payload).await?;
+            } else if !is_quiet() {
+                progress::note(deco!(
+                    "💡".yellow(),
+                    "Radio plays until stopped; `jorik 247 on` (or --stay) keeps the bot in the channel if the stream drops"
+                ));
+            }
         }
         Commands::Skip { guild_id, user_id } => {
             let payload = SimplePayload {
@@ -387,9 +1116,33 @@ async fn main() -> Result<()> {
             user_id,
             limit,
             offset,
+            all,
+            output,
+        } => {
+            format::set_table(output);
+            if all {
+                queue_all(&client, &cli.base_url, token.as_deref(), guild_id, user_id).await?;
+            } else {
+                let payload = QueuePayload {
+                    action: "queue",
+                    guild_id,
+                    user_id,
+                    limit,
+                    offset,
+                };
+                post_audio(&client, &cli.base_url, token.as_deref(), &payload).await?;
+            }
+        }
+        Commands::History {
+            guild_id,
+            user_id,
+            limit,
+            offset,
+            output,
         } => {
+            format::set_table(output);
             let payload = QueuePayload {
-                action: "queue",
+                action: "history",
                 guild_id,
                 user_id,
                 limit,
@@ -413,6 +1166,51 @@ async fn main() -> Result<()> {
             };
             post_audio(&client, &cli.base_url, token.as_deref(), &payload).await?;
         }
+        Commands::Daemon {
+            listen,
+            guild_id,
+            channel_id,
+            user_id,
+            no_hooks,
+        } => {
+            let listener = listen_on(&listen).await?;
+            let addr = listener.local_addr()?;
+            status!("🛰️".cyan(), "Local API listening on http://{}", addr);
+            let daemon = daemon::Daemon {
+                http: client.clone(),
+                base_url: cli.base_url.clone(),
+                token: cli.token.clone(),
+                guild_id,
+                channel_id,
+                user_id,
+                loopback: addr.ip().is_loopback(),
+            };
+            // Hooks need an event stream, which needs a guild to follow.
+            let watch_guild = daemon.guild_id.clone().filter(|_| !no_hooks);
+            match watch_guild.zip(hooks::Hooks::new(&settings.hooks, true)) {
+                Some((guild_id, hooks)) => {
+                    tokio::select! {
+                        served = daemon.serve(listener) => served?,
+                        watched = run_hooks(&cli.base_url, token.as_deref(), guild_id, hooks) => watched?,
+                    }
+                }
+                None => daemon.serve(listener).await?,
+            }
+        }
+        Commands::MpdBridge { listen, guild_id } => {
+            mpd_bridge(&client, &cli.base_url, token, &listen, guild_id).await?;
+        }
+        Commands::Overlay { port, bind, guild_id } => {
+            overlay(&cli.base_url, token.as_deref(), std::net::SocketAddr::new(bind, port), guild_id).await?;
+        }
+        Commands::Status {
+            bar,
+            follow,
+            guild_id,
+            user_id,
+        } => {
+            bar_status(&client, &cli.base_url, token.as_deref(), bar, follow, guild_id, user_id).await?;
+        }
         Commands::Loop {
             mode,
             guild_id,
@@ -453,21 +1251,68 @@ async fn main() -> Result<()> {
             post_audio(&client, &cli.base_url, token.as_deref(), &payload).await?;
         }
         Commands::Auth { command } => match command {
-            AuthSubcommand::Login => {
-                login(&cli.base_url).await?;
+            AuthSubcommand::Login { profile, device, manual, browser, port, callback_host, bind } => {
+                let callback_wanted = browser || port.is_some() || callback_host.is_some() || bind.is_some();
+                if manual {
+                    manual_login(&client, &cli.base_url, profile.as_deref()).await?;
+                } else if device || (!callback_wanted && is_headless()) {
+                    if !device && !is_quiet() {
+                        status!("💡".cyan(), "No local browser here, so sign in from another device (pass --browser to wait for a callback anyway).");
+                    }
+                    device_login(&client, &cli.base_url, profile.as_deref()).await?;
+                } else {
+                    login(&cli.base_url, profile.as_deref(), port, callback_host.as_deref(), bind).await?;
+                }
             }
             AuthSubcommand::Signout => {
                 signout(&client, &cli.base_url, token.as_deref()).await?;
             }
             AuthSubcommand::Info => {
-                auth_info()?;
+                auth_info(&client, &cli.base_url).await?;
+            }
+            AuthSubcommand::Refresh => {
+                auth_refresh(&client, &cli.base_url, token.as_deref()).await?;
+            }
+            AuthSubcommand::Switch { name } => {
+                auth_switch(&name)?;
             }
+            AuthSubcommand::Profiles => {
+                auth_profiles();
+            }
+            AuthSubcommand::Token { show } => {
+                auth_token(token.as_deref(), show)?;
+            }
+        },
+        Commands::Whoami => whoami(&client, &cli.base_url, token.as_deref()).await?,
+        Commands::Config { command } => match command {
+            ConfigSubcommand::Show => config_show()?,
+            ConfigSubcommand::Get { key } => config_get(&key)?,
+            ConfigSubcommand::Set { key, value } => config_set(&key, Some(&value))?,
+            ConfigSubcommand::Unset { key } => config_set(&key, None)?,
+            ConfigSubcommand::Sources => config_sources(&settings, &cli.base_url, base_url_given, cli.token.as_deref(), cli.retries, cli.no_update_check)?,
+            ConfigSubcommand::Export { file, no_secrets } => config_export(&file, no_secrets)?,
+            ConfigSubcommand::Import { file, no_secrets } => config_import(&file, no_secrets)?,
+        },
+        Commands::Scrobble { command } => match command {
+            ScrobbleSubcommand::Enable { service } => scrobble_enable(&client, service).await?,
+            ScrobbleSubcommand::Status => scrobble_status(&settings.scrobble),
+            ScrobbleSubcommand::Disable => scrobble_disable()?,
         },
-        Commands::Lyrics { guild_id, user_id } => {
+        Commands::Pending { command } => match command {
+            PendingSubcommand::List => pending_list(),
+            PendingSubcommand::Flush => pending_flush(&client, &cli.base_url, token.as_deref()).await?,
+            PendingSubcommand::Clear => pending_clear()?,
+        },
+        Commands::Lyrics {
+            query,
+            guild_id,
+            user_id,
+        } => {
             let payload = LyricsPayload {
                 action: "lyrics".to_string(),
                 guild_id,
                 user_id,
+                query,
             };
             post_audio(&client, &cli.base_url, token.as_deref(), &payload).await?;
         }
@@ -476,6 +1321,16 @@ async fn main() -> Result<()> {
             guild_id,
             user_id,
         } => {
+            let Some(style) = style.or_else(|| {
+                settings
+                    .guild(guild_id.as_deref())
+                    .and_then(|g| g.filters.clone())
+            }) else {
+                return Err(exit::fail(
+                    ExitCode::Usage,
+                    t!("No filter style given, and this guild has no `filters` in config.toml"),
+                ));
+            };
             let filters = match style.to_lowercase().as_str() {
                 "clear" => AudioFilters::default(),
                 "bassboost" => AudioFilters {
@@ -549,10 +1404,19 @@ async fn main() -> Result<()> {
                     }),
                     ..Default::default()
                 },
-                _ => {
-                    eprintln!("Unknown filter style: {}", style);
-                    return Ok(());
-                }
+                _ => match settings
+                    .filter_presets
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&style))
+                {
+                    Some((_, preset)) => preset.clone(),
+                    None => {
+                        return Err(exit::fail(
+                            ExitCode::Usage,
+                            t!("Unknown filter style: {}", style),
+                        ));
+                    }
+                },
             };
 
             let payload = FilterPayload {
@@ -569,19 +1433,23 @@ async fn main() -> Result<()> {
     if let Ok(Some((latest, assets))) = update_check.await {
         println!(
             "\n{} {} -> {}",
-            "A new version of jorik-cli is available:".yellow().bold(),
+            i18n::tr("A new version of jorik-cli is available:").yellow().bold(),
             env!("CARGO_PKG_VERSION").red(),
             latest.green().bold()
         );
 
-        print!("Do you want to update and install the latest version? [y/N]: ");
-        io::stdout().flush()?;
+        if interactive {
+            print!("{}", i18n::tr("Do you want to update and install the latest version? [y/N]: "));
+            io::stdout().flush()?;
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
 
-        if input.trim().eq_ignore_ascii_case("y") {
-            trigger_update(&client, &latest, &assets).await?;
+            if input.trim().eq_ignore_ascii_case("y") {
+                trigger_update(&client, &latest, &assets).await?;
+            }
+        } else {
+            println!("{}", i18n::tr("Download it at: https://github.com/fireflyteam/jorik-cli/releases"));
         }
     }
 
@@ -590,7 +1458,7 @@ async fn main() -> Result<()> {
 
 async fn trigger_update(client: &Client, _latest: &str, assets: &[GiteaAsset]) -> Result<()> {
     if cfg!(target_os = "linux") {
-        println!("Running update script...");
+        println!("{}", i18n::tr("Running update script..."));
         let status = Command::new("sh")
             .arg("-c")
             .arg("curl -sL https://shorty.pp.ua/jorikcli | bash")
@@ -600,20 +1468,20 @@ async fn trigger_update(client: &Client, _latest: &str, assets: &[GiteaAsset]) -
         if status.success() {
             println!(
                 "\n{}",
-                "Update successful! You can now use the latest version."
+                i18n::tr("Update successful! You can now use the latest version.")
                     .green()
                     .bold()
             );
         } else {
-            println!("\n{}", "Update failed.".red().bold());
+            println!("\n{}", i18n::tr("Update failed.").red().bold());
         }
     } else if cfg!(target_os = "windows") {
         if let Some(asset) = assets.iter().find(|a| a.name.ends_with("setup.exe")) {
-            println!("Downloading installer...");
             let temp_dir = std::env::temp_dir();
             let installer_path = temp_dir.join(&asset.name);
 
             {
+                interrupt::set_partial_file(Some(installer_path.clone()));
                 let mut file = File::create(&installer_path)?;
                 let mut response = client.get(&asset.browser_download_url).send().await?;
 
@@ -621,12 +1489,25 @@ async fn trigger_update(client: &Client, _latest: &str, assets: &[GiteaAsset]) -
                     bail!("Failed to download installer: {}", response.status());
                 }
 
-                while let Some(chunk) = response.chunk().await? {
+                let spinner = progress::Spinner::download(
+                    i18n::tr("Downloading installer..."),
+                    response.content_length(),
+                    spinners_enabled(),
+                );
+                if !spinner.is_visible() {
+                    println!("{}", i18n::tr("Downloading installer..."));
+                }
+                let mut downloaded = 0u64;
+                while let Some(chunk) = response.chunk().await? {
                     file.write_all(&chunk)?;
+                    downloaded += chunk.len() as u64;
+                    spinner.set_bytes(downloaded);
                 }
+                interrupt::set_partial_file(None);
             }
+            println!("{}", i18n::tr("Installer downloaded."));
 
-            println!("Running installer...");
+            println!("{}", i18n::tr("Running installer..."));
             Command::new(&installer_path)
                 .arg("/SILENT")
                 .spawn()
@@ -634,255 +1515,1466 @@ async fn trigger_update(client: &Client, _latest: &str, assets: &[GiteaAsset]) -
 
             println!(
                 "\n{}",
-                "Update started! The application will now exit to complete the installation."
+                i18n::tr("Update started! The application will now exit to complete the installation.")
                     .green()
                     .bold()
             );
             std::process::exit(0);
         } else {
-            println!("{}", "No Windows installer found for this release.".red());
+            println!("{}", i18n::tr("No Windows installer found for this release.").red());
             println!(
                 "Download it manually at: https://github.com/fireflyteam/jorik-cli/releases"
             );
         }
     } else {
-        println!("Automatic updates are not supported on this platform.");
-        println!("Download it at: https://github.com/fireflyteam/jorik-cli/releases");
+        println!("{}", i18n::tr("Automatic updates are not supported on this platform."));
+        println!("{}", i18n::tr("Download it at: https://github.com/fireflyteam/jorik-cli/releases"));
     }
     Ok(())
 }
 
 async fn health(client: &Client, base_url: &str) -> Result<()> {
     let url = build_url(base_url, "/health");
-    let resp = client
-        .get(&url)
-        .send()
+    let resp = send(client.get(&url))
         .await
         .with_context(|| format!("GET {url}"))?;
+    if RAW.load(Ordering::Relaxed) {
+        return print_raw(resp).await;
+    }
 
     if resp.status().is_success() {
-        println!("{} Server is healthy", "✔".green());
+        status!("✔".green(), "Server is healthy");
     } else {
-        println!("{} Server returned status {}", "✘".red(), resp.status());
+        status!("✘".red(), "Server returned status {}", resp.status());
+        return Err(exit::reported(ExitCode::Rejected));
     }
     Ok(())
 }
 
+async fn ping(
+    client: &Client,
+    base_url: &str,
+    token: Option<&str>,
+    guild_id: Option<String>,
+    count: usize,
+) -> Result<()> {
+    let count = count.max(1);
+    let url = build_url(base_url, "/health");
+    if !is_quiet() {
+        status!("📡".cyan(), "HTTP round-trip to {}", url);
+    }
+
+    let mut http_samples = Vec::with_capacity(count);
+    for i in 1..=count {
+        let start = Instant::now();
+        match client.get(&url).send().await {
+            Ok(resp) => {
+                let elapsed = start.elapsed();
+                if !is_quiet() {
+                    println!("  #{} {} ms ({})", i, elapsed.as_millis(), resp.status());
+                }
+                http_samples.push(elapsed);
+            }
+            Err(e) => println!("  #{} {} {}", i, "✘".red(), e),
+        }
+    }
+    print_latency_summary("HTTP", &http_samples);
+    if http_samples.is_empty() {
+        return Err(exit::reported(ExitCode::Network));
+    }
+
+    let Some(token) = token else {
+        if !is_quiet() {
+            println!(
+                "\n{} No token present; skipping WebSocket timing",
+                "ℹ️".blue()
+            );
+        }
+        return Ok(());
+    };
+
+    let ws_url = build_ws_url(base_url, token)?;
+    if !is_quiet() {
+        println!(
+            "\n{} WebSocket connect{}",
+            "📡".cyan(),
+            if guild_id.is_some() { " + subscribe" } else { "" }
+        );
+    }
+
+    let mut connect_samples = Vec::with_capacity(count);
+    let mut subscribe_samples = Vec::with_capacity(count);
+    for i in 1..=count {
+        let request = build_ws_request(base_url, &ws_url, token)?;
+        let start = Instant::now();
+        let mut ws_stream = match timeout(Duration::from_secs(5), tls::connect_ws(request)).await {
+            Ok(Ok((stream, _))) => stream,
+            Ok(Err(e)) => {
+                println!("  #{} {} {}", i, "✘".red(), e);
+                continue;
+            }
+            Err(_) => {
+                println!("  #{} {} connect timed out", i, "✘".red());
+                continue;
+            }
+        };
+        let connected = start.elapsed();
+        connect_samples.push(connected);
+
+        if let Some(gid) = &guild_id {
+            let sub = WsSubscribe {
+                event_type: "subscribe",
+                guild_id: gid.clone(),
+            };
+            let json = serde_json::to_string(&sub).context("serializing subscribe")?;
+            let sub_start = Instant::now();
+            ws_stream.send(Message::Text(json.into())).await.ok();
+            // The server answers a subscribe with the initial state; time the first text frame.
+            let first = timeout(Duration::from_secs(5), async {
+                while let Some(Ok(msg)) = ws_stream.next().await {
+                    if msg.is_text() {
+                        return true;
+                    }
+                }
+                false
+            })
+            .await;
+            if let Ok(true) = first {
+                let subscribed = sub_start.elapsed();
+                if !is_quiet() {
+                    println!(
+                        "  #{} connect {} ms, subscribe {} ms",
+                        i,
+                        connected.as_millis(),
+                        subscribed.as_millis()
+                    );
+                }
+                subscribe_samples.push(subscribed);
+            } else {
+                println!(
+                    "  #{} connect {} ms, subscribe {} no reply",
+                    i,
+                    connected.as_millis(),
+                    "✘".red()
+                );
+            }
+        } else if !is_quiet() {
+            println!("  #{} connect {} ms", i, connected.as_millis());
+        }
+        ws_stream.close(None).await.ok();
+    }
+    print_latency_summary("WS connect", &connect_samples);
+    if guild_id.is_some() {
+        print_latency_summary("WS subscribe", &subscribe_samples);
+    }
+    if connect_samples.is_empty() {
+        return Err(exit::reported(ExitCode::Network));
+    }
+    Ok(())
+}
+
+/// `jorik events`: relay the guild's WebSocket events to stdout, one JSON
+/// object per line. Status and reconnect notes go to stderr so the stream
+/// stays clean for `jq` and friends.
+async fn events(
+    base_url: &str,
+    token: Option<&str>,
+    guild_id: Option<String>,
+    types: &[String],
+    reconnect: bool,
+    mut hooks: Option<hooks::Hooks>,
+) -> Result<()> {
+    stream_events(base_url, token, guild_id, reconnect, |event| {
+        if let Some(hooks) = &mut hooks {
+            for problem in hooks.observe(&event) {
+                progress::note(format!("{} {}", "⚠".yellow(), problem));
+            }
+        }
+        let kind = event.get("type").and_then(Value::as_str).unwrap_or("");
+        if !types.is_empty() && !types.iter().any(|t| t == kind) {
+            return true;
+        }
+        let mut stdout = io::stdout().lock();
+        // Whoever read the stream is gone (`| head`, say) when this fails.
+        writeln!(stdout, "{}", event).and_then(|_| stdout.flush()).is_ok()
+    })
+    .await
+}
+
+/// Follow `guild_id` for `jorik daemon`, only to run `hooks`.
+async fn run_hooks(base_url: &str, token: Option<&str>, guild_id: String, mut hooks: hooks::Hooks) -> Result<()> {
+    stream_events(base_url, token, Some(guild_id), true, |event| {
+        for problem in hooks.observe(&event) {
+            progress::note(format!("{} {}", "⚠".yellow(), problem));
+        }
+        true
+    })
+    .await
+}
+
+/// Follow a guild's `/ws` events, handing each to `on_event` until it
+/// returns false. A dropped connection is retried with backoff unless
+/// `reconnect` is off; a refused token ends it either way.
+async fn stream_events(
+    base_url: &str,
+    token: Option<&str>,
+    guild_id: Option<String>,
+    reconnect: bool,
+    mut on_event: impl FnMut(Value) -> bool,
+) -> Result<()> {
+    let Some(token) = token else {
+        return Err(not_authenticated());
+    };
+    let Some(guild_id) = guild_id else {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            i18n::tr("No guild to follow; pass --guild-id or set default_guild_id"),
+        ));
+    };
+    // A bad base URL fails now rather than being retried forever.
+    build_ws_url(base_url, token)?;
+
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        let reason = match timeout(Duration::from_secs(10), ws::connect(base_url, token)).await {
+            Ok(Ok(mut ws_stream)) => {
+                backoff = Duration::from_secs(1);
+                if !is_quiet() {
+                    eprintln!("{}", deco!("📡".cyan(), "Streaming events for guild {}", guild_id));
+                }
+                match ws::subscribe(&mut ws_stream, &guild_id).await {
+                    Ok(()) => loop {
+                        match ws_stream.next().await {
+                            Some(Ok(Message::Text(text))) => {
+                                let Ok(event) = serde_json::from_str::<Value>(&text) else {
+                                    continue;
+                                };
+                                if !on_event(event) {
+                                    return Ok(());
+                                }
+                            }
+                            Some(Ok(Message::Close(frame))) => {
+                                break frame.map_or_else(
+                                    || "closed by the server".to_string(),
+                                    |f| format!("closed by the server: {}", f.reason),
+                                );
+                            }
+                            Some(Ok(_)) => {}
+                            Some(Err(e)) => break e.to_string(),
+                            None => break "connection closed".to_string(),
+                        }
+                    },
+                    Err(e) => e.to_string(),
+                }
+            }
+            Ok(Err(ref e))
+                if let Some(tokio_tungstenite::tungstenite::Error::Http(resp)) = e.downcast_ref()
+                    && matches!(resp.status().as_u16(), 401 | 403) =>
+            {
+                // Reconnecting won't fix a bad token.
+                return Err(exit::fail(
+                    ExitCode::Auth,
+                    t!("The server refused the WebSocket connection ({})", resp.status()),
+                ));
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => "connect timed out".to_string(),
+        };
+        if !reconnect {
+            return Err(exit::fail(
+                ExitCode::Network,
+                t!("Event stream ended: {}", reason),
+            ));
+        }
+        if !is_quiet() {
+            progress::note(format!(
+                "{} {} — reconnecting in {}s",
+                "↻".yellow(),
+                reason,
+                backoff.as_secs()
+            ));
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(Duration::from_secs(30));
+    }
+}
+
+fn print_latency_summary(label: &str, samples: &[Duration]) {
+    if samples.is_empty() {
+        status!("✘".red(), "{}: no successful samples", label);
+        return;
+    }
+    let min = samples.iter().min().copied().unwrap_or_default();
+    let max = samples.iter().max().copied().unwrap_or_default();
+    let avg = samples.iter().sum::<Duration>() / samples.len() as u32;
+    status!(
+        "✔".green(),
+        "{}: min {} ms / avg {} ms / max {} ms ({} samples)",
+        label.bold(),
+        min.as_millis(),
+        avg.as_millis(),
+        max.as_millis(),
+        samples.len()
+    );
+}
+
 async fn post_audio<T: serde::Serialize>(
     client: &Client,
     base_url: &str,
     token: Option<&str>,
     payload: &T,
+) -> Result<()> {
+    post_audio_with_spinner(client, base_url, token, payload, None).await
+}
+
+/// `post_audio`, showing a spinner labelled `spinner` until the server answers.
+async fn post_audio_with_spinner<T: serde::Serialize>(
+    client: &Client,
+    base_url: &str,
+    token: Option<&str>,
+    payload: &T,
+    spinner: Option<&str>,
+) -> Result<()> {
+    let url = build_url(base_url, "/webhook/audio");
+    let request = |bearer: Option<&str>| {
+        let req = client.post(&url).json(payload);
+        match bearer {
+            Some(bearer) => req.bearer_auth(bearer),
+            None => req,
+        }
+    };
+    let spinner = spinner.map(|label| progress::Spinner::start(label, spinners_enabled()));
+    let resp = send_with_retries(request(token))
+        .await
+        .with_context(|| format!("POST {url}"));
+    drop(spinner);
+    let resp = resp?;
+
+    // Only a saved token can be replaced by logging in again; one passed via
+    // flag/env would still win on the retry.
+    if resp.status() == reqwest::StatusCode::UNAUTHORIZED
+        && token.is_none_or(|bearer| load_token().as_deref() == Some(bearer))
+    {
+        if !offer_login(client, base_url).await? {
+            print_response(resp).await.ok();
+            return Err(exit::reported(ExitCode::LoginRequired));
+        }
+        let resp = send_with_retries(request(load_token().as_deref()))
+            .await
+            .with_context(|| format!("POST {url}"))?;
+        return print_response(resp).await;
+    }
+
+    let expiring = resp.headers().contains_key(TOKEN_EXPIRING_HEADER);
+    print_response(resp).await?;
+
+    // Rotate transparently, but only for the token we persisted ourselves;
+    // a token passed via flag/env is owned by whoever supplied it.
+    if expiring
+        && let Some(bearer) = token
+        && load_token().as_deref() == Some(bearer)
+    {
+        match refresh_token(client, base_url, bearer).await {
+            Ok(_) => println!("{}", i18n::tr("🔄 Token was about to expire and has been refreshed.").dimmed()),
+            Err(e) => eprintln!("{} {}", "✘".red(), t!("Token refresh failed: {}", format!("{:#}", e))),
+        }
+    }
+    Ok(())
+}
+
+/// The IDs of the last request that failed, quoted under the error so a bug
+/// report can point at the right line of the server's logs.
+#[derive(Clone)]
+struct RequestIds {
+    sent: String,
+    /// What the server filed it under, when it says and that differs.
+    server: Option<String>,
+}
+
+impl std::fmt::Display for RequestIds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.server {
+            Some(server) => write!(f, "{}", t!("Request ID: {} (server: {})", self.sent, server)),
+            None => write!(f, "{}", t!("Request ID: {}", self.sent)),
+        }
+    }
+}
+
+/// Set by `send` on a failed request and cleared by a successful one.
+static FAILED_REQUEST: std::sync::Mutex<Option<RequestIds>> = std::sync::Mutex::new(None);
+
+/// Send `req`, tracing it and the response to stderr under `--verbose`.
+/// Under `--dry-run` the request is printed as JSON instead, and the error
+/// returned ends the command with exit code 0.
+async fn send(req: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+    let request_id = new_request_id();
+    let req = req.header(REQUEST_ID_HEADER, &request_id);
+    if DRY_RUN.load(Ordering::Relaxed) {
+        let built = req.build()?;
+        let headers: serde_json::Map<String, Value> = built
+            .headers()
+            .iter()
+            .filter(|(name, _)| *name != reqwest::header::AUTHORIZATION)
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    Value::from(value.to_str().unwrap_or("<binary>")),
+                )
+            })
+            .collect();
+        let body = built.body().and_then(|b| b.as_bytes()).map(|bytes| {
+            serde_json::from_slice::<Value>(bytes)
+                .unwrap_or_else(|_| Value::from(String::from_utf8_lossy(bytes)))
+        });
+        let request = serde_json::json!({
+            "method": built.method().as_str(),
+            "url": built.url().as_str(),
+            "headers": headers,
+            "body": body,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&request).unwrap_or_default()
+        );
+        return Err(exit::reported(ExitCode::DryRun));
+    }
+    let target = req
+        .try_clone()
+        .filter(|_| logfile::enabled())
+        .and_then(|r| r.build().ok())
+        .map(|r| (r.method().to_string(), r.url().to_string()));
+    let start = Instant::now();
+    let result = if VERBOSE.load(Ordering::Relaxed) {
+        send_traced(req).await
+    } else {
+        req.send().await
+    };
+    let server_id = result
+        .as_ref()
+        .ok()
+        .and_then(|resp| server_request_id(resp.headers()))
+        .filter(|id| *id != request_id);
+    if let Some((method, url)) = target {
+        let ms = start.elapsed().as_millis() as u64;
+        match &result {
+            Ok(resp) => logfile::event(
+                "http",
+                serde_json::json!({"method": method, "url": url, "status": resp.status().as_u16(), "ms": ms, "request_id": request_id, "server_request_id": server_id}),
+            ),
+            Err(e) => logfile::event(
+                "http",
+                serde_json::json!({"method": method, "url": url, "error": format!("{:#}", e), "ms": ms, "request_id": request_id}),
+            ),
+        }
+    }
+    let failed = !result.as_ref().is_ok_and(|resp| resp.status().is_success());
+    if let Ok(mut last) = FAILED_REQUEST.lock() {
+        *last = failed.then_some(RequestIds {
+            sent: request_id,
+            server: server_id,
+        });
+    }
+    Ok(result?)
+}
+
+/// A header as `--verbose` shows it: credentials both ways, cookies and a
+/// token the server rotates in through a header of its own are redacted.
+fn traced_header<'a>(name: &reqwest::header::HeaderName, value: &'a reqwest::header::HeaderValue) -> &'a str {
+    let name = name.as_str();
+    let secret = matches!(name, "authorization" | "proxy-authorization" | "cookie" | "set-cookie")
+        || (name.contains("token") && name != TOKEN_EXPIRING_HEADER)
+        || name.contains("secret")
+        || name.contains("api-key");
+    if secret {
+        "<redacted>"
+    } else {
+        value.to_str().unwrap_or("<binary>")
+    }
+}
+
+/// The `--verbose` half of `send`: dump request and response to stderr.
+async fn send_traced(req: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    if let Some(Ok(built)) = req.try_clone().map(|r| r.build()) {
+        eprintln!("{} {} {}", ">".cyan(), built.method(), built.url());
+        for (name, value) in built.headers() {
+            eprintln!("{} {}: {}", ">".cyan(), name, traced_header(name, value));
+        }
+        if let Some(body) = built.body().and_then(|b| b.as_bytes()) {
+            eprintln!("{}", pretty_body(body));
+        }
+    }
+
+    let start = Instant::now();
+    let resp = match req.send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            eprintln!("{} {:#}", "<".red(), e);
+            return Err(e);
+        }
+    };
+    eprintln!(
+        "{} {} ({} ms)",
+        "<".cyan(),
+        resp.status(),
+        start.elapsed().as_millis()
+    );
+    for (name, value) in resp.headers() {
+        eprintln!("{} {}: {}", "<".cyan(), name, traced_header(name, value));
+    }
+
+    // Buffer the body so it can be logged, then hand back an equivalent response.
+    let status = resp.status();
+    let version = resp.version();
+    let headers = resp.headers().clone();
+    let body = resp.bytes().await?;
+    eprintln!("{}", pretty_body(&body));
+    let mut rebuilt = tokio_tungstenite::tungstenite::http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok(rebuilt.into())
+}
+
+fn pretty_body(body: &[u8]) -> String {
+    serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|json| serde_json::to_string_pretty(&json).ok())
+        .unwrap_or_else(|| String::from_utf8_lossy(body).into_owned())
+        .dimmed()
+        .to_string()
+}
+
+/// Enqueue `payload`. With `offline_queue` on, commands saved earlier are
+/// replayed first so nothing jumps ahead of them, and a server that can't be
+/// reached gets this one saved as well instead of failing.
+async fn post_play(
+    client: &Client,
+    base_url: &str,
+    token: Option<&str>,
+    payload: &PlayPayload,
+    label: &str,
+    offline_queue: bool,
+) -> Result<()> {
+    if !offline_queue || pending::path().is_none() {
+        return post_audio_with_spinner(client, base_url, token, payload, Some(label)).await;
+    }
+    if pending_count(base_url) > 0 && flush_pending(client, base_url, token).await?.unreachable {
+        return spool(base_url, payload);
+    }
+    match post_audio_with_spinner(client, base_url, token, payload, Some(label)).await {
+        Err(e) if is_unreachable(&e) => spool(base_url, payload),
+        other => other,
+    }
+}
+
+/// `radio --list`: every station, the ones from config.toml marked.
+fn radio_list(custom: &std::collections::BTreeMap<String, String>) {
+    let stations = radio::stations(custom);
+    let width = stations.keys().map(|name| name.chars().count()).max().unwrap_or(0);
+    for station in stations.values() {
+        let name = format!("{:width$}", station.name, width = width);
+        if station.custom {
+            println!("{}  {}  {}", name.bold(), station.url, i18n::tr("(config.toml)").dimmed());
+        } else {
+            println!("{}  {}", name.bold(), station.url);
+        }
+    }
+}
+
+/// Queries for `play --from`, one per line of `path` or of stdin for "-".
+fn read_batch(path: &std::path::Path) -> Result<Vec<String>> {
+    let text = if path.as_os_str() == "-" {
+        let mut text = String::new();
+        io::Read::read_to_string(&mut io::stdin(), &mut text).context("reading stdin")?;
+        text
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?
+    };
+    let queries: Vec<String> = playlist::parse(&text).iter().map(|q| clean_query(q)).collect();
+    if queries.is_empty() {
+        return Err(exit::fail(ExitCode::Usage, t!("No tracks in {}", path.display())));
+    }
+    Ok(queries)
+}
+
+/// The tracks `play --expand` or `--pick` finds behind `query`, each as
+/// what to show and what to enqueue; `None` to play `query` as it is.
+async fn expanded_tracks(
+    client: &Client,
+    settings: &Settings,
+    query: &str,
+    expand: bool,
+    pick: bool,
+) -> Result<Option<Vec<(String, String)>>> {
+    if expand && let Some(collection) = spotify::Collection::parse(query) {
+        let tracks = spotify_tracks(client, &settings.spotify, &collection).await?;
+        return Ok(Some(tracks.into_iter().map(|q| (q.clone(), q)).collect()));
+    }
+    if !pick {
+        return Ok(None);
+    }
+    let Some(list) = youtube::playlist_id(query) else {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            i18n::tr("--pick needs a YouTube playlist link, or a Spotify one with --expand"),
+        ));
+    };
+    if settings.youtube.api_key.is_none() {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            i18n::tr("--pick needs a YouTube Data API key: set api_key under [youtube] in config.toml"),
+        ));
+    }
+    let spinner = progress::Spinner::start(t!("Reading the playlist from YouTube…"), spinners_enabled());
+    let videos = youtube::playlist(client, &settings.youtube, &list).await;
+    drop(spinner);
+    let videos = videos?;
+    if videos.is_empty() {
+        return Err(exit::fail(ExitCode::Failure, i18n::tr("No videos to enqueue in that playlist")));
+    }
+    Ok(Some(
+        videos.iter().map(|v| (format!("{} — {}", v.title, v.channel), v.url())).collect(),
+    ))
+}
+
+/// `play --expand`: the tracks of a Spotify playlist or album, looked up
+/// with the app under `[spotify]`.
+async fn spotify_tracks(
+    client: &Client,
+    settings: &SpotifySettings,
+    collection: &spotify::Collection,
+) -> Result<Vec<String>> {
+    if settings.client_id.is_none() || settings.client_secret.is_none() {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            i18n::tr("--expand needs a Spotify app: set client_id and client_secret under [spotify] in config.toml"),
+        ));
+    }
+    let spinner = progress::Spinner::start(t!("Reading the tracks from Spotify…"), spinners_enabled());
+    let tracks = spotify::expand(client, settings, collection).await;
+    drop(spinner);
+    let tracks = tracks?;
+    if tracks.is_empty() {
+        return Err(exit::fail(ExitCode::Failure, i18n::tr("No tracks to enqueue in that Spotify link")));
+    }
+    Ok(tracks)
+}
+
+/// Enqueue `queries` in order, `BULK_PLAY_MAX` per `play_bulk` request, with
+/// the IDs and requester of `single`. From the first chunk a server turns
+/// down as an unknown action on, they go one `play` at a time instead.
+/// This is how files and playlists are imported, so the spinner counts the
+/// tracks queued so far.
+async fn play_batch(
+    client: &Client,
+    base_url: &str,
+    token: Option<&str>,
+    single: &PlayPayload,
+    queries: &[String],
+    offline_queue: bool,
 ) -> Result<()> {
     let url = build_url(base_url, "/webhook/audio");
-    let mut req = client.post(&url).json(payload);
+    let mut done = 0;
+    // Saved commands have to go out first, and only single plays are saved;
+    // leave both to `post_play`.
+    let spooling = offline_queue && pending::path().is_some();
+    interrupt::set_progress(Some(t!("{} of {} tracks queued", 0, queries.len())));
+    if !(spooling && pending_count(base_url) > 0) {
+        for chunk in queries.chunks(BULK_PLAY_MAX) {
+            let mut req = client.post(&url).json(&BulkPlayPayload::like(single, chunk));
+            if let Some(bearer) = token {
+                req = req.bearer_auth(bearer);
+            }
+            let label = t!("Enqueueing tracks… {}/{}", done, queries.len());
+            let spinner = progress::Spinner::start(label, spinners_enabled());
+            let resp = send_with_retries(req).await;
+            drop(spinner);
+            let resp = match resp {
+                Err(e) if spooling && is_unreachable(&e) => break,
+                resp => resp.with_context(|| format!("POST {url}"))?,
+            };
+            let status = resp.status();
+            let text = resp.text().await.context("reading response body")?;
+            let error = serde_json::from_str::<ErrorResponse>(&text).ok().map(|e| e.error);
+            if api::bulk_unsupported(status, error.as_deref()) {
+                break;
+            }
+            print_body(status, &text)?;
+            done += chunk.len();
+            interrupt::set_progress(Some(t!("{} of {} tracks queued", done, queries.len())));
+        }
+    }
+    for (i, query) in queries.iter().enumerate().skip(done) {
+        let payload = PlayPayload {
+            query: query.clone(),
+            ..single.clone()
+        };
+        let label = t!("Enqueueing {}… {}/{}", payload.query, i, queries.len());
+        post_play(client, base_url, token, &payload, &label, offline_queue).await?;
+        interrupt::set_progress(Some(t!("{} of {} tracks queued", i + 1, queries.len())));
+    }
+    interrupt::set_progress(None);
+    Ok(())
+}
+
+/// Tracks per request for `queue --all`.
+const QUEUE_ALL_PAGE: usize = 100;
+
+/// Requests `queue --all` makes at most, whatever the server says.
+const QUEUE_ALL_MAX_PAGES: usize = 1000;
+
+/// Fetch every upcoming track, one `limit`/`offset` page at a time, and
+/// print them as a single reply so `--output` and `--format` see them all.
+async fn queue_all(
+    client: &Client,
+    base_url: &str,
+    token: Option<&str>,
+    guild_id: Option<String>,
+    user_id: Option<String>,
+) -> Result<()> {
+    let url = build_url(base_url, "/webhook/audio");
+    let mut first: Option<Value> = None;
+    let mut upcoming: Vec<Value> = Vec::new();
+    let mut total = None;
+    let mut last_page: Option<Vec<Value>> = None;
+    for pages in 1.. {
+        let payload = QueuePayload {
+            action: "queue",
+            guild_id: guild_id.clone(),
+            user_id: user_id.clone(),
+            limit: QUEUE_ALL_PAGE,
+            offset: upcoming.len(),
+        };
+        let mut req = client.post(&url).json(&payload);
+        if let Some(bearer) = token {
+            req = req.bearer_auth(bearer);
+        }
+        let label = match total {
+            Some(total) => t!("Fetching queue… {}/{}", upcoming.len(), total),
+            None => i18n::tr("Fetching queue…").to_string(),
+        };
+        let spinner = progress::Spinner::start(label, spinners_enabled());
+        let resp = send_with_retries(req).await.with_context(|| format!("POST {url}"));
+        drop(spinner);
+        let resp = resp?;
+        let status = resp.status();
+        let text = resp.text().await.context("reading response body")?;
+        let json = match serde_json::from_str::<Value>(&text) {
+            Ok(json) if status.is_success() && json.get("error").is_none_or(Value::is_null) => json,
+            // Reports the refusal (or prints what came back) with the usual exit code.
+            _ => return print_body(status, &text),
+        };
+        let page = QueueResponse::from_json(&json)
+            .map_err(|e| exit::fail(ExitCode::Failure, t!("Unexpected response from the server: {}", e)))?;
+        let tracks = json
+            .get("queue")
+            .unwrap_or(&json)
+            .get("upcoming")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        total = page.total_upcoming.or(total);
+        first.get_or_insert(json);
+        // An empty page is the end; the same page again means the server
+        // ignores `offset` and there is nothing more to get.
+        if tracks.is_empty() || last_page.as_ref() == Some(&tracks) {
+            break;
+        }
+        upcoming.extend(tracks.iter().cloned());
+        last_page = Some(tracks);
+        // Without a total, only an empty page says it's over: pages may
+        // be capped below QUEUE_ALL_PAGE.
+        if total.is_some_and(|total| upcoming.len() >= total) {
+            break;
+        }
+        if pages == QUEUE_ALL_MAX_PAGES {
+            eprintln!(
+                "{}",
+                deco!("⚠️".yellow(), "Stopped after {} tracks; the queue may go on", upcoming.len())
+            );
+            break;
+        }
+    }
+
+    let mut reply = first.unwrap_or_default();
+    let target = match reply.get_mut("queue") {
+        Some(nested) if nested.is_object() => nested,
+        _ => &mut reply,
+    };
+    if let Some(target) = target.as_object_mut() {
+        // Drop the other spellings, or they would clash with ours.
+        for key in ["totalUpcoming", "total"] {
+            target.remove(key);
+        }
+        target.insert("total_upcoming".to_string(), upcoming.len().into());
+        target.insert("upcoming".to_string(), Value::Array(upcoming));
+    }
+    print_body(reqwest::StatusCode::OK, &reply.to_string())
+}
+
+/// Bind `listen` for `daemon` and `mpd-bridge`, warning when other
+/// machines can reach it: neither asks its clients for a token.
+async fn listen_on(listen: &str) -> Result<tokio::net::TcpListener> {
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .map_err(|e| exit::fail(ExitCode::Failure, t!("Could not listen on {}: {}", listen, e)))?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        status!("⚠️".yellow(), "{} is reachable from other machines, and anyone there can control the player", addr);
+    }
+    Ok(listener)
+}
+
+async fn mpd_bridge(
+    client: &Client,
+    base_url: &str,
+    token: Option<String>,
+    listen: &str,
+    guild_id: Option<String>,
+) -> Result<()> {
+    let Some(guild_id) = guild_id else {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            i18n::tr("No guild to control; pass --guild-id or set default_guild_id"),
+        ));
+    };
+    let listener = listen_on(listen).await?;
+    let addr = listener.local_addr()?;
+    status!("🎛️".cyan(), "MPD bridge for guild {} listening on {}", guild_id, addr);
+    let jorik = api::JorikClient::with_http(client.clone(), base_url, token);
+    mpd::serve(jorik, guild_id, listener).await
+}
+
+/// `jorik overlay`: serve the page on `addr`, kept current from the
+/// guild's event stream.
+async fn overlay(base_url: &str, token: Option<&str>, addr: std::net::SocketAddr, guild_id: Option<String>) -> Result<()> {
+    if token.is_none() {
+        return Err(not_authenticated());
+    }
+    let Some(guild_id) = guild_id else {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            i18n::tr("No guild to follow; pass --guild-id or set default_guild_id"),
+        ));
+    };
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| exit::fail(ExitCode::Failure, t!("Could not listen on {}: {}", addr, e)))?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        status!("⚠️".yellow(), "{} is reachable from other machines, and anyone there can see what's playing", addr);
+    }
+    status!("🎥".cyan(), "Overlay for guild {} at http://{}/", guild_id, addr);
+    let page = overlay::Overlay::new(addr.ip().is_loopback());
+    let followed = page.clone();
+    tokio::select! {
+        served = page.serve(listener) => served,
+        streamed = stream_events(base_url, token, Some(guild_id), true, |event| {
+            followed.apply(&event);
+            true
+        }) => streamed,
+    }
+}
+
+/// `jorik status`: the now-playing snapshot in `bar`'s format, or with
+/// `follow` a line for every change the guild's event stream brings.
+async fn bar_status(
+    client: &Client,
+    base_url: &str,
+    token: Option<&str>,
+    bar: bar::Bar,
+    follow: bool,
+    guild_id: Option<String>,
+    user_id: Option<String>,
+) -> Result<()> {
+    if follow {
+        // The stream opens with the whole state, so no snapshot is needed.
+        let mut snapshot = bar::Snapshot::default();
+        let mut shown = None;
+        return stream_events(base_url, token, guild_id, true, |event| {
+            snapshot.apply(&event);
+            let line = snapshot.render(bar, true);
+            if shown.as_ref() == Some(&line) {
+                return true;
+            }
+            let mut stdout = io::stdout().lock();
+            let written = writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_ok();
+            shown = Some(line);
+            written
+        })
+        .await;
+    }
+
+    let url = build_url(base_url, "/webhook/audio");
+    let payload = SimplePayload {
+        action: "nowplaying",
+        guild_id,
+        user_id,
+    };
+    let mut req = client.post(&url).json(&payload);
     if let Some(bearer) = token {
         req = req.bearer_auth(bearer);
     }
-    let resp = req.send().await.with_context(|| format!("POST {url}"))?;
-    print_response(resp).await
+    let resp = send_with_retries(req).await.with_context(|| format!("POST {url}"))?;
+    let status = resp.status();
+    let text = resp.text().await.context("reading response body")?;
+    let json = match serde_json::from_str::<Value>(&text) {
+        Ok(json) if status.is_success() && json.get("error").is_none_or(Value::is_null) => json,
+        _ => return print_body(status, &text),
+    };
+    let reply = shape::parse::<NowPlayingResponse>(&json)
+        .map_err(|e| exit::fail(ExitCode::Failure, t!("Unexpected response from the server: {}", e)))?;
+    println!("{}", bar::Snapshot::from_now_playing(reply.now_playing).render(bar, false));
+    Ok(())
+}
+
+/// Whether `err` means no connection was made at all. A timeout is left out:
+/// the server may already have acted on it, and replaying would double it.
+fn is_unreachable(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .any(reqwest::Error::is_connect)
+}
+
+fn pending_count(base_url: &str) -> usize {
+    pending::load().iter().filter(|c| c.base_url == base_url).count()
+}
+
+fn spool(base_url: &str, payload: &PlayPayload) -> Result<()> {
+    let payload = serde_json::to_value(payload).context("serializing play command")?;
+    pending::push(&pending::PendingCommand::new(base_url, payload))?;
+    status!(
+        "📥".yellow(),
+        "Server unreachable, saved for later ({} pending). Run `jorik pending flush` once it is back.",
+        pending_count(base_url)
+    );
+    Ok(())
+}
+
+#[derive(Default)]
+struct Flushed {
+    sent: usize,
+    refused: usize,
+    /// Stopped early because the server could not be reached
+    unreachable: bool,
+}
+
+/// Replay the saved commands meant for `base_url`, oldest first, removing
+/// each one the server answers, even with a refusal, since sending it again
+/// would not change that. Stops at the first one it can't take right now
+/// (no connection, auth, 429 or 5xx) and keeps it and everything after.
+async fn flush_pending(client: &Client, base_url: &str, token: Option<&str>) -> Result<Flushed> {
+    let url = build_url(base_url, "/webhook/audio");
+    let mut commands = pending::load();
+    let mut flushed = Flushed::default();
+    let mut index = 0;
+    while index < commands.len() {
+        let command = &commands[index];
+        if command.base_url != base_url {
+            index += 1;
+            continue;
+        }
+        let mut req = client.post(&url).json(&command.payload);
+        if let Some(bearer) = token {
+            req = req.bearer_auth(bearer);
+        }
+        let spinner = progress::Spinner::start(t!("Replaying {}…", command.query()), spinners_enabled());
+        let resp = send_with_retries(req).await;
+        drop(spinner);
+        let resp = match resp {
+            Err(e) if is_unreachable(&e) => {
+                flushed.unreachable = true;
+                return Ok(flushed);
+            }
+            resp => resp.with_context(|| format!("POST {url}"))?,
+        };
+        let status = resp.status();
+        let retry_later = status.is_server_error()
+            || status == reqwest::StatusCode::UNAUTHORIZED
+            || status == reqwest::StatusCode::FORBIDDEN
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+        let outcome = print_response(resp).await;
+        if retry_later {
+            return outcome.map(|()| flushed);
+        }
+        match outcome {
+            Ok(()) => flushed.sent += 1,
+            Err(_) => flushed.refused += 1,
+        }
+        // Saved after every command so an interrupted flush never sends one twice.
+        commands.remove(index);
+        save_pending(&commands)?;
+    }
+    Ok(flushed)
+}
+
+/// `pending::save`, warning about the lines it had to set aside.
+fn save_pending(commands: &[pending::PendingCommand]) -> Result<()> {
+    let set_aside = pending::save(commands)?;
+    if set_aside > 0
+        && let Some(path) = pending::path()
+    {
+        let bad = pending::bad_path(&path).display().to_string();
+        eprintln!("{}", deco!("⚠️".yellow(), "Moved {} unreadable pending command(s) to {}", set_aside, bad));
+    }
+    Ok(())
+}
+
+fn pending_list() {
+    let commands = pending::load();
+    if commands.is_empty() {
+        status!("ℹ️".blue(), "No pending commands.");
+        return;
+    }
+    for (i, command) in commands.iter().enumerate() {
+        let when = chrono::DateTime::from_timestamp(command.queued_at, 0)
+            .map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let mut details = vec![when];
+        if let Some(guild_id) = command.guild_id() {
+            details.push(t!("guild {}", guild_id));
+        }
+        details.push(command.base_url.clone());
+        println!(
+            "{:>3}. {} {}",
+            i + 1,
+            command.query().bold(),
+            format!("({})", details.join(", ")).dimmed()
+        );
+    }
+}
+
+async fn pending_flush(client: &Client, base_url: &str, token: Option<&str>) -> Result<()> {
+    if pending_count(base_url) == 0 {
+        status!("ℹ️".blue(), "Nothing pending for {}.", base_url);
+        return Ok(());
+    }
+    let flushed = flush_pending(client, base_url, token).await?;
+    if flushed.unreachable {
+        return Err(exit::fail(
+            ExitCode::Network,
+            t!("{} is still unreachable; {} command(s) kept for later", base_url, pending_count(base_url)),
+        ));
+    }
+    if flushed.refused > 0 {
+        status!(
+            "⚠️".yellow(),
+            "Replayed {} pending command(s); the server refused {}",
+            flushed.sent,
+            flushed.refused
+        );
+        return Err(exit::reported(ExitCode::Rejected));
+    }
+    status!("✔".green(), "Replayed {} pending command(s)", flushed.sent);
+    Ok(())
+}
+
+fn pending_clear() -> Result<()> {
+    let count = pending::load().len();
+    save_pending(&[])?;
+    status!("🗑".green(), "Cleared {} pending command(s)", count);
+    Ok(())
+}
+
+/// Send `req`, retrying network errors and 5xx responses with jittered
+/// exponential backoff. The last response or error is returned as-is.
+async fn send_with_retries(req: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+    let retries = RETRIES.load(Ordering::Relaxed);
+    let mut attempt = 0;
+    let mut rate_limited = 0;
+    loop {
+        // A body that can't be replayed (a stream) gets a single try.
+        let Some(this_try) = req.try_clone() else {
+            return send(req).await;
+        };
+        let reason = match send(this_try).await {
+            // Being paced by the server is not a failure, so this has its own
+            // budget and works without `--retries`.
+            Ok(resp)
+                if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    && rate_limited < RATE_LIMIT_RETRIES =>
+            {
+                rate_limited += 1;
+                let delay = retry_after(&resp)
+                    .unwrap_or(Duration::from_secs(1 << rate_limited.min(5)));
+                if delay > MAX_RETRY_AFTER {
+                    return Ok(resp);
+                }
+                if !is_quiet() {
+                    progress::note(format!(
+                        "{} rate limited, retrying in {}s ({}/{})",
+                        "⏳".yellow(),
+                        delay.as_secs_f64().ceil(),
+                        rate_limited,
+                        RATE_LIMIT_RETRIES
+                    ));
+                }
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+            Ok(resp) if resp.status().is_server_error() && attempt < retries => resp.status().to_string(),
+            Err(e)
+                if attempt < retries
+                    && e.downcast_ref::<reqwest::Error>()
+                        .is_some_and(|e| e.is_connect() || e.is_timeout() || e.is_request()) =>
+            {
+                e.to_string()
+            }
+            other => return other,
+        };
+        attempt += 1;
+        // 250ms, 500ms, 1s, ... capped at 8s; sleep a random point in the upper half.
+        let backoff = 250u64 << (attempt - 1).min(5);
+        let jitter = RandomState::new().build_hasher().finish() % (backoff / 2 + 1);
+        let delay = Duration::from_millis(backoff / 2 + jitter);
+        if !is_quiet() {
+            progress::note(format!(
+                "{} {} — retrying in {} ms ({}/{})",
+                "↻".yellow(),
+                reason,
+                delay.as_millis(),
+                attempt,
+                retries
+            ));
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Retries of a 429, independent of `--retries`.
+const RATE_LIMIT_RETRIES: u32 = 5;
+/// A longer `Retry-After` than this is passed on as the failure instead of
+/// leaving the command hanging.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// The wait a 429 asks for, given as seconds or as an HTTP date.
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    let value = resp.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = at.signed_duration_since(chrono::Utc::now());
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Trade `token` for a new one, saving it only when `token` is the saved
+/// one: a token passed via flag/env is owned by whoever supplied it. The
+/// flag says whether it was saved.
+async fn refresh_token(client: &Client, base_url: &str, token: &str) -> Result<(RefreshResponse, bool)> {
+    let url = build_url(base_url, "/webhook/auth/refresh");
+    let resp = send(client.post(&url).bearer_auth(token))
+        .await
+        .with_context(|| format!("POST {url}"))?;
+    if !resp.status().is_success() {
+        return Err(exit::fail(
+            exit::for_status(resp.status(), None),
+            t!("Server returned status {}", resp.status()),
+        ));
+    }
+    let refreshed: RefreshResponse = resp.json().await.context("parsing refresh response")?;
+    let saved = load_token().as_deref() == Some(token);
+    if saved {
+        update_saved_token(&refreshed.token, refreshed.expires_at)?;
+    }
+    Ok((refreshed, saved))
+}
+
+/// Pass a response through untouched for `--raw`, mapping the status to an exit code.
+async fn print_raw(mut resp: reqwest::Response) -> Result<()> {
+    let status = resp.status();
+    eprintln!("{}", status);
+    let mut stdout = io::stdout();
+    while let Some(chunk) = resp.chunk().await.context("reading response body")? {
+        stdout.write_all(&chunk)?;
+    }
+    stdout.flush()?;
+    if !status.is_success() {
+        return Err(exit::reported(exit::for_status(status, None)));
+    }
+    Ok(())
 }
 
 async fn print_response(resp: reqwest::Response) -> Result<()> {
+    if RAW.load(Ordering::Relaxed) {
+        return print_raw(resp).await;
+    }
     let status = resp.status();
     let text = resp.text().await.context("reading response body")?;
+    print_body(status, &text)
+}
 
-    if let Ok(json) = serde_json::from_str::<Value>(&text) {
-        if let Some(summary) = summarize(&json) {
-            println!("{}", summary);
-        } else if !status.is_success() {
-            // Fallback for errors that summarize didn't catch
-            println!("{} Request failed ({})", "✘".red(), status);
-            println!("{}", json);
-        } else {
-            // Fallback for success
-            println!("{} Success", "✔".green());
-            println!("{}", json);
+/// `print_response` for a body that has already been read.
+fn print_body(status: reqwest::StatusCode, text: &str) -> Result<()> {
+    if RAW.load(Ordering::Relaxed) {
+        eprintln!("{}", status);
+        print!("{}", text);
+        io::stdout().flush()?;
+        if !status.is_success() {
+            return Err(exit::reported(exit::for_status(status, None)));
+        }
+        return Ok(());
+    }
+    let mut error = None;
+
+    if let Ok(json) = serde_json::from_str::<Value>(text) {
+        error = json.get("error").and_then(|v| v.as_str()).map(str::to_string);
+        match summarize(&json) {
+            Ok(Some(summary)) => println!("{}", summary),
+            Err(e) if status.is_success() => {
+                // Still show what came back; the request itself may have worked.
+                println!("{}", json);
+                return Err(exit::fail(
+                    ExitCode::Failure,
+                    format!(
+                        "{}\n{}",
+                        t!("Unexpected response from the server: {}", e),
+                        t!(
+                            "💡 Hint: the server may be on a different version than jorik-cli {}; update whichever is older.",
+                            env!("CARGO_PKG_VERSION")
+                        )
+                        .yellow()
+                    ),
+                ));
+            }
+            Ok(None) | Err(_) if !status.is_success() => {
+                // Fallback for errors that summarize didn't catch
+                status!("✘".red(), "Request failed ({})", status);
+                println!("{}", json);
+            }
+            _ => {
+                // Fallback for success
+                if !is_quiet() {
+                    status!("✔".green(), "Success");
+                }
+                println!("{}", json);
+            }
         }
     } else if !status.is_success() {
-        println!("{} Request failed ({})", "✘".red(), status);
+        status!("✘".red(), "Request failed ({})", status);
         println!("{}", text);
     } else {
-        println!("{} Success", "✔".green());
+        if !is_quiet() {
+            status!("✔".green(), "Success");
+        }
         println!("{}", text);
     }
 
+    if !status.is_success() || error.is_some() {
+        return Err(exit::reported(exit::for_status(status, error.as_deref())));
+    }
     Ok(())
 }
 
-fn summarize(json: &Value) -> Option<String> {
-    let obj = json.as_object()?;
+fn summarize(json: &Value) -> Result<Option<String>, ShapeError> {
+    let Some(obj) = json.as_object() else {
+        return Ok(None);
+    };
 
     // Handle Errors
-    if let Some(err) = obj.get("error").and_then(|v| v.as_str()) {
-        let msg = obj
-            .get("message")
-            .and_then(|v| v.as_str())
-            .unwrap_or("Unknown error");
-        let hint = if err == "unauthorized" {
+    if obj.get("error").is_some_and(|e| !e.is_null()) {
+        let err: ErrorResponse = shape::parse(json)?;
+        let msg = err.message.as_deref().unwrap_or(i18n::tr("Unknown error"));
+        let hint = if err.error == "unauthorized" && !is_quiet() {
             // If a legacy token exists locally, show a specific hint asking the user to re-login.
-            if config_dir()
-                .map(|p| p.join("jorik-cli").join("token"))
+            if config_root()
+                .map(|p| p.join("token"))
                 .map(|p| p.exists())
                 .unwrap_or(false)
             {
                 format!(
                     "\n{}",
-                    "💡 Hint: Found a legacy token file — run `jorik auth login` to re-authenticate and save username/avatar.".yellow()
+                    i18n::tr("💡 Hint: Found a legacy token file — run `jorik auth login` to re-authenticate and save username/avatar.").yellow()
                 )
             } else {
                 format!(
                     "\n{}",
-                    "💡 Hint: Run `jorik auth login` or check your token.".yellow()
+                    i18n::tr("💡 Hint: Run `jorik auth login` or check your token.").yellow()
                 )
             }
         } else {
             String::new()
         };
-        return Some(format!("{} {}{}", "✘".red(), msg, hint));
+        return Ok(Some(deco!("✘".red(), "{}{}", msg, hint)));
     }
 
     let action = obj.get("action").and_then(|v| v.as_str()).unwrap_or("");
 
-    match action {
-        "play" => {
-            let tracks = obj.get("tracks").and_then(|v| v.as_array());
-            let count = tracks.map(|t| t.len()).unwrap_or(0);
-            let first = tracks.and_then(|t| t.first()).and_then(|v| v.as_object());
-            let title = first
-                .and_then(|o| o.get("title"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown Track");
-            let artist = first.and_then(|o| o.get("author")).and_then(|v| v.as_str());
-
-            let display_title = if let Some(a) = artist {
-                format!("{} by {}", title, a)
-            } else {
-                title.to_string()
-            };
+    let summary = match action {
+        "play" | "play_bulk" => {
+            let tracks = track_list(json, "tracks")?;
+            let count = tracks.len();
+            let display_title = display_title(
+                tracks.first().unwrap_or(&Track::default()),
+                i18n::tr("Unknown Track"),
+            );
 
             if count > 1 {
-                Some(format!(
-                    "{} Added {} tracks to queue (starting with {})",
+                Some(deco!(
                     "🎶".cyan(),
+                    "Added {} tracks to queue (starting with {})",
                     count,
                     display_title.bold()
                 ))
             } else {
-                Some(format!(
-                    "{} Added {} to queue",
+                Some(deco!(
                     "🎶".cyan(),
+                    "Added {} to queue",
                     display_title.bold()
                 ))
             }
         }
         "skip" => {
-            if let Some(skipped) = obj.get("skipped").and_then(|v| v.as_object()) {
-                let title = skipped
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown Track");
-                let artist = skipped.get("author").and_then(|v| v.as_str());
-                let display_title = if let Some(a) = artist {
-                    format!("{} by {}", title, a)
-                } else {
-                    title.to_string()
-                };
-                Some(format!(
-                    "{} Skipped {}",
+            if let Some(skipped) = track_at(json, "skipped")? {
+                let display_title = display_title(&skipped, i18n::tr("Unknown Track"));
+                Some(deco!(
                     "⏭️".magenta(),
+                    "Skipped {}",
                     display_title.bold()
                 ))
             } else {
-                Some(format!("{} Nothing to skip", "ℹ️".blue()))
+                Some(deco!("ℹ️".blue(), "Nothing to skip"))
             }
         }
-        "stop" => Some(format!("{} Playback stopped and queue cleared", "⏹️".red())),
+        "stop" => Some(deco!("⏹️".red(), "Playback stopped and queue cleared")),
         "pause" => {
             let state = obj.get("state").and_then(|v| v.as_str()).unwrap_or("");
             match state {
-                "paused" => Some(format!("{} Playback paused", "⏸️".yellow())),
-                "resumed" => Some(format!("{} Playback resumed", "▶️".green())),
-                _ => Some(format!("{} Toggled pause", "⏯️".yellow())),
+                "paused" => Some(deco!("⏸️".yellow(), "Playback paused")),
+                "resumed" => Some(deco!("▶️".green(), "Playback resumed")),
+                _ => Some(deco!("⏯️".yellow(), "Toggled pause")),
             }
         }
         "queue" => {
-            let current = obj.get("current").and_then(|v| v.as_object());
-            let upcoming = obj.get("upcoming").and_then(|v| v.as_array());
-            let total = obj
-                .get("total_upcoming")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0);
+            let queue = QueueResponse::from_json(json)?;
+            if !queue.has_queue() {
+                return Err(ShapeError::new("", "neither `current` nor `upcoming` is there"));
+            }
+            let current = queue.current.flatten();
+            let upcoming = queue.upcoming;
+            let total = queue.total_upcoming.unwrap_or(0);
+
+            if let Some(table) = format::table() {
+                let tracks = current.iter().map(|c| (0, c)).chain(
+                    upcoming
+                        .iter()
+                        .flatten()
+                        .enumerate()
+                        .map(|(i, t)| (i + 1, t)),
+                );
+                return Ok(Some(format::render_table(table, tracks)));
+            }
+
+            if let Some(template) = format::template() {
+                let mut lines = Vec::new();
+                if let Some(curr) = &current {
+                    let fields = format::TrackFields {
+                        position: Some(0),
+                        ..format::TrackFields::of(curr)
+                    };
+                    lines.push(format::render(template, &fields));
+                }
+                for (i, track) in upcoming.iter().flatten().enumerate() {
+                    let fields = format::TrackFields {
+                        position: Some(i + 1),
+                        ..format::TrackFields::of(track)
+                    };
+                    lines.push(format::render(template, &fields));
+                }
+                return Ok(Some(lines.join("\n")));
+            }
 
             let mut output = String::new();
-            output.push_str(&format!("{}\n", "Current Queue".bold().underline()));
-
-            if let Some(curr) = current {
-                let title = curr
-                    .get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown");
-                let artist = curr.get("author").and_then(|v| v.as_str());
-                let display_title = if let Some(a) = artist {
-                    format!("{} by {}", title, a)
-                } else {
-                    title.to_string()
-                };
-                output.push_str(&format!("{} {}\n", "▶️".green(), display_title.bold()));
+            if !is_quiet() {
+                output.push_str(&format!("{}\n", i18n::tr("Current Queue").bold().underline()));
+            }
+
+            if let Some(curr) = &current {
+                let display_title = display_title(curr, i18n::tr("Unknown"));
+                output.push_str(&deco!("▶️".green(), "{}\n", display_title.bold()));
             } else {
-                output.push_str("Nothing playing currently.\n");
+                output.push_str(&format!("{}\n", i18n::tr("Nothing playing currently.")));
             }
 
             if let Some(list) = upcoming {
                 if !list.is_empty() {
-                    output.push_str("\nUp Next:\n");
+                    if !is_quiet() {
+                        output.push_str(&format!("\n{}\n", i18n::tr("Up Next:")));
+                    }
                     for (i, item) in list.iter().enumerate() {
-                        let title = item
-                            .get("title")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("Unknown");
-                        let artist = item.get("author").and_then(|v| v.as_str());
-                        let display_title = if let Some(a) = artist {
-                            format!("{} by {}", title, a)
-                        } else {
-                            title.to_string()
-                        };
+                        let display_title = display_title(item, i18n::tr("Unknown"));
                         output.push_str(&format!("{}. {}\n", i + 1, display_title));
                     }
-                    if total > list.len() as u64 {
-                        output.push_str(&format!("... and {} more\n", total - list.len() as u64));
+                    if total > list.len() {
+                        output.push_str(&format!("... and {} more\n", total - list.len()));
                     }
                 } else {
-                    output.push_str("\nQueue is empty.\n");
+                    output.push_str(&format!("\n{}\n", i18n::tr("Queue is empty.")));
                 }
             }
             Some(output)
         }
+        "history" => {
+            let key = if obj.contains_key("tracks") { "tracks" } else { "history" };
+            let tracks = track_list(json, key)?;
+
+            if let Some(table) = format::table() {
+                let rows = tracks.iter().enumerate().map(|(i, t)| (i + 1, t));
+                return Ok(Some(format::render_table(table, rows)));
+            }
+
+            if let Some(template) = format::template() {
+                let lines: Vec<String> = tracks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, track)| {
+                        let fields = format::TrackFields {
+                            position: Some(i + 1),
+                            ..format::TrackFields::of(track)
+                        };
+                        format::render(template, &fields)
+                    })
+                    .collect();
+                return Ok(Some(lines.join("\n")));
+            }
+
+            if tracks.is_empty() {
+                return Ok(Some(deco!("📜".blue(), "Nothing has been played yet")));
+            }
+            let mut output = String::new();
+            if !is_quiet() {
+                output.push_str(&format!("{}\n", i18n::tr("Recently Played").bold().underline()));
+            }
+            for (i, track) in tracks.iter().enumerate() {
+                let display_title = display_title(track, i18n::tr("Unknown"));
+                output.push_str(&format!("{}. {}\n", i + 1, display_title));
+            }
+            Some(output)
+        }
         "clear" => {
             let removed = obj.get("removed").and_then(|v| v.as_u64()).unwrap_or(0);
-            Some(format!(
-                "{} Cleared {} tracks from queue",
+            Some(deco!(
                 "🗑️".red(),
+                "Cleared {} tracks from queue",
                 removed
             ))
         }
         "nowplaying" => {
-            if let Some(np) = obj.get("now_playing").and_then(|v| v.as_object()) {
-                let track = np.get("track").and_then(|v| v.as_object());
-                let title = track
-                    .and_then(|t| t.get("title"))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("Unknown");
-                let artist = track.and_then(|t| t.get("author")).and_then(|v| v.as_str());
-
-                let display_title = if let Some(a) = artist {
-                    format!("{} by {}", title, a)
-                } else {
-                    title.to_string()
-                };
+            if let Some(np) = shape::parse::<NowPlayingResponse>(json)?.now_playing {
+                let track = np.track.unwrap_or_default();
+                let display_title = display_title(&track, i18n::tr("Unknown"));
+                let elapsed = np.elapsed_ms;
+                let duration = np.duration_ms;
 
-                let elapsed = np.get("elapsedMs").and_then(|v| v.as_u64()).unwrap_or(0);
-                let duration = np.get("durationMs").and_then(|v| v.as_u64()).unwrap_or(0);
+                if let Some(template) = format::template() {
+                    let fields = format::TrackFields {
+                        track: Some(&track),
+                        duration_ms: Some(duration),
+                        elapsed_ms: Some(elapsed),
+                        position: None,
+                    };
+                    return Ok(Some(format::render(template, &fields)));
+                }
 
                 let progress = if duration > 0 {
                     let pct = (elapsed as f64 / duration as f64 * 20.0).round() as usize;
@@ -901,20 +2993,27 @@ fn summarize(json: &Value) -> Option<String> {
                     (duration % 60000) / 1000
                 );
 
-                Some(format!(
-                    "{} {}\n{} {}",
+                if is_quiet() {
+                    return Ok(Some(format!("{} {}", display_title, time_str)));
+                }
+
+                Some(deco!(
                     "▶️".green(),
+                    "{}\n{} {}",
                     display_title.bold(),
                     progress,
                     time_str
                 ))
+            } else if format::template().is_some() {
+                // Status bars expect an empty line rather than prose when idle.
+                Some(String::new())
             } else {
-                Some(format!("{} Nothing is playing right now", "zzz".blue()))
+                Some(deco!("zzz".blue(), "Nothing is playing right now"))
             }
         }
         "loop" => {
             let mode = obj.get("mode").and_then(|v| v.as_str()).unwrap_or("off");
-            Some(format!("{} Loop mode set to: {}", "🔁".cyan(), mode.bold()))
+            Some(deco!("🔁".cyan(), "Loop mode set to: {}", mode.bold()))
         }
         "247" => {
             let enabled = obj
@@ -922,23 +3021,27 @@ fn summarize(json: &Value) -> Option<String> {
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false);
             if enabled {
-                Some(format!("{} 24/7 mode enabled", "🌙".yellow()))
+                Some(deco!("🌙".yellow(), "24/7 mode enabled"))
             } else {
-                Some(format!("{} 24/7 mode disabled", "☀️".yellow()))
+                Some(deco!("☀️".yellow(), "24/7 mode disabled"))
             }
         }
-        "shuffle" => Some(format!("{} Queue shuffled", "🔀".magenta())),
+        "shuffle" => Some(deco!("🔀".magenta(), "Queue shuffled")),
         "filter" => {
             let msg = obj
                 .get("message")
                 .and_then(|v| v.as_str())
                 .unwrap_or("Filters updated");
-            Some(format!("{} {}", "🎚️".cyan(), msg))
+            Some(deco!("🎚️".cyan(), "{}", msg))
         }
         "lyrics" => {
-            if let Some(data) = obj.get("data").and_then(|v| v.as_object()) {
+            let data = json.get("data").unwrap_or(&Value::Null);
+            let data = shape::parse::<Option<serde_json::Map<String, Value>>>(data).map_err(|e| e.within("data"))?;
+            if let Some(data) = &data {
                 let mut output = String::new();
-                output.push_str(&format!("{}\n\n", "🎤 Lyrics".magenta().bold()));
+                if !is_quiet() {
+                    output.push_str(&format!("{}\n\n", i18n::tr("🎤 Lyrics").magenta().bold()));
+                }
 
                 if let Some(text) = data.get("text").and_then(|v| v.as_str()) {
                     output.push_str(text);
@@ -955,246 +3058,790 @@ fn summarize(json: &Value) -> Option<String> {
                     }
                 }
 
-                if let Some(source) = data.get("sourceName").and_then(|v| v.as_str()) {
-                    output.push_str(&format!("\n\nSource: {}", source.dimmed()));
-                }
-                Some(output)
-            } else {
-                Some(format!("{} No lyrics data found", "ℹ️".blue()))
+                if let Some(source) = data.get("sourceName").and_then(|v| v.as_str())
+                    && !is_quiet()
+                {
+                    output.push_str(&format!("\n\n{}", t!("Source: {}", source.dimmed())));
+                }
+                Some(output)
+            } else {
+                Some(deco!("ℹ️".blue(), "No lyrics data found"))
+            }
+        }
+        _ => None,
+    };
+    Ok(summary)
+}
+
+/// `Title by Author`, or just the title when the author is unknown.
+fn display_title(track: &Track, unknown: &str) -> String {
+    let title = track.title.as_deref().unwrap_or(unknown);
+    match &track.author {
+        Some(author) => t!("{} by {}", title, author),
+        None => title.to_string(),
+    }
+}
+
+/// The track under `key`, if the reply has one.
+fn track_at(json: &Value, key: &str) -> Result<Option<Track>, ShapeError> {
+    shape::parse(json.get(key).unwrap_or(&Value::Null)).map_err(|e| e.within(key))
+}
+
+/// The track list under `key`, empty when the reply has none.
+fn track_list(json: &Value, key: &str) -> Result<Vec<Track>, ShapeError> {
+    shape::parse::<Option<Vec<Track>>>(json.get(key).unwrap_or(&Value::Null))
+        .map(Option::unwrap_or_default)
+        .map_err(|e| e.within(key))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+async fn login(
+    base_url: &str,
+    profile: Option<&str>,
+    port: Option<u16>,
+    callback_host: Option<&str>,
+    bind: Option<std::net::IpAddr>,
+) -> Result<()> {
+    if is_stateless() {
+        return Err(login_unavailable());
+    }
+    // Start a local listener so we can receive the issued bearer token
+    // via a callback redirect from the webhook server. If no callback is
+    // received within the timeout, point at `--device` and `--manual`.
+    let callback_host = callback_host.map(|h| h.trim_start_matches('[').trim_end_matches(']'));
+    let ipv6 = callback_host.is_some_and(|h| h.parse::<std::net::Ipv6Addr>().is_ok());
+    let bind = bind.unwrap_or(if ipv6 {
+        std::net::Ipv6Addr::LOCALHOST.into()
+    } else {
+        std::net::Ipv4Addr::LOCALHOST.into()
+    });
+    let listener = TcpListener::bind((bind, port.unwrap_or(0)))
+        .await
+        .with_context(|| match port {
+            Some(port) => format!("binding port {port} for the callback; is it in use?"),
+            None => "binding local listener; try `jorik auth login --device` or `jorik auth login --manual` instead".to_string(),
+        })?;
+    let local_addr = listener
+        .local_addr()?;
+    let mut callback_url = match callback_host {
+        Some(host) if ipv6 => format!("http://[{}]:{}/oauth-callback", host, local_addr.port()),
+        Some(host) => format!("http://{}:{}/oauth-callback", host, local_addr.port()),
+        None => format!("http://{}/oauth-callback", local_addr),
+    };
+    // Off loopback anyone could call back first, with a token of their own;
+    // only the redirect that carries this state is taken.
+    let state = (!bind.is_loopback()).then(callback_state).transpose()?;
+    if let Some(state) = &state {
+        status!("⚠️".yellow(), "{} is reachable from other machines until the browser calls back", local_addr);
+        callback_url.push_str(&format!("?state={state}"));
+    }
+    if !is_quiet() {
+        status!(
+            "📬".yellow(),
+            "Local callback URL: {}",
+            callback_url.as_str().underline()
+        );
+    }
+
+    // Build authorize URL with callback parameter (the webhook server will
+    // embed this callback into the OAuth `state` so it can redirect back).
+    let mut auth_url =
+        Url::parse(&build_url(base_url, "/authorize")).context("parsing authorize URL")?;
+    auth_url
+        .query_pairs_mut()
+        .append_pair("callback", &callback_url);
+
+    status!("🔑".yellow(), "Opening browser for authorization...");
+    println!("{}", t!("Link: {}", auth_url.as_str().underline()));
+    let _ = that(auth_url.as_str());
+
+    // Wait for the browser's callback, turning away any that don't carry
+    // the state, until the timeout.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(120);
+    loop {
+        match timeout_at(deadline, listener.accept()).await {
+            Ok(Ok((mut stream, _addr))) => {
+                // Read the request (headers should fit into this buffer for our simple case).
+                let mut buf = vec![0u8; 8192];
+                let n = stream
+                    .read(&mut buf)
+                    .await?;
+                let req = String::from_utf8_lossy(&buf[..n]);
+                let first_line = req.lines().next().unwrap_or("");
+                let path = first_line.split_whitespace().nth(1).unwrap_or("");
+                // Prepend a scheme+host so `Url::parse` can parse query params.
+                if let Ok(parsed) = Url::parse(&format!("http://localhost{}", path)) {
+                    if let Some(state) = &state
+                        && !parsed.query_pairs().any(|(k, v)| k == "state" && v == state.as_str())
+                    {
+                        let body = "Wrong state";
+                        let resp = format!(
+                            "HTTP/1.1 403 Forbidden\r\nContent-Length: {}\r\n\r\n{}",
+                            body.len(),
+                            body
+                        );
+                        stream.write_all(resp.as_bytes()).await.ok();
+                        // Anything local can connect here; keep waiting for the browser.
+                        if !is_quiet() {
+                            eprintln!("{}", deco!("⚠️".yellow(), "Turned away a callback that did not come from this login"));
+                        }
+                        continue;
+                    }
+                    let token_pair = parsed.query_pairs().find(|(k, _)| k == "token");
+                    let avatar_pair = parsed.query_pairs().find(|(k, _)| k == "avatar");
+                    let username_pair = parsed.query_pairs().find(|(k, _)| k == "username");
+                    if let Some((_k, v)) = token_pair {
+                        let token = v.into_owned();
+                        let token_trim = token.trim();
+                        if token_trim.is_empty() {
+                            let body = "Missing token";
+                            let resp = format!(
+                                "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\n\r\n{}",
+                                body.len(),
+                                body
+                            );
+                            stream.write_all(resp.as_bytes()).await.ok();
+                            return Err(exit::fail(ExitCode::Auth, t!("No token provided")));
+                        }
+
+                        let avatar_val = avatar_pair.map(|(_, val)| val.into_owned());
+                        let username_val = username_pair.map(|(_, val)| val.into_owned());
+                        save_login(
+                            base_url,
+                            profile,
+                            token_trim,
+                            avatar_val.as_deref(),
+                            username_val.as_deref(),
+                            None,
+                        )?;
+
+                        // Build a small, readable success page and kick off confetti animation.
+                        let escaped_username = username_val
+                            .as_deref()
+                            .map(escape_html)
+                            .unwrap_or_else(|| "User".to_string());
+                        let escaped_avatar = avatar_val.as_deref().map(escape_html);
+                        let saved_path_html = if let Some(path) = config_file_path() {
+                            format!(
+                                "<p>Saved to <code>{}</code></p>",
+                                escape_html(&path.display().to_string())
+                            )
+                        } else {
+                            "".to_string()
+                        };
+
+                        let mut body = String::new();
+                        body.push_str(
+                            r##"<!doctype html><html><head><meta charset="utf-8"/><meta name="viewport" content="width=device-width,initial-scale=1"/><title>Authorization complete</title><style>"##,
+                        );
+                        body.push_str(r##"body{font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",Roboto,\"Helvetica Neue\",Arial, sans-serif;background:#2f3136;color:#dcddde;margin:0;padding:0;display:flex;align-items:center;justify-content:center;height:100vh}"##);
+                        body.push_str(r##".container{max-width:560px;width:100%;padding:28px;background:#36393f;border-radius:12px;box-shadow:0 6px 20px rgba(0,0,0,0.6)}"##);
+                        body.push_str(
+                            r##".header{display:flex;align-items:center;gap:16px;margin-bottom:18px}"##,
+                        );
+                        body.push_str(r##".badge{width:56px;height:56px;display:flex;align-items:center;justify-content:center;border-radius:50%;background:#2f3136}"##);
+                        body.push_str(r##".check{width:34px;height:34px;border-radius:50%;background:#43b581;color:#fff;display:flex;align-items:center;justify-content:center;font-weight:700;font-size:16px}"##);
+                        body.push_str(r##".avatar{width:56px;height:56px;border-radius:50%;object-fit:cover;border:2px solid rgba(0,0,0,0.4)}"##);
+                        body.push_str(r##".user{font-size:16px;font-weight:600;margin:0;color:#fff}"##);
+                        body.push_str(r##".sp{color:#b9bbbe;font-size:13px;margin-top:4px}"##);
+                        body.push_str(r##".path{display:inline-block;background:#2f3136;padding:6px 8px;border-radius:6px;color:#b9bbbe;font-family:monospace;margin-top:8px}"##);
+                        body.push_str(
+                            r##"</style></head><body><div class=\"container\"><div class=\"header\">"##,
+                        );
+                        if let Some(avatar) = &escaped_avatar {
+                            body.push_str(&format!(
+                                r##"<img class=\"avatar\" src=\"{}\" alt=\"avatar"##,
+                                avatar
+                            ));
+                        } else {
+                            body.push_str(r##"<div class=\"badge\"><div class=\"check\">✓</div></div>"##);
+                        }
+                        body.push_str(&format!(
+                            r##"<div><div class=\"user\">{}</div><div class=\"sp\">Authorization complete</div>{}"##,
+                            escaped_username,
+                            saved_path_html
+                        ));
+                        body.push_str(r##"</div><div><p class=\"sp\">Token saved to your config. You may close this window.</p></div>"##);
+
+                        // confetti
+                        body.push_str(r##"<script src=\"https://cdn.jsdelivr.net/npm/canvas-confetti@1.6.0/dist/confetti.browser.min.js\"></script>"##);
+                        body.push_str(
+                            r##"<script>
+      const duration = 15 * 1000,
+        animationEnd = Date.now() + duration,
+        defaults = { startVelocity: 30, spread: 360, ticks: 60, zIndex: 0 };
+
+      function randomInRange(min, max) {
+        return Math.random() * (max - min) + min;
+      }
+
+      const interval = setInterval(function() {
+        const timeLeft = animationEnd - Date.now();
+
+        if (timeLeft <= 0) {
+          return clearInterval(interval);
+        }
+
+        const particleCount = 50 * (timeLeft / duration);
+
+        confetti(
+          Object.assign({}, defaults, {
+            particleCount,
+            origin: { x: randomInRange(0.1, 0.3), y: Math.random() - 0.2 },
+          })
+        );
+        confetti(
+          Object.assign({}, defaults, {
+            particleCount,
+            origin: { x: randomInRange(0.7, 0.9), y: Math.random() - 0.2 },
+          })
+        );
+      }, 250);
+    </script>"##,
+                        );
+                        body.push_str("</div></body></html>");
+
+                        let resp = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nConnection: close\r\n\r\n{}",
+                            body
+                        );
+                        stream.write_all(resp.as_bytes()).await.ok();
+                        stream.shutdown().await.ok();
+
+                        if let Some(path) = config_file_path() {
+                            status!("✔".green(), "Token saved to {}", path.display());
+                        }
+                        return Ok(())
+                    }
+                }
+
+                // If we reached here, callback didn't include a token. Respond with 400 and return OK.
+                let body = "No token in callback";
+                let resp = format!(
+                    "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                stream.write_all(resp.as_bytes()).await.ok();
+                return Ok(());
+            }
+            _ => {
+                bail!(
+                    "No callback received within timeout (120s). If the browser cannot reach this machine (for example over SSH), run `jorik auth login --device`, or `jorik auth login --manual` to paste a token."
+                );
+            }
+        }
+    }
+}
+
+/// A value nobody else can guess for the login callback: 128 bits from the
+/// OS's secure random source.
+fn callback_state() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).context("reading the system's random source")?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Store a freshly issued token, either as the named profile (pinned to
+/// `base_url`) or into the active one.
+fn save_login(
+    base_url: &str,
+    profile: Option<&str>,
+    token: &str,
+    avatar_url: Option<&str>,
+    username: Option<&str>,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    if let Some(name) = profile {
+        let auth = Auth {
+            token: token.to_string(),
+            avatar_url: avatar_url.map(str::to_string),
+            username: username.map(str::to_string),
+            expires_at,
+            base_url: Some(base_url.to_string()),
+        };
+        save_profile(name, &auth)
+    } else {
+        save_token(token, avatar_url, username)?;
+        if expires_at.is_some() {
+            update_saved_token(token, expires_at)?;
+        }
+        Ok(())
+    }
+}
+
+/// `auth login --device`, and plain `auth login` on headless machines: for
+/// when a browser (if any) cannot reach the CLI's 127.0.0.1 callback. The server hands out a
+/// short code that is entered on any other device, and the CLI polls until
+/// a token has been issued for it.
+async fn device_login(client: &Client, base_url: &str, profile: Option<&str>) -> Result<()> {
+    if is_stateless() {
+        return Err(login_unavailable());
+    }
+    let url = build_url(base_url, "/webhook/auth/device");
+    let resp = send(client.post(&url))
+        .await
+        .with_context(|| format!("POST {url}"))?;
+    if !resp.status().is_success() {
+        return Err(exit::fail(
+            exit::for_status(resp.status(), None),
+            t!("Server returned status {}", resp.status()),
+        ));
+    }
+    let code: DeviceCode = resp.json().await.context("parsing device code response")?;
+
+    status!("🔑".yellow(), "On another device, open the link below and enter the code.");
+    println!("{}", t!("Link: {}", code.verification_url.as_str().underline()));
+    println!("{}", t!("Code: {}", code.user_code.bold()));
+    if let Some(complete) = &code.verification_url_complete
+        && !is_quiet()
+    {
+        println!("{}", t!("Or open: {}", complete.as_str().underline()));
+    }
+    if !is_quiet()
+        && let Some(qr) = qr::render(code.verification_url_complete.as_ref().unwrap_or(&code.verification_url))
+    {
+        println!("\n{}\n", qr);
+        status!("📱".cyan(), "Or scan the code above with your phone.");
+    }
+    if !is_quiet() {
+        status!("⏳".cyan(), "Waiting for authorization...");
+    }
+
+    let poll_url = build_url(base_url, "/webhook/auth/device/token");
+    let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+    let mut interval = Duration::from_secs(code.interval.max(1));
+    loop {
+        tokio::time::sleep(interval).await;
+        if Instant::now() >= deadline {
+            return Err(exit::fail(
+                ExitCode::Auth,
+                t!("The code expired before it was entered. Run `jorik auth login --device` again."),
+            ));
+        }
+        let resp = send(
+            client
+                .post(&poll_url)
+                .json(&serde_json::json!({ "device_code": code.device_code })),
+        )
+        .await
+        .with_context(|| format!("POST {poll_url}"))?;
+        let status = resp.status();
+        let reply: DeviceToken = resp.json().await.unwrap_or_default();
+        if let Some(token) = reply.token.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            save_login(
+                base_url,
+                profile,
+                token,
+                reply.avatar_url.as_deref(),
+                reply.username.as_deref(),
+                reply.expires_at,
+            )?;
+            match reply.username.as_deref() {
+                Some(name) => status!("✔".green(), "Logged in as {}", name.bold()),
+                None => status!("✔".green(), "Logged in"),
+            }
+            if let Some(path) = config_file_path() {
+                status!("✔".green(), "Token saved to {}", path.display());
+            }
+            return Ok(());
+        }
+        match reply.error.as_deref() {
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += Duration::from_secs(5),
+            Some("access_denied") => {
+                return Err(exit::fail(ExitCode::Auth, t!("Authorization was denied")));
+            }
+            Some("expired_token") => {
+                return Err(exit::fail(
+                    ExitCode::Auth,
+                    t!("The code expired before it was entered. Run `jorik auth login --device` again."),
+                ));
+            }
+            _ if status.is_success() => {
+                return Err(exit::fail(ExitCode::Auth, t!("No token provided")));
+            }
+            _ => {
+                return Err(exit::fail(
+                    exit::for_status(status, None),
+                    t!("Server returned status {}", status),
+                ));
             }
         }
-        _ => None,
     }
 }
 
-fn escape_html(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
+/// After the server answered 401, ask whether to log in again right away.
+/// Returns whether a new token was saved; never asks when not interactive.
+async fn offer_login(client: &Client, base_url: &str) -> Result<bool> {
+    if !INTERACTIVE.load(Ordering::Relaxed) || is_stateless() {
+        return Ok(false);
+    }
+    status!("🔒".yellow(), "The server no longer accepts your token.");
+    print!("{}", i18n::tr("Log in again now? [Y/n]: "));
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let answer = input.trim();
+    if !(answer.is_empty() || answer.eq_ignore_ascii_case("y")) {
+        return Ok(false);
+    }
+
+    if is_headless() {
+        device_login(client, base_url, None).await?;
+    } else {
+        login(base_url, None, None, None, None).await?;
+    }
+    if !is_quiet() {
+        status!("🔁".cyan(), "Retrying the request...");
+    }
+    Ok(true)
 }
 
-async fn login(base_url: &str) -> Result<()> {
-    // Start a local listener so we can receive the issued bearer token
-    // via a callback redirect from the webhook server. If no callback is
-    // received within the timeout, fall back to the manual paste flow.
-    let listener = TcpListener::bind(("127.0.0.1", 0))
-        .await
-        .context("binding local listener; the legacy manual token-paste flow is deprecated. Please run `jorik auth login` on a device where your browser can redirect to http://127.0.0.1 so the CLI can automatically capture token, avatar and username")?;
-    let local_addr = listener
-        .local_addr()?;
-    let callback_url = format!("http://{}/oauth-callback", local_addr);
-    println!(
-        "{} Local callback URL: {}",
-        "📬".yellow(),
-        callback_url.as_str().underline()
-    );
+/// Whether a browser opened from here would be no use to the user: over
+/// SSH it is not theirs, and without a display (outside macOS and Windows)
+/// there is none.
+fn is_headless() -> bool {
+    let remote = std::env::var_os("SSH_TTY").is_some() || std::env::var_os("SSH_CONNECTION").is_some();
+    let no_display = cfg!(all(unix, not(target_os = "macos")))
+        && std::env::var_os("DISPLAY").is_none()
+        && std::env::var_os("WAYLAND_DISPLAY").is_none();
+    remote || no_display
+}
 
-    // Build authorize URL with callback parameter (the webhook server will
-    // embed this callback into the OAuth `state` so it can redirect back).
-    let mut auth_url =
-        Url::parse(&build_url(base_url, "/authorize")).context("parsing authorize URL")?;
-    auth_url
-        .query_pairs_mut()
-        .append_pair("callback", &callback_url);
+fn login_unavailable() -> anyhow::Error {
+    exit::fail(
+        ExitCode::Usage,
+        t!("`auth login` saves a token and is unavailable with --no-config; pass --token or JORIK_TOKEN instead"),
+    )
+}
 
-    println!("{} Opening browser for authorization...", "🔑".yellow());
-    println!("Link: {}", auth_url.as_str().underline());
-    let _ = that(auth_url.as_str());
+/// `auth login --manual`: for when neither the callback nor another device
+/// can help. The pasted token is checked against `/webhook/auth/whoami`,
+/// which also supplies the username and avatar to save with it.
+async fn manual_login(client: &Client, base_url: &str, profile: Option<&str>) -> Result<()> {
+    if is_stateless() {
+        return Err(login_unavailable());
+    }
+    let token = read_hidden(i18n::tr("Token (input is hidden): "))?;
+    if token.is_empty() {
+        return Err(exit::fail(ExitCode::Auth, t!("No token provided")));
+    }
 
-    // Wait for a single incoming connection (with timeout).
-    match timeout(Duration::from_secs(120), listener.accept()).await {
-        Ok(Ok((mut stream, _addr))) => {
-            // Read the request (headers should fit into this buffer for our simple case).
-            let mut buf = vec![0u8; 8192];
-            let n = stream
-                .read(&mut buf)
-                .await?;
-            let req = String::from_utf8_lossy(&buf[..n]);
-            let first_line = req.lines().next().unwrap_or("");
-            let path = first_line.split_whitespace().nth(1).unwrap_or("");
-            // Prepend a scheme+host so `Url::parse` can parse query params.
-            if let Ok(parsed) = Url::parse(&format!("http://localhost{}", path)) {
-                let token_pair = parsed.query_pairs().find(|(k, _)| k == "token");
-                let avatar_pair = parsed.query_pairs().find(|(k, _)| k == "avatar");
-                let username_pair = parsed.query_pairs().find(|(k, _)| k == "username");
-                if let Some((_k, v)) = token_pair {
-                    let token = v.into_owned();
-                    let token_trim = token.trim();
-                    if token_trim.is_empty() {
-                        let body = "Missing token";
-                        let resp = format!(
-                            "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\n\r\n{}",
-                            body.len(),
-                            body
-                        );
-                        stream.write_all(resp.as_bytes()).await.ok();
-                        bail!("No token provided");
-                    }
+    let url = build_url(base_url, "/webhook/auth/whoami");
+    let resp = send(client.get(&url).bearer_auth(&token))
+        .await
+        .with_context(|| format!("GET {url}"))?;
+    let status = resp.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(exit::fail(ExitCode::Auth, t!("The server rejected this token")));
+    }
+    if !status.is_success() {
+        return Err(exit::fail(
+            exit::for_status(status, None),
+            t!("Server returned status {}", status),
+        ));
+    }
+    let me: WhoamiResponse = resp.json().await.context("parsing whoami response")?;
 
-                    let avatar_val = avatar_pair.map(|(_, val)| val.into_owned());
-                    let username_val = username_pair.map(|(_, val)| val.into_owned());
-                    save_token(token_trim, avatar_val.as_deref(), username_val.as_deref())?;
-
-                    // Build a small, readable success page and kick off confetti animation.
-                    let escaped_username = username_val
-                        .as_deref()
-                        .map(|s| escape_html(s))
-                        .unwrap_or_else(|| "User".to_string());
-                    let escaped_avatar = avatar_val.as_deref().map(|s| escape_html(s));
-                    let saved_path_html = if let Some(path) = config_file_path() {
-                        format!(
-                            "<p>Saved to <code>{}</code></p>",
-                            escape_html(&path.display().to_string())
-                        )
-                    } else {
-                        "".to_string()
-                    };
+    save_login(
+        base_url,
+        profile,
+        &token,
+        me.avatar_url.as_deref(),
+        me.username.as_deref(),
+        None,
+    )?;
+    match me.username.as_deref() {
+        Some(name) => status!("✔".green(), "Logged in as {}", name.bold()),
+        None => status!("✔".green(), "Logged in"),
+    }
+    if let Some(path) = config_file_path() {
+        status!("✔".green(), "Token saved to {}", path.display());
+    }
+    Ok(())
+}
 
-                    let mut body = String::new();
-                    body.push_str(
-                        r##"<!doctype html><html><head><meta charset="utf-8"/><meta name="viewport" content="width=device-width,initial-scale=1"/><title>Authorization complete</title><style>"##,
-                    );
-                    body.push_str(r##"body{font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",Roboto,\"Helvetica Neue\",Arial, sans-serif;background:#2f3136;color:#dcddde;margin:0;padding:0;display:flex;align-items:center;justify-content:center;height:100vh}"##);
-                    body.push_str(r##".container{max-width:560px;width:100%;padding:28px;background:#36393f;border-radius:12px;box-shadow:0 6px 20px rgba(0,0,0,0.6)}"##);
-                    body.push_str(
-                        r##".header{display:flex;align-items:center;gap:16px;margin-bottom:18px}"##,
-                    );
-                    body.push_str(r##".badge{width:56px;height:56px;display:flex;align-items:center;justify-content:center;border-radius:50%;background:#2f3136}"##);
-                    body.push_str(r##".check{width:34px;height:34px;border-radius:50%;background:#43b581;color:#fff;display:flex;align-items:center;justify-content:center;font-weight:700;font-size:16px}"##);
-                    body.push_str(r##".avatar{width:56px;height:56px;border-radius:50%;object-fit:cover;border:2px solid rgba(0,0,0,0.4)}"##);
-                    body.push_str(r##".user{font-size:16px;font-weight:600;margin:0;color:#fff}"##);
-                    body.push_str(r##".sp{color:#b9bbbe;font-size:13px;margin-top:4px}"##);
-                    body.push_str(r##".path{display:inline-block;background:#2f3136;padding:6px 8px;border-radius:6px;color:#b9bbbe;font-family:monospace;margin-top:8px}"##);
-                    body.push_str(
-                        r##"</style></head><body><div class=\"container\"><div class=\"header\">"##,
-                    );
-                    if let Some(avatar) = &escaped_avatar {
-                        body.push_str(&format!(
-                            r##"<img class=\"avatar\" src=\"{}\" alt=\"avatar"##,
-                            avatar
-                        ));
-                    } else {
-                        body.push_str(r##"<div class=\"badge\"><div class=\"check\">✓</div></div>"##);
-                    }
-                    body.push_str(&format!(
-                        r##"<div><div class=\"user\">{}</div><div class=\"sp\">Authorization complete</div>{}"##,
-                        escaped_username,
-                        saved_path_html
-                    ));
-                    body.push_str(r##"</div><div><p class=\"sp\">Token saved to your config. You may close this window.</p></div>"##);
+/// Prompt for a line without echoing it. Piped input is read as is, so
+/// `jorik auth login --manual < token.txt` works too.
+fn read_hidden(prompt: &str) -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
 
-                    // confetti
-                    body.push_str(r##"<script src=\"https://cdn.jsdelivr.net/npm/canvas-confetti@1.6.0/dist/confetti.browser.min.js\"></script>"##);
-                    body.push_str(
-                        r##"<script>
-  const duration = 15 * 1000,
-    animationEnd = Date.now() + duration,
-    defaults = { startVelocity: 30, spread: 360, ticks: 60, zIndex: 0 };
+    print!("{}", prompt);
+    io::stdout().flush()?;
+    if !atty::is(atty::Stream::Stdin) {
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        println!();
+        return Ok(input.trim().to_string());
+    }
 
-  function randomInRange(min, max) {
-    return Math.random() * (max - min) + min;
-  }
+    terminal::enable_raw_mode()?;
+    let mut input = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(exit::fail(ExitCode::Interrupted, t!("Cancelled")));
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            },
+            Ok(Event::Paste(text)) => input.push_str(&text),
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    println!();
+    result.map(|()| input.trim().to_string())
+}
 
-  const interval = setInterval(function() {
-    const timeLeft = animationEnd - Date.now();
+fn not_authenticated() -> anyhow::Error {
+    exit::fail(
+        ExitCode::Auth,
+        t!("Not authenticated. Run `jorik auth login` to authenticate."),
+    )
+}
 
-    if (timeLeft <= 0) {
-      return clearInterval(interval);
+async fn whoami(client: &Client, base_url: &str, token: Option<&str>) -> Result<()> {
+    let Some(token) = token else {
+        return Err(not_authenticated());
+    };
+    let url = build_url(base_url, "/webhook/auth/whoami");
+    let resp = send(client.get(&url).bearer_auth(token))
+        .await
+        .with_context(|| format!("GET {url}"))?;
+    if RAW.load(Ordering::Relaxed) {
+        return print_raw(resp).await;
     }
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        if let Ok(json) = serde_json::from_str::<Value>(&text)
+            && let Ok(Some(summary)) = summarize(&json)
+        {
+            println!("{}", summary);
+            return Err(exit::reported(exit::for_status(status, None)));
+        }
+        return Err(exit::fail(
+            exit::for_status(status, None),
+            t!("Server returned status {}: {}", status, text),
+        ));
+    }
+    let me: WhoamiResponse = resp.json().await.context("parsing whoami response")?;
 
-    const particleCount = 50 * (timeLeft / duration);
-
-    confetti(
-      Object.assign({}, defaults, {
-        particleCount,
-        origin: { x: randomInRange(0.1, 0.3), y: Math.random() - 0.2 },
-      })
-    );
-    confetti(
-      Object.assign({}, defaults, {
-        particleCount,
-        origin: { x: randomInRange(0.7, 0.9), y: Math.random() - 0.2 },
-      })
+    status!(
+        "👤".cyan(),
+        "User: {}{}",
+        me.username.as_deref().unwrap_or(i18n::tr("Unknown")).bold(),
+        me.user_id
+            .as_deref()
+            .map(|id| format!(" ({})", id))
+            .unwrap_or_default()
     );
-  }, 250);
-</script>"##,
-                    );
-                    body.push_str("</div></body></html>");
-
-                    let resp = format!(
-                        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nConnection: close\r\n\r\n{}",
-                        body
-                    );
-                    stream.write_all(resp.as_bytes()).await.ok();
-                    stream.shutdown().await.ok();
-
-                    if let Some(path) = config_file_path() {
-                        println!("{} Token saved to {}", "✔".green(), path.display());
-                    }
-                    return Ok(())
-                }
-            }
-
-            // If we reached here, callback didn't include a token. Respond with 400 and return OK.
-            let body = "No token in callback";
-            let resp = format!(
-                "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\n\r\n{}",
-                body.len(),
-                body
+    if let Some(avatar) = &me.avatar_url {
+        status!("🖼️".cyan(), "Avatar: {}", avatar);
+    }
+    if me.guilds.is_empty() {
+        status!("🏠".cyan(), "Guilds: (none)");
+    } else {
+        status!("🏠".cyan(), "Guilds: {}", me.guilds.len());
+        for guild in &me.guilds {
+            println!(
+                "   - {} ({})",
+                guild.name.as_deref().unwrap_or(i18n::tr("Unknown")),
+                guild.id.dimmed()
             );
-            stream.write_all(resp.as_bytes()).await.ok();
-            Ok(())
         }
-        _ => {
-            bail!(
-                "No callback received within timeout (120s). The legacy manual token-paste flow is deprecated. Please run `jorik auth login` and complete the authorization in your browser so the CLI can automatically capture token, avatar and username."
-            );
+    }
+
+    // Compare against what we have on disk so stale profiles are easy to spot.
+    match load_auth() {
+        Some(saved) => {
+            if saved.token != token {
+                status!(
+                    "ℹ️".blue(),
+                    "Using a token from --token/JORIK_TOKEN, not the saved profile"
+                );
+            } else if saved.username.is_some() && saved.username != me.username {
+                status!(
+                    "⚠️".yellow(),
+                    "Saved username '{}' does not match the server ('{}'). Run `jorik auth login` to refresh it.",
+                    saved.username.as_deref().unwrap_or_default(),
+                    me.username.as_deref().unwrap_or(i18n::tr("Unknown"))
+                );
+            } else {
+                status!("✔".green(), "Saved auth matches the server");
+            }
         }
+        None => status!("ℹ️".blue(), "No saved auth to compare against"),
     }
+    Ok(())
 }
 
-fn auth_info() -> Result<()> {
+async fn auth_info(client: &Client, base_url: &str) -> Result<()> {
     if let Some(auth) = load_auth() {
         if let Some(path) = config_file_path() {
-            println!("{} Auth file: {}", "ℹ️".blue(), path.display());
+            status!("ℹ️".blue(), "Auth file: {}", path.display());
         }
-        println!(
-            "{} User: {}",
+        status!("🗂️".cyan(), "Profile: {}", load_auth_store().active);
+        if let Some(url) = &auth.base_url {
+            status!("🌐".cyan(), "Server: {}", url);
+        }
+        status!(
             "👤".cyan(),
+            "User: {}",
             auth.username
                 .clone()
                 .unwrap_or_else(|| "Unknown".to_string())
         );
-        if let Some(avatar) = auth.avatar_url {
-            println!("{} Avatar: {}", "🖼️".cyan(), avatar);
+        if let Some(avatar) = &auth.avatar_url {
+            status!("🖼️".cyan(), "Avatar: {}", avatar);
         } else {
-            println!("{} Avatar: (none)", "🖼️".cyan());
+            status!("🖼️".cyan(), "Avatar: (none)");
         }
 
-        let token = auth.token;
+        let token = &auth.token;
         let masked = if token.len() > 8 {
             format!("{}...{}", &token[0..4], &token[token.len() - 4..])
         } else {
-            token
+            token.clone()
         };
-        println!("{} Token: {}", "🔑".cyan(), masked);
+        status!("🔑".cyan(), "Token: {}", masked);
+
+        let mut expires_at = auth.expires_at;
+        match introspect(client, base_url, token).await {
+            Ok(Some(info)) if info.active => {
+                status!("✔".green(), "Token is valid");
+                if !info.scopes.is_empty() {
+                    status!("🔐".cyan(), "Scopes: {}", info.scopes.join(", "));
+                }
+                expires_at = info.expires_at.or(expires_at);
+            }
+            Ok(Some(_)) => {
+                status!(
+                    "✘".red(),
+                    "Token is no longer valid. Run `jorik auth login` to authenticate."
+                );
+                return Ok(());
+            }
+            Ok(None) => status!("ℹ️".blue(), "This server cannot check tokens; showing saved details only"),
+            Err(e) if exit::is_dry_run(&e) => return Err(e),
+            Err(e) => status!("⚠️".yellow(), "Could not check the token: {}", format!("{:#}", e)),
+        }
+
+        if let Some(expires) = expires_at.and_then(|ts| chrono::DateTime::from_timestamp(ts, 0)) {
+            status!(
+                "⏳".cyan(),
+                "Expires: {}",
+                expires.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            );
+        }
+        match expires_soon(expires_at) {
+            Some(left) if left <= 0 => status!(
+                "⚠️".yellow(),
+                "Token has expired. Run `jorik auth login` to authenticate."
+            ),
+            Some(left) => status!(
+                "⚠️".yellow(),
+                "Token expires in {}. Run `jorik auth refresh` to renew it.",
+                format_remaining(left)
+            ),
+            None => {}
+        }
         Ok(())
     } else {
-        println!(
-            "{} Not authenticated. Run `jorik auth login` to authenticate.",
-            "ℹ️".blue()
+        status!(
+            "ℹ️".blue(),
+            "Not authenticated. Run `jorik auth login` to authenticate."
         );
         Ok(())
     }
 }
 
+/// Ask the server about `token`. `None` when it has no introspection
+/// endpoint; a 401 reads as an inactive token.
+async fn introspect(client: &Client, base_url: &str, token: &str) -> Result<Option<TokenInfo>> {
+    let url = build_url(base_url, "/webhook/auth/introspect");
+    let resp = send(client.get(&url).bearer_auth(token))
+        .await
+        .with_context(|| format!("GET {url}"))?;
+    match resp.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(None),
+        reqwest::StatusCode::UNAUTHORIZED => Ok(Some(TokenInfo::default())),
+        status if !status.is_success() => Err(exit::fail(
+            exit::for_status(status, None),
+            t!("Server returned status {}", status),
+        )),
+        _ => Ok(Some(resp.json().await.context("parsing introspection response")?)),
+    }
+}
+
+async fn auth_refresh(client: &Client, base_url: &str, token: Option<&str>) -> Result<()> {
+    let Some(token) = token else {
+        return Err(not_authenticated());
+    };
+    if !is_quiet() {
+        // stderr: stdout may get the new token.
+        eprintln!("{}", deco!("🔄".yellow(), "Refreshing token..."));
+    }
+    let (refreshed, saved) = refresh_token(client, base_url, token).await?;
+    let expires = refreshed
+        .expires_at
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|at| at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string());
+    if !saved {
+        // Nothing saved to update: the new token goes to stdout, alone.
+        eprintln!("{}", deco!("✔".green(), "Token refreshed; it was not saved, so here it is"));
+        if let Some(expires) = expires {
+            eprintln!("{}", deco!("⏳".cyan(), "Expires: {}", expires));
+        }
+        println!("{}", refreshed.token);
+        return Ok(());
+    }
+    if let Some(path) = config_file_path() {
+        status!("✔".green(), "Token refreshed and saved to {}", path.display());
+    }
+    if let Some(expires) = expires {
+        status!("⏳".cyan(), "Expires: {}", expires);
+    }
+    Ok(())
+}
+
+fn auth_token(token: Option<&str>, show: bool) -> Result<()> {
+    let Some(token) = token else {
+        return Err(not_authenticated());
+    };
+    if !show {
+        return Err(exit::fail(
+            ExitCode::Usage,
+            t!("Refusing to print the token without --show. Run `jorik auth token --show`."),
+        ));
+    }
+    println!("{}", token);
+    Ok(())
+}
+
 async fn signout(client: &Client, base_url: &str, token: Option<&str>) -> Result<()> {
     // If token present, attempt to revoke it on the server first.
     if let Some(tok) = token {
-        println!("{} Revoking token on server...", "🔒".yellow());
+        if !is_quiet() {
+            status!("🔒".yellow(), "Revoking token on server...");
+        }
         let url = build_url(base_url, "/webhook/auth/revoke");
-        match client.post(&url).bearer_auth(tok).send().await {
+        match send(client.post(&url).bearer_auth(tok)).await {
             Ok(resp) => {
                 if resp.status().is_success() {
                     match resp.json::<serde_json::Value>().await {
@@ -1204,38 +3851,468 @@ async fn signout(client: &Client, base_url: &str, token: Option<&str>) -> Result
                                 .and_then(|v| v.as_bool())
                                 .unwrap_or(false);
                             if revoked {
-                                println!("{} Server revoked token", "✔".green());
+                                status!("✔".green(), "Server revoked token");
                             } else {
-                                println!("{} Server did not revoke token", "ℹ️".blue());
+                                status!("ℹ️".blue(), "Server did not revoke token");
                             }
                         }
                         Err(e) => {
-                            println!("{} Failed to parse server response: {}", "✘".red(), e);
+                            status!("✘".red(), "Failed to parse server response: {}", e);
                         }
                     }
                 } else {
-                    println!("{} Server returned status {}", "✘".red(), resp.status());
+                    status!("✘".red(), "Server returned status {}", resp.status());
                 }
             }
+            Err(e) if exit::is_dry_run(&e) => return Err(e),
             Err(e) => {
-                println!(
-                    "{} Failed to contact server to revoke token: {}",
+                status!(
                     "✘".red(),
+                    "Failed to contact server to revoke token: {}",
                     e
                 );
             }
         }
     } else {
-        println!("{} No token present; skipping server revoke", "ℹ️".blue());
+        if !is_quiet() {
+            status!("ℹ️".blue(), "No token present; skipping server revoke");
+        }
     }
 
-    // Remove local auth file regardless of remote result
+    if is_stateless() {
+        return Ok(());
+    }
+
+    // Remove the local profile regardless of remote result
     let path = config_file_path().context("cannot determine config path")?;
-    if path.exists() {
-        fs::remove_file(&path).context("removing auth file")?;
-        println!("{} Signed out and removed {}", "✔".green(), path.display());
+    if let Some(name) = remove_active_profile()? {
+        status!(
+            "✔".green(),
+            "Signed out and removed profile '{}' from {}",
+            name,
+            path.display()
+        );
+        let store = load_auth_store();
+        if !store.profiles.is_empty() {
+            status!("ℹ️".blue(), "Active profile is now '{}'", store.active);
+        }
+    } else {
+        status!("ℹ️".blue(), "No auth found");
+    }
+    Ok(())
+}
+
+fn settings_value() -> Result<serde_json::Map<String, Value>> {
+    match serde_json::to_value(load_settings()).context("serializing settings")? {
+        Value::Object(map) => Ok(map),
+        _ => unreachable!("Settings serializes to an object"),
+    }
+}
+
+fn unknown_setting(key: &str, map: &serde_json::Map<String, Value>) -> anyhow::Error {
+    let known: Vec<&str> = map.keys().map(String::as_str).collect();
+    exit::fail(
+        ExitCode::Usage,
+        t!("Unknown setting '{}'. Known settings: {}", key, known.join(", ")),
+    )
+}
+
+fn config_show() -> Result<()> {
+    let map = settings_value()?;
+    if let Some(path) = settings_file_path()
+        && !is_quiet()
+    {
+        status!("⚙️".cyan(), "{}", path.display());
+    }
+    for (key, value) in &map {
+        println!("{} = {}", key.bold(), value);
+    }
+    Ok(())
+}
+
+fn config_get(key: &str) -> Result<()> {
+    let map = settings_value()?;
+    match map.get(key) {
+        Some(Value::String(s)) => println!("{}", s),
+        Some(Value::Null) => {}
+        Some(value) => println!("{}", value),
+        None => return Err(unknown_setting(key, &map)),
+    }
+    Ok(())
+}
+
+/// Set `key` to `value`, or back to its default when `value` is `None`.
+fn config_set(key: &str, value: Option<&str>) -> Result<()> {
+    let mut map = settings_value()?;
+    if !map.contains_key(key) {
+        return Err(unknown_setting(key, &map));
+    }
+
+    if key == "language"
+        && let Some(lang) = value
+        && lang != "auto"
+        && !i18n::available().any(|l| l == lang)
+    {
+        let known: Vec<&str> = i18n::available().collect();
+        return Err(exit::fail(
+            ExitCode::Usage,
+            t!("Unknown language '{}'. Available: auto, {}", lang, known.join(", ")),
+        ));
+    }
+
+    let settings = match value {
+        None => {
+            map.remove(key);
+            serde_json::from_value::<Settings>(Value::Object(map))
+                .context("resetting setting")?
+        }
+        Some(raw) => with_setting(&load_settings(), key, raw).ok_or_else(|| {
+            exit::fail(
+                ExitCode::Usage,
+                t!("Invalid value '{}' for setting '{}'", raw, key),
+            )
+        })?,
+    };
+
+    save_settings(&settings)?;
+    if value.is_none() {
+        forget_keys(key)?;
+    }
+    let shown = serde_json::to_value(&settings)
+        .ok()
+        .and_then(|v| v.get(key).cloned())
+        .unwrap_or(Value::Null);
+    status!("✔".green(), "{} = {}", key.bold(), shown);
+    Ok(())
+}
+
+/// `config sources`: each setting as this run sees it, and which layer
+/// (flag, environment, config file, login profile or default) supplied it.
+fn config_sources(
+    settings: &Settings,
+    base_url: &str,
+    base_url_given: bool,
+    token: Option<&str>,
+    retries_flag: Option<u32>,
+    no_update_check: bool,
+) -> Result<()> {
+    let effective = match serde_json::to_value(settings).context("serializing settings")? {
+        Value::Object(map) => map,
+        _ => unreachable!("Settings serializes to an object"),
+    };
+    // Keys actually written in the file, as opposed to filled in by serde.
+    let in_file: Vec<String> = settings_file_path()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|text| text.parse::<toml::Table>().ok())
+        .map(|t| t.keys().cloned().collect())
+        .unwrap_or_default();
+    let file_name = settings_file_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "config.toml".to_string());
+    let from_env = |var: &str, value: &str| std::env::var(var).is_ok_and(|v| v == value);
+
+    let mut rows: Vec<(String, String, String)> = Vec::new();
+    // Mirrors how `main` settles it: flag or env, else the login profile's
+    // server, else the setting.
+    let base_url_source = if base_url_given && from_env("JORIK_BASE_URL", base_url) {
+        "env JORIK_BASE_URL".to_string()
+    } else if base_url_given {
+        "flag --base-url".to_string()
+    } else if load_auth().and_then(|a| a.base_url).as_deref() == Some(base_url) {
+        t!("profile {}", load_auth_store().active)
+    } else if base_url != DEFAULT_BASE_URL && base_url == settings.base_url {
+        file_name.clone()
+    } else {
+        i18n::tr("default").to_string()
+    };
+    rows.push(("base_url".to_string(), base_url.to_string(), base_url_source));
+
+    let token_source = match token {
+        Some(t) if from_env("JORIK_TOKEN", t) => "env JORIK_TOKEN".to_string(),
+        Some(_) => "flag --token".to_string(),
+        None if load_auth().is_some() => t!("profile {}", load_auth_store().active),
+        None => i18n::tr("(none)").to_string(),
+    };
+    let token_shown = if token.is_some() || load_auth().is_some() { "<set>" } else { "" };
+    rows.push(("token".to_string(), token_shown.to_string(), token_source));
+
+    for (key, _) in SETTING_DOCS.iter().filter(|(k, _)| *k != "base_url") {
+        let mut value = effective.get(*key).map(Value::to_string).unwrap_or_default();
+        let source = match *key {
+            "retries" if let Some(n) = retries_flag => {
+                value = n.to_string();
+                "flag --retries".to_string()
+            }
+            "update_check" if no_update_check => "flag --no-update-check".to_string(),
+            _ if std::env::var_os(setting_env_var(key)).is_some() => format!("env {}", setting_env_var(key)),
+            _ if in_file.iter().any(|k| k == key) => file_name.clone(),
+            _ => i18n::tr("default").to_string(),
+        };
+        rows.push((key.to_string(), value, source));
+    }
+
+    let width = rows.iter().map(|(k, _, _)| k.len()).max().unwrap_or(0);
+    for (key, value, source) in rows {
+        println!("{:width$} = {}  {}", key.bold(), value, format!("({})", source).dimmed(), width = width);
+    }
+    Ok(())
+}
+
+fn config_export(file: &std::path::Path, no_secrets: bool) -> Result<()> {
+    let auth = Some(load_auth_store())
+        .filter(|store| !no_secrets && (!store.profiles.is_empty() || !store.keys.is_empty()));
+    let settings = load_settings();
+    let with_secrets = auth.is_some();
+    let bundle = ConfigBundle {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        settings,
+        auth,
+    };
+    let body = toml::to_string(&bundle).context("serializing settings")?;
+    let text = format!(
+        "# Exported by jorik-cli {}. Load it with `jorik config import <file>`.\n{}",
+        bundle.version, body
+    );
+
+    if file.as_os_str() == "-" {
+        print!("{}", text);
     } else {
-        println!("{} No auth found", "ℹ️".blue());
+        // Readable by the owner only, as auth.json is, since tokens may be in it.
+        write_private(file, &text).with_context(|| format!("writing {}", file.display()))?;
+        status!("✔".green(), "Exported settings to {}", file.display());
+    }
+    if with_secrets && !is_quiet() {
+        eprintln!(
+            "{}",
+            deco!("⚠️".yellow(), "The export contains your tokens; keep it private or use --no-secrets.")
+        );
+    }
+    Ok(())
+}
+
+fn config_import(file: &std::path::Path, no_secrets: bool) -> Result<()> {
+    let contents = std::fs::read_to_string(file).with_context(|| format!("reading {}", file.display()))?;
+    let bundle: ConfigBundle = toml::from_str(&contents)
+        .map_err(|e| exit::fail(ExitCode::Usage, t!("{} is not a jorik config export: {}", file.display(), e)))?;
+
+    // Keep what is replaced, even a file too broken to load.
+    if let Some(current) = settings_file_path()
+        && current.exists()
+    {
+        let backup = current.with_extension("toml.bak");
+        std::fs::copy(&current, &backup).context("backing up config.toml")?;
+        std::fs::remove_file(&current).context("replacing config.toml")?;
+        if !is_quiet() {
+            status!("ℹ️".blue(), "Previous settings kept in {}", backup.display());
+        }
+    }
+    save_settings(&bundle.settings)?;
+    status!("✔".green(), "Imported settings from {}", file.display());
+    if !bundle.settings.hooks.is_empty() {
+        // They run on their own later, so say so while it is still news.
+        status!("⚠️".yellow(), "The imported settings run commands on playback events; check [hooks] in config.toml");
+    }
+
+    if no_secrets {
+        return Ok(());
+    }
+    // Exports from before the keys moved to auth.json carry them in the settings.
+    let mut keys = bundle.settings.keys();
+    let mut names = Vec::new();
+    let mut store = load_auth_store();
+    if let Some(imported) = bundle.auth {
+        if store.profiles.is_empty() {
+            store.active = imported.active;
+        }
+        for (name, auth) in imported.profiles {
+            // A login already here may be newer than the exported one.
+            if store.profiles.contains_key(&name) {
+                eprintln!(
+                    "{}",
+                    deco!("⚠️".yellow(), "Kept the saved login '{}' rather than the one in the file", name)
+                );
+                continue;
+            }
+            store.profiles.insert(name.clone(), auth);
+            names.push(name);
+        }
+        keys.extend(imported.keys);
+    }
+    if names.is_empty() && keys.is_empty() {
+        return Ok(());
+    }
+    store.keys.extend(keys);
+    save_auth_store(&store)?;
+    if !names.is_empty() {
+        status!("✔".green(), "Imported logins: {}", names.join(", "));
+    }
+    Ok(())
+}
+
+/// How long `scrobble enable lastfm` waits for the browser approval.
+const LASTFM_APPROVAL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// `scrobble enable`: check the keys with the service, then save them
+/// under `[scrobble]`, replacing any account set up before.
+async fn scrobble_enable(client: &Client, service: ScrobbleService) -> Result<()> {
+    let lastfm_refused = |e: anyhow::Error| match e.downcast_ref::<scrobble::LastFmError>() {
+        Some(refusal) => exit::fail(ExitCode::Auth, refusal.to_string()),
+        None => e,
+    };
+    let account = match service {
+        ScrobbleService::Lastfm { api_key, api_url } => {
+            let api = api_url.clone().unwrap_or_else(|| scrobble::LASTFM_API.to_string());
+            let api_secret = read_hidden(i18n::tr("Shared secret (input is hidden): "))?;
+            if api_secret.is_empty() {
+                return Err(exit::fail(ExitCode::Auth, t!("No shared secret provided")));
+            }
+            let token = scrobble::lastfm_token(client, &api, &api_key, &api_secret)
+                .await
+                .map_err(lastfm_refused)?;
+            let auth_url = scrobble::lastfm_auth_url(&api, &api_key, &token);
+            status!("🔑".yellow(), "Allow jorik-cli to scrobble to your account in the browser.");
+            println!("{}", t!("Link: {}", auth_url.as_str().underline()));
+            if !is_headless() {
+                let _ = that(&auth_url);
+            }
+            if !is_quiet() {
+                status!("⏳".cyan(), "Waiting for authorization...");
+            }
+            let deadline = Instant::now() + LASTFM_APPROVAL_TIMEOUT;
+            let (session_key, username) = loop {
+                if let Some(session) = scrobble::lastfm_session(client, &api, &api_key, &api_secret, &token)
+                    .await
+                    .map_err(lastfm_refused)?
+                {
+                    break session;
+                }
+                if Instant::now() >= deadline {
+                    return Err(exit::fail(
+                        ExitCode::Auth,
+                        t!("Access was not allowed in time. Run `jorik scrobble enable lastfm` again."),
+                    ));
+                }
+                tokio::time::sleep(Duration::from_secs(3)).await;
+            };
+            ScrobbleSettings {
+                service: Some("lastfm".to_string()),
+                username: Some(username),
+                api_url,
+                lastfm_api_key: Some(api_key),
+                lastfm_api_secret: Some(api_secret),
+                lastfm_session_key: Some(session_key),
+                ..Default::default()
+            }
+        }
+        ScrobbleService::Listenbrainz { api_url } => {
+            let api = api_url.clone().unwrap_or_else(|| scrobble::LISTENBRAINZ_API.to_string());
+            let token = read_hidden(i18n::tr("Token (input is hidden): "))?;
+            if token.is_empty() {
+                return Err(exit::fail(ExitCode::Auth, t!("No token provided")));
+            }
+            let Some(username) = scrobble::listenbrainz_user(client, &api, &token).await? else {
+                return Err(exit::fail(ExitCode::Auth, t!("ListenBrainz rejected this token")));
+            };
+            ScrobbleSettings {
+                service: Some("listenbrainz".to_string()),
+                username: Some(username),
+                api_url,
+                listenbrainz_token: Some(token),
+                ..Default::default()
+            }
+        }
+    };
+
+    let mut settings = load_settings();
+    let service = scrobble::service_name(account.service.as_deref().unwrap_or_default()).to_string();
+    let username = account.username.clone().unwrap_or_default();
+    settings.scrobble = account;
+    save_keys(&settings)?;
+    save_settings(&settings)?;
+    status!("✔".green(), "Scrobbling to {} as {}", service, username.bold());
+    if !is_quiet() {
+        status!("ℹ️".blue(), "Tracks are scrobbled while `jorik tui` is open.");
+    }
+    Ok(())
+}
+
+fn scrobble_status(scrobble: &ScrobbleSettings) {
+    let Some(service) = scrobble.service.as_deref() else {
+        status!(
+            "ℹ️".blue(),
+            "Scrobbling is off. Turn it on with `jorik scrobble enable lastfm` or `jorik scrobble enable listenbrainz`."
+        );
+        return;
+    };
+    let name = scrobble::service_name(service);
+    if scrobble::Scrobbler::new(Client::new(), scrobble).is_none() {
+        status!(
+            "⚠️".yellow(),
+            "Keys for {} are missing; run `jorik scrobble enable {}` again.",
+            name,
+            service
+        );
+        return;
+    }
+    status!("✔".green(), "Scrobbling to {}", name);
+    status!("👤".cyan(), "User: {}", scrobble.username.as_deref().unwrap_or(i18n::tr("Unknown")));
+    if let Some(url) = &scrobble.api_url {
+        status!("🌐".cyan(), "Server: {}", url);
     }
+    if !is_quiet() {
+        status!("ℹ️".blue(), "Tracks are scrobbled while `jorik tui` is open.");
+    }
+}
+
+fn scrobble_disable() -> Result<()> {
+    let mut settings = load_settings();
+    if settings.scrobble.service.is_none() && !settings.scrobble.has_secrets() {
+        status!("ℹ️".blue(), "Scrobbling is already off.");
+        return Ok(());
+    }
+    settings.scrobble = ScrobbleSettings::default();
+    save_keys(&settings)?;
+    save_settings(&settings)?;
+    status!("✔".green(), "Scrobbling is off and its keys are removed");
+    Ok(())
+}
+
+fn auth_switch(name: &str) -> Result<()> {
+    let mut store = load_auth_store();
+    if !store.profiles.contains_key(name) {
+        let known: Vec<&str> = store.profiles.keys().map(String::as_str).collect();
+        return Err(exit::fail(
+            ExitCode::Usage,
+            t!(
+                "No profile named '{}'. Known profiles: {}",
+                name,
+                if known.is_empty() { i18n::tr("(none)").to_string() } else { known.join(", ") }
+            ),
+        ));
+    }
+    store.active = name.to_string();
+    save_auth_store(&store)?;
+    status!("✔".green(), "Switched to profile {}", name.bold());
     Ok(())
 }
+
+fn auth_profiles() {
+    let store = load_auth_store();
+    if store.profiles.is_empty() {
+        status!(
+            "ℹ️".blue(),
+            "No profiles saved. Run `jorik auth login --profile <name>` to create one."
+        );
+        return;
+    }
+    for (name, auth) in &store.profiles {
+        let marker = if *name == store.active { "*".green().bold() } else { " ".normal() };
+        println!(
+            "{} {} — {} @ {}",
+            marker,
+            name.bold(),
+            auth.username.as_deref().unwrap_or(i18n::tr("Unknown")),
+            auth.base_url.as_deref().unwrap_or("(settings host)")
+        );
+    }
+}
@@ -0,0 +1,583 @@
+//! `jorik mpd-bridge`: enough of the MPD protocol for mpc, ncmpcpp and
+//! other MPD clients to drive one guild's player.
+//!
+//! The bridge keeps no state of its own: every answer comes from the
+//! webhook API. Its playlist is the current track at position 0 followed
+//! by the upcoming ones, so a track leaves it once played, as if MPD's
+//! consume mode were always on.
+
+use crate::api::{JorikClient, LoopPayload, QueueItemPayload, QueuePayload, QueueResponse, SeekPayload, Track};
+use anyhow::Result;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Write as _;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+
+/// The protocol version announced on connect; clients gate features on it.
+const PROTOCOL_VERSION: &str = "0.23.0";
+
+/// How many upcoming tracks the playlist holds.
+const PLAYLIST_LIMIT: usize = 100;
+
+/// How long one fetch of the player answers for. Clients send `status` and
+/// `currentsong` back to back; this spares the server the second request.
+const CACHE_FOR: Duration = Duration::from_secs(1);
+
+/// How often an `idle` client's player is checked for changes.
+const IDLE_POLL: Duration = Duration::from_secs(2);
+
+// MPD's ACK error codes.
+const ACK_ARG: u32 = 2;
+const ACK_UNKNOWN: u32 = 5;
+const ACK_NO_EXIST: u32 = 50;
+const ACK_SYSTEM: u32 = 52;
+
+/// Every command the bridge answers, for `commands`.
+const COMMANDS: &[&str] = &[
+    "add", "binarylimit", "clear", "close", "commands", "currentsong", "decoders", "delete", "deleteid", "idle",
+    "listplaylists", "lsinfo", "next", "noidle", "notcommands", "outputs", "password", "pause", "ping", "play",
+    "playid", "playlistid", "playlistinfo", "plchanges", "random", "repeat", "replay_gain_status", "seek",
+    "seekcur", "seekid", "single", "stats", "status", "stop", "tagtypes", "urlhandlers",
+];
+
+/// A command refused with `ACK [code@index] {command} message`.
+struct Ack {
+    code: u32,
+    message: String,
+}
+
+impl Ack {
+    fn new(code: u32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for Ack {
+    fn from(err: anyhow::Error) -> Self {
+        Self::new(ACK_SYSTEM, format!("{err:#}").replace('\n', " "))
+    }
+}
+
+/// The guild's player as the API last described it.
+#[derive(Clone, Default)]
+struct Player {
+    current: Option<Track>,
+    upcoming: Vec<Track>,
+    elapsed_ms: u64,
+    duration_ms: u64,
+    paused: bool,
+    /// `off`, `track` or `queue`
+    loop_mode: String,
+    shuffle: bool,
+}
+
+impl Player {
+    fn len(&self) -> usize {
+        usize::from(self.current.is_some()) + self.upcoming.len()
+    }
+
+    /// `(pos, track)` for the whole playlist, skipping the empty slot 0
+    /// when nothing is playing.
+    fn songs(&self) -> impl Iterator<Item = (usize, &Track)> {
+        let current = self.current.iter().map(|t| (0, t));
+        current.chain(self.upcoming.iter().enumerate().map(|(i, t)| (i + 1, t)))
+    }
+
+    /// MPD's playlist version: it changes whenever the playlist does, which
+    /// is what clients compare to know to fetch it again.
+    fn version(&self) -> u32 {
+        let mut hasher = DefaultHasher::new();
+        for (pos, track) in self.songs() {
+            pos.hash(&mut hasher);
+            track.title.hash(&mut hasher);
+            track.author.hash(&mut hasher);
+            track.uri.hash(&mut hasher);
+        }
+        // MPD starts at 1; 0 would read as "never fetched".
+        (hasher.finish() as u32).max(1)
+    }
+
+    fn state(&self) -> &'static str {
+        match (&self.current, self.paused) {
+            (None, _) => "stop",
+            (Some(_), true) => "pause",
+            (Some(_), false) => "play",
+        }
+    }
+
+    /// The subsystems `idle` reports as changed between `self` and `now`.
+    fn changes(&self, now: &Player) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        let same_track = |a: &Option<Track>, b: &Option<Track>| {
+            a.as_ref().map(|t| (&t.title, &t.uri)) == b.as_ref().map(|t| (&t.title, &t.uri))
+        };
+        if !same_track(&self.current, &now.current) || self.paused != now.paused {
+            changed.push("player");
+        }
+        if self.version() != now.version() {
+            changed.push("playlist");
+        }
+        if self.loop_mode != now.loop_mode || self.shuffle != now.shuffle {
+            changed.push("options");
+        }
+        changed
+    }
+}
+
+/// What every connection shares: the API and the last fetch of the player.
+struct Bridge {
+    client: JorikClient,
+    guild_id: String,
+    cache: Mutex<Option<(Instant, Player)>>,
+}
+
+impl Bridge {
+    /// The player, fetched again unless the last fetch is fresh.
+    async fn player(&self) -> Result<Player> {
+        let mut cache = self.cache.lock().await;
+        if let Some((at, player)) = &*cache
+            && at.elapsed() < CACHE_FOR
+        {
+            return Ok(player.clone());
+        }
+        let player = self.fetch().await?;
+        *cache = Some((Instant::now(), player.clone()));
+        Ok(player)
+    }
+
+    async fn fetch(&self) -> Result<Player> {
+        let payload = QueuePayload {
+            action: "queue",
+            guild_id: Some(self.guild_id.clone()),
+            user_id: None,
+            limit: PLAYLIST_LIMIT,
+            offset: 0,
+        };
+        let (queue, now_playing) = tokio::join!(self.client.audio(&payload), self.client.now_playing(&self.guild_id));
+        let queue = queue?;
+        let parsed = QueueResponse::from_json(&queue)?;
+        let flags = queue.get("queue").filter(|q| q.is_object()).unwrap_or(&queue);
+        let flag = |keys: &[&str]| keys.iter().find_map(|k| flags.get(*k).or_else(|| queue.get(*k)));
+        let loop_mode = match flag(&["loop_mode", "loopMode", "loop", "repeat", "repeatMode"]).and_then(Value::as_str) {
+            Some("track" | "song") => "track",
+            Some("queue" | "all") => "queue",
+            _ => "off",
+        };
+        let np = now_playing?.unwrap_or_default();
+        Ok(Player {
+            current: np.track.or(parsed.current.flatten()),
+            upcoming: parsed.upcoming.unwrap_or_default(),
+            elapsed_ms: np.elapsed_ms,
+            duration_ms: np.duration_ms,
+            paused: np.paused || flag(&["paused"]).and_then(Value::as_bool).unwrap_or(false),
+            loop_mode: loop_mode.to_string(),
+            shuffle: flag(&["shuffle", "shuffled"]).and_then(Value::as_bool).unwrap_or(false),
+        })
+    }
+
+    /// Send an action; the next command sees what it did.
+    async fn act<T: serde::Serialize>(&self, payload: &T) -> Result<(), Ack> {
+        self.client.audio(payload).await?;
+        self.cache.lock().await.take();
+        Ok(())
+    }
+
+    async fn simple(&self, action: &'static str) -> Result<(), Ack> {
+        self.client.simple(action, &self.guild_id).await?;
+        self.cache.lock().await.take();
+        Ok(())
+    }
+
+    fn item(&self, action: &'static str, position: usize) -> QueueItemPayload {
+        QueueItemPayload {
+            action,
+            guild_id: Some(self.guild_id.clone()),
+            user_id: None,
+            position,
+        }
+    }
+
+    async fn set_loop(&self, mode: &str) -> Result<(), Ack> {
+        self.act(&LoopPayload {
+            action: "loop",
+            guild_id: Some(self.guild_id.clone()),
+            user_id: None,
+            loop_mode: mode.to_string(),
+        })
+        .await
+    }
+
+    /// Play playlist position `pos`: resume for 0, jump ahead otherwise.
+    async fn play(&self, pos: Option<usize>) -> Result<(), Ack> {
+        let player = self.player().await?;
+        match pos {
+            None | Some(0) if player.paused => self.simple("pause").await,
+            None | Some(0) => Ok(()),
+            Some(pos) if pos < player.len() => self.act(&self.item("jump", pos)).await,
+            Some(_) => Err(Ack::new(ACK_ARG, "Bad song index")),
+        }
+    }
+
+    async fn seek(&self, pos: usize, time: &str) -> Result<(), Ack> {
+        if pos != 0 {
+            return Err(Ack::new(ACK_ARG, "Only the current song can be seeked"));
+        }
+        let (sign, secs) = match time.chars().next() {
+            Some(c @ ('+' | '-')) => (Some(c), &time[1..]),
+            _ => (None, time),
+        };
+        let secs = secs
+            .parse::<f64>()
+            .ok()
+            .filter(|s| s.is_finite() && *s >= 0.0)
+            .ok_or_else(|| Ack::new(ACK_ARG, format!("Not a number: {time}")))?;
+        let ms = (secs * 1000.0) as u64;
+        let elapsed = self.player().await?.elapsed_ms;
+        let position_ms = match sign {
+            Some('+') => elapsed.saturating_add(ms),
+            Some(_) => elapsed.saturating_sub(ms),
+            None => ms,
+        };
+        self.act(&SeekPayload {
+            action: "seek",
+            guild_id: Some(self.guild_id.clone()),
+            user_id: None,
+            position_ms,
+        })
+        .await
+    }
+
+    /// Run one command, returning the lines of its reply without the `OK`.
+    async fn run(&self, name: &str, args: &[String]) -> Result<String, Ack> {
+        let arg = |i: usize| args.get(i).map(String::as_str);
+        let mut out = String::new();
+        match name {
+            "ping" | "password" | "binarylimit" => {}
+            "tagtypes" if args.is_empty() => out.push_str("tagtype: Artist\ntagtype: Title\n"),
+            // `tagtypes enable …` and the like: the two tags are all there is.
+            "tagtypes" => {}
+            "commands" => {
+                for command in COMMANDS {
+                    writeln!(out, "command: {command}").ok();
+                }
+            }
+            "notcommands" | "decoders" | "listplaylists" | "lsinfo" | "urlhandlers" => {}
+            "outputs" => out.push_str("outputid: 0\noutputname: Jorik\nplugin: jorik\noutputenabled: 1\n"),
+            "replay_gain_status" => out.push_str("replay_gain_mode: off\n"),
+            "stats" => {
+                let player = self.player().await?;
+                writeln!(out, "songs: {}\nuptime: 0\nplaytime: 0", player.len()).ok();
+            }
+            "status" => {
+                let player = self.player().await?;
+                let flag = |on: bool| u8::from(on);
+                writeln!(out, "volume: -1").ok();
+                writeln!(out, "repeat: {}", flag(player.loop_mode != "off")).ok();
+                writeln!(out, "random: {}", flag(player.shuffle)).ok();
+                writeln!(out, "single: {}", flag(player.loop_mode == "track")).ok();
+                writeln!(out, "consume: 1").ok();
+                writeln!(out, "playlist: {}", player.version()).ok();
+                writeln!(out, "playlistlength: {}", player.len()).ok();
+                writeln!(out, "state: {}", player.state()).ok();
+                if player.current.is_some() {
+                    let (elapsed, duration) = (player.elapsed_ms as f64 / 1000.0, player.duration_ms as f64 / 1000.0);
+                    writeln!(out, "song: 0\nsongid: 1").ok();
+                    writeln!(out, "time: {}:{}", player.elapsed_ms / 1000, player.duration_ms / 1000).ok();
+                    writeln!(out, "elapsed: {elapsed:.3}\nduration: {duration:.3}").ok();
+                }
+                if !player.upcoming.is_empty() {
+                    writeln!(out, "nextsong: 1\nnextsongid: 2").ok();
+                }
+            }
+            "currentsong" => {
+                if let Some(track) = &self.player().await?.current {
+                    write_song(&mut out, 0, track);
+                }
+            }
+            "playlistinfo" | "playlistid" | "plchanges" => {
+                let player = self.player().await?;
+                let range = match (name, arg(0)) {
+                    (_, None) => 0..usize::MAX,
+                    // Changes since a version: everything, unless it is this one.
+                    ("plchanges", Some(version)) if version == player.version().to_string() => 0..0,
+                    ("plchanges", Some(_)) => 0..usize::MAX,
+                    ("playlistid", Some(id)) => {
+                        let pos = id.parse::<usize>().ok().and_then(|id| id.checked_sub(1)).filter(|&p| p < player.len());
+                        let pos = pos.ok_or_else(|| Ack::new(ACK_NO_EXIST, "No such song"))?;
+                        pos..pos + 1
+                    }
+                    (_, Some(range)) => parse_range(range, player.len())?,
+                };
+                for (pos, track) in player.songs().filter(|(pos, _)| range.contains(pos)) {
+                    write_song(&mut out, pos, track);
+                }
+            }
+            "play" => self.play(arg(0).map(parse_pos).transpose()?).await?,
+            "playid" => {
+                let pos = arg(0).map(parse_pos).transpose()?.map(|id| id.saturating_sub(1));
+                self.play(pos).await?;
+            }
+            "pause" => {
+                let player = self.player().await?;
+                let wanted = match arg(0) {
+                    Some("1") => true,
+                    Some("0") => false,
+                    Some(other) => return Err(Ack::new(ACK_ARG, format!("Boolean (0/1) expected: {other}"))),
+                    None => !player.paused,
+                };
+                if player.current.is_some() && wanted != player.paused {
+                    self.simple("pause").await?;
+                }
+            }
+            "next" => self.simple("skip").await?,
+            "stop" => self.simple("stop").await?,
+            "clear" => self.simple("clear").await?,
+            "seekcur" => {
+                let time = arg(0).ok_or_else(|| Ack::new(ACK_ARG, "wrong number of arguments"))?;
+                self.seek(0, time).await?;
+            }
+            "seek" | "seekid" => {
+                let (Some(song), Some(time)) = (arg(0), arg(1)) else {
+                    return Err(Ack::new(ACK_ARG, "wrong number of arguments"));
+                };
+                let pos = parse_pos(song)?;
+                self.seek(if name == "seekid" { pos.saturating_sub(1) } else { pos }, time).await?;
+            }
+            "add" => {
+                let query = arg(0).filter(|q| !q.is_empty()).ok_or_else(|| Ack::new(ACK_ARG, "wrong number of arguments"))?;
+                self.client.play(&self.guild_id, None, query).await?;
+                self.cache.lock().await.take();
+            }
+            "delete" | "deleteid" => {
+                let pos = arg(0).map(parse_pos).transpose()?.ok_or_else(|| Ack::new(ACK_ARG, "wrong number of arguments"))?;
+                let pos = if name == "deleteid" { pos.saturating_sub(1) } else { pos };
+                let player = self.player().await?;
+                match pos {
+                    // Deleting what plays is skipping it.
+                    0 if player.current.is_some() => self.simple("skip").await?,
+                    pos if pos > 0 && pos < player.len() => self.act(&self.item("remove", pos)).await?,
+                    _ => return Err(Ack::new(ACK_ARG, "Bad song index")),
+                }
+            }
+            "repeat" | "single" => {
+                let on = parse_bool(arg(0))?;
+                let loop_mode = self.player().await?.loop_mode;
+                let mode = match (name, on, loop_mode.as_str()) {
+                    ("repeat", true, "off") => "queue",
+                    ("repeat", false, _) => "off",
+                    ("single", true, _) => "track",
+                    ("single", false, "track") => "queue",
+                    (_, _, current) => current,
+                };
+                if mode != loop_mode {
+                    self.set_loop(mode).await?;
+                }
+            }
+            "random" => {
+                if parse_bool(arg(0))? != self.player().await?.shuffle {
+                    self.simple("shuffle").await?;
+                }
+            }
+            _ => return Err(Ack::new(ACK_UNKNOWN, format!("unknown command \"{name}\""))),
+        }
+        Ok(out)
+    }
+}
+
+fn write_song(out: &mut String, pos: usize, track: &Track) {
+    let clean = |s: &str| s.replace(['\n', '\r'], " ");
+    let title = track.title.as_deref().unwrap_or_default();
+    let file = track.uri.as_deref().unwrap_or(title);
+    writeln!(out, "file: {}", clean(file)).ok();
+    if let Some(author) = &track.author {
+        writeln!(out, "Artist: {}", clean(author)).ok();
+    }
+    if !title.is_empty() {
+        writeln!(out, "Title: {}", clean(title)).ok();
+    }
+    if let Some(ms) = track.duration_ms {
+        writeln!(out, "Time: {}\nduration: {:.3}", ms / 1000, ms as f64 / 1000.0).ok();
+    }
+    writeln!(out, "Pos: {pos}\nId: {}", pos + 1).ok();
+}
+
+fn parse_pos(arg: &str) -> Result<usize, Ack> {
+    arg.parse().map_err(|_| Ack::new(ACK_ARG, format!("Integer expected: {arg}")))
+}
+
+fn parse_bool(arg: Option<&str>) -> Result<bool, Ack> {
+    match arg {
+        Some("1") => Ok(true),
+        Some("0") => Ok(false),
+        _ => Err(Ack::new(ACK_ARG, "Boolean (0/1) expected")),
+    }
+}
+
+/// `POS` or `START:END` (END optional), as `playlistinfo` takes them.
+fn parse_range(arg: &str, len: usize) -> Result<std::ops::Range<usize>, Ack> {
+    let range = match arg.split_once(':') {
+        Some((start, "")) => parse_pos(start)?..len,
+        Some((start, end)) => parse_pos(start)?..parse_pos(end)?,
+        None => {
+            let pos = parse_pos(arg)?;
+            pos..pos + 1
+        }
+    };
+    if range.start >= len {
+        return Err(Ack::new(ACK_ARG, "Bad song index"));
+    }
+    Ok(range)
+}
+
+/// Split a command line into its name and arguments; arguments may be
+/// double-quoted, with `\"` and `\\` inside.
+fn parse_line(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut chars = line.trim().chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        if c == '"' {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => word.extend(chars.next()),
+                    c => word.push(c),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+        }
+        words.push(word);
+    }
+    words
+}
+
+/// Accept MPD clients on `listener` until the process ends.
+pub async fn serve(client: JorikClient, guild_id: String, listener: TcpListener) -> Result<()> {
+    let bridge = Arc::new(Bridge {
+        client,
+        guild_id,
+        cache: Mutex::new(None),
+    });
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let bridge = bridge.clone();
+        tokio::spawn(async move {
+            // A client going away mid-reply is no concern of the others.
+            serve_client(&bridge, stream).await.ok();
+        });
+    }
+}
+
+async fn serve_client(bridge: &Bridge, stream: TcpStream) -> Result<()> {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    write.write_all(format!("OK MPD {PROTOCOL_VERSION}\n").as_bytes()).await?;
+    // Commands gathered between command_list_begin and command_list_end,
+    // and whether each gets its own `list_OK`.
+    let mut list: Option<(Vec<Vec<String>>, bool)> = None;
+    while let Some(line) = lines.next_line().await? {
+        let words = parse_line(&line);
+        let Some(name) = words.first().map(String::as_str) else {
+            continue;
+        };
+        match (name, &mut list) {
+            ("command_list_begin" | "command_list_ok_begin", None) => {
+                list = Some((Vec::new(), name == "command_list_ok_begin"));
+                continue;
+            }
+            ("command_list_end", Some(_)) => {}
+            (_, Some((commands, _))) => {
+                commands.push(words);
+                continue;
+            }
+            // Only ends an idle, which `idle` reads itself; MPD ignores
+            // it otherwise, without so much as an OK.
+            ("noidle", None) => continue,
+            _ => {}
+        }
+        let (commands, list_ok) = list.take().unwrap_or_else(|| (vec![words.clone()], false));
+        let mut reply = String::new();
+        let mut failed = false;
+        for (index, words) in commands.iter().enumerate() {
+            let name = words[0].as_str();
+            let result = match name {
+                "close" => return Ok(()),
+                "idle" => idle(bridge, &mut lines, &words[1..]).await,
+                "noidle" => Ok(String::new()),
+                _ => bridge.run(name, &words[1..]).await,
+            };
+            match result {
+                Ok(out) => {
+                    reply.push_str(&out);
+                    if list_ok {
+                        reply.push_str("list_OK\n");
+                    }
+                }
+                Err(ack) => {
+                    writeln!(reply, "ACK [{}@{index}] {{{name}}} {}", ack.code, ack.message).ok();
+                    failed = true;
+                    break;
+                }
+            }
+        }
+        if !failed {
+            reply.push_str("OK\n");
+        }
+        write.write_all(reply.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Wait for the player to change, or for `noidle`. `subsystems` narrows
+/// which changes count, as in MPD.
+async fn idle(
+    bridge: &Bridge,
+    lines: &mut tokio::io::Lines<BufReader<tokio::net::tcp::OwnedReadHalf>>,
+    subsystems: &[String],
+) -> Result<String, Ack> {
+    let before = bridge.player().await?;
+    let mut poll = tokio::time::interval(IDLE_POLL);
+    poll.tick().await;
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                // Whatever else a client sends during idle, MPD takes as noidle.
+                Ok(Some(_)) => return Ok(String::new()),
+                // Gone; the read after this finds that out again.
+                Ok(None) | Err(_) => return Ok(String::new()),
+            },
+            _ = poll.tick() => {
+                // A failed poll is left for the next one; the server may be back by then.
+                let Ok(now) = bridge.player().await else { continue };
+                let changed: Vec<_> = before
+                    .changes(&now)
+                    .into_iter()
+                    .filter(|c| subsystems.is_empty() || subsystems.iter().any(|s| s == c))
+                    .collect();
+                if !changed.is_empty() {
+                    return Ok(changed.iter().map(|c| format!("changed: {c}\n")).collect());
+                }
+            }
+        }
+    }
+}
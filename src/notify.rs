@@ -0,0 +1,65 @@
+use anyhow::{Result, bail};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Show a desktop notification. Like the clipboard, this goes through the
+/// tools the desktop already has: `notify-send` on Linux, `osascript` on
+/// macOS and a PowerShell toast on Windows. `icon` is an image file; macOS
+/// notifications from `osascript` cannot carry one and ignore it.
+pub fn send(title: &str, body: &str, icon: Option<&Path>) -> Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("powershell");
+        c.args(["-NoProfile", "-Command", &windows_toast(title, body, icon)]);
+        c
+    } else if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification {} with title {}",
+            applescript_string(body),
+            applescript_string(title)
+        );
+        let mut c = Command::new("osascript");
+        c.args(["-e", &script]);
+        c
+    } else {
+        let mut c = Command::new("notify-send");
+        c.args(["--app-name", "Jorik CLI"]);
+        if let Some(icon) = icon {
+            c.arg("--icon").arg(icon);
+        }
+        // A title starting with '-' is still a title.
+        c.arg("--").args([title, body]);
+        c
+    };
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if !status.success() {
+        bail!("notifier exited with {}", status);
+    }
+    Ok(())
+}
+
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `ToastImageAndText02` filled in through the WinRT API, which needs no
+/// extra modules.
+fn windows_toast(title: &str, body: &str, icon: Option<&Path>) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+    let icon = icon.map_or_else(String::new, |p| p.display().to_string());
+    format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastImageAndText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode({})) | Out-Null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode({})) | Out-Null; \
+         $xml.GetElementsByTagName('image').Item(0).SetAttribute('src', {}); \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('Jorik CLI').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        quote(title),
+        quote(body),
+        quote(&icon)
+    )
+}
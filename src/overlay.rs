@@ -0,0 +1,94 @@
+//! `jorik overlay`: a page with the current track, its artwork and
+//! progress, for OBS to show as a browser source. The page polls `/state`,
+//! which the guild's event stream keeps current.
+
+use crate::bar::Snapshot;
+use crate::httpd;
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::net::{TcpListener, TcpStream};
+
+const PAGE: &str = include_str!("overlay.html");
+
+/// The player, and when it was last heard of.
+pub struct Overlay {
+    state: Mutex<(Snapshot, Instant)>,
+    /// Whether to insist on a loopback `Host`
+    loopback: bool,
+}
+
+impl Overlay {
+    pub fn new(loopback: bool) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new((Snapshot::default(), Instant::now())),
+            loopback,
+        })
+    }
+
+    /// Take in a `/ws` event.
+    pub fn apply(&self, event: &Value) {
+        let mut state = self.state.lock().unwrap();
+        state.0.apply(event);
+        state.1 = Instant::now();
+    }
+
+    /// What `/state` answers: the track, with the position moved on by
+    /// however long it has played since the server last said.
+    fn state_json(&self) -> Value {
+        let state = self.state.lock().unwrap();
+        let (snapshot, at) = &*state;
+        let Some(track) = &snapshot.track else {
+            return json!({"playing": false});
+        };
+        let duration = snapshot.duration_ms.or(track.duration_ms);
+        let mut elapsed = snapshot.elapsed_ms.unwrap_or(0);
+        if !snapshot.paused {
+            elapsed += at.elapsed().as_millis() as u64;
+        }
+        if let Some(duration) = duration {
+            elapsed = elapsed.min(duration);
+        }
+        json!({
+            "playing": true,
+            "paused": snapshot.paused,
+            "title": track.title,
+            "author": track.author,
+            "artwork": track.artwork_url,
+            "requester": track.requester_name(),
+            "elapsed_ms": elapsed,
+            "duration_ms": duration,
+        })
+    }
+
+    /// Answer requests on `listener` until the process ends.
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let overlay = self.clone();
+            tokio::spawn(async move {
+                // A browser giving up on a request is no concern of the others.
+                overlay.serve_client(stream).await.ok();
+            });
+        }
+    }
+
+    async fn serve_client(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let request = match httpd::read_request(&mut stream).await {
+            Ok(request) => request,
+            Err(e) => return httpd::respond(&mut stream, e.status, "text/plain", e.message.as_bytes()).await,
+        };
+        if self.loopback && !request.for_loopback_host() {
+            return httpd::respond(&mut stream, 403, "text/plain", b"the Host header must name this machine").await;
+        }
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/") => httpd::respond(&mut stream, 200, "text/html; charset=utf-8", PAGE.as_bytes()).await,
+            ("GET", "/state") => {
+                let state = self.state_json().to_string();
+                httpd::respond(&mut stream, 200, "application/json", state.as_bytes()).await
+            }
+            _ => httpd::respond(&mut stream, 404, "text/plain", b"not found").await,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A play request saved while the server could not be reached, waiting for
+/// `jorik pending flush` (or the next play that gets through).
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PendingCommand {
+    /// Unix seconds of the failed attempt
+    pub queued_at: i64,
+    /// Server it was meant for; only replayed against the same one
+    pub base_url: String,
+    /// The `/webhook/audio` body, sent again as-is
+    pub payload: Value,
+}
+
+impl PendingCommand {
+    pub fn new(base_url: &str, payload: Value) -> Self {
+        Self {
+            queued_at: chrono::Utc::now().timestamp(),
+            base_url: base_url.to_string(),
+            payload,
+        }
+    }
+
+    /// The query or URL that was being enqueued.
+    pub fn query(&self) -> &str {
+        self.payload.get("query").and_then(Value::as_str).unwrap_or("")
+    }
+
+    pub fn guild_id(&self) -> Option<&str> {
+        self.payload.get("guild_id").and_then(Value::as_str)
+    }
+}
+
+pub fn path() -> Option<PathBuf> {
+    crate::api::config_root().map(|p| p.join("pending.jsonl"))
+}
+
+/// Everything in the journal, oldest first. Lines that no longer parse are
+/// skipped rather than blocking the rest.
+pub fn load() -> Vec<PendingCommand> {
+    let Some(text) = path().and_then(|p| fs::read_to_string(p).ok()) else {
+        return Vec::new();
+    };
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Append one command to the journal.
+pub fn push(command: &PendingCommand) -> Result<()> {
+    let path = writable()?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context("opening pending.jsonl")?;
+    let line = serde_json::to_string(command).context("serializing pending command")?;
+    writeln!(file, "{line}").context("writing pending.jsonl")?;
+    Ok(())
+}
+
+/// Replace the journal with `commands`, removing the file once it is empty.
+/// Lines that `load` skipped are not lost with it but appended to
+/// `pending.jsonl.bad`; returns how many were.
+pub fn save(commands: &[PendingCommand]) -> Result<usize> {
+    let path = writable()?;
+    let set_aside = set_aside_unparsable(&path)?;
+    if commands.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).context("removing pending.jsonl")
+            }
+            _ => Ok(set_aside),
+        };
+    }
+    let mut text = String::new();
+    for command in commands {
+        text.push_str(&serde_json::to_string(command).context("serializing pending command")?);
+        text.push('\n');
+    }
+    fs::write(&path, text).context("writing pending.jsonl")?;
+    Ok(set_aside)
+}
+
+/// `pending.jsonl.bad`, next to the journal.
+pub fn bad_path(path: &Path) -> PathBuf {
+    path.with_extension("jsonl.bad")
+}
+
+fn set_aside_unparsable(path: &Path) -> Result<usize> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return Ok(0);
+    };
+    let bad: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter(|line| serde_json::from_str::<PendingCommand>(line).is_err())
+        .collect();
+    if bad.is_empty() {
+        return Ok(0);
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(bad_path(path))
+        .context("opening pending.jsonl.bad")?;
+    for line in &bad {
+        writeln!(file, "{line}").context("writing pending.jsonl.bad")?;
+    }
+    Ok(bad.len())
+}
+
+fn writable() -> Result<PathBuf> {
+    let Some(path) = path() else {
+        bail!("not saving pending commands: running with --no-config (JORIK_STATELESS)");
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("creating config directory")?;
+    }
+    Ok(path)
+}
@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+/// A local playlist: a text file in the playlists directory with one track
+/// (URL or search query) per line. Blank lines and `#` comments are skipped,
+/// so plain `.m3u` files work as well.
+pub struct Playlist {
+    /// File name without its extension.
+    pub name: String,
+    pub tracks: Vec<String>,
+}
+
+/// `<config>/playlists`, or `None` in stateless mode.
+pub fn dir() -> Option<PathBuf> {
+    crate::api::config_root().map(|p| p.join("playlists"))
+}
+
+/// Every playlist in `dir()`, sorted by name, along with the files that could
+/// not be read. A missing directory means none.
+pub fn load_all() -> Result<(Vec<Playlist>, Vec<anyhow::Error>)> {
+    let Some(dir) = dir() else {
+        return Ok(Default::default());
+    };
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", dir.display())),
+    };
+    let mut playlists = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                skipped.push(anyhow::Error::new(e).context(format!("reading {}", dir.display())));
+                continue;
+            }
+        };
+        if !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match fs::read_to_string(&path).with_context(|| format!("reading {}", path.display())) {
+            Ok(text) => playlists.push(Playlist {
+                name: name.to_string(),
+                tracks: parse(&text),
+            }),
+            Err(e) => skipped.push(e),
+        }
+    }
+    playlists.sort_by_key(|p| p.name.to_lowercase());
+    Ok((playlists, skipped))
+}
+
+/// One track per non-blank line, skipping `#` comments.
+pub fn parse(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,203 @@
+//! Discord Rich Presence: the track the TUI is playing, shown on the user's
+//! profile through the IPC socket of the Discord client running locally.
+//! Nothing goes through Discord's servers from here; if no client is
+//! running there is simply nowhere to connect.
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// What the profile shows while a track is loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Playing {
+    pub title: String,
+    pub author: String,
+    /// Unix ms the track started at, as if it had played straight through;
+    /// `None` while paused, which stops Discord's clock
+    pub started_at: Option<i64>,
+    /// Unix ms it will end, when the length is known and it is playing
+    pub ends_at: Option<i64>,
+}
+
+impl Playing {
+    /// Whether showing `self` instead of `shown` is worth a message. The
+    /// clocks in the two drift a little every second, which is not.
+    pub fn differs_from(&self, shown: &Playing) -> bool {
+        let moved = |a: Option<i64>, b: Option<i64>| match (a, b) {
+            (Some(a), Some(b)) => (a - b).abs() > 2_000,
+            (a, b) => a.is_some() != b.is_some(),
+        };
+        self.title != shown.title
+            || self.author != shown.author
+            || moved(self.started_at, shown.started_at)
+            || moved(self.ends_at, shown.ends_at)
+    }
+}
+
+// Opcodes of the IPC frames.
+const HANDSHAKE: u32 = 0;
+const FRAME: u32 = 1;
+const CLOSE: u32 = 2;
+const PING: u32 = 3;
+const PONG: u32 = 4;
+
+/// How long Discord gets to answer a frame before the connection counts as dead.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Discord refuses `details` and `state` outside 2..=128 characters.
+const MAX_TEXT: usize = 128;
+
+trait Pipe: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Pipe for T {}
+
+/// A handshaken connection to the local Discord client. The presence is
+/// cleared by Discord itself once this is dropped.
+pub struct Connection {
+    pipe: Box<dyn Pipe>,
+    nonce: u64,
+}
+
+impl Connection {
+    /// Connect as the Discord application `client_id`, whose name is what
+    /// the profile says is being played.
+    pub async fn open(client_id: &str) -> Result<Self> {
+        let mut connection = Self {
+            pipe: open_pipe().await?,
+            nonce: 0,
+        };
+        connection.write(HANDSHAKE, &json!({"v": 1, "client_id": client_id})).await?;
+        let ready = connection.read().await?;
+        if ready.get("evt").and_then(Value::as_str) != Some("READY") {
+            bail!("Discord refused the handshake: {}", message(&ready));
+        }
+        Ok(connection)
+    }
+
+    /// Show `playing`, or nothing when it is `None`.
+    pub async fn set(&mut self, playing: Option<&Playing>) -> Result<()> {
+        self.nonce += 1;
+        let activity = playing.map(|p| {
+            let mut activity = json!({
+                // "Listening to …" rather than "Playing …"
+                "type": 2,
+                "details": text(&p.title),
+            });
+            if let Some(start) = p.started_at {
+                activity["timestamps"] = json!({"start": start});
+                if let Some(end) = p.ends_at {
+                    activity["timestamps"]["end"] = end.into();
+                }
+            }
+            let state = match (p.author.is_empty(), p.started_at.is_none()) {
+                (false, false) => p.author.clone(),
+                (false, true) => format!("{} · {}", p.author, crate::i18n::tr("Paused")),
+                (true, true) => crate::i18n::tr("Paused").to_string(),
+                (true, false) => String::new(),
+            };
+            if !state.is_empty() {
+                activity["state"] = text(&state).into();
+            }
+            activity
+        });
+        let command = json!({
+            "cmd": "SET_ACTIVITY",
+            "args": {"pid": std::process::id(), "activity": activity},
+            "nonce": self.nonce.to_string(),
+        });
+        self.write(FRAME, &command).await?;
+        let reply = self.read().await?;
+        if reply.get("evt").and_then(Value::as_str) == Some("ERROR") {
+            bail!("Discord rejected the presence: {}", message(&reply));
+        }
+        Ok(())
+    }
+
+    async fn write(&mut self, op: u32, payload: &Value) -> Result<()> {
+        let body = payload.to_string();
+        let mut frame = Vec::with_capacity(8 + body.len());
+        frame.extend(op.to_le_bytes());
+        frame.extend((body.len() as u32).to_le_bytes());
+        frame.extend(body.as_bytes());
+        self.pipe.write_all(&frame).await.context("writing to Discord")?;
+        Ok(())
+    }
+
+    /// The next reply, answering any pings that arrive first.
+    async fn read(&mut self) -> Result<Value> {
+        loop {
+            let (op, value) = tokio::time::timeout(REPLY_TIMEOUT, self.read_frame())
+                .await
+                .context("Discord did not answer")??;
+            match op {
+                PING => self.write(PONG, &value).await?,
+                CLOSE => bail!("Discord closed the connection: {}", message(&value)),
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    async fn read_frame(&mut self) -> Result<(u32, Value)> {
+        let mut head = [0u8; 8];
+        self.pipe.read_exact(&mut head).await.context("reading from Discord")?;
+        let op = u32::from_le_bytes([head[0], head[1], head[2], head[3]]);
+        let len = u32::from_le_bytes([head[4], head[5], head[6], head[7]]) as usize;
+        let mut body = vec![0; len];
+        self.pipe.read_exact(&mut body).await.context("reading from Discord")?;
+        Ok((op, serde_json::from_slice(&body).context("parsing Discord's reply")?))
+    }
+}
+
+/// `s` cut down to what Discord accepts, padded if it is a single character.
+fn text(s: &str) -> String {
+    let mut s: String = s.chars().take(MAX_TEXT).collect();
+    if s.chars().count() < 2 {
+        s.push(' ');
+    }
+    s
+}
+
+fn message(reply: &Value) -> String {
+    reply
+        .get("data")
+        .and_then(|d| d.get("message"))
+        .or_else(|| reply.get("message"))
+        .and_then(Value::as_str)
+        .unwrap_or("no reason given")
+        .to_string()
+}
+
+/// The client listens on the first free `discord-ipc-<n>` in its runtime
+/// directory; Flatpak and Snap builds keep theirs one level down.
+#[cfg(unix)]
+async fn open_pipe() -> Result<Box<dyn Pipe>> {
+    use std::path::PathBuf;
+    let dirs = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .map(PathBuf::from)
+        .chain([PathBuf::from("/tmp")]);
+    for dir in dirs {
+        for sub in ["", "app/com.discordapp.Discord", "snap.discord"] {
+            for n in 0..10 {
+                let path = dir.join(sub).join(format!("discord-ipc-{n}"));
+                if let Ok(stream) = tokio::net::UnixStream::connect(&path).await {
+                    return Ok(Box::new(stream));
+                }
+            }
+        }
+    }
+    bail!("no Discord client is running")
+}
+
+#[cfg(windows)]
+async fn open_pipe() -> Result<Box<dyn Pipe>> {
+    for n in 0..10 {
+        let name = format!(r"\\.\pipe\discord-ipc-{n}");
+        if let Ok(pipe) = tokio::net::windows::named_pipe::ClientOptions::new().open(&name) {
+            return Ok(Box::new(pipe));
+        }
+    }
+    bail!("no Discord client is running")
+}
@@ -0,0 +1,126 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const TICK: Duration = Duration::from_millis(80);
+
+/// Whether some spinner currently owns the last stderr line.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+/// Set on the way out; no spinner draws again.
+static HALTED: AtomicBool = AtomicBool::new(false);
+
+/// Spinner on stderr for a request that may take a while. Drawn from a
+/// background thread so it keeps moving while the runtime awaits the
+/// response; the line is cleared on drop.
+pub struct Spinner {
+    stop: Arc<AtomicBool>,
+    bytes: Arc<AtomicU64>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    /// Start spinning next to `message`. Does nothing when `enabled` is false
+    /// or stderr is not a terminal, so piped output stays clean.
+    pub fn start(message: impl Into<String>, enabled: bool) -> Self {
+        Self::spawn(message.into(), None, enabled)
+    }
+
+    /// Like `start`, also showing how much of `total` bytes (if known) has
+    /// been reported through `set_bytes`.
+    pub fn download(message: impl Into<String>, total: Option<u64>, enabled: bool) -> Self {
+        Self::spawn(message.into(), Some(total), enabled)
+    }
+
+    fn spawn(message: String, total: Option<Option<u64>>, enabled: bool) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let bytes = Arc::new(AtomicU64::new(0));
+        let handle = (enabled && atty::is(atty::Stream::Stderr)).then(|| {
+            ACTIVE.store(true, Ordering::Relaxed);
+            let (stop, bytes) = (stop.clone(), bytes.clone());
+            thread::spawn(move || {
+                for frame in FRAMES.iter().cycle() {
+                    let counter = match total {
+                        Some(total) => format!(" {}", byte_progress(bytes.load(Ordering::Relaxed), total)),
+                        None => String::new(),
+                    };
+                    // Checked under the lock so `halt` can't be drawn over.
+                    let mut err = io::stderr().lock();
+                    if stop.load(Ordering::Relaxed) || HALTED.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    write!(err, "\r\x1b[2K{} {}{}", frame, message, counter).ok();
+                    err.flush().ok();
+                    drop(err);
+                    thread::sleep(TICK);
+                }
+            })
+        });
+        Self {
+            stop,
+            bytes,
+            handle,
+        }
+    }
+
+    /// False when the spinner was disabled and callers should print plain progress instead.
+    pub fn is_visible(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    pub fn set_bytes(&self, bytes: u64) {
+        self.bytes.store(bytes, Ordering::Relaxed);
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.stop.store(true, Ordering::Relaxed);
+            handle.join().ok();
+            clear_line();
+            ACTIVE.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+fn clear_line() {
+    let mut err = io::stderr().lock();
+    write!(err, "\r\x1b[2K").ok();
+    err.flush().ok();
+}
+
+/// Wipe any spinner line for good, before exiting mid-request.
+pub fn halt() {
+    let mut err = io::stderr().lock();
+    HALTED.store(true, Ordering::Relaxed);
+    if ACTIVE.load(Ordering::Relaxed) {
+        write!(err, "\r\x1b[2K").ok();
+        err.flush().ok();
+    }
+}
+
+/// `eprintln!` that first wipes a spinner line so the two don't interleave;
+/// the spinner redraws itself below on its next tick.
+pub fn note(msg: impl std::fmt::Display) {
+    if ACTIVE.load(Ordering::Relaxed) {
+        clear_line();
+    }
+    eprintln!("{}", msg);
+}
+
+/// `1.2/5.0 MB (24%)`, or just `1.2 MB` when the size is unknown.
+fn byte_progress(done: u64, total: Option<u64>) -> String {
+    let mb = |b: u64| b as f64 / 1_000_000.0;
+    match total {
+        Some(total) if total > 0 => format!(
+            "{:.1}/{:.1} MB ({}%)",
+            mb(done),
+            mb(total),
+            done.saturating_mul(100) / total
+        ),
+        _ => format!("{:.1} MB", mb(done)),
+    }
+}
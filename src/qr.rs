@@ -0,0 +1,16 @@
+use colored::Colorize;
+use qrcode::QrCode;
+use qrcode::render::unicode::Dense1x2;
+
+/// `text` as a terminal QR code, two modules per character row. Colours are
+/// forced to black on white so phones can scan it on dark themes too.
+/// `None` if `text` is too long to encode.
+pub fn render(text: &str) -> Option<String> {
+    let code = QrCode::new(text.as_bytes()).ok()?;
+    let art = code.render::<Dense1x2>().quiet_zone(true).build();
+    let lines: Vec<String> = art
+        .lines()
+        .map(|line| line.black().on_white().to_string())
+        .collect();
+    Some(lines.join("\n"))
+}
@@ -0,0 +1,110 @@
+//! Internet radio for `jorik radio`: stations by name, from the list below
+//! and `[radio]` in config.toml, and a check that a stream URL really is
+//! one before the server is asked to play it.
+
+use crate::exit::{self, ExitCode};
+use anyhow::{Context, Result, bail};
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Stations everyone has. `[radio]` in config.toml adds more, and replaces
+/// these by name.
+pub const STATIONS: &[(&str, &str)] = &[
+    ("fip", "https://icecast.radiofrance.fr/fip-hifi.aac"),
+    ("groovesalad", "https://ice1.somafm.com/groovesalad-128-mp3"),
+    ("dronezone", "https://ice1.somafm.com/dronezone-128-mp3"),
+    ("secretagent", "https://ice1.somafm.com/secretagent-128-mp3"),
+    ("indiepop", "https://ice1.somafm.com/indiepop-128-mp3"),
+    ("kexp", "https://kexp-mp3-128.streamguys1.com/kexp128.mp3"),
+    ("radioparadise", "https://stream.radioparadise.com/mp3-192"),
+    ("radioparadise-mellow", "https://stream.radioparadise.com/mellow-192"),
+];
+
+/// A named station, and whether it came from config.toml.
+pub struct Station {
+    pub name: String,
+    pub url: String,
+    pub custom: bool,
+}
+
+/// Every station, by name: the built-in ones with `custom` laid over them.
+pub fn stations(custom: &BTreeMap<String, String>) -> BTreeMap<String, Station> {
+    let built_in = STATIONS.iter().map(|&(name, url)| (name.to_string(), url.to_string(), false));
+    let custom = custom.iter().map(|(name, url)| (name.to_lowercase(), url.clone(), true));
+    built_in
+        .chain(custom)
+        .map(|(name, url, custom)| (name.clone(), Station { name, url, custom }))
+        .collect()
+}
+
+/// `station` as a stream URL: an http(s) URL as given, or a name looked up
+/// case-insensitively. `None` for an unknown name.
+pub fn resolve(station: &str, custom: &BTreeMap<String, String>) -> Option<String> {
+    let station = station.trim();
+    if let Ok(url) = url::Url::parse(station)
+        && matches!(url.scheme(), "http" | "https")
+    {
+        return Some(station.to_string());
+    }
+    stations(custom).remove(&station.to_lowercase()).map(|s| s.url)
+}
+
+/// Make sure `url` answers with audio, and return what to play: the URL
+/// itself, or for an .m3u or .pls playlist of a station the first stream
+/// in it. HLS playlists are left for the server, which plays them whole.
+/// Something other than audio is a usage error; not getting an answer is
+/// left to the network one.
+pub async fn check(client: &Client, url: &str) -> Result<String> {
+    let resp = client
+        .get(url)
+        // Only the headers are waited for; a live stream never ends.
+        .timeout(Duration::from_secs(10))
+        .header("Icy-MetaData", "0")
+        .send()
+        .await
+        .with_context(|| format!("GET {url}"))?;
+    if !resp.status().is_success() {
+        bail!("{url} answered with status {}", resp.status());
+    }
+    let content_type = resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or_default().trim().to_lowercase())
+        .unwrap_or_default();
+    let path = resp.url().path().to_lowercase();
+    let shoutcast = resp.headers().keys().any(|k| k.as_str().starts_with("icy-"));
+
+    let m3u = content_type.contains("mpegurl") || path.ends_with(".m3u") || path.ends_with(".m3u8");
+    let pls = content_type == "audio/x-scpls" || path.ends_with(".pls");
+    if m3u || pls {
+        let text = resp.text().await.with_context(|| format!("reading {url}"))?;
+        if text.contains("#EXT-X-") {
+            return Ok(url.to_string());
+        }
+        // .pls lists its streams as `File1=<url>`, .m3u one per line.
+        let entry = |line: &str| match line.split_once('=') {
+            Some((key, url)) if pls && key.starts_with("File") => url.trim().to_string(),
+            _ => line.to_string(),
+        };
+        let first = text
+            .lines()
+            .map(|line| entry(line.trim()))
+            .find(|entry| entry.starts_with("http://") || entry.starts_with("https://"));
+        return match first {
+            Some(stream) => Ok(stream),
+            None => Err(exit::fail(ExitCode::Usage, format!("the playlist at {url} has no stream in it"))),
+        };
+    }
+    if content_type.starts_with("audio/") || content_type == "application/ogg" || shoutcast {
+        return Ok(url.to_string());
+    }
+    let sends = if content_type.is_empty() {
+        format!("{url} did not say what it sends")
+    } else {
+        format!("{url} sends {content_type}, not an audio stream")
+    };
+    Err(exit::fail(ExitCode::Usage, format!("{sends}; pass --no-check to play it anyway")))
+}
@@ -0,0 +1,307 @@
+//! Scrobbling: the tracks the TUI hears, submitted to Last.fm or
+//! ListenBrainz with the keys `jorik scrobble enable` saved under
+//! `[scrobble]` in config.toml.
+
+use crate::api::{ScrobbleSettings, Track};
+use anyhow::{Context, Result, bail};
+use md5::{Digest, Md5};
+use reqwest::Client;
+use serde_json::{Value, json};
+use std::fmt;
+use std::time::Duration;
+
+pub const LASTFM_API: &str = "https://ws.audioscrobbler.com/2.0/";
+pub const LISTENBRAINZ_API: &str = "https://api.listenbrainz.org";
+
+/// How `jorik scrobble status` and the logs call a `[scrobble] service`.
+pub fn service_name(service: &str) -> &str {
+    match service {
+        "lastfm" => "Last.fm",
+        "listenbrainz" => "ListenBrainz",
+        other => other,
+    }
+}
+
+/// A track as the scrobbling services take it.
+#[derive(Clone, Debug)]
+pub struct Listen {
+    pub artist: String,
+    pub title: String,
+    pub duration_ms: Option<u64>,
+    pub uri: Option<String>,
+    /// Unix seconds it started playing at
+    pub started_at: i64,
+}
+
+impl Listen {
+    /// `None` for tracks without both an author and a title, which neither
+    /// service accepts.
+    pub fn from_track(track: &Track, started_at: i64) -> Option<Self> {
+        Some(Self {
+            artist: track.author.clone().filter(|a| !a.is_empty())?,
+            title: track.title.clone().filter(|t| !t.is_empty())?,
+            duration_ms: track.duration_ms.filter(|&ms| ms > 0),
+            uri: track.uri.clone(),
+            started_at,
+        })
+    }
+
+    /// Last.fm's rule, which ListenBrainz asks clients to follow too: a
+    /// track over 30 seconds long, heard for half its length or 4 minutes.
+    pub fn counts(&self, heard: Duration) -> bool {
+        let Some(length) = self.duration_ms.map(Duration::from_millis) else {
+            return heard >= Duration::from_secs(4 * 60);
+        };
+        length > Duration::from_secs(30) && heard >= (length / 2).min(Duration::from_secs(4 * 60))
+    }
+}
+
+/// What the TUI saw happen in the guild on screen.
+pub enum Heard {
+    /// A track began; `None` if it lacks what a listen needs
+    Started(Option<Listen>),
+    /// The track ended, having got `position_ms` in
+    Ended { position_ms: u64 },
+}
+
+/// Submits listens to the one service set up in `[scrobble]`.
+pub enum Scrobbler {
+    LastFm {
+        client: Client,
+        api: String,
+        api_key: String,
+        api_secret: String,
+        session_key: String,
+    },
+    ListenBrainz {
+        client: Client,
+        api: String,
+        token: String,
+    },
+}
+
+impl Scrobbler {
+    /// `None` unless a service is chosen and has every key it needs.
+    pub fn new(client: Client, settings: &ScrobbleSettings) -> Option<Self> {
+        let api = |default: &str| settings.api_url.clone().unwrap_or_else(|| default.to_string());
+        match settings.service.as_deref()? {
+            "lastfm" => Some(Self::LastFm {
+                client,
+                api: api(LASTFM_API),
+                api_key: settings.lastfm_api_key.clone()?,
+                api_secret: settings.lastfm_api_secret.clone()?,
+                session_key: settings.lastfm_session_key.clone()?,
+            }),
+            "listenbrainz" => Some(Self::ListenBrainz {
+                client,
+                api: api(LISTENBRAINZ_API),
+                token: settings.listenbrainz_token.clone()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Show `listen` as what the user is listening to right now. It is not
+    /// kept in their history; `scrobble` does that once it has played.
+    pub async fn now_playing(&self, listen: &Listen) -> Result<()> {
+        match self {
+            Self::LastFm { client, api, api_key, api_secret, session_key } => {
+                let mut params = vec![
+                    ("method", "track.updateNowPlaying".to_string()),
+                    ("api_key", api_key.clone()),
+                    ("sk", session_key.clone()),
+                    ("artist", listen.artist.clone()),
+                    ("track", listen.title.clone()),
+                ];
+                if let Some(ms) = listen.duration_ms {
+                    params.push(("duration", (ms / 1000).to_string()));
+                }
+                lastfm_call(client, api, api_secret, params).await.map(drop)
+            }
+            Self::ListenBrainz { client, api, token } => {
+                listenbrainz_submit(client, api, token, "playing_now", listen_payload(listen, None)).await
+            }
+        }
+    }
+
+    /// Add `listen` to the user's history.
+    pub async fn scrobble(&self, listen: &Listen) -> Result<()> {
+        match self {
+            Self::LastFm { client, api, api_key, api_secret, session_key } => {
+                let mut params = vec![
+                    ("method", "track.scrobble".to_string()),
+                    ("api_key", api_key.clone()),
+                    ("sk", session_key.clone()),
+                    ("artist", listen.artist.clone()),
+                    ("track", listen.title.clone()),
+                    ("timestamp", listen.started_at.to_string()),
+                ];
+                if let Some(ms) = listen.duration_ms {
+                    params.push(("duration", (ms / 1000).to_string()));
+                }
+                let reply = lastfm_call(client, api, api_secret, params).await?;
+                // A scrobble Last.fm ignores (e.g. as spam) still comes back 200.
+                let ignored = reply.pointer("/scrobbles/@attr/ignored").and_then(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()));
+                if ignored.unwrap_or(0) > 0 {
+                    let why = reply.pointer("/scrobbles/scrobble/ignoredMessage/#text").and_then(Value::as_str);
+                    bail!("Last.fm ignored the scrobble: {}", why.unwrap_or("no reason given"));
+                }
+                Ok(())
+            }
+            Self::ListenBrainz { client, api, token } => {
+                let payload = listen_payload(listen, Some(listen.started_at));
+                listenbrainz_submit(client, api, token, "single", payload).await
+            }
+        }
+    }
+}
+
+/// An `{"error", "message"}` reply from the Last.fm API; get at it with
+/// `err.downcast_ref::<LastFmError>()`.
+#[derive(Debug)]
+pub struct LastFmError {
+    pub code: u64,
+    pub message: String,
+}
+
+impl LastFmError {
+    /// `auth.getSession` for a token the user has not approved yet.
+    const TOKEN_NOT_AUTHORIZED: u64 = 14;
+}
+
+impl fmt::Display for LastFmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Last.fm error {}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for LastFmError {}
+
+/// Call a Last.fm API method, signed with the API secret as every
+/// authenticated and auth method must be.
+async fn lastfm_call(client: &Client, api: &str, secret: &str, mut params: Vec<(&str, String)>) -> Result<Value> {
+    params.sort_by(|a, b| a.0.cmp(b.0));
+    let mut signed = String::new();
+    for (key, value) in &params {
+        signed.push_str(key);
+        signed.push_str(value);
+    }
+    signed.push_str(secret);
+    let signature = Md5::digest(signed.as_bytes()).iter().map(|b| format!("{b:02x}")).collect::<String>();
+    params.push(("api_sig", signature));
+    // `format` is left out of the signature by design.
+    params.push(("format", "json".to_string()));
+
+    let resp = client.post(api).form(&params).send().await.with_context(|| format!("POST {api}"))?;
+    let status = resp.status();
+    let reply: Value = resp.json().await.with_context(|| format!("parsing the reply from {api}"))?;
+    if let Some(code) = reply.get("error").and_then(Value::as_u64) {
+        let message = reply.get("message").and_then(Value::as_str).unwrap_or_default().to_string();
+        return Err(LastFmError { code, message }.into());
+    }
+    if !status.is_success() {
+        bail!("Last.fm returned status {status}");
+    }
+    Ok(reply)
+}
+
+fn listen_payload(listen: &Listen, listened_at: Option<i64>) -> Value {
+    let mut additional = json!({
+        "media_player": "Jorik",
+        "submission_client": "jorik-cli",
+        "submission_client_version": env!("CARGO_PKG_VERSION"),
+    });
+    if let Some(ms) = listen.duration_ms {
+        additional["duration_ms"] = ms.into();
+    }
+    if let Some(uri) = &listen.uri {
+        additional["origin_url"] = uri.clone().into();
+    }
+    let mut payload = json!({
+        "track_metadata": {
+            "artist_name": listen.artist,
+            "track_name": listen.title,
+            "additional_info": additional,
+        },
+    });
+    if let Some(at) = listened_at {
+        payload["listened_at"] = at.into();
+    }
+    payload
+}
+
+async fn listenbrainz_submit(client: &Client, api: &str, token: &str, listen_type: &str, payload: Value) -> Result<()> {
+    let url = format!("{}/1/submit-listens", api.trim_end_matches('/'));
+    let resp = client
+        .post(&url)
+        .header("Authorization", format!("Token {token}"))
+        .json(&json!({"listen_type": listen_type, "payload": [payload]}))
+        .send()
+        .await
+        .with_context(|| format!("POST {url}"))?;
+    let status = resp.status();
+    if !status.is_success() {
+        let reply: Value = resp.json().await.unwrap_or_default();
+        let message = reply.get("error").and_then(Value::as_str).unwrap_or_default();
+        bail!("ListenBrainz returned status {status}: {message}");
+    }
+    Ok(())
+}
+
+/// Start connecting the Last.fm API account `api_key` to a user: the token
+/// to approve in the browser at `lastfm_auth_url`.
+pub async fn lastfm_token(client: &Client, api: &str, api_key: &str, api_secret: &str) -> Result<String> {
+    let params = vec![("method", "auth.getToken".to_string()), ("api_key", api_key.to_string())];
+    let reply = lastfm_call(client, api, api_secret, params).await?;
+    reply
+        .get("token")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .context("Last.fm sent no token")
+}
+
+/// The page where the user lets the API account scrobble for them. Other
+/// servers speaking the same API (Libre.fm) keep it at `/api/auth/`.
+pub fn lastfm_auth_url(api: &str, api_key: &str, token: &str) -> String {
+    let page = match url::Url::parse(api) {
+        Ok(url) if api != LASTFM_API => format!("{}/api/auth/", url.origin().ascii_serialization()),
+        _ => "https://www.last.fm/api/auth/".to_string(),
+    };
+    format!("{page}?api_key={api_key}&token={token}")
+}
+
+/// The session key and username for a `token` the user has approved, or
+/// `None` while they have not yet.
+pub async fn lastfm_session(client: &Client, api: &str, api_key: &str, api_secret: &str, token: &str) -> Result<Option<(String, String)>> {
+    let params = vec![
+        ("method", "auth.getSession".to_string()),
+        ("api_key", api_key.to_string()),
+        ("token", token.to_string()),
+    ];
+    match lastfm_call(client, api, api_secret, params).await {
+        Ok(reply) => {
+            let session = reply.get("session").context("Last.fm sent no session")?;
+            let field = |name| session.get(name).and_then(Value::as_str).map(str::to_string);
+            let key = field("key").context("Last.fm sent no session key")?;
+            Ok(Some((key, field("name").unwrap_or_default())))
+        }
+        Err(e) if e.downcast_ref::<LastFmError>().is_some_and(|e| e.code == LastFmError::TOKEN_NOT_AUTHORIZED) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The ListenBrainz user `token` belongs to, or `None` if it is not valid.
+pub async fn listenbrainz_user(client: &Client, api: &str, token: &str) -> Result<Option<String>> {
+    let url = format!("{}/1/validate-token", api.trim_end_matches('/'));
+    let resp = client
+        .get(&url)
+        .header("Authorization", format!("Token {token}"))
+        .send()
+        .await
+        .with_context(|| format!("GET {url}"))?;
+    let reply: Value = resp.json().await.with_context(|| format!("parsing the reply from {url}"))?;
+    if reply.get("valid").and_then(Value::as_bool) != Some(true) {
+        return Ok(None);
+    }
+    Ok(Some(reply.get("user_name").and_then(Value::as_str).unwrap_or_default().to_string()))
+}
@@ -0,0 +1,159 @@
+//! Spotify playlists and albums taken apart on this side, for servers that
+//! only resolve single tracks: `jorik play --expand` reads the tracks with
+//! the app keys under `[spotify]` and enqueues each as a search.
+
+use crate::api::SpotifySettings;
+use anyhow::{Context, Result, bail};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+
+pub const API: &str = "https://api.spotify.com/v1";
+pub const ACCOUNTS: &str = "https://accounts.spotify.com";
+
+/// Something with tracks in, by its Spotify ID.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Collection {
+    Playlist(String),
+    Album(String),
+}
+
+impl Collection {
+    /// From an open.spotify.com link or a `spotify:` URI; `None` for
+    /// anything else, single tracks included.
+    pub fn parse(query: &str) -> Option<Self> {
+        let query = query.trim();
+        let parts: Vec<String> = if let Some(uri) = query.strip_prefix("spotify:") {
+            // Old playlist URIs have `user:<name>:` in front.
+            uri.split(':').map(str::to_string).collect()
+        } else {
+            let url = url::Url::parse(query).ok()?;
+            if !matches!(url.host_str()?, "open.spotify.com" | "play.spotify.com") {
+                return None;
+            }
+            url.path_segments()?
+                .filter(|s| !s.is_empty() && !s.starts_with("intl-") && *s != "embed")
+                .map(str::to_string)
+                .collect()
+        };
+        let kind = parts.iter().position(|p| p == "playlist" || p == "album")?;
+        let id = parts
+            .get(kind + 1)
+            .filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric()))?
+            .clone();
+        Some(match parts[kind].as_str() {
+            "playlist" => Self::Playlist(id),
+            _ => Self::Album(id),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Token {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<String>,
+}
+
+/// A playlist entry; `track` is null for ones Spotify has taken down.
+#[derive(Deserialize)]
+struct PlaylistItem {
+    track: Option<SpotifyTrack>,
+}
+
+#[derive(Deserialize)]
+struct SpotifyTrack {
+    name: String,
+    #[serde(default)]
+    artists: Vec<Artist>,
+    /// "episode" for podcast episodes in playlists
+    #[serde(default, rename = "type")]
+    kind: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Artist {
+    name: String,
+}
+
+impl SpotifyTrack {
+    /// What the server is asked to search for.
+    fn query(&self) -> String {
+        let artists: Vec<&str> = self.artists.iter().map(|a| a.name.as_str()).collect();
+        if artists.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} - {}", artists.join(", "), self.name)
+        }
+    }
+}
+
+/// The tracks of `collection` in order, as "Artist - Title" searches.
+pub async fn expand(client: &Client, settings: &SpotifySettings, collection: &Collection) -> Result<Vec<String>> {
+    let (Some(client_id), Some(client_secret)) = (&settings.client_id, &settings.client_secret) else {
+        bail!("no Spotify app keys under [spotify]");
+    };
+    let accounts = settings.accounts_url.as_deref().unwrap_or(ACCOUNTS).trim_end_matches('/');
+    let url = format!("{accounts}/api/token");
+    let resp = client
+        .post(&url)
+        .basic_auth(client_id, Some(client_secret))
+        .form(&[("grant_type", "client_credentials")])
+        .send()
+        .await
+        .with_context(|| format!("POST {url}"))?;
+    if matches!(resp.status(), StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED) {
+        bail!("Spotify turned down the client_id and client_secret under [spotify]");
+    }
+    if !resp.status().is_success() {
+        bail!("Spotify returned status {} for a token", resp.status());
+    }
+    let token: Token = resp.json().await.context("parsing the token from Spotify")?;
+
+    let api = settings.api_url.as_deref().unwrap_or(API).trim_end_matches('/');
+    let (mut next, what) = match collection {
+        Collection::Playlist(id) => (
+            Some(format!("{api}/playlists/{id}/tracks?limit=100")),
+            "playlist",
+        ),
+        Collection::Album(id) => (Some(format!("{api}/albums/{id}/tracks?limit=50")), "album"),
+    };
+    let mut queries = Vec::new();
+    while let Some(url) = next {
+        let resp = client
+            .get(&url)
+            .bearer_auth(&token.access_token)
+            .send()
+            .await
+            .with_context(|| format!("GET {url}"))?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            // Spotify's own playlists are off limits to app keys, too.
+            bail!("Spotify has no such {what}, or it is private or made by Spotify");
+        }
+        if !resp.status().is_success() {
+            bail!("Spotify returned status {} for the {what}", resp.status());
+        }
+        let tracks: Vec<SpotifyTrack> = match collection {
+            Collection::Playlist(_) => {
+                let page: Page<PlaylistItem> = resp.json().await.context("parsing the playlist from Spotify")?;
+                next = page.next;
+                page.items.into_iter().filter_map(|item| item.track).collect()
+            }
+            Collection::Album(_) => {
+                let page: Page<SpotifyTrack> = resp.json().await.context("parsing the album from Spotify")?;
+                next = page.next;
+                page.items
+            }
+        };
+        queries.extend(
+            tracks
+                .iter()
+                .filter(|t| t.kind.as_deref().is_none_or(|k| k == "track") && !t.name.is_empty())
+                .map(SpotifyTrack::query),
+        );
+    }
+    Ok(queries)
+}
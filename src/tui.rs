@@ -1,25 +1,36 @@
-use crate::api::{self, AudioFilters, EqualizerBand, FilterPayload, KaraokeOptions, LoopPayload, LowPassOptions, LyricsPayload, PlayPayload, QueuePayload, RotationOptions, SimplePayload, TimescaleOptions, TremoloOptions, TwentyFourSevenPayload, VibratoOptions, WsEvent, WsSubscribe, PlaybackState};
+use crate::api::{self, AudioFilters, BulkPlayPayload, EqualizerBand, ErrorResponse, FilterPayload, KaraokeOptions, LoopPayload, LowPassOptions, LyricsPayload, MovePayload, PlayPayload, QueueItemPayload, QueuePayload, RotationOptions, SeekPayload, SimplePayload, TimescaleOptions, TremoloOptions, QueueResponse, Track, TwentyFourSevenPayload, VibratoOptions, WsEvent, WsSubscribe, PlaybackState};
 use crate::ascii::ASCII_LOGO;
-use anyhow::Result;
-use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crate::exit::{self, ExitCode};
+use crate::playlist::{self, Playlist};
+use crate::presence;
+use crate::hooks::Hooks;
+use crate::scrobble::{self, Heard, Scrobbler};
+use crate::{i18n, t};
+use anyhow::{Context, Result, bail};
+use ratatui::crossterm::event::{self, Event, EventStream, KeyCode, KeyEventKind};
 use ratatui::{
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap, BarChart, Bar, BarGroup, Gauge, Tabs},
+    symbols::Marker,
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap, BarChart, Bar, BarGroup, Gauge, LineGauge, Tabs},
+    widgets::canvas::{Canvas, Line as CanvasLine, Points},
     DefaultTerminal, Frame,
 };
 use ratatui::style::Stylize;
+use ratatui_image::{StatefulImage, picker::Picker, protocol::StatefulProtocol};
 use reqwest::Client;
+use serde::Deserialize;
 use serde_json::Value;
-use std::{sync::Arc, time::{Duration, Instant}};
-use tokio::sync::Mutex;
+use std::{collections::{BTreeMap, VecDeque}, sync::Arc, time::{Duration, Instant}};
+use tokio::sync::{Mutex, Notify};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::time::{interval, timeout};
 use tokio::net::TcpListener;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use futures_util::{StreamExt, SinkExt};
-use tokio_tungstenite::{connect_async, tungstenite::{protocol::Message, client::IntoClientRequest, http::HeaderValue}};
-use url::Url;
+use tokio_tungstenite::tungstenite::protocol::Message;
 
 
 
@@ -30,66 +41,175 @@ struct Theme {
     primary: Color,
     highlight: Color,
     text_secondary: Color,
+    /// Visualizer bars, from the low end of the spectrum to the top.
+    viz_low: Color,
+    viz_mid: Color,
+    viz_peak: Color,
+    /// Shade bars by height (low -> mid -> peak) instead of one colour per column.
+    viz_gradient: bool,
+    status_bg: Color,
 }
 
-fn get_theme(name: &str) -> Theme {
+impl Theme {
+    fn new(bg: Color, border: Color, primary: Color, highlight: Color, text_secondary: Color) -> Self {
+        Self {
+            bg,
+            border,
+            primary,
+            highlight,
+            text_secondary,
+            viz_low: primary,
+            viz_mid: highlight,
+            viz_peak: Color::Rgb(255, 210, 245),
+            viz_gradient: true,
+            status_bg: bg,
+        }
+    }
+}
+
+/// Visualizer styles in the order `v` and the Settings view cycle through them.
+const VIZ_STYLES: [&str; 8] = ["Bars", "Blocky", "Line", "Wave", "Dots", "Mirror", "Braille", "Waveform"];
+const COMPACT_MODES: [&str; 3] = ["Auto", "On", "Off"];
+/// Frame rates offered in Settings; the config file takes any value.
+const FRAME_RATES: [u32; 4] = [15, 30, 60, 120];
+/// Below this terminal size "Auto" compact mode takes over; the regular
+/// layouts need the logo's 11 rows plus room for the queue.
+const COMPACT_WIDTH: u16 = 80;
+const COMPACT_HEIGHT: u16 = 24;
+
+/// The rate `step` places after `current` in `FRAME_RATES`, wrapping around;
+/// a rate not in the list steps from where it would sort.
+fn cycle_frame_rate(current: u32, step: isize) -> u32 {
+    let len = FRAME_RATES.len() as isize;
+    let i = match FRAME_RATES.binary_search(&current) {
+        Ok(i) => i as isize + step,
+        Err(i) if step > 0 => i as isize,
+        Err(i) => i as isize - 1,
+    };
+    FRAME_RATES[i.rem_euclid(len) as usize]
+}
+
+/// The name `step` places after `current` in `names`, wrapping around; an
+/// unknown `current` starts over at the first name.
+fn cycle_name<'a>(names: &[&'a str], current: &str, step: isize) -> &'a str {
+    let len = names.len() as isize;
+    let i = names
+        .iter()
+        .position(|n| *n == current)
+        .map_or(0, |i| (i as isize + step).rem_euclid(len) as usize);
+    names[i]
+}
+
+const BUILTIN_THEMES: [&str; 7] = ["Default", "Midnight", "Emerald", "Ruby", "Ocean", "Synthwave", "Sepia"];
+
+fn builtin_theme(name: &str) -> Theme {
     match name {
-        "Midnight" => Theme {
-            bg: Color::Rgb(5, 5, 15),
-            border: Color::Rgb(40, 40, 60),
-            primary: Color::Rgb(100, 100, 255),
-            highlight: Color::Rgb(150, 150, 255),
-            text_secondary: Color::Rgb(120, 120, 140),
-        },
-        "Emerald" => Theme {
-            bg: Color::Rgb(5, 15, 5),
-            border: Color::Rgb(40, 60, 40),
-            primary: Color::Rgb(50, 200, 50),
-            highlight: Color::Rgb(100, 255, 100),
-            text_secondary: Color::Rgb(120, 140, 120),
-        },
-        "Ruby" => Theme {
-            bg: Color::Rgb(15, 5, 5),
-            border: Color::Rgb(60, 40, 40),
-            primary: Color::Rgb(200, 50, 50),
-            highlight: Color::Rgb(255, 100, 100),
-            text_secondary: Color::Rgb(140, 120, 120),
-        },
-        "Ocean" => Theme {
-            bg: Color::Rgb(5, 10, 20),
-            border: Color::Rgb(40, 60, 100),
-            primary: Color::Rgb(50, 150, 255),
-            highlight: Color::Rgb(100, 200, 255),
-            text_secondary: Color::Rgb(120, 130, 160),
-        },
-        "Synthwave" => Theme {
-            bg: Color::Rgb(20, 10, 30),
-            border: Color::Rgb(100, 40, 100),
-            primary: Color::Rgb(255, 50, 255),
-            highlight: Color::Rgb(255, 150, 50), // Orange highlight
-            text_secondary: Color::Rgb(160, 120, 180),
-        },
-        "Sepia" => Theme {
-            bg: Color::Rgb(30, 25, 20),
-            border: Color::Rgb(80, 70, 60),
-            primary: Color::Rgb(180, 140, 100),
-            highlight: Color::Rgb(220, 180, 140),
-            text_secondary: Color::Rgb(140, 130, 120),
-        },
-        _ => Theme { // Default Jorik Purple
-            bg: Color::Rgb(15, 15, 25),
-            border: Color::Rgb(60, 60, 80),
-            primary: JORIK_PURPLE,
-            highlight: JORIK_HIGHLIGHT,
-            text_secondary: Color::Rgb(150, 150, 170),
-        },
+        "Midnight" => Theme::new(
+            Color::Rgb(5, 5, 15),
+            Color::Rgb(40, 40, 60),
+            Color::Rgb(100, 100, 255),
+            Color::Rgb(150, 150, 255),
+            Color::Rgb(120, 120, 140),
+        ),
+        "Emerald" => Theme::new(
+            Color::Rgb(5, 15, 5),
+            Color::Rgb(40, 60, 40),
+            Color::Rgb(50, 200, 50),
+            Color::Rgb(100, 255, 100),
+            Color::Rgb(120, 140, 120),
+        ),
+        "Ruby" => Theme::new(
+            Color::Rgb(15, 5, 5),
+            Color::Rgb(60, 40, 40),
+            Color::Rgb(200, 50, 50),
+            Color::Rgb(255, 100, 100),
+            Color::Rgb(140, 120, 120),
+        ),
+        "Ocean" => Theme::new(
+            Color::Rgb(5, 10, 20),
+            Color::Rgb(40, 60, 100),
+            Color::Rgb(50, 150, 255),
+            Color::Rgb(100, 200, 255),
+            Color::Rgb(120, 130, 160),
+        ),
+        "Synthwave" => Theme::new(
+            Color::Rgb(20, 10, 30),
+            Color::Rgb(100, 40, 100),
+            Color::Rgb(255, 50, 255),
+            Color::Rgb(255, 150, 50), // Orange highlight
+            Color::Rgb(160, 120, 180),
+        ),
+        "Sepia" => Theme::new(
+            Color::Rgb(30, 25, 20),
+            Color::Rgb(80, 70, 60),
+            Color::Rgb(180, 140, 100),
+            Color::Rgb(220, 180, 140),
+            Color::Rgb(140, 130, 120),
+        ),
+        _ => Theme::new( // Default Jorik Purple
+            Color::Rgb(15, 15, 25),
+            Color::Rgb(60, 60, 80),
+            JORIK_PURPLE,
+            JORIK_HIGHLIGHT,
+            Color::Rgb(150, 150, 170),
+        ),
+    }
+}
+
+/// Resolve `name` against the custom themes from settings, then the built-ins.
+fn get_theme(name: &str, custom: &BTreeMap<String, api::CustomTheme>) -> Theme {
+    let Some(c) = custom.get(name) else {
+        return builtin_theme(name);
+    };
+    let mut theme = builtin_theme(c.base.as_deref().unwrap_or("Default"));
+    for (slot, value) in [
+        (&mut theme.bg, &c.background),
+        (&mut theme.border, &c.border),
+        (&mut theme.primary, &c.primary),
+        (&mut theme.highlight, &c.highlight),
+        (&mut theme.text_secondary, &c.text_secondary),
+        (&mut theme.viz_low, &c.visualizer_low),
+        (&mut theme.viz_mid, &c.visualizer_mid),
+        (&mut theme.viz_peak, &c.visualizer_peak),
+        (&mut theme.status_bg, &c.status_bar),
+    ] {
+        if let Some(color) = value.as_deref().and_then(parse_hex_color) {
+            *slot = color;
+        }
+    }
+    if let Some(gradient) = c.visualizer_gradient {
+        theme.viz_gradient = gradient;
     }
+    theme
+}
+
+/// `#rrggbb` (the `#` is optional).
+fn parse_hex_color(s: &str) -> Option<Color> {
+    let hex = s.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Built-in theme names followed by the custom ones, in settings order.
+fn theme_names(app: &App) -> Vec<String> {
+    BUILTIN_THEMES
+        .iter()
+        .map(|n| n.to_string())
+        .chain(app.custom_themes.keys().filter(|n| !BUILTIN_THEMES.contains(&n.as_str())).cloned())
+        .collect()
 }
 
 // Approx color from the logo
 const JORIK_PURPLE: Color = Color::Rgb(130, 110, 230); // Soft purple/indigo
 const JORIK_HIGHLIGHT: Color = Color::Rgb(160, 140, 250);
 
+/// Volume keys and filter sliders only send once the value has been still for this long.
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(300);
+const MAX_VOLUME: u16 = 200;
+
 #[derive(PartialEq)]
 enum InputMode {
     Normal,
@@ -109,6 +229,11 @@ enum View {
     Debug,
     AppInfo,
     UpdateFound,
+    History,
+    Playlists,
+    TrackDetails,
+    GuildPicker,
+    Profiles,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -118,6 +243,348 @@ enum SettingsField {
     Theme,
     VizStyle,
     Layout,
+    GuildId,
+    UserId,
+    ChannelId,
+    Confirm,
+    Compact,
+    Notify,
+    FrameRate,
+    Accessible,
+    ExportDir,
+}
+
+/// `ETag` / `Last-Modified` of the last full queue fetch. Sent back with the
+/// next one, so an unchanged queue costs a bodiless 304 and the list, its
+/// selection and scroll stay exactly as they are.
+#[derive(Clone)]
+struct QueueValidator {
+    /// The `limit` it was fetched with; another window is another reply
+    limit: usize,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl QueueValidator {
+    fn from_headers(limit: usize, headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        (etag.is_some() || last_modified.is_some()).then_some(Self {
+            limit,
+            etag,
+            last_modified,
+        })
+    }
+}
+
+/// An upcoming track: its display label plus the server's track object,
+/// kept for the details popup.
+struct QueueTrack {
+    label: String,
+    requester: Option<String>,
+    info: Track,
+}
+
+impl QueueTrack {
+    fn from_track(info: Track) -> Self {
+        Self {
+            label: track_label(&info),
+            requester: info.requester_name().map(str::to_string),
+            info,
+        }
+    }
+}
+
+/// Dimmed ` · name` after a track, for shared servers.
+fn requester_span(requester: Option<&str>, theme: &Theme) -> Span<'static> {
+    match requester {
+        Some(name) => Span::styled(format!("  · {}", name), Style::default().fg(theme.text_secondary).add_modifier(Modifier::DIM)),
+        None => Span::raw(""),
+    }
+}
+
+/// A track that played earlier in a guild.
+struct HistoryEntry {
+    label: String,
+    /// What to enqueue to play it again: the URI when known, else the label as a search.
+    query: String,
+}
+
+impl HistoryEntry {
+    fn from_track(track: &Track) -> Self {
+        let label = track_label(track);
+        let query = track.uri.clone().unwrap_or_else(|| label.clone());
+        Self { label, query }
+    }
+}
+
+/// Tracks kept per guild, from the server or recorded locally.
+const HISTORY_LIMIT: usize = 50;
+/// Upcoming tracks fetched per request; more are loaded as the list is scrolled.
+const QUEUE_PAGE: usize = 20;
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+/// Errors get longer to be read, and can be dismissed early with Esc.
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(8);
+/// Lines kept in the Debug console.
+const DEBUG_LOG_LIMIT: usize = 1000;
+/// Submitted queries kept for Up/Down recall in the Play / Search popup.
+const INPUT_HISTORY_LIMIT: usize = 50;
+
+/// Short message shown in the corner until `duration` passes.
+struct Toast {
+    message: String,
+    shown_at: Instant,
+    error: bool,
+}
+
+impl Toast {
+    fn duration(&self) -> Duration {
+        if self.error { ERROR_TOAST_DURATION } else { TOAST_DURATION }
+    }
+}
+
+/// Single-line text input with a cursor and a recall history. The cursor is a
+/// byte offset that always sits on a `char` boundary.
+#[derive(Default)]
+struct LineInput {
+    text: String,
+    cursor: usize,
+    history: Vec<String>,
+    /// Entry shown while browsing the history; `None` when editing a fresh line.
+    history_pos: Option<usize>,
+    /// The unsent line to return to when browsing past the newest entry.
+    draft: String,
+}
+
+impl LineInput {
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    fn insert_str(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.text[..self.cursor].char_indices().next_back().map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.text[self.cursor..].chars().next().map_or(self.cursor, |c| self.cursor + c.len_utf8())
+    }
+
+    /// Start of the word before the cursor, skipping whitespace first.
+    fn word_start(&self) -> usize {
+        let before = self.text[..self.cursor].trim_end();
+        before.rfind(char::is_whitespace).map_or(0, |i| i + before[i..].chars().next().map_or(1, char::len_utf8))
+    }
+
+    /// End of the word after the cursor, skipping whitespace first.
+    fn word_end(&self) -> usize {
+        let after = &self.text[self.cursor..];
+        let skipped = after.len() - after.trim_start().len();
+        let word = &after[skipped..];
+        self.cursor + skipped + word.find(char::is_whitespace).unwrap_or(word.len())
+    }
+
+    fn left(&mut self) {
+        self.cursor = self.prev_boundary();
+    }
+
+    fn right(&mut self) {
+        self.cursor = self.next_boundary();
+    }
+
+    fn word_left(&mut self) {
+        self.cursor = self.word_start();
+    }
+
+    fn word_right(&mut self) {
+        self.cursor = self.word_end();
+    }
+
+    fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    fn end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    fn backspace(&mut self) {
+        let start = self.prev_boundary();
+        self.text.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    fn delete(&mut self) {
+        let end = self.next_boundary();
+        self.text.drain(self.cursor..end);
+    }
+
+    fn delete_word_back(&mut self) {
+        let start = self.word_start();
+        self.text.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    fn delete_to_start(&mut self) {
+        self.text.drain(..self.cursor);
+        self.cursor = 0;
+    }
+
+    fn delete_to_end(&mut self) {
+        self.text.truncate(self.cursor);
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.cursor = text.len();
+        self.text = text;
+    }
+
+    /// Show the previous history entry, stashing the current line on the first step.
+    fn history_prev(&mut self) {
+        let pos = match self.history_pos {
+            _ if self.history.is_empty() => return,
+            None => {
+                self.draft = std::mem::take(&mut self.text);
+                self.history.len() - 1
+            }
+            Some(i) => i.saturating_sub(1),
+        };
+        self.history_pos = Some(pos);
+        self.set_text(self.history[pos].clone());
+    }
+
+    /// Show the next history entry, or the stashed line after the newest one.
+    fn history_next(&mut self) {
+        let Some(i) = self.history_pos else {
+            return;
+        };
+        if i + 1 < self.history.len() {
+            self.history_pos = Some(i + 1);
+            self.set_text(self.history[i + 1].clone());
+        } else {
+            self.history_pos = None;
+            let draft = std::mem::take(&mut self.draft);
+            self.set_text(draft);
+        }
+    }
+
+    /// Take the line for sending and remember it for recall.
+    fn submit(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        if !text.trim().is_empty() && self.history.last() != Some(&text) {
+            self.history.push(text.clone());
+            if self.history.len() > INPUT_HISTORY_LIMIT {
+                self.history.remove(0);
+            }
+        }
+        self.clear();
+        text
+    }
+
+    fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+        self.history_pos = None;
+        self.draft.clear();
+    }
+
+    /// Terminal columns taken by the text before the cursor.
+    fn cursor_width(&self) -> usize {
+        Span::raw(&self.text[..self.cursor]).width()
+    }
+}
+
+/// Player state that belongs to one guild. The selected guild's state lives
+/// directly on `App`; other tabs are parked in `App::tabs` and swapped in
+/// when selected, so the rest of the TUI only ever looks at `App`.
+struct GuildTab {
+    guild_id: Option<String>,
+    queue: Vec<QueueTrack>,
+    queue_state: ListState,
+    pending_remove: Option<usize>,
+    filters: AudioFilters,
+    volume: u16,
+    filters_changed_at: Option<Instant>,
+    current_track: Option<String>,
+    current_uri: Option<String>,
+    current_info: Option<Track>,
+    loop_mode: String,
+    always_on: Option<bool>,
+    shuffle: Option<bool>,
+    history: Vec<HistoryEntry>,
+    queue_total: Option<usize>,
+    guild_name: Option<String>,
+    refreshed_at: Option<chrono::DateTime<chrono::Local>>,
+    queue_validator: Option<QueueValidator>,
+    lyrics_text: Option<String>,
+    lyrics_scroll: u16,
+    lyrics_lines: Vec<(u64, String)>,
+    lyrics_follow: bool,
+    lyrics_track: Option<String>,
+    spectrogram: Option<Vec<Vec<u8>>>,
+    elapsed_ms: u64,
+    duration_ms: u64,
+    scrub_ms: Option<u64>,
+    paused: bool,
+    last_state_update: Instant,
+    smoothed_bars: Vec<f32>,
+    art_url: Option<String>,
+    art_loaded_url: Option<String>,
+    art: Option<StatefulProtocol>,
+    /// Why this guild can't be shown, until `r` reloads it
+    fatal_error: Option<String>,
+}
+
+impl GuildTab {
+    fn new(guild_id: Option<String>) -> Self {
+        Self {
+            guild_id,
+            queue: Vec::new(),
+            queue_state: ListState::default(),
+            pending_remove: None,
+            filters: AudioFilters::default(),
+            volume: 100,
+            filters_changed_at: None,
+            current_track: None,
+            current_uri: None,
+            current_info: None,
+            loop_mode: "off".to_string(),
+            always_on: None,
+            shuffle: None,
+            history: Vec::new(),
+            queue_total: None,
+            guild_name: None,
+            refreshed_at: None,
+            queue_validator: None,
+            lyrics_text: None,
+            lyrics_scroll: 0,
+            lyrics_lines: Vec::new(),
+            lyrics_follow: true,
+            lyrics_track: None,
+            spectrogram: None,
+            elapsed_ms: 0,
+            duration_ms: 0,
+            scrub_ms: None,
+            paused: true,
+            last_state_update: Instant::now(),
+            smoothed_bars: vec![0.0; 64],
+            art_url: None,
+            art_loaded_url: None,
+            art: None,
+            fatal_error: None,
+        }
+    }
 }
 
 struct App {
@@ -126,15 +593,42 @@ struct App {
     token: Option<String>,
     guild_id: Option<String>,
     user_id: Option<String>,
+    channel_id: Option<String>,
     
-    queue: Vec<String>,
+    queue: Vec<QueueTrack>,
+    /// Highlighted queue entry; `None` until the user starts navigating.
+    queue_state: ListState,
+    /// Rows visible in the queue panel at the last draw, for PgUp/PgDn.
+    queue_page: usize,
+    /// Upcoming tracks on the server, which may be more than `queue` holds.
+    queue_total: Option<usize>,
+    /// A further queue page is being fetched.
+    queue_loading_more: bool,
+    /// Queue search started with `/`; kept after Enter so `n`/`N` can step through matches.
+    queue_search: Option<String>,
+    /// The search term is being typed, and the queue shows only matching rows.
+    queue_search_editing: bool,
+    /// Queue index awaiting a y/n answer before it is removed.
+    pending_remove: Option<usize>,
+    /// Filters last applied from the filter editor; volume changes resend them.
+    filters: AudioFilters,
+    /// Player volume in percent (100 = unchanged).
+    volume: u16,
+    /// When the volume or the filter editor last changed something not yet sent.
+    filters_changed_at: Option<Instant>,
     current_track: Option<String>,
-    error_message: Option<String>,
+    /// URI of `current_track`, used when recording it to the history.
+    current_uri: Option<String>,
+    /// The server's object for `current_track`, for the details popup.
+    current_info: Option<Track>,
     fatal_error: Option<String>,
     loop_mode: String, // "off", "track", "queue"
+    /// 24/7 mode and shuffle as last reported by the server; `None` until it says.
+    always_on: Option<bool>,
+    shuffle: Option<bool>,
     is_loading: bool,
     
-    input: String,
+    input: LineInput,
     input_mode: InputMode,
     view: View,
     
@@ -143,12 +637,97 @@ struct App {
     
     filter_state: ListState,
     filter_items: Vec<&'static str>,
+    /// Presets saved from the filter editor, listed after `filter_items`.
+    filter_presets: BTreeMap<String, AudioFilters>,
+    /// `[guilds.<id>]` from config.toml; only edited by hand, so never saved back.
+    guild_overrides: BTreeMap<String, api::GuildSettings>,
+    filter_draft: FilterDraft,
+    filter_param_state: ListState,
+    /// Whether the editor's arrow keys work on the sliders rather than the presets.
+    filter_params_focused: bool,
+    toast: Option<Toast>,
+    /// Idle time before the screensaver; `None` when it is turned off.
+    screensaver_after: Option<Duration>,
+    /// Time between WS pings; `None` when they are turned off.
+    ws_heartbeat: Option<Duration>,
+    /// Last moment something was playing or a key was pressed.
+    idle_since: Instant,
+    screensaver: bool,
+    /// Track shown on the screensaver, kept after playback ends.
+    last_track: Option<String>,
+    /// Queue entry shown in the details popup; `None` means the current track.
+    details_index: Option<usize>,
+    /// Ask y/n before Stop and Clear Queue (and removing a track).
+    confirm_destructive: bool,
+    notify_track_start: bool,
+    /// Feeds `async_discord_presence`; `None` unless `discord_client_id` is set.
+    presence: Option<UnboundedSender<Option<presence::Playing>>>,
+    /// What was last sent there.
+    presence_shown: Option<presence::Playing>,
+    /// Feeds `async_scrobble`; `None` unless `[scrobble]` is set up.
+    scrobble: Option<UnboundedSender<Heard>>,
+    /// `[hooks]`, run on the events of every open tab; `None` unless set.
+    hooks: Option<Hooks>,
+    frame_rate: u32,
+    /// ASCII-only drawing, no visualizer and spelled-out state labels.
+    accessible: bool,
+    /// Player panels the user can hide to make room for the rest.
+    show_visualizer: bool,
+    /// Covers the album art too, which takes the logo's place in most layouts.
+    show_logo: bool,
+    show_queue: bool,
+    /// "Auto", "On" or "Off"; see `is_compact`.
+    compact_mode: String,
+    /// Stop or clear action waiting for a y/n answer.
+    pending_action: Option<&'static str>,
+    /// Name being typed for `s` (save preset), while the prompt is open.
+    preset_name_input: Option<String>,
     
     auth_menu_state: ListState,
     auth_menu_items: Vec<&'static str>,
 
+    /// Recently played tracks, newest first.
+    history: Vec<HistoryEntry>,
+    /// Display name of `guild_id`, when the server sends one.
+    guild_name: Option<String>,
+    /// When the queue or player state last arrived, for the status bar.
+    refreshed_at: Option<chrono::DateTime<chrono::Local>>,
+    /// Makes the next full queue fetch conditional. Dropped whenever the
+    /// list is edited locally ahead of the server, so a 304 can't keep that.
+    queue_validator: Option<QueueValidator>,
+    /// Logged-in Discord user, shown in the status bar.
+    username: Option<String>,
+    history_state: ListState,
+
+    /// Local playlists, loaded when the browser opens.
+    playlists: Vec<Playlist>,
+    playlist_state: ListState,
+    playlist_track_state: ListState,
+    /// Whether the browser's arrow keys move through tracks rather than playlists.
+    playlist_tracks_focused: bool,
+    /// Guild picker: the user's guilds, then the voice channels of the chosen one.
+    picker_guilds: Vec<api::GuildInfo>,
+    picker_guild_state: ListState,
+    picker_channels: Vec<api::ChannelInfo>,
+    /// Row 0 is "whichever channel I'm in", the channels follow.
+    picker_channel_state: ListState,
+    picker_channels_focused: bool,
+    /// Loading or error text shown in place of the guild list.
+    picker_status: Option<String>,
+    /// Saved auth profiles (name, login) and the active one's name, read
+    /// when the switcher opens.
+    profiles: Vec<(String, api::Auth)>,
+    active_profile: String,
+    profile_state: ListState,
+
     lyrics_text: Option<String>,
     lyrics_scroll: u16,
+    /// Timestamped lines `(start ms, text)`, when the server has synced lyrics.
+    lyrics_lines: Vec<(u64, String)>,
+    /// Keep the current synced line centred; manual scrolling turns this off.
+    lyrics_follow: bool,
+    /// Track the lyrics above belong to, so the Lyrics layout knows when to refetch.
+    lyrics_track: Option<String>,
     
     auth_info_text: Option<String>,
 
@@ -156,12 +735,26 @@ struct App {
     spectrogram: Option<Vec<Vec<u8>>>,
     elapsed_ms: u64,
     duration_ms: u64,
+    /// Position picked on the seek bar, sent once Enter confirms it.
+    scrub_ms: Option<u64>,
+    /// Where the seek bar was last drawn, for mouse clicks.
+    seek_bar: Rect,
     paused: bool,
     last_state_update: Instant,
 
+    /// What the settings below started out as, JORIK_* overrides included.
+    /// Only the ones that differ from it are saved, so a one-run override
+    /// never ends up in config.toml.
+    seeded_settings: api::Settings,
     settings_input: String,
     offset_input: String,
+    guild_input: String,
+    user_input: String,
+    channel_input: String,
+    /// Export folder as typed in Settings; empty means the Desktop.
+    export_dir_input: String,
     theme: String,
+    custom_themes: BTreeMap<String, api::CustomTheme>,
     viz_style: String,
     layout: String,
     settings_field: SettingsField,
@@ -171,12 +764,33 @@ struct App {
 
     update_info: Option<(String, Vec<api::GiteaAsset>)>,
 
-    debug_logs: Vec<String>,
+    debug_logs: VecDeque<String>,
+    /// Debug console filter: every whitespace-separated word must appear in a line.
+    debug_filter: String,
+    debug_filter_editing: bool,
+    /// The `:` command line, kept between uses for its history.
+    command: LineInput,
+    command_editing: bool,
+    /// Lines scrolled back from the newest one.
+    debug_scroll: usize,
     ws_connected: bool,
     ws_connecting: bool,
     ws_sender: Option<tokio::sync::mpsc::UnboundedSender<Message>>,
 
     smoothed_bars: Vec<f32>,
+
+    /// Set once the terminal has been queried for graphics support.
+    picker: Option<Picker>,
+    /// Artwork URL of the current track, as reported by the server.
+    art_url: Option<String>,
+    /// The URL `art` was (or is being) loaded from.
+    art_loaded_url: Option<String>,
+    art: Option<StatefulProtocol>,
+
+    /// One entry per guild tab; the entry at `active_tab` is a stale
+    /// placeholder while its state is live on `App`.
+    tabs: Vec<GuildTab>,
+    active_tab: usize,
 }
 
 impl App {
@@ -185,8 +799,10 @@ impl App {
         settings: api::Settings,
         token: Option<String>,
         guild_id: Option<String>,
+        extra_guilds: Vec<String>,
         user_id: Option<String>,
     ) -> Self {
+        let seeded_settings = settings.clone();
         let mut menu_state = ListState::default();
         menu_state.select(Some(0));
         
@@ -198,46 +814,159 @@ impl App {
         
         let view = if token.is_some() { View::Main } else { View::LoginRequired };
 
+        let mut tabs = vec![GuildTab::new(guild_id.clone())];
+        for gid in extra_guilds {
+            if !tabs.iter().any(|t| t.guild_id.as_deref() == Some(gid.as_str())) {
+                tabs.push(GuildTab::new(Some(gid)));
+            }
+        }
+        let GuildTab {
+            guild_id,
+            queue,
+            queue_state,
+            pending_remove,
+            filters,
+            volume,
+            filters_changed_at,
+            current_track,
+            current_uri,
+            current_info,
+            loop_mode,
+            always_on,
+            shuffle,
+            history,
+            queue_total,
+            guild_name,
+            refreshed_at,
+            queue_validator,
+            lyrics_text,
+            lyrics_scroll,
+            lyrics_lines,
+            lyrics_follow,
+            lyrics_track,
+            spectrogram,
+            elapsed_ms,
+            duration_ms,
+            scrub_ms,
+            paused,
+            last_state_update,
+            smoothed_bars,
+            art_url,
+            art_loaded_url,
+            art,
+            fatal_error,
+        } = GuildTab::new(guild_id);
+
         Self {
             client,
             base_url: settings.base_url.clone(),
             token,
             guild_id,
             user_id,
-            queue: Vec::new(),
-            current_track: None,
-            error_message: None,
-            fatal_error: None,
-            loop_mode: "off".to_string(),
+            channel_id: settings.default_channel_id.clone(),
+            queue,
+            queue_state,
+            queue_page: 10,
+            queue_total,
+            queue_loading_more: false,
+            queue_search: None,
+            queue_search_editing: false,
+            pending_remove,
+            filters,
+            volume,
+            filters_changed_at,
+            current_track,
+            current_uri,
+            current_info,
+            fatal_error,
+            loop_mode,
+            always_on,
+            shuffle,
             is_loading: false,
-            input: String::new(),
+            input: LineInput::default(),
             input_mode: InputMode::Normal,
             view,
             menu_state,
             menu_items: vec![
                 " [+] Skip ", " [||] Pause/Resume ", " [X] Stop ", " [/] Shuffle ", 
                 " [C] Clear Queue ", " [T] Loop Track ", " [Q] Loop Queue ", " [.] Loop Off ",
-                " [24/7] Mode Toggle ", " [F] Filters... ", " [L] Lyrics ", " [P] Play Turip ",
-                " [A] Auth ", " [S] Settings ", " [!] Exit TUI "
+                " [24/7] Mode Toggle ", " [F] Filters... ", " [L] Lyrics ", " [H] History ", " [Y] Playlists ", " [P] Play Turip ",
+                " [G] Guilds ", " [O] Profiles ", " [A] Auth ", " [S] Settings ", " [!] Exit TUI "
             ],
             filter_state,
             filter_items: vec![
                 "Clear", "Bassboost", "Nightcore", "Vaporwave", 
                 "8D", "Soft", "Tremolo", "Vibrato", "Karaoke"
             ],
+            filter_presets: settings.filter_presets,
+            guild_overrides: settings.guilds,
+            filter_draft: FilterDraft::from_filters(&AudioFilters::default()),
+            filter_param_state: ListState::default(),
+            filter_params_focused: false,
+            toast: None,
+            screensaver_after: (settings.screensaver_minutes > 0).then(|| Duration::from_secs(settings.screensaver_minutes * 60)),
+            ws_heartbeat: (settings.ws_heartbeat_secs > 0).then(|| Duration::from_secs(settings.ws_heartbeat_secs)),
+            idle_since: Instant::now(),
+            screensaver: false,
+            last_track: None,
+            details_index: None,
+            confirm_destructive: settings.confirm_destructive,
+            notify_track_start: settings.notify_track_start,
+            presence: None,
+            presence_shown: None,
+            scrobble: None,
+            hooks: Hooks::new(&settings.hooks, false),
+            frame_rate: settings.frame_rate.max(1),
+            accessible: settings.accessible,
+            show_visualizer: settings.show_visualizer,
+            show_logo: settings.show_logo,
+            show_queue: settings.show_queue,
+            compact_mode: settings.compact_mode.clone(),
+            pending_action: None,
+            preset_name_input: None,
             auth_menu_state,
             auth_menu_items: vec!["Login", "Signout", "Info"],
-            lyrics_text: None,
-            lyrics_scroll: 0,
+            history,
+            guild_name,
+            refreshed_at,
+            queue_validator,
+            username: api::load_auth().and_then(|a| a.username),
+            history_state: ListState::default(),
+            playlists: Vec::new(),
+            playlist_state: ListState::default(),
+            playlist_track_state: ListState::default(),
+            playlist_tracks_focused: false,
+            picker_guilds: Vec::new(),
+            picker_guild_state: ListState::default(),
+            picker_channels: Vec::new(),
+            picker_channel_state: ListState::default(),
+            picker_channels_focused: false,
+            picker_status: None,
+            profiles: Vec::new(),
+            active_profile: String::new(),
+            profile_state: ListState::default(),
+            lyrics_text,
+            lyrics_scroll,
+            lyrics_lines,
+            lyrics_follow,
+            lyrics_track,
             auth_info_text: None,
-            spectrogram: None,
-            elapsed_ms: 0,
-            duration_ms: 0,
-            paused: true,
-            last_state_update: Instant::now(),
+            spectrogram,
+            elapsed_ms,
+            duration_ms,
+            scrub_ms,
+            seek_bar: Rect::default(),
+            paused,
+            last_state_update,
+            seeded_settings,
             settings_input: settings.base_url.clone(),
             offset_input: settings.visualizer_offset.to_string(),
+            guild_input: settings.default_guild_id.clone().unwrap_or_default(),
+            user_input: settings.default_user_id.clone().unwrap_or_default(),
+            channel_input: settings.default_channel_id.clone().unwrap_or_default(),
+            export_dir_input: settings.export_dir.clone().unwrap_or_default(),
             theme: settings.theme,
+            custom_themes: settings.custom_themes,
             viz_style: settings.visualizer_style,
             layout: settings.layout,
             settings_field: SettingsField::Host,
@@ -245,216 +974,765 @@ impl App {
             needs_reconnect: false,
             visualizer_offset: settings.visualizer_offset,
             update_info: None,
-            debug_logs: Vec::new(),
+            debug_logs: VecDeque::new(),
+            debug_filter: String::new(),
+            debug_filter_editing: false,
+            command: LineInput::default(),
+            command_editing: false,
+            debug_scroll: 0,
             ws_connected: false,
             ws_connecting: false,
             ws_sender: None,
-            smoothed_bars: vec![0.0; 64],
+            smoothed_bars,
+            picker: None,
+            art_url,
+            art_loaded_url,
+            art,
+            tabs,
+            active_tab: 0,
         }
     }
 
     fn log(&mut self, msg: impl Into<String>) {
+        let msg = msg.into();
+        crate::logfile::event("tui", serde_json::json!({ "message": msg }));
         let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
-        self.debug_logs.push(format!("[{}] {}", timestamp, msg.into()));
-        if self.debug_logs.len() > 100 {
-            self.debug_logs.remove(0);
+        let line = format!("[{}] {}", timestamp, msg);
+        if self.debug_scroll > 0 && self.debug_line_matches(&line) {
+            // Keep the lines being read in place while new ones arrive.
+            self.debug_scroll += 1;
+        }
+        self.debug_logs.push_back(line);
+        if self.debug_logs.len() > DEBUG_LOG_LIMIT {
+            self.debug_logs.pop_front();
         }
     }
 
-    fn save_spectrogram(&mut self) {
-        let spec = match &self.spectrogram {
-            Some(s) => s,
-            None => {
-                self.log("Save failed: No spectrogram data available.");
-                return;
-            }
+    /// Log `msg` and flash it in a toast.
+    fn toast(&mut self, msg: impl Into<String>) {
+        self.show_toast(msg.into(), false);
+    }
+
+    /// A failure that doesn't stop anything else: shown in red over the
+    /// screen, which keeps its last good contents.
+    fn error_toast(&mut self, msg: impl Into<String>) {
+        self.show_toast(msg.into(), true);
+    }
+
+    fn show_toast(&mut self, message: String, error: bool) {
+        self.log(message.clone());
+        self.toast = Some(Toast {
+            message,
+            shown_at: Instant::now(),
+            error,
+        });
+    }
+
+    fn debug_line_matches(&self, line: &str) -> bool {
+        let line = line.to_lowercase();
+        self.debug_filter.split_whitespace().all(|t| line.contains(&t.to_lowercase()))
+    }
+
+    /// Debug lines passing the filter, oldest first.
+    fn filtered_debug_logs(&self) -> Vec<&str> {
+        self.debug_logs
+            .iter()
+            .filter(|line| self.debug_line_matches(line))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Where exported files go: the export folder from settings, else the
+    /// Desktop. Logs why and returns `None` when neither is usable.
+    fn export_path(&mut self, filename: String) -> Option<std::path::PathBuf> {
+        let dir = match Some(self.export_dir_input.trim()).filter(|d| !d.is_empty()) {
+            Some(dir) => std::path::PathBuf::from(dir),
+            None => match dirs::desktop_dir() {
+                Some(dir) => dir,
+                None => {
+                    self.log("Save failed: Could not find Desktop directory. Set an export folder in Settings.");
+                    return None;
+                }
+            },
         };
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            self.log(format!("Save failed: {}: {}", dir.display(), e));
+            return None;
+        }
+        Some(dir.join(filename))
+    }
 
-        let desktop = match dirs::desktop_dir() {
-            Some(d) => d,
-            None => {
-                self.log("Save failed: Could not find Desktop directory.");
+    fn export_debug_log(&mut self) {
+        let text = self.filtered_debug_logs().join("\n") + "\n";
+        let filename = format!("jorik_debug_{}.log", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+        let Some(path) = self.export_path(filename) else {
+            return;
+        };
+        match std::fs::write(&path, text) {
+            Ok(()) => self.log(format!("Debug log saved to: {:?}", path)),
+            Err(e) => self.log(format!("Save failed: {}", e)),
+        }
+    }
+
+    /// Write the current track's spectrogram as `json`, `png` (a heatmap) or `csv`.
+    fn save_spectrogram(&mut self, format: &str) {
+        let Some(spec) = &self.spectrogram else {
+            self.log("Save failed: No spectrogram data available.");
+            return;
+        };
+        let data = match format {
+            "png" => {
+                let mut png = Vec::new();
+                crate::image::spectrogram_heatmap(spec)
+                    .write_to(&mut std::io::Cursor::new(&mut png), ::image::ImageFormat::Png)
+                    .map(|_| png)
+                    .map_err(anyhow::Error::from)
+            }
+            // One row per frame, one column per frequency bin.
+            "csv" => {
+                let bins = spec.iter().map(Vec::len).max().unwrap_or(0);
+                let mut csv = String::from("frame");
+                (0..bins).for_each(|b| csv.push_str(&format!(",bin{}", b)));
+                csv.push('\n');
+                for (i, frame) in spec.iter().enumerate() {
+                    csv.push_str(&i.to_string());
+                    frame.iter().for_each(|v| csv.push_str(&format!(",{}", v)));
+                    csv.push('\n');
+                }
+                Ok(csv.into_bytes())
+            }
+            _ => serde_json::to_vec_pretty(spec).map_err(anyhow::Error::from),
+        };
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                self.log(format!("Save failed: Could not encode spectrogram: {}", e));
                 return;
             }
         };
 
         let filename = format!(
-            "spectrogram_{}.json",
-            chrono::Local::now().format("%Y%m%d_%H%M%S")
+            "spectrogram_{}.{}",
+            chrono::Local::now().format("%Y%m%d_%H%M%S"),
+            format
         );
-        let path = desktop.join(filename);
+        let Some(path) = self.export_path(filename) else {
+            return;
+        };
 
-        match serde_json::to_string_pretty(spec) {
-            Ok(json) => {
-                if let Ok(_) = std::fs::write(&path, json) {
-                    self.log(format!("Spectrogram saved to: {:?}", path));
-                } else {
-                    self.log("Save failed: Could not write to file.");
-                }
-            }
-            Err(_) => {
-                self.log("Save failed: Could not serialize spectrogram.");
-            }
+        match std::fs::write(&path, data) {
+            Ok(()) => self.log(format!("Spectrogram saved to: {:?}", path)),
+            Err(e) => self.log(format!("Save failed: {}", e)),
         }
     }
 
     fn parse_queue_response(&mut self, json: &Value) {
-        // Handle nested queue object if present
-        let target = if let Some(queue) = json.get("queue") {
-            queue
-        } else {
-            json
+        self.refreshed_at = Some(chrono::Local::now());
+        let target = json.get("queue").unwrap_or(json);
+        for source in [Some(json), Some(target), json.get("playback"), json.get("player")].into_iter().flatten() {
+            self.parse_player_flags(source);
+        }
+
+        let response = match QueueResponse::from_json(json) {
+            Ok(response) => response,
+            Err(e) => {
+                self.log(format!("Ignoring malformed queue response: {}", e));
+                return;
+            }
         };
+        let described = response.has_queue();
+        if let Some(name) = response.guild_name {
+            self.guild_name = Some(name);
+        }
 
         // Capture guild_id if provided by server
-        if let Some(gid) = json.get("guild_id").and_then(|v| v.as_str()) {
+        if let Some(gid) = response.guild_id {
             if self.guild_id.is_none() {
                 self.log(format!("Discovered Guild ID: {}", gid));
             }
-            self.guild_id = Some(gid.to_string());
-        } else if let Some(gid) = json.get("guildId").and_then(|v| v.as_str()) {
-            if self.guild_id.is_none() {
-                self.log(format!("Discovered Guild ID: {}", gid));
-            }
-            self.guild_id = Some(gid.to_string());
+            self.guild_id = Some(gid);
         }
 
-        if let Some(current) = target.get("current").and_then(|v| v.as_object()) {
-            let title = current.get("title").and_then(|v| v.as_str()).unwrap_or("Unknown");
-            let author = current.get("author").and_then(|v| v.as_str()).unwrap_or("");
-            self.current_track = Some(format!("{} - {}", title, author));
-        } else {
-            // Only clear current_track if we are sure we are looking at a queue object
-            if target.get("current").is_some() || target.get("upcoming").is_some() {
-                self.current_track = None;
+        if let Some(current) = response.current.flatten() {
+            let track = track_label(&current);
+            if self.current_track.as_ref() != Some(&track) {
+                // A position picked on the previous track means nothing on this one.
+                self.scrub_ms = None;
             }
+            self.current_track = Some(track);
+            self.current_uri = current.uri.clone();
+            self.art_url = current.artwork_url.clone();
+            self.current_info = Some(current);
+        } else if described {
+            // Only clear current_track if we are sure we are looking at a queue object
+            self.current_track = None;
+            self.scrub_ms = None;
+            self.current_uri = None;
+            self.current_info = None;
+            self.art_url = None;
         }
 
-        if let Some(upcoming) = target.get("upcoming").and_then(|v| v.as_array()) {
-            self.queue.clear();
-            for item in upcoming {
-                let title = item.get("title").and_then(|v| v.as_str()).unwrap_or("Unknown");
-                let author = item.get("author").and_then(|v| v.as_str()).unwrap_or("");
-                self.queue.push(format!("{} - {}", title, author));
+        if let Some(upcoming) = response.upcoming {
+            self.queue = upcoming.into_iter().map(QueueTrack::from_track).collect();
+            self.queue_total = response.total_upcoming.map(|total| total.max(self.queue.len()));
+            // Keep the highlight on a real row when the queue shrinks.
+            if let Some(i) = self.queue_state.selected() {
+                self.queue_state
+                    .select(self.queue.len().checked_sub(1).map(|last| i.min(last)));
             }
         }
     }
 
-    fn update_realtime(&mut self) {
-        if self.current_track.is_some() && !self.paused {
-            let now = Instant::now();
-            let delta = now.duration_since(self.last_state_update).as_millis() as u64;
-            self.elapsed_ms += delta;
-            self.last_state_update = now;
-            
-            if self.duration_ms > 0 && self.elapsed_ms > self.duration_ms {
-                self.elapsed_ms = self.duration_ms;
-            }
-
-            // Smoothing logic
-            if let Some(spec) = &self.spectrogram {
-                let adjusted_ms = self.elapsed_ms.saturating_add_signed(self.visualizer_offset);
-                let frame_index = (adjusted_ms as f64 / 42.66).floor() as usize;
-                if frame_index < spec.len() {
-                    let target_bars = &spec[frame_index];
-                    for i in 0..64.min(target_bars.len()) {
-                        let target = target_bars[i] as f32;
-                        let current = self.smoothed_bars[i];
-                        
-                        // Variable noise floor: higher for sub-bass to ignore rumble
-                        let floor = if i < 3 { 60.0 } else { 30.0 };
-                        let raw_signal = (target - floor).max(0.0);
-                        
-                        // Simple direct scaling
-                        let gain = if i == 0 { 0.1 } else { 0.6 };
-                        let scaled_target = (raw_signal * gain).min(100.0);
-
-                        // Factors adjusted for 60fps
-                        if scaled_target > current {
-                            self.smoothed_bars[i] = current + (scaled_target - current) * 0.4; 
-                        } else {
-                            self.smoothed_bars[i] = current - (current - scaled_target) * 0.15;
-                        }
-                    }
-                }
-            }
-        } else {
-            self.last_state_update = Instant::now();
-            // Fade out bars when idle
-            for i in 0..64 {
-                self.smoothed_bars[i] *= 0.95;
-            }
+    /// Loop, 24/7, shuffle and pause state from a queue or state payload, so the
+    /// header follows changes made from Discord or other clients. Keys that
+    /// aren't there leave the current value alone.
+    fn parse_player_flags(&mut self, json: &Value) {
+        let get = |keys: &[&str]| keys.iter().find_map(|k| json.get(*k));
+        if let Some(mode) = get(&["loop_mode", "loopMode", "loop", "repeat", "repeatMode"]).and_then(parse_loop_mode) {
+            self.loop_mode = mode.to_string();
+        }
+        if let Some(on) = get(&["twenty_four_seven", "twentyFourSeven", "247"]).and_then(Value::as_bool) {
+            self.always_on = Some(on);
+        }
+        if let Some(on) = get(&["shuffle", "shuffled"]).and_then(Value::as_bool) {
+            self.shuffle = Some(on);
+        }
+        if let Some(paused) = json.get("paused").and_then(Value::as_bool) {
+            self.paused = paused;
         }
     }
-}
 
-// Spawning helpers
-async fn async_fetch_queue(app_arc: Arc<Mutex<App>>) {
-    let (client, url, token, payload) = {
-        let mut app = app_arc.lock().await;
+    /// Append a further page of upcoming tracks fetched at `offset`. Dropped
+    /// if the queue was replaced in the meantime, as the page no longer lines up.
+    fn append_queue_page(&mut self, json: &Value, offset: usize) {
+        let response = match QueueResponse::from_json(json) {
+            Ok(response) => response,
+            Err(e) => {
+                self.log(format!("Ignoring malformed queue page: {}", e));
+                return;
+            }
+        };
+        let Some(upcoming) = response.upcoming else {
+            return;
+        };
+        if self.queue.len() != offset {
+            return;
+        }
+        let page_len = upcoming.len();
+        self.queue.extend(upcoming.into_iter().map(QueueTrack::from_track));
+        self.queue_total = match response.total_upcoming {
+            Some(total) => Some(total.max(self.queue.len())),
+            // Without a total, a short page is the last one.
+            None if page_len < QUEUE_PAGE => Some(self.queue.len()),
+            None => self.queue_total,
+        };
+    }
+
+    /// Queue indices matching the search term, in queue order.
+    fn queue_matches(&self) -> Vec<usize> {
+        match self.queue_search.as_deref() {
+            Some(term) if !term.is_empty() => (0..self.queue.len()).filter(|&i| fuzzy_match(&self.queue[i].label, term)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Select the next (or previous) search match after the highlight, wrapping around.
+    fn jump_to_match(&mut self, forward: bool) {
+        let matches = self.queue_matches();
+        let next = match self.queue_state.selected() {
+            Some(cur) if forward => matches.iter().find(|&&i| i > cur).or(matches.first()),
+            Some(cur) => matches.iter().rev().find(|&&i| i < cur).or(matches.last()),
+            None if forward => matches.first(),
+            None => matches.last(),
+        };
+        if let Some(&i) = next {
+            self.queue_state.select(Some(i));
+        }
+    }
+
+    /// Whether the server holds upcoming tracks beyond the loaded ones.
+    fn has_more_queue(&self) -> bool {
+        self.queue_total.is_some_and(|total| total > self.queue.len())
+    }
+
+    /// The active filter preset with the current volume applied. Filters are
+    /// replaced as a whole on the server, so both always travel together.
+    fn current_filters(&self) -> AudioFilters {
+        let mut filters = self.filters.clone();
+        if self.volume != 100 {
+            filters.volume = Some(self.volume as f32 / 100.0);
+        }
+        filters
+    }
+
+    /// The filter payload for a volume or filter change that has settled, if any.
+    fn take_settled_filters(&mut self) -> Option<FilterPayload> {
+        if self.filters_changed_at?.elapsed() < FILTER_DEBOUNCE {
+            return None;
+        }
+        self.take_pending_filters()
+    }
+
+    /// The filter payload for any unsent change, settled or not.
+    fn take_pending_filters(&mut self) -> Option<FilterPayload> {
+        self.filters_changed_at.take()?;
+        self.log(format!("Applying filters (volume {}%)", self.volume));
+        Some(FilterPayload {
+            action: "filter",
+            guild_id: self.guild_id.clone(),
+            user_id: self.user_id.clone(),
+            filters: self.current_filters(),
+        })
+    }
+
+    /// Remember a finished track locally, so the history view has something
+    /// to show when the server keeps no history.
+    fn record_played(&mut self, entry: HistoryEntry) {
+        if self.history.first().is_some_and(|h| h.label == entry.label) {
+            return;
+        }
+        self.history.insert(0, entry);
+        self.history.truncate(HISTORY_LIMIT);
+    }
+
+    /// Whether a track is highlighted or a find is open. Only then do the
+    /// one-key shortcuts that could also start a query (`+`, `/`, `y`, ...)
+    /// act; otherwise they are typed into the search like any other key.
+    fn browsing_queue(&self) -> bool {
+        self.queue_state.selected().is_some() || self.queue_search.is_some()
+    }
+
+    /// Move the queue highlight by `delta` rows, clamped to the list.
+    fn move_queue_selection(&mut self, delta: isize) {
+        let Some(last) = self.queue.len().checked_sub(1) else {
+            self.queue_state.select(None);
+            return;
+        };
+        let next = match self.queue_state.selected() {
+            Some(i) => i.saturating_add_signed(delta).min(last),
+            None if delta < 0 => last,
+            None => 0,
+        };
+        self.queue_state.select(Some(next));
+    }
+
+    /// `[guilds.<id>]` overrides for the guild on screen.
+    fn guild_settings(&self) -> Option<&api::GuildSettings> {
+        self.guild_overrides.get(self.guild_id.as_deref()?)
+    }
+
+    fn visualizer_offset_ms(&self) -> i64 {
+        self.guild_settings()
+            .and_then(|g| g.visualizer_offset)
+            .unwrap_or(self.visualizer_offset)
+    }
+
+    /// Voice channel for play requests. The guild's own channel replaces the
+    /// global default, but not one picked for this session.
+    fn play_channel(&self) -> Option<String> {
+        let default = Some(self.channel_input.clone()).filter(|c| !c.is_empty());
+        match self.guild_settings().and_then(|g| g.channel_id.clone()) {
+            Some(channel) if self.channel_id == default => Some(channel),
+            _ => self.channel_id.clone(),
+        }
+    }
+
+    /// The visualizer is never drawn in accessibility mode, whatever `V` says.
+    fn visualizer_shown(&self) -> bool {
+        self.show_visualizer && !self.accessible
+    }
+
+    /// Start every tab over empty, keeping only its guild.
+    fn reset_tabs(&mut self) {
+        let active = self.active_tab;
+        self.swap_tab(active);
+        for tab in &mut self.tabs {
+            *tab = GuildTab::new(tab.guild_id.take());
+        }
+        self.swap_tab(active);
+    }
+
+    /// Exchange the live player state with the parked state of tab `i`.
+    fn swap_tab(&mut self, i: usize) {
+        let tab = &mut self.tabs[i];
+        std::mem::swap(&mut self.guild_id, &mut tab.guild_id);
+        std::mem::swap(&mut self.queue, &mut tab.queue);
+        std::mem::swap(&mut self.queue_state, &mut tab.queue_state);
+        std::mem::swap(&mut self.queue_total, &mut tab.queue_total);
+        std::mem::swap(&mut self.pending_remove, &mut tab.pending_remove);
+        std::mem::swap(&mut self.filters, &mut tab.filters);
+        std::mem::swap(&mut self.volume, &mut tab.volume);
+        std::mem::swap(&mut self.filters_changed_at, &mut tab.filters_changed_at);
+        std::mem::swap(&mut self.current_track, &mut tab.current_track);
+        std::mem::swap(&mut self.current_uri, &mut tab.current_uri);
+        std::mem::swap(&mut self.current_info, &mut tab.current_info);
+        std::mem::swap(&mut self.loop_mode, &mut tab.loop_mode);
+        std::mem::swap(&mut self.always_on, &mut tab.always_on);
+        std::mem::swap(&mut self.shuffle, &mut tab.shuffle);
+        std::mem::swap(&mut self.history, &mut tab.history);
+        std::mem::swap(&mut self.guild_name, &mut tab.guild_name);
+        std::mem::swap(&mut self.refreshed_at, &mut tab.refreshed_at);
+        std::mem::swap(&mut self.queue_validator, &mut tab.queue_validator);
+        std::mem::swap(&mut self.lyrics_text, &mut tab.lyrics_text);
+        std::mem::swap(&mut self.lyrics_scroll, &mut tab.lyrics_scroll);
+        std::mem::swap(&mut self.lyrics_lines, &mut tab.lyrics_lines);
+        std::mem::swap(&mut self.lyrics_follow, &mut tab.lyrics_follow);
+        std::mem::swap(&mut self.lyrics_track, &mut tab.lyrics_track);
+        std::mem::swap(&mut self.spectrogram, &mut tab.spectrogram);
+        std::mem::swap(&mut self.elapsed_ms, &mut tab.elapsed_ms);
+        std::mem::swap(&mut self.duration_ms, &mut tab.duration_ms);
+        std::mem::swap(&mut self.scrub_ms, &mut tab.scrub_ms);
+        std::mem::swap(&mut self.paused, &mut tab.paused);
+        std::mem::swap(&mut self.last_state_update, &mut tab.last_state_update);
+        std::mem::swap(&mut self.smoothed_bars, &mut tab.smoothed_bars);
+        std::mem::swap(&mut self.art_url, &mut tab.art_url);
+        std::mem::swap(&mut self.art_loaded_url, &mut tab.art_loaded_url);
+        std::mem::swap(&mut self.art, &mut tab.art);
+        std::mem::swap(&mut self.fatal_error, &mut tab.fatal_error);
+    }
+
+    /// Guild of tab `i`, reading the live state for the active tab.
+    fn tab_guild(&self, i: usize) -> Option<&str> {
+        if i == self.active_tab {
+            self.guild_id.as_deref()
+        } else {
+            self.tabs[i].guild_id.as_deref()
+        }
+    }
+
+    /// Whether tab `i` has a track playing right now.
+    fn tab_playing(&self, i: usize) -> bool {
+        if i == self.active_tab {
+            self.current_track.is_some() && !self.paused
+        } else {
+            self.tabs[i].current_track.is_some() && !self.tabs[i].paused
+        }
+    }
+
+    fn has_guild_tab(&self, guild_id: &str) -> bool {
+        (0..self.tabs.len()).any(|i| self.tab_guild(i) == Some(guild_id))
+    }
+
+    fn tab_guilds(&self) -> Vec<Option<String>> {
+        (0..self.tabs.len()).map(|i| self.tab_guild(i).map(str::to_string)).collect()
+    }
+
+    fn select_tab(&mut self, i: usize) {
+        if i == self.active_tab || i >= self.tabs.len() {
+            return;
+        }
+        self.swap_tab(self.active_tab);
+        self.swap_tab(i);
+        self.active_tab = i;
+        // An artwork download finishing while the tab was parked is dropped; fetch it again.
+        if self.art.is_none() {
+            self.art_loaded_url = None;
+        }
+        self.log(format!("Switched to guild {}", self.guild_id.as_deref().unwrap_or("?")));
+    }
+
+    /// Run `f` against the tab for `guild_id`, swapping it in for the
+    /// duration if it is parked; `None` means the active tab. Returns `None`
+    /// when no tab has that guild (e.g. it was changed in Settings meanwhile).
+    fn with_guild<R>(&mut self, guild_id: Option<&str>, f: impl FnOnce(&mut App) -> R) -> Option<R> {
+        let i = match guild_id {
+            None => self.active_tab,
+            Some(gid) => (0..self.tabs.len()).find(|&i| self.tab_guild(i) == Some(gid))?,
+        };
+        if i == self.active_tab {
+            return Some(f(self));
+        }
+        self.swap_tab(self.active_tab);
+        self.swap_tab(i);
+        let result = f(self);
+        self.swap_tab(i);
+        self.swap_tab(self.active_tab);
+        Some(result)
+    }
+
+    /// How soon the screen needs redrawing with no input or background
+    /// change: every frame while the visualizer moves, a few times a second
+    /// for the progress bar, otherwise just often enough for clocks and timeouts.
+    fn frame_interval(&self) -> Duration {
+        let playing = self.current_track.is_some() && !self.paused;
+        let visualizer = playing && self.visualizer_shown() && self.spectrogram.is_some() && self.view == View::Main && !self.screensaver;
+        if visualizer || self.filters_changed_at.is_some() {
+            Duration::from_secs(1) / self.frame_rate
+        } else if self.current_track.is_some() || self.toast.is_some() {
+            // A paused track still needs ticks for the blinking banner.
+            Duration::from_millis(250)
+        } else {
+            Duration::from_secs(1)
+        }
+    }
+
+    /// Start the screensaver once nothing has played for `screensaver_after`.
+    fn update_idle(&mut self) {
+        if self.current_track.is_some() && !self.paused {
+            self.idle_since = Instant::now();
+            if self.last_track != self.current_track {
+                self.last_track = self.current_track.clone();
+            }
+        }
+        let interruptible = matches!(self.view, View::Main | View::Lyrics | View::Menu | View::Debug)
+            && self.input_mode == InputMode::Normal
+            && self.fatal_error.is_none();
+        if let Some(after) = self.screensaver_after
+            && interruptible
+            && self.idle_since.elapsed() >= after
+        {
+            self.screensaver = true;
+        }
+    }
+
+    /// Hand the Discord presence task the current track whenever it, or the
+    /// point it is at, changed since the last time.
+    fn update_presence(&mut self) {
+        let Some(sender) = &self.presence else {
+            return;
+        };
+        let playing = self.current_track.as_ref().map(|label| {
+            let info = self.current_info.as_ref();
+            let started_at = chrono::Utc::now().timestamp_millis() - self.elapsed_ms as i64;
+            let playing = !self.paused;
+            presence::Playing {
+                title: info.and_then(|i| i.title.clone()).unwrap_or_else(|| label.clone()),
+                author: info.and_then(|i| i.author.clone()).unwrap_or_default(),
+                started_at: playing.then_some(started_at),
+                ends_at: (playing && self.duration_ms > 0).then(|| started_at + self.duration_ms as i64),
+            }
+        });
+        let changed = match (&playing, &self.presence_shown) {
+            (Some(playing), Some(shown)) => playing.differs_from(shown),
+            (playing, shown) => playing.is_some() != shown.is_some(),
+        };
+        if changed {
+            sender.send(playing.clone()).ok();
+            self.presence_shown = playing;
+        }
+    }
+
+    /// Any input counts as activity; returns true when it only dismissed the screensaver.
+    fn wake(&mut self) -> bool {
+        self.idle_since = Instant::now();
+        if !self.screensaver {
+            return false;
+        }
+        self.screensaver = false;
+        self.view = View::Main;
+        true
+    }
+
+    fn update_realtime(&mut self) {
+        let now = Instant::now();
+        let delta = now.duration_since(self.last_state_update).as_millis() as u64;
+        // The smoothing factors below were tuned per frame at 60fps; raised to
+        // the number of such frames elapsed they behave the same at any rate.
+        let frames = delta as f32 / (1000.0 / 60.0);
+        let step = |factor: f32| 1.0 - (1.0 - factor).powf(frames);
+        if self.current_track.is_some() && !self.paused {
+            self.elapsed_ms += delta;
+            self.last_state_update = now;
+            
+            if self.duration_ms > 0 && self.elapsed_ms > self.duration_ms {
+                self.elapsed_ms = self.duration_ms;
+            }
+
+            // Smoothing logic
+            if let Some(spec) = &self.spectrogram {
+                let adjusted_ms = self.elapsed_ms.saturating_add_signed(self.visualizer_offset_ms());
+                let frame_index = (adjusted_ms as f64 / 42.66).floor() as usize;
+                if frame_index < spec.len() {
+                    let target_bars = &spec[frame_index];
+                    for (i, &bar) in target_bars.iter().enumerate().take(64) {
+                        let target = bar as f32;
+                        let current = self.smoothed_bars[i];
+                        
+                        // Variable noise floor: higher for sub-bass to ignore rumble
+                        let floor = if i < 3 { 60.0 } else { 30.0 };
+                        let raw_signal = (target - floor).max(0.0);
+                        
+                        // Simple direct scaling
+                        let gain = if i == 0 { 0.1 } else { 0.6 };
+                        let scaled_target = (raw_signal * gain).min(100.0);
+
+                        if scaled_target > current {
+                            self.smoothed_bars[i] = current + (scaled_target - current) * step(0.4);
+                        } else {
+                            self.smoothed_bars[i] = current - (current - scaled_target) * step(0.15);
+                        }
+                    }
+                }
+            }
+        } else {
+            self.last_state_update = now;
+            // Fade out bars when idle
+            let fade = 1.0 - step(0.05);
+            for i in 0..64 {
+                self.smoothed_bars[i] *= fade;
+            }
+        }
+    }
+}
+
+// Spawning helpers
+/// A loop mode as servers spell it (`"song"`, `"all"`, 0-2, ...) in this
+/// client's terms: `"off"`, `"track"` or `"queue"`.
+fn parse_loop_mode(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::String(s) => match s.to_lowercase().as_str() {
+            "off" | "none" | "disabled" => Some("off"),
+            "track" | "song" | "one" | "single" => Some("track"),
+            "queue" | "all" | "playlist" => Some("queue"),
+            _ => None,
+        },
+        Value::Number(n) => match n.as_u64()? {
+            0 => Some("off"),
+            1 => Some("track"),
+            2 => Some("queue"),
+            _ => None,
+        },
+        Value::Bool(false) => Some("off"),
+        _ => None,
+    }
+}
+
+/// `Title - Author` for one track.
+fn track_label(track: &Track) -> String {
+    let title = track.title.as_deref().unwrap_or(i18n::tr("Unknown"));
+    format!("{} - {}", title, track.author.as_deref().unwrap_or(""))
+}
+
+/// Case-insensitive subsequence match, so `bhrp` finds "Bohemian Rhapsody".
+fn fuzzy_match(text: &str, term: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    term.chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|c| text.any(|t| t == c))
+}
+
+async fn async_fetch_queue(app_arc: Arc<Mutex<App>>) {
+    let _redraw = RedrawOnDrop;
+    let guild_id = app_arc.lock().await.guild_id.clone();
+    async_fetch_guild_queue(app_arc, guild_id).await;
+}
+
+/// Refresh the queue of one guild tab, whether or not it is the active one.
+/// Pages already loaded by scrolling are fetched again so the list keeps its length.
+async fn async_fetch_guild_queue(app_arc: Arc<Mutex<App>>, guild_id: Option<String>) {
+    let _redraw = RedrawOnDrop;
+    let loaded = {
+        let mut app = app_arc.lock().await;
+        app.with_guild(guild_id.as_deref(), |app| app.queue.len()).unwrap_or(0)
+    };
+    async_fetch_queue_window(app_arc, guild_id, 0, loaded.max(QUEUE_PAGE)).await;
+}
+
+/// Fetch the next page of the active queue once the selection reaches the
+/// last loaded track.
+fn load_more_queue(app: &mut App, app_arc: Arc<Mutex<App>>) {
+    let at_end = app.queue_state.selected().is_some_and(|i| i + 1 >= app.queue.len());
+    if !at_end || !app.has_more_queue() || app.queue_loading_more {
+        return;
+    }
+    app.queue_loading_more = true;
+    let (guild_id, offset) = (app.guild_id.clone(), app.queue.len());
+    tokio::spawn(async_fetch_queue_window(app_arc, guild_id, offset, QUEUE_PAGE));
+}
+
+/// Request `limit` upcoming tracks from `offset`: offset 0 replaces the queue,
+/// anything else appends a page to it.
+async fn async_fetch_queue_window(app_arc: Arc<Mutex<App>>, guild_id: Option<String>, offset: usize, limit: usize) {
+    let _redraw = RedrawOnDrop;
+    let (client, url, token, payload, validator) = {
+        let mut app = app_arc.lock().await;
         app.is_loading = true;
         let payload = QueuePayload {
             action: "queue",
-            guild_id: app.guild_id.clone(),
+            guild_id: guild_id.clone(),
             user_id: app.user_id.clone(),
-            limit: 20,
-            offset: 0,
+            limit,
+            offset,
         };
         let url = api::build_url(&app.base_url, "/webhook/audio");
-        (app.client.clone(), url, app.token.clone(), payload)
+        let validator = app
+            .with_guild(guild_id.as_deref(), |app| app.queue_validator.clone())
+            .flatten()
+            .filter(|v| offset == 0 && v.limit == limit);
+        (app.client.clone(), url, app.token.clone(), payload, validator)
     };
 
-    let mut req = client.post(&url).json(&payload);
+    let request_id = api::new_request_id();
+    let mut req = client.post(&url).header(api::REQUEST_ID_HEADER, &request_id).json(&payload);
     if let Some(bearer) = &token {
         req = req.bearer_auth(bearer);
     }
+    if let Some(validator) = validator {
+        if let Some(etag) = validator.etag {
+            req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(since) = validator.last_modified {
+            req = req.header(reqwest::header::IF_MODIFIED_SINCE, since);
+        }
+    }
+
+    let result = match req.send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            let validator = QueueValidator::from_headers(limit, resp.headers());
+            Ok((status, validator, resp.text().await.unwrap_or_default()))
+        }
+        Err(e) => Err(e),
+    };
 
-    let result = req.send().await;
-    
     let mut app = app_arc.lock().await;
     app.is_loading = false;
-    match result {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                if let Ok(json) = resp.json::<Value>().await {
+    if offset > 0 {
+        app.queue_loading_more = false;
+    }
+    app.with_guild(guild_id.as_deref(), |app| match result {
+        Ok((reqwest::StatusCode::NOT_MODIFIED, _, _)) => {
+            app.refreshed_at = Some(chrono::Local::now());
+        }
+        Ok((status, validator, text)) if status.is_success() => {
+            if let Ok(json) = serde_json::from_str::<Value>(&text) {
+                if offset == 0 {
                     app.parse_queue_response(&json);
-                    app.error_message = None;
+                    app.queue_validator = validator;
+                } else {
+                    app.append_queue_page(&json, offset);
                 }
+            }
+        }
+        Ok((_, _, text)) => {
+            app.log(format!("Queue request failed (request ID {})", request_id));
+            if let Ok(err) = serde_json::from_str::<ErrorResponse>(&text)
+                && err.error == "bad_request"
+                && err.message.as_deref() == Some("user_not_in_voice_channel_or_guild_unknown")
+            {
+                app.fatal_error = Some(i18n::tr("User not in voice channel or guild unknown.\n\nPress 'r' to reload.").to_string());
+            } else if text.contains("guild_id is required") {
+                app.error_toast(i18n::tr("Not connected to a voice channel or Guild ID missing."));
             } else {
-                 let text = resp.text().await.unwrap_or_default();
-                 
-                 let mut handled = false;
-                 if let Ok(json_err) = serde_json::from_str::<Value>(&text) {
-                     if json_err.get("error").and_then(|v| v.as_str()) == Some("bad_request") &&
-                        json_err.get("message").and_then(|v| v.as_str()) == Some("user_not_in_voice_channel_or_guild_unknown") {
-                            app.fatal_error = Some("User not in voice channel or guild unknown.\n\nPress 'r' to reload.".to_string());
-                            handled = true;
-                     }
-                 }
-
-                 if !handled {
-                     if text.contains("guild_id is required") {
-                         app.error_message = Some("Not connected to a voice channel or Guild ID missing.".to_string());
-                     } else {
-                         app.error_message = Some(format!("Error: {}", text));
-                     }
-                 }
+                app.error_toast(t!("Error: {}", text));
             }
         }
         Err(e) => {
-            app.error_message = Some(format!("Network error: {}", e));
+            app.error_toast(t!("Network error: {}", e));
         }
-    }
+    });
 }
 
 async fn async_play_track(app_arc: Arc<Mutex<App>>, query: String) {
+    let _redraw = RedrawOnDrop;
     let (ws_sender, ws_connected, client, url, token, payload) = {
         let mut app = app_arc.lock().await;
         app.is_loading = true;
         let payload = PlayPayload {
             action: "play",
             guild_id: app.guild_id.clone(),
-            channel_id: None,
+            channel_id: app.play_channel(),
             query: api::clean_query(&query),
             user_id: app.user_id.clone(),
             requested_by: None,
@@ -464,29 +1742,27 @@ async fn async_play_track(app_arc: Arc<Mutex<App>>, query: String) {
         (app.ws_sender.clone(), app.ws_connected, app.client.clone(), url, app.token.clone(), payload)
     };
 
-    if ws_connected {
-        if let Some(sender) = ws_sender {
-            let ws_action = api::WsAction {
-                event_type: "action",
-                id: format!("play-{}", chrono::Local::now().timestamp_millis()),
-                payload: &payload,
-            };
-            if let Ok(json) = serde_json::to_string(&ws_action) {
-                if let Ok(_) = sender.send(Message::Text(json.into())) {
-                    // Success sending via WS
-                    // We still set is_loading to false after a bit, or let the WS event handle it.
-                    // Actually, WS event will refresh the queue anyway.
-                    tokio::time::sleep(Duration::from_millis(100)).await;
-                    let mut app = app_arc.lock().await;
-                    app.is_loading = false;
-                    return;
-                }
-            }
+    if ws_connected && let Some(sender) = ws_sender {
+        let ws_action = api::WsAction {
+            event_type: "action",
+            id: format!("play-{}", chrono::Local::now().timestamp_millis()),
+            payload: &payload,
+        };
+        if let Ok(json) = serde_json::to_string(&ws_action)
+            && sender.send(Message::Text(json.into())).is_ok()
+        {
+            // Success sending via WS
+            // We still set is_loading to false after a bit, or let the WS event handle it.
+            // Actually, WS event will refresh the queue anyway.
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let mut app = app_arc.lock().await;
+            app.is_loading = false;
+            return;
         }
     }
 
     // Fallback to REST
-    let mut req = client.post(&url).json(&payload);
+    let mut req = client.post(&url).header(api::REQUEST_ID_HEADER, api::new_request_id()).json(&payload);
     if let Some(bearer) = &token {
         req = req.bearer_auth(bearer);
     }
@@ -496,49 +1772,68 @@ async fn async_play_track(app_arc: Arc<Mutex<App>>, query: String) {
     async_fetch_queue(app_arc).await;
 }
 
-async fn async_fetch_lyrics(app_arc: Arc<Mutex<App>>) {
+/// Fetch lyrics for the current track; `open` switches to the Lyrics view,
+/// otherwise they only feed the Lyrics layout's pane.
+async fn async_fetch_lyrics(app_arc: Arc<Mutex<App>>, open: bool) {
+    let _redraw = RedrawOnDrop;
     let (ws_sender, ws_connected, client, url, token, payload) = {
         let mut app = app_arc.lock().await;
         app.is_loading = true;
+        app.lyrics_track = app.current_track.clone();
         let payload = LyricsPayload {
             action: "lyrics".to_string(),
             guild_id: app.guild_id.clone(),
             user_id: app.user_id.clone(),
+            query: None,
         };
         let url = api::build_url(&app.base_url, "/webhook/audio");
         (app.ws_sender.clone(), app.ws_connected, app.client.clone(), url, app.token.clone(), payload)
     };
 
-    if ws_connected {
-        if let Some(sender) = ws_sender {
-            let ws_action = api::WsAction {
-                event_type: "action",
-                id: format!("lyrics-{}", chrono::Local::now().timestamp_millis()),
-                payload: &payload,
-            };
-            if let Ok(json) = serde_json::to_string(&ws_action) {
-                if let Ok(_) = sender.send(Message::Text(json.into())) {
-                }
-            }
+    if ws_connected && let Some(sender) = ws_sender {
+        let ws_action = api::WsAction {
+            event_type: "action",
+            id: format!("lyrics-{}", chrono::Local::now().timestamp_millis()),
+            payload: &payload,
+        };
+        if let Ok(json) = serde_json::to_string(&ws_action) {
+            let _ = sender.send(Message::Text(json.into()));
         }
     }
 
-    let mut req = client.post(&url).json(&payload);
+    let mut req = client.post(&url).header(api::REQUEST_ID_HEADER, api::new_request_id()).json(&payload);
     if let Some(bearer) = &token {
         req = req.bearer_auth(bearer);
     }
 
-    let result = req.send().await;
-    
+    let result = match req.send().await {
+        Ok(resp) => Ok(resp.json::<Value>().await.ok()),
+        Err(e) => Err(e),
+    };
+
     let mut app = app_arc.lock().await;
-    app.view = View::Lyrics;
-    app.lyrics_scroll = 0;
+    if open {
+        app.view = View::Lyrics;
+    }
     app.is_loading = false;
-    
-    match result {
-        Ok(resp) => {
-            if let Ok(json) = resp.json::<Value>().await {
-                if let Some(data) = json.get("data").and_then(|v| v.as_object()) {
+    app.with_guild(payload.guild_id.as_deref(), |app| {
+        app.lyrics_scroll = 0;
+        app.lyrics_lines.clear();
+        app.lyrics_follow = true;
+        app.lyrics_text = Some(match result {
+            Ok(Some(json)) => match json.get("data").and_then(|v| v.as_object()) {
+                Some(data) => {
+                    if let Some(lines) = data.get("lines").and_then(|v| v.as_array()) {
+                        app.lyrics_lines = lines
+                            .iter()
+                            .filter_map(|line| {
+                                let ts = line.get("timestamp").and_then(|v| v.as_u64())?;
+                                let text = line.get("line").and_then(|v| v.as_str()).unwrap_or("");
+                                Some((ts, text.to_string()))
+                            })
+                            .collect();
+                        app.lyrics_lines.sort_by_key(|(ts, _)| *ts);
+                    }
                     let mut output = String::new();
                     if let Some(text) = data.get("text").and_then(|v| v.as_str()) {
                         output.push_str(text);
@@ -549,24 +1844,344 @@ async fn async_fetch_lyrics(app_arc: Arc<Mutex<App>>) {
                         }
                     }
                     if output.trim().is_empty() {
-                         app.lyrics_text = Some("No lyrics found.".to_string());
+                        i18n::tr("No lyrics found.").to_string()
                     } else {
-                         app.lyrics_text = Some(output);
+                        output
                     }
-                } else {
-                    app.lyrics_text = Some("No lyrics found.".to_string());
                 }
-            } else {
-                app.lyrics_text = Some("Failed to parse lyrics.".to_string());
+                None => i18n::tr("No lyrics found.").to_string(),
+            },
+            Ok(None) => i18n::tr("Failed to parse lyrics.").to_string(),
+            Err(e) => t!("Failed to fetch lyrics: {}", e),
+        });
+    });
+}
+
+async fn async_fetch_history(app_arc: Arc<Mutex<App>>) {
+    let _redraw = RedrawOnDrop;
+    let (client, url, token, payload) = {
+        let mut app = app_arc.lock().await;
+        app.is_loading = true;
+        let payload = QueuePayload {
+            action: "history",
+            guild_id: app.guild_id.clone(),
+            user_id: app.user_id.clone(),
+            limit: HISTORY_LIMIT,
+            offset: 0,
+        };
+        let url = api::build_url(&app.base_url, "/webhook/audio");
+        (app.client.clone(), url, app.token.clone(), payload)
+    };
+
+    let mut req = client.post(&url).header(api::REQUEST_ID_HEADER, api::new_request_id()).json(&payload);
+    if let Some(bearer) = &token {
+        req = req.bearer_auth(bearer);
+    }
+
+    let result = match req.send().await {
+        Ok(resp) if resp.status().is_success() => resp.json::<Value>().await.map_err(anyhow::Error::from),
+        Ok(resp) => Err(anyhow::anyhow!("HTTP {}", resp.status())),
+        Err(e) => Err(e.into()),
+    };
+
+    let mut app = app_arc.lock().await;
+    app.is_loading = false;
+    let tracks = result.and_then(|json| {
+        let tracks = match json.get("tracks").or_else(|| json.get("history")) {
+            Some(list) => Option::<Vec<Track>>::deserialize(list)?.unwrap_or_default(),
+            None => Vec::new(),
+        };
+        Ok(tracks.iter().map(HistoryEntry::from_track).collect::<Vec<_>>())
+    });
+    app.with_guild(payload.guild_id.as_deref(), |app| match tracks {
+        // Fall back to what this session recorded when the server has nothing.
+        Ok(tracks) if !tracks.is_empty() => app.history = tracks,
+        Ok(_) => {}
+        Err(e) => app.log(format!("History unavailable, showing local history: {:#}", e)),
+    });
+}
+
+/// Load the guild picker's list: `/webhook/guilds`, or the guilds in the
+/// whoami response on servers without that endpoint.
+async fn async_fetch_picker_guilds(app_arc: Arc<Mutex<App>>) {
+    let _redraw = RedrawOnDrop;
+    let (client, base_url, token) = {
+        let mut app = app_arc.lock().await;
+        app.picker_status = Some(i18n::tr("Loading...").to_string());
+        (app.client.clone(), app.base_url.clone(), app.token.clone())
+    };
+
+    let result = async {
+        let resp = get_json(&client, &api::build_url(&base_url, "/webhook/guilds"), token.as_deref()).await;
+        match resp {
+            Ok(Some(json)) => Ok(list_field(&json, "guilds")),
+            Ok(None) => {
+                let json = get_json(&client, &api::build_url(&base_url, "/webhook/auth/whoami"), token.as_deref())
+                    .await?
+                    .context("whoami is not available")?;
+                Ok(serde_json::from_value::<api::WhoamiResponse>(json)?.guilds)
             }
+            Err(e) => Err(e),
+        }
+    }
+    .await;
+
+    let mut app = app_arc.lock().await;
+    match result {
+        Ok(guilds) => {
+            app.picker_status = guilds.is_empty().then(|| i18n::tr("No guilds found. Is the bot on one of your servers?").to_string());
+            app.picker_guild_state.select((!guilds.is_empty()).then_some(0));
+            app.picker_guilds = guilds;
         }
         Err(e) => {
-            app.lyrics_text = Some(format!("Failed to fetch lyrics: {}", e));
+            app.log(format!("Loading guilds failed: {:#}", e));
+            app.picker_status = Some(t!("Loading guilds failed: {}", format!("{:#}", e)));
+        }
+    }
+}
+
+/// Voice channels of `guild_id` for the picker's right pane.
+async fn async_fetch_picker_channels(app_arc: Arc<Mutex<App>>, guild_id: String) {
+    let _redraw = RedrawOnDrop;
+    let (client, url, token) = {
+        let mut app = app_arc.lock().await;
+        app.is_loading = true;
+        let url = api::build_url(&app.base_url, &format!("/webhook/guilds/{}/channels", guild_id));
+        (app.client.clone(), url, app.token.clone())
+    };
+
+    let result = get_json(&client, &url, token.as_deref()).await;
+
+    let mut app = app_arc.lock().await;
+    app.is_loading = false;
+    // The cursor may have moved on to another guild meanwhile.
+    let selected = app.picker_guild_state.selected().and_then(|i| app.picker_guilds.get(i));
+    if selected.map(|g| g.id.as_str()) != Some(guild_id.as_str()) {
+        return;
+    }
+    match result {
+        Ok(json) => app.picker_channels = json.map(|json| list_field(&json, "channels")).unwrap_or_default(),
+        Err(e) => app.log(format!("Loading channels of {} failed: {:#}", guild_id, e)),
+    }
+}
+
+/// Check the token with `/webhook/auth/introspect` once a session starts and
+/// warn (as a toast) if it is no longer accepted or is about to expire. On
+/// servers without the endpoint the expiry saved at login is used.
+async fn async_check_token(app_arc: Arc<Mutex<App>>) {
+    let _redraw = RedrawOnDrop;
+    let (client, base_url, token) = {
+        let app = app_arc.lock().await;
+        (app.client.clone(), app.base_url.clone(), app.token.clone())
+    };
+    let Some(token) = token else {
+        return;
+    };
+    let saved_expiry = api::load_auth()
+        .filter(|auth| auth.token == token)
+        .and_then(|auth| auth.expires_at);
+
+    let url = api::build_url(&base_url, "/webhook/auth/introspect");
+    let info = match client.get(&url).header(api::REQUEST_ID_HEADER, api::new_request_id()).bearer_auth(&token).send().await {
+        Ok(resp) if resp.status() == reqwest::StatusCode::UNAUTHORIZED => Some(api::TokenInfo::default()),
+        Ok(resp) if resp.status().is_success() => resp.json::<api::TokenInfo>().await.ok(),
+        Ok(_) | Err(_) => None,
+    };
+
+    let mut app = app_arc.lock().await;
+    if app.token.as_deref() != Some(token.as_str()) {
+        return; // Switched profiles while this was in flight.
+    }
+    if info.as_ref().is_some_and(|info| !info.active) {
+        app.error_toast(t!("Your token is no longer valid. Run `jorik auth login` to sign in again."));
+        return;
+    }
+    let expires_at = info.and_then(|info| info.expires_at).or(saved_expiry);
+    match api::expires_soon(expires_at) {
+        Some(left) if left <= 0 => {
+            app.error_toast(t!("Your token has expired. Run `jorik auth login` to sign in again."));
+        }
+        Some(left) => {
+            app.error_toast(t!(
+                "Your token expires in {}. Run `jorik auth refresh` to renew it.",
+                api::format_remaining(left)
+            ));
+        }
+        None => {}
+    }
+}
+
+/// GET `url` as JSON; `None` when the server doesn't have the endpoint.
+async fn get_json(client: &Client, url: &str, token: Option<&str>) -> Result<Option<Value>> {
+    let mut req = client.get(url).header(api::REQUEST_ID_HEADER, api::new_request_id());
+    if let Some(bearer) = token {
+        req = req.bearer_auth(bearer);
+    }
+    let resp = req.send().await?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !resp.status().is_success() {
+        bail!("HTTP {}", resp.status());
+    }
+    Ok(Some(resp.json().await?))
+}
+
+/// Items of a list response, which is either a bare array or one under
+/// `key` or `data`. Entries that don't parse are skipped.
+fn list_field<T: serde::de::DeserializeOwned>(json: &Value, key: &str) -> Vec<T> {
+    let list = json
+        .as_array()
+        .or_else(|| json.get(key).and_then(Value::as_array))
+        .or_else(|| json.get("data").and_then(Value::as_array))
+        .or_else(|| json.get("data").and_then(|d| d.get(key)).and_then(Value::as_array));
+    list.map(|items| items.iter().filter_map(|v| serde_json::from_value(v.clone()).ok()).collect())
+        .unwrap_or_default()
+}
+
+async fn async_fetch_art(app_arc: Arc<Mutex<App>>, url: String) {
+    let _redraw = RedrawOnDrop;
+    let client = app_arc.lock().await.client.clone();
+    let result = crate::image::fetch_artwork(&client, &url).await;
+    let mut app = app_arc.lock().await;
+    match result {
+        // The track may have changed while we were downloading.
+        Ok(img) if app.art_loaded_url.as_deref() == Some(&url) => {
+            app.art = app.picker.as_ref().map(|p| p.new_resize_protocol(img));
+        }
+        Ok(_) => {}
+        Err(e) => app.log(format!("Artwork failed ({}): {:#}", url, e)),
+    }
+}
+
+/// Refresh the guild's queue, then announce its new current track on the desktop.
+async fn async_notify_track_start(app_arc: Arc<Mutex<App>>, guild_id: Option<String>) {
+    let _redraw = RedrawOnDrop;
+    async_fetch_guild_queue(app_arc.clone(), guild_id.clone()).await;
+    let (info, client) = {
+        let mut app = app_arc.lock().await;
+        (app.with_guild(guild_id.as_deref(), |app| app.current_info.clone()), app.client.clone())
+    };
+    let Some(info) = info.flatten() else {
+        return;
+    };
+    let title = info.title.unwrap_or_else(|| i18n::tr("Unknown").to_string());
+    let author = info.author.unwrap_or_default();
+    let art = match info.artwork_url.as_deref() {
+        Some(url) => crate::image::fetch_artwork(&client, url).await.ok(),
+        None => None,
+    };
+    let result = tokio::task::spawn_blocking(move || {
+        // Notifiers want a file, so the artwork goes through a PNG in the
+        // user's own cache directory rather than a shared, guessable /tmp.
+        let icon = dirs::cache_dir().map(|dir| dir.join("jorik-cli")).and_then(|dir| {
+            std::fs::create_dir_all(&dir).ok()?;
+            let icon = dir.join("notification.png");
+            art?.save(&icon).ok()?;
+            Some(icon)
+        });
+        crate::notify::send(&title, &author, icon.as_deref())
+    })
+    .await;
+    if let Ok(Err(e)) = result {
+        app_arc.lock().await.log(format!("Notification failed: {:#}", e));
+    }
+}
+
+/// Scrobble what `track_start` and `track_end` say the guild on screen
+/// played, once it has played long enough to count, and show each new
+/// track as playing now.
+async fn async_scrobble(app_arc: Arc<Mutex<App>>, scrobbler: Scrobbler, mut heard: UnboundedReceiver<Heard>) {
+    let mut playing: Option<(scrobble::Listen, Instant)> = None;
+    while let Some(event) = heard.recv().await {
+        let now = Instant::now();
+        let (finished, started) = match event {
+            // A server that sends no track_end still moves on to the next track.
+            Heard::Started(listen) => (playing.take().map(|(l, since)| (l, now - since)), listen),
+            // Time since the start also counts pauses, the position also
+            // counts seeking ahead; what was heard is at most either.
+            Heard::Ended { position_ms } => (
+                playing.take().map(|(l, since)| (l, (now - since).min(Duration::from_millis(position_ms)))),
+                None,
+            ),
+        };
+        if let Some((listen, heard)) = finished
+            && listen.counts(heard)
+        {
+            let result = scrobbler.scrobble(&listen).await;
+            let mut app = app_arc.lock().await;
+            match result {
+                Ok(()) => app.log(format!("Scrobbled: {} - {}", listen.artist, listen.title)),
+                Err(e) => app.log(format!("Scrobble failed: {:#}", e)),
+            }
+        }
+        if let Some(listen) = started {
+            if let Err(e) = scrobbler.now_playing(&listen).await {
+                app_arc.lock().await.log(format!("Now playing update failed: {:#}", e));
+            }
+            playing = Some((listen, now));
+        }
+    }
+}
+
+/// How often to look for a Discord client again after failing to reach one.
+const PRESENCE_RETRY: Duration = Duration::from_secs(15);
+
+/// Show on Discord whatever `update_presence` last sent. Discord may start
+/// (or restart) after the TUI, so an update that didn't get through is
+/// retried until one does; the failure is logged once, not on every retry.
+async fn async_discord_presence(
+    app_arc: Arc<Mutex<App>>,
+    client_id: String,
+    mut updates: UnboundedReceiver<Option<presence::Playing>>,
+) {
+    let mut connection: Option<presence::Connection> = None;
+    let mut pending = None;
+    let mut failing = false;
+    let mut retry = interval(PRESENCE_RETRY);
+    retry.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Some(update) => pending = Some(update),
+                None => return,
+            },
+            _ = retry.tick(), if pending.is_some() => {}
+        }
+        let Some(playing) = pending.take() else {
+            continue;
+        };
+        let result = async {
+            if connection.is_none() {
+                connection = Some(presence::Connection::open(&client_id).await?);
+            }
+            match &mut connection {
+                Some(connection) => connection.set(playing.as_ref()).await,
+                None => Ok(()),
+            }
+        }
+        .await;
+        match result {
+            Ok(()) if failing => {
+                failing = false;
+                app_arc.lock().await.log("Discord presence connected");
+            }
+            Ok(()) => {}
+            Err(e) => {
+                if !failing {
+                    app_arc.lock().await.log(format!("Discord presence failed: {:#}", e));
+                }
+                failing = true;
+                connection = None;
+                pending = Some(playing);
+                retry.reset();
+            }
         }
     }
 }
 
 async fn async_simple_command<T: serde::Serialize + Send + Sync + 'static>(app_arc: Arc<Mutex<App>>, endpoint: String, payload: T) {
+    let _redraw = RedrawOnDrop;
     let (ws_sender, ws_connected, client, url, token) = {
         let mut app = app_arc.lock().await;
         app.is_loading = true;
@@ -574,25 +2189,23 @@ async fn async_simple_command<T: serde::Serialize + Send + Sync + 'static>(app_a
         (app.ws_sender.clone(), app.ws_connected, app.client.clone(), url, app.token.clone())
     };
 
-    if ws_connected && endpoint.contains("/webhook/audio") {
-        if let Some(sender) = ws_sender {
-            let ws_action = api::WsAction {
-                event_type: "action",
-                id: format!("cmd-{}", chrono::Local::now().timestamp_millis()),
-                payload: &payload,
-            };
-            if let Ok(json) = serde_json::to_string(&ws_action) {
-                if let Ok(_) = sender.send(Message::Text(json.into())) {
-                    tokio::time::sleep(Duration::from_millis(100)).await;
-                    let mut app = app_arc.lock().await;
-                    app.is_loading = false;
-                    return;
-                }
-            }
+    if ws_connected && endpoint.contains("/webhook/audio") && let Some(sender) = ws_sender {
+        let ws_action = api::WsAction {
+            event_type: "action",
+            id: format!("cmd-{}", chrono::Local::now().timestamp_millis()),
+            payload: &payload,
+        };
+        if let Ok(json) = serde_json::to_string(&ws_action)
+            && sender.send(Message::Text(json.into())).is_ok()
+        {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let mut app = app_arc.lock().await;
+            app.is_loading = false;
+            return;
         }
     }
 
-    let mut req = client.post(&url).json(&payload);
+    let mut req = client.post(&url).header(api::REQUEST_ID_HEADER, api::new_request_id()).json(&payload);
     if let Some(bearer) = &token {
         req = req.bearer_auth(bearer);
     }
@@ -610,10 +2223,11 @@ fn escape_html(s: &str) -> String {
 }
 
 async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
+    let _redraw = RedrawOnDrop;
     let (base_url, is_login_required_screen) = {
         let mut app = app_arc.lock().await;
         app.is_loading = true;
-        app.auth_info_text = Some("Initializing login...".to_string());
+        app.auth_info_text = Some(i18n::tr("Initializing login...").to_string());
         
         let is_login_required = app.view == View::LoginRequired;
         
@@ -632,7 +2246,7 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
         Err(e) => {
             let mut app = app_arc.lock().await;
             app.is_loading = false;
-            app.auth_info_text = Some(format!("Failed to bind listener: {}", e));
+            app.auth_info_text = Some(t!("Failed to bind listener: {}", e));
             return;
         }
     };
@@ -642,7 +2256,7 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
         Err(e) => {
             let mut app = app_arc.lock().await;
             app.is_loading = false;
-            app.auth_info_text = Some(format!("Failed to get local addr: {}", e));
+            app.auth_info_text = Some(t!("Failed to get local addr: {}", e));
             return;
         }
     };
@@ -654,7 +2268,7 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
         Err(e) => {
             let mut app = app_arc.lock().await;
             app.is_loading = false;
-            app.auth_info_text = Some(format!("Invalid base URL: {}", e));
+            app.auth_info_text = Some(t!("Invalid base URL: {}", e));
             return;
         }
     };
@@ -663,8 +2277,9 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
 
     {
         let mut app = app_arc.lock().await;
-        app.auth_info_text = Some(format!("Opening browser...\n\nIf it doesn't open, visit:\n{}", auth_url.as_str()));
+        app.auth_info_text = Some(t!("Opening browser...\n\nIf it doesn't open, visit:\n{}", auth_url.as_str()));
     }
+    request_redraw();
     
     let _ = open::that(auth_url.as_str());
 
@@ -677,7 +2292,7 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
                 Err(e) => {
                     let mut app = app_arc.lock().await;
                     app.is_loading = false;
-                    app.auth_info_text = Some(format!("Error reading callback: {}", e));
+                    app.auth_info_text = Some(t!("Error reading callback: {}", e));
                     return;
                 }
             };
@@ -706,7 +2321,7 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
                         
                         let mut app = app_arc.lock().await;
                         app.is_loading = false;
-                        app.auth_info_text = Some("No token provided in callback.".to_string());
+                        app.auth_info_text = Some(i18n::tr("No token provided in callback.").to_string());
                         return;
                     }
 
@@ -716,7 +2331,7 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
                     if let Err(e) = api::save_token(&token_trim, avatar_val.as_deref(), username_val.as_deref()) {
                         let mut app = app_arc.lock().await;
                         app.is_loading = false;
-                        app.auth_info_text = Some(format!("Failed to save token: {}", e));
+                        app.auth_info_text = Some(t!("Failed to save token: {}", e));
                         return;
                     }
 
@@ -817,7 +2432,8 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
                         let mut app = app_arc.lock().await;
                         app.is_loading = false;
                         app.token = Some(token_trim.clone());
-                        app.auth_info_text = Some(format!("Login Successful!\n\nUser: {}\nToken saved.", username_val.unwrap_or_default()));
+                        app.username = username_val.clone();
+                        app.auth_info_text = Some(t!("Login Successful!\n\nUser: {}\nToken saved.", username_val.unwrap_or_default()));
                     }
 
                     // Small delay to ensure stability
@@ -841,61 +2457,73 @@ async fn async_auth_login(app_arc: Arc<Mutex<App>>) {
                     
                     let mut app = app_arc.lock().await;
                     app.is_loading = false;
-                    app.auth_info_text = Some("Login failed: Missing token in callback.".to_string());
+                    app.auth_info_text = Some(i18n::tr("Login failed: Missing token in callback.").to_string());
                 }
             }
         }
         _ => {
             let mut app = app_arc.lock().await;
             app.is_loading = false;
-            app.auth_info_text = Some("Login timed out.".to_string());
+            app.auth_info_text = Some(i18n::tr("Login timed out.").to_string());
         }
     }
 }
 
 async fn async_auth_signout(app_arc: Arc<Mutex<App>>) {
+    let _redraw = RedrawOnDrop;
     let (client, base_url, token) = {
         let mut app = app_arc.lock().await;
         app.is_loading = true;
         app.view = View::AuthResult;
-        app.auth_info_text = Some("Signing out...".to_string());
+        app.auth_info_text = Some(i18n::tr("Signing out...").to_string());
         (app.client.clone(), app.base_url.clone(), app.token.clone())
     };
 
     if let Some(tok) = token {
         let url = api::build_url(&base_url, "/webhook/auth/revoke");
-        let _ = client.post(&url).bearer_auth(tok).send().await;
+        let _ = client.post(&url).header(api::REQUEST_ID_HEADER, api::new_request_id()).bearer_auth(tok).send().await;
     }
 
-    // Remove local file
-    if let Some(path) = api::config_file_path() {
-        if path.exists() {
-             let _ = std::fs::remove_file(path);
-        }
-    }
+    // Remove the active profile from the local auth file
+    let _ = api::remove_active_profile();
 
     let mut app = app_arc.lock().await;
     app.is_loading = false;
     app.token = None;
+    app.username = None;
     app.auth_info_text = None;
     app.view = View::LoginRequired;
 }
 
+async fn subscribe<S>(ws_stream: &mut S, guild_id: &str)
+where
+    S: futures_util::Sink<Message> + Unpin,
+{
+    let sub = WsSubscribe {
+        event_type: "subscribe",
+        guild_id: guild_id.to_string(),
+    };
+    if let Ok(json) = serde_json::to_string(&sub) {
+        let _ = ws_stream.send(Message::Text(json.into())).await;
+    }
+}
+
 async fn spawn_websocket(app_arc: Arc<Mutex<App>>, mut ws_rx: tokio::sync::mpsc::UnboundedReceiver<Message>) {
     let mut last_waiting_log = Instant::now();
     
     loop {
-        let (base_url, token, guild_id) = {
+        let (base_url, token, guilds) = {
             let app = app_arc.lock().await;
-            (app.base_url.clone(), app.token.clone(), app.guild_id.clone())
+            let guilds: Vec<String> = app.tab_guilds().into_iter().flatten().collect();
+            (app.base_url.clone(), app.token.clone(), guilds)
         };
 
-        if token.is_none() || guild_id.is_none() {
+        if token.is_none() || guilds.is_empty() {
             if last_waiting_log.elapsed() > Duration::from_secs(10) {
                 let mut app = app_arc.lock().await;
                 if token.is_none() {
                     app.log("WS waiting for token...");
-                } else if guild_id.is_none() {
+                } else if guilds.is_empty() {
                     app.log("WS waiting for Guild ID (join a voice channel or specify --guild-id)...");
                 }
                 last_waiting_log = Instant::now();
@@ -905,21 +2533,14 @@ async fn spawn_websocket(app_arc: Arc<Mutex<App>>, mut ws_rx: tokio::sync::mpsc:
         }
 
         let token = token.unwrap();
-        let guild_id = guild_id.unwrap();
 
-        let ws_url = match Url::parse(&base_url) {
-            Ok(u) => {
-                let scheme = if u.scheme() == "https" { "wss" } else { "ws" };
-                let mut u = u;
-                u.set_scheme(scheme).ok();
-                u.set_path("/ws");
-                u.query_pairs_mut().append_pair("token", &token);
-                u
-            }
+        let ws_url = match api::build_ws_url(&base_url, &token) {
+            Ok(u) => u,
             Err(e) => {
                 let mut app = app_arc.lock().await;
                 app.log(format!("WS URL Parse Error: {}", e));
-                tokio::time::sleep(Duration::from_secs(5)).await;
+                drop(app);
+                retry_pause(&app_arc).await;
                 continue;
             }
         };
@@ -929,66 +2550,101 @@ async fn spawn_websocket(app_arc: Arc<Mutex<App>>, mut ws_rx: tokio::sync::mpsc:
             app.log(format!("WS Connecting to {}", ws_url));
             app.ws_connected = false;
             app.ws_connecting = true;
+            app.needs_reconnect = false;
         }
+        request_redraw();
 
-        let request = match ws_url.as_str().into_client_request() {
-            Ok(mut req) => {
-                let headers = req.headers_mut();
-                headers.insert("User-Agent", HeaderValue::from_static("jorik-cli"));
-                headers.insert("Origin", HeaderValue::from_str(&base_url).unwrap_or_else(|_| HeaderValue::from_static("jorik-cli")));
-                if let Some(host) = ws_url.host_str() {
-                    headers.insert("Host", HeaderValue::from_str(host).unwrap_or_else(|_| HeaderValue::from_static("localhost")));
-                }
-                headers.insert("Authorization", HeaderValue::from_str(&format!("Bearer {}", token)).unwrap_or_else(|_| HeaderValue::from_static("")));
-                req
-            }
+        let request = match api::build_ws_request(&base_url, &ws_url, &token) {
+            Ok(req) => req,
             Err(e) => {
                 let mut app = app_arc.lock().await;
                 app.log(format!("WS Request Error: {}", e));
-                tokio::time::sleep(Duration::from_secs(5)).await;
+                drop(app);
+                retry_pause(&app_arc).await;
                 continue;
             }
         };
 
-        match connect_async(request).await {
+        match jorik_core::tls::connect_ws(request).await {
             Ok((mut ws_stream, _)) => {
-                {
+                let heartbeat = {
                     let mut app = app_arc.lock().await;
                     app.log("WS Connected");
                     app.ws_connected = true;
                     app.ws_connecting = false;
-                }
-                
-                let sub = WsSubscribe {
-                    event_type: "subscribe",
-                    guild_id: guild_id.clone(),
+                    app.ws_heartbeat
                 };
-                if let Ok(json) = serde_json::to_string(&sub) {
-                    let _ = ws_stream.send(Message::Text(json.into())).await;
+                request_redraw();
+                // A connection dropped by a NAT or proxy can stay open on our
+                // side with nothing arriving; only a missing pong shows it.
+                let mut ping = interval(heartbeat.unwrap_or(Duration::from_secs(3600)));
+                ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                ping.tick().await;
+                let stale_after = heartbeat.unwrap_or_default() * 2;
+                let mut last_heard = Instant::now();
+                
+                let mut subscribed = Vec::new();
+                for guild_id in guilds {
+                    subscribe(&mut ws_stream, &guild_id).await;
+                    subscribed.push(guild_id);
                 }
 
                 loop {
                     tokio::select! {
                         msg = ws_stream.next() => {
+                            if let Some(Ok(_)) = msg {
+                                last_heard = Instant::now();
+                            }
                             match msg {
                                 Some(Ok(Message::Text(text))) => {
+                                    let _redraw = RedrawOnDrop;
                                     if let Ok(event) = serde_json::from_str::<WsEvent>(&text) {
                                         let mut app = app_arc.lock().await;
                                         app.log(format!("WS Event: {}", event.event_type));
                                         
-                                        match event.event_type.as_str() {
-                                            "spectrogram_update" => {
-                                                if event.guild_id.as_deref() == app.guild_id.as_deref() {
-                                                    if let Some(data) = event.data {
-                                                        if let Ok(spectrogram) = serde_json::from_value::<Vec<Vec<u8>>>(data) {
-                                                            app.log(format!("Received Spectrogram ({} frames)", spectrogram.len()));
-                                                            app.spectrogram = Some(spectrogram);
-                                                        }
-                                                    }
+                                        // Events for a parked tab update that tab in place.
+                                        let guild = event.guild_id.clone().filter(|g| app.has_guild_tab(g));
+                                        if guild.is_some()
+                                            && let Some(hooks) = &mut app.hooks
+                                            && let Ok(raw) = serde_json::from_str::<Value>(&text)
+                                        {
+                                            for problem in hooks.observe(&raw) {
+                                                app.log(problem);
+                                            }
+                                        }
+                                        // Only the guild on screen is scrobbled; a parked tab is
+                                        // somewhere the user is not listening right now.
+                                        if let Some(scrobble) = &app.scrobble
+                                            && guild.is_some()
+                                            && guild == app.guild_id
+                                        {
+                                            match event.event_type.as_str() {
+                                                "track_start" => {
+                                                    let started_at = chrono::Utc::now().timestamp();
+                                                    let listen = event.data.as_ref()
+                                                        .and_then(|d| Track::deserialize(d.get("track").unwrap_or(d)).ok())
+                                                        .and_then(|t| scrobble::Listen::from_track(&t, started_at));
+                                                    scrobble.send(Heard::Started(listen)).ok();
+                                                }
+                                                "track_end" => {
+                                                    scrobble.send(Heard::Ended { position_ms: app.elapsed_ms }).ok();
+                                                }
+                                                _ => {}
+                                            }
+                                        }
+                                        match (event.event_type.as_str(), guild.as_deref()) {
+                                            ("spectrogram_update", Some(gid)) => {
+                                                if let Some(data) = event.data
+                                                    && let Ok(spectrogram) = serde_json::from_value::<Vec<Vec<u8>>>(data)
+                                                {
+                                                    app.with_guild(Some(gid), |app| {
+                                                        app.log(format!("Received Spectrogram ({} frames)", spectrogram.len()));
+                                                        app.spectrogram = Some(spectrogram);
+                                                    });
                                                 }
                                             }
-                                            "state_update" | "initial_state" => {
-                                                if event.guild_id.as_deref() == app.guild_id.as_deref() {
+                                            ("state_update" | "initial_state", Some(gid)) => {
+                                                app.with_guild(Some(gid), |app| {
                                                     if let Some(data) = &event.data {
                                                         app.parse_queue_response(data);
                                                     }
@@ -1016,31 +2672,51 @@ async fn spawn_websocket(app_arc: Arc<Mutex<App>>, mut ws_rx: tokio::sync::mpsc:
                                                             app.spectrogram = Some(spec);
                                                         }
                                                     }
+                                                });
+                                            }
+                                            ("queue_update", Some(gid)) => {
+                                                app.log("Received Queue Update");
+                                                if let Some(data) = event.data {
+                                                    app.with_guild(Some(gid), |app| app.parse_queue_response(&data));
+                                                } else {
+                                                    // Fallback to REST if data is missing
+                                                    tokio::spawn(async_fetch_guild_queue(app_arc.clone(), guild.clone()));
                                                 }
                                             }
-                                            "queue_update" => {
-                                                if event.guild_id.as_deref() == app.guild_id.as_deref() {
-                                                    app.log("Received Queue Update");
-                                                    if let Some(data) = event.data {
-                                                        app.parse_queue_response(&data);
-                                                    } else {
-                                                        // Fallback to REST if data is missing
-                                                        tokio::spawn(async_fetch_queue(app_arc.clone()));
+                                            ("track_end", Some(gid)) => {
+                                                app.with_guild(Some(gid), |app| {
+                                                    let track = event.data.as_ref()
+                                                        .and_then(|d| d.get("track").or(Some(d)))
+                                                        .and_then(|t| Track::deserialize(t).ok())
+                                                        .filter(|t| t.title.is_some())
+                                                        .map(|t| HistoryEntry::from_track(&t));
+                                                    let current = app.current_track.clone().map(|label| HistoryEntry {
+                                                        query: app.current_uri.clone().unwrap_or_else(|| label.clone()),
+                                                        label,
+                                                    });
+                                                    if let Some(entry) = track.or(current) {
+                                                        app.record_played(entry);
                                                     }
-                                                }
+                                                });
+                                                app.log("WS Event: track_end, refreshing queue");
+                                                tokio::spawn(async_fetch_guild_queue(app_arc.clone(), guild.clone()));
                                             }
-                                            "track_start" | "track_end" | "player_update" => {
-                                                if event.guild_id.as_deref() == app.guild_id.as_deref() {
-                                                    app.log(format!("WS Event: {}, refreshing queue", event.event_type));
-                                                    // Trigger a full REST refresh to get the latest queue state
-                                                    tokio::spawn(async_fetch_queue(app_arc.clone()));
-                                                }
+                                            ("track_start", Some(_)) if app.notify_track_start => {
+                                                app.log("WS Event: track_start, refreshing queue");
+                                                tokio::spawn(async_notify_track_start(app_arc.clone(), guild.clone()));
+                                            }
+                                            ("track_start" | "player_update", Some(_)) => {
+                                                app.log(format!("WS Event: {}, refreshing queue", event.event_type));
+                                                // Trigger a full REST refresh to get the latest queue state
+                                                tokio::spawn(async_fetch_guild_queue(app_arc.clone(), guild.clone()));
                                             }
-                                            "action_response" => {
+                                            ("action_response", _) => {
                                                 let success = event.success.unwrap_or(false);
                                                 let id = event.id.as_deref().unwrap_or("unknown");
                                                 app.log(format!("WS Action Response [{}]: success={}", id, success));
                                             }
+                                            // A guild without a tab here.
+                                            (_, None) if event.guild_id.is_some() => {}
                                             _ => {
                                                 app.log(format!("WS Unhandled Event: {}", event.event_type));
                                             }
@@ -1070,13 +2746,42 @@ async fn spawn_websocket(app_arc: Arc<Mutex<App>>, mut ws_rx: tokio::sync::mpsc:
                                 break;
                             }
                         }
+                        _ = ping.tick(), if heartbeat.is_some() => {
+                            if last_heard.elapsed() >= stale_after {
+                                let mut app = app_arc.lock().await;
+                                app.log(format!(
+                                    "WS Stale: nothing heard for {}s, reconnecting",
+                                    last_heard.elapsed().as_secs()
+                                ));
+                                // Straight back in rather than after the usual retry pause.
+                                app.needs_reconnect = true;
+                                break;
+                            }
+                            if let Err(e) = ws_stream.send(Message::Ping(Vec::new().into())).await {
+                                let mut app = app_arc.lock().await;
+                                app.log(format!("WS Ping Error: {}", e));
+                                break;
+                            }
+                        }
                         _ = tokio::time::sleep(Duration::from_millis(500)) => {
                             let mut app = app_arc.lock().await;
                             if app.needs_reconnect {
-                                app.log("WS Forcing reconnect due to settings change");
-                                app.needs_reconnect = false;
+                                app.log("WS Forcing reconnect");
                                 break;
                             }
+                            // A tab moved to another guild; events for the old
+                            // one are dropped as belonging to no tab.
+                            let added: Vec<String> = app
+                                .tab_guilds()
+                                .into_iter()
+                                .flatten()
+                                .filter(|g| !subscribed.contains(g))
+                                .collect();
+                            drop(app);
+                            for guild_id in added {
+                                subscribe(&mut ws_stream, &guild_id).await;
+                                subscribed.push(guild_id);
+                            }
                         }
                     }
                 }
@@ -1093,41 +2798,90 @@ async fn spawn_websocket(app_arc: Arc<Mutex<App>>, mut ws_rx: tokio::sync::mpsc:
             app.ws_connected = false;
             app.ws_connecting = false;
         }
-        tokio::time::sleep(Duration::from_secs(5)).await;
+        request_redraw();
+        retry_pause(&app_arc).await;
     }
 }
 
+/// The 5s wait between WS connection attempts, cut short when a reconnect
+/// was asked for (Shift+R or a settings change).
+async fn retry_pause(app_arc: &Arc<Mutex<App>>) {
+    for _ in 0..50 {
+        {
+            let mut app = app_arc.lock().await;
+            if app.needs_reconnect {
+                app.needs_reconnect = false;
+                return;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// `client` is the process-wide one from `api::http_client`; every task the
+/// TUI spawns clones it, so they all share its connection pool.
 pub async fn run(
+    client: Client,
     settings: api::Settings,
     token: Option<String>,
     guild_id: Option<String>,
+    extra_guilds: Vec<String>,
     user_id: Option<String>,
 ) -> Result<Option<(String, Vec<api::GiteaAsset>)>> {
-    let client = Client::builder()
-        .user_agent("jorik-cli-tui")
-        .timeout(Duration::from_secs(10))
-        .build()?;
-
+    let update_check = settings.update_check;
+    let discord_client_id = settings.discord_client_id.clone().filter(|id| !id.is_empty());
+    let scrobbler = Scrobbler::new(client.clone(), &settings.scrobble);
     let (ws_tx, ws_rx) = tokio::sync::mpsc::unbounded_channel::<Message>();
 
-    let mut app_struct = App::new(client.clone(), settings, token, guild_id, user_id);
+    let mut app_struct = App::new(client.clone(), settings, token, guild_id, extra_guilds, user_id);
     app_struct.ws_sender = Some(ws_tx);
-    
+    let presence_rx = discord_client_id.as_ref().map(|_| {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        app_struct.presence = Some(tx);
+        rx
+    });
+    let scrobble = scrobbler.map(|scrobbler| {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        app_struct.scrobble = Some(tx);
+        (scrobbler, rx)
+    });
+    if jorik_core::tls::insecure() {
+        app_struct.error_toast(i18n::tr("TLS certificate verification is disabled (--insecure)"));
+    }
+    // No guild given and none saved: ask instead of leaving it to the server's guess.
+    let pick_guild = app_struct.guild_id.is_none() && app_struct.token.is_some();
+
     let app = Arc::new(Mutex::new(app_struct));
+    if pick_guild {
+        open_guild_picker(&mut *app.lock().await, app.clone());
+    }
     
     // Initial fetch
-    tokio::spawn(async_fetch_queue(app.clone()));
+    let guilds = app.lock().await.tab_guilds();
+    for guild_id in guilds {
+        apply_guild_filters(&mut *app.lock().await, &app, guild_id.clone());
+        tokio::spawn(async_fetch_guild_queue(app.clone(), guild_id));
+    }
     tokio::spawn(spawn_websocket(app.clone(), ws_rx));
+    tokio::spawn(async_check_token(app.clone()));
+    if let (Some(client_id), Some(rx)) = (discord_client_id, presence_rx) {
+        tokio::spawn(async_discord_presence(app.clone(), client_id, rx));
+    }
+    if let Some((scrobbler, rx)) = scrobble {
+        tokio::spawn(async_scrobble(app.clone(), scrobbler, rx));
+    }
 
-    let app_update = app.clone();
-    let client_update = client.clone();
-    tokio::spawn(async move {
-        if let Some(update) = crate::check_for_updates(&client_update).await {
-            let mut app = app_update.lock().await;
-            app.update_info = Some(update);
-            app.view = View::UpdateFound;
-        }
-    });
+    if update_check {
+        let app_update = app.clone();
+        let client_update = client.clone();
+        tokio::spawn(async move {
+            if let Some(update) = crate::check_for_updates(&client_update).await {
+                let mut app = app_update.lock().await;
+                app.update_info = Some(update);
+                app.view = View::UpdateFound;
+            }
+        });
+    }
 
     let app_clone = app.clone();
     tokio::spawn(async move {
@@ -1135,107 +2889,262 @@ pub async fn run(
         let mut interval = interval(Duration::from_secs(20));
         loop {
             interval.tick().await;
-            async_fetch_queue(app_clone.clone()).await;
+            let guilds = app_clone.lock().await.tab_guilds();
+            for guild_id in guilds {
+                async_fetch_guild_queue(app_clone.clone(), guild_id).await;
+            }
         }
     });
 
-    let mut terminal = ratatui::init();
-    let res = run_loop(&mut terminal, app).await;
-    ratatui::restore();
-    res
+    let mut terminal = ratatui::init();
+    crate::interrupt::set_terminal_taken(true);
+    // Pasted text arrives as one event instead of a burst of key presses.
+    ratatui::crossterm::execute!(std::io::stdout(), event::EnableBracketedPaste, event::EnableMouseCapture).ok();
+    app.lock().await.picker = Some(crate::image::tui_picker());
+    let res = run_loop(&mut terminal, app).await;
+    restore_terminal();
+    crate::interrupt::set_terminal_taken(false);
+    res
+}
+
+/// Leave raw mode and the alternate screen, undoing everything `run` set up.
+pub fn restore_terminal() {
+    ratatui::crossterm::execute!(std::io::stdout(), event::DisableBracketedPaste, event::DisableMouseCapture).ok();
+    ratatui::restore();
+}
+
+/// Woken by background tasks once they have changed `App`, so the run loop
+/// only redraws when there is something new to show.
+static REDRAW: Notify = Notify::const_new();
+
+fn request_redraw() {
+    REDRAW.notify_one();
+}
+
+/// Requests a redraw when dropped; background tasks hold one so whatever they
+/// changed shows up on every return path.
+struct RedrawOnDrop;
+
+impl Drop for RedrawOnDrop {
+    fn drop(&mut self) {
+        request_redraw();
+    }
+}
+
+/// The tab a guild tab key (Alt+1-9, `[` or `]`) switches to.
+fn guild_tab_key(app: &App, key: event::KeyEvent) -> Option<usize> {
+    match key.code {
+        KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(event::KeyModifiers::ALT) => {
+            Some(c as usize - '1' as usize)
+        }
+        KeyCode::Char('[') | KeyCode::Char('х') => Some((app.active_tab + app.tabs.len() - 1) % app.tabs.len()),
+        KeyCode::Char(']') | KeyCode::Char('ъ') => Some((app.active_tab + 1) % app.tabs.len()),
+        _ => None,
+    }
 }
 
 async fn run_loop(terminal: &mut DefaultTerminal, app_arc: Arc<Mutex<App>>) -> Result<Option<(String, Vec<api::GiteaAsset>)>> {
+    let mut events = EventStream::new();
     loop {
+        let frame_interval;
         {
             let mut app = app_arc.lock().await;
             app.update_realtime();
-            terminal.draw(|f| ui(f, &mut *app))?;
+            app.update_idle();
+            app.update_presence();
+            // Half-block artwork is just noise without the right font or to a screen reader.
+            if !app.accessible && app.art_url != app.art_loaded_url {
+                app.art_loaded_url = app.art_url.clone();
+                app.art = None;
+                if let Some(url) = app.art_url.clone() {
+                    tokio::spawn(async_fetch_art(app_arc.clone(), url));
+                }
+            }
+            if app.layout == "Lyrics" && app.current_track.is_some() && app.lyrics_track != app.current_track {
+                // Claim the track now so the next frames don't start a second fetch.
+                app.lyrics_track = app.current_track.clone();
+                app.lyrics_lines.clear();
+                app.lyrics_text = None;
+                tokio::spawn(async_fetch_lyrics(app_arc.clone(), false));
+            }
+            if let Some(payload) = app.take_settled_filters() {
+                tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), payload));
+            }
+            terminal.draw(|f| {
+                ui(f, &mut app);
+                if app.accessible {
+                    asciify(f.buffer_mut());
+                }
+            })?;
+            frame_interval = app.frame_interval();
         }
 
-        if event::poll(Duration::from_millis(16))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    let mut app = app_arc.lock().await;
+        let event = tokio::select! {
+            event = events.next() => match event {
+                Some(event) => event?,
+                None => return Ok(None),
+            },
+            _ = REDRAW.notified() => continue,
+            _ = tokio::time::sleep(frame_interval) => continue,
+        };
+        let key = match event {
+            // Raw mode turns Ctrl-C into a key press rather than a signal.
+            Event::Key(key)
+                if key.kind == KeyEventKind::Press
+                    && key.code == KeyCode::Char('c')
+                    && key.modifiers.contains(event::KeyModifiers::CONTROL) =>
+            {
+                return Err(exit::reported(ExitCode::Interrupted));
+            }
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            Event::Paste(text) => {
+                let mut app = app_arc.lock().await;
+                if !app.wake() {
+                    handle_paste(&mut app, &text, app_arc.clone());
+                }
+                continue;
+            }
+            Event::Mouse(mouse) => {
+                let mut app = app_arc.lock().await;
+                if !app.wake() {
+                    handle_mouse(&mut app, mouse);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        {
+            let mut app = app_arc.lock().await;
 
-                    if app.fatal_error.is_some() {
-                        if let KeyCode::Char('r') | KeyCode::Char('к') = key.code {
-                            app.fatal_error = None;
-                            app.error_message = None;
-                            drop(app);
-                            tokio::spawn(async_fetch_queue(app_arc.clone()));
-                        }
-                        continue;
-                    }
-                    
-                    if app.input_mode == InputMode::Editing {
-                        handle_editing_keys(&mut *app, key, app_arc.clone());
-                        continue;
-                    }
+            if app.wake() {
+                continue;
+            }
 
-                    if app.is_settings_editing {
-                        handle_settings_keys(&mut *app, key, app_arc.clone());
-                        continue;
-                    }
+            // The error is the tab's own; other tabs can still be switched to.
+            if app.fatal_error.is_some() && guild_tab_key(&app, key).is_none() {
+                if let KeyCode::Char('r') | KeyCode::Char('к') = key.code {
+                    app.fatal_error = None;
+                    drop(app);
+                    tokio::spawn(async_fetch_queue(app_arc.clone()));
+                }
+                continue;
+            }
+            
+            if app.input_mode == InputMode::Editing {
+                handle_editing_keys(&mut app, key, app_arc.clone());
+                continue;
+            }
 
-                    // Global Tab Switching (1-4)
-                    match key.code {
-                        KeyCode::Char('1') => { app.view = View::Main; continue; }
-                        KeyCode::Char('2') => { 
-                            if app.view != View::Lyrics {
-                                tokio::spawn(async_fetch_lyrics(app_arc.clone()));
-                            }
-                            app.view = View::Lyrics; 
-                            continue; 
-                        }
-                        KeyCode::Char('3') => { 
-                            app.settings_input = app.base_url.clone();
-                            app.view = View::Settings; 
-                            continue; 
-                        }
-                        KeyCode::Char('4') => { app.view = View::Debug; continue; }
-                        _ => {}
-                    }
+            if app.is_settings_editing {
+                handle_settings_keys(&mut app, key, app_arc.clone());
+                continue;
+            }
 
-                    // Global Quit (q) - except in Settings where it might be typed
-                    if matches!(key.code, KeyCode::Char('q') | KeyCode::Char('й')) && app.view != View::Settings {
-                        return Ok(None);
+            if app.preset_name_input.is_some() {
+                handle_filter_menu_keys(&mut app, key, app_arc.clone());
+                continue;
+            }
+
+            if app.queue_search_editing {
+                handle_queue_search_keys(&mut app, key);
+                continue;
+            }
+
+            if app.debug_filter_editing {
+                handle_debug_keys(&mut app, key);
+                continue;
+            }
+
+            if app.command_editing {
+                if handle_command_keys(&mut app, key, app_arc.clone()) {
+                    return Ok(None);
+                }
+                continue;
+            }
+
+            // Esc dismisses an error toast, unless something above was being typed into.
+            if key.code == KeyCode::Esc && app.toast.as_ref().is_some_and(|t| t.error) {
+                app.toast = None;
+                continue;
+            }
+
+            if let Some(i) = guild_tab_key(&app, key) {
+                // Don't let a pending volume or filter change land on the wrong guild.
+                if let Some(payload) = app.take_pending_filters() {
+                    tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), payload));
+                }
+                app.select_tab(i);
+                continue;
+            }
+
+            // Global Tab Switching (1-4)
+            match key.code {
+                KeyCode::Char('1') => { app.view = View::Main; continue; }
+                KeyCode::Char('2') => { 
+                    if app.view != View::Lyrics {
+                        tokio::spawn(async_fetch_lyrics(app_arc.clone(), true));
                     }
+                    app.view = View::Lyrics; 
+                    continue; 
+                }
+                KeyCode::Char('3') => { 
+                    app.settings_input = app.base_url.clone();
+                    app.view = View::Settings; 
+                    continue; 
+                }
+                KeyCode::Char('4') => { app.view = View::Debug; continue; }
+                _ => {}
+            }
 
-                    // View-Specific Handlers
-                    match app.view {
-                        View::UpdateFound => {
-                            if let Some(update) = handle_update_keys(&mut *app, key) {
-                                return Ok(Some(update));
-                            }
-                        }
-                        View::Main => handle_player_keys(&mut *app, key, app_arc.clone()),
-                        View::Lyrics => handle_lyrics_keys(&mut *app, key),
-                        View::Settings => handle_settings_keys(&mut *app, key, app_arc.clone()),
-                        View::Debug => handle_debug_keys(&mut *app, key),
-                        View::Menu => { if handle_menu_keys(&mut *app, key, app_arc.clone())? { return Ok(None); } },
-                        View::FilterMenu => handle_filter_menu_keys(&mut *app, key, app_arc.clone()),
-                        View::AuthMenu => handle_auth_menu_keys(&mut *app, key, app_arc.clone()),
-                        View::AuthResult => {
-                            if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace) {
-                                app.view = View::AuthMenu;
-                            }
-                        }
-                        View::AppInfo => {
-                            if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace | KeyCode::Char('i') | KeyCode::Char('ш')) {
-                                app.view = View::Main;
-                            }
-                        }
-                        View::LoginRequired => {
-                            if key.code == KeyCode::Enter {
-                                tokio::spawn(async_auth_login(app_arc.clone()));
-                            } else if key.code == KeyCode::Char('\\') {
-                                app.settings_input = app.base_url.clone();
-                                app.view = View::Settings;
-                            } else if matches!(key.code, KeyCode::Char('q') | KeyCode::Char('й')) {
-                                return Ok(None);
-                            }
-                        }
+            // Global Quit (q) - except in Settings where it might be typed
+            if matches!(key.code, KeyCode::Char('q') | KeyCode::Char('й'))
+                && app.view != View::Settings
+                && !key.modifiers.contains(event::KeyModifiers::ALT)
+            {
+                return Ok(None);
+            }
+
+            // View-Specific Handlers
+            match app.view {
+                View::UpdateFound => {
+                    if let Some(update) = handle_update_keys(&mut app, key) {
+                        return Ok(Some(update));
+                    }
+                }
+                View::Main => handle_player_keys(&mut app, key, app_arc.clone()),
+                View::Lyrics => handle_lyrics_keys(&mut app, key),
+                View::Settings => handle_settings_keys(&mut app, key, app_arc.clone()),
+                View::Debug => handle_debug_keys(&mut app, key),
+                View::Menu => { if handle_menu_keys(&mut app, key, app_arc.clone())? { return Ok(None); } },
+                View::FilterMenu => handle_filter_menu_keys(&mut app, key, app_arc.clone()),
+                View::History => handle_history_keys(&mut app, key, app_arc.clone()),
+                View::Playlists => handle_playlist_keys(&mut app, key, app_arc.clone()),
+                View::GuildPicker => handle_guild_picker_keys(&mut app, key, app_arc.clone()),
+                View::Profiles => handle_profile_keys(&mut app, key, app_arc.clone()),
+                View::AuthMenu => handle_auth_menu_keys(&mut app, key, app_arc.clone()),
+                View::AuthResult => {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace) {
+                        app.view = View::AuthMenu;
+                    }
+                }
+                View::AppInfo => {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace | KeyCode::Char('i') | KeyCode::Char('ш') | KeyCode::Char('I') | KeyCode::Char('Ш')) {
+                        app.view = View::Main;
+                    }
+                }
+                View::TrackDetails => {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Backspace | KeyCode::Char('i') | KeyCode::Char('ш')) {
+                        app.view = View::Main;
+                    }
+                }
+                View::LoginRequired => {
+                    if key.code == KeyCode::Enter {
+                        tokio::spawn(async_auth_login(app_arc.clone()));
+                    } else if key.code == KeyCode::Char('\\') {
+                        app.settings_input = app.base_url.clone();
+                        app.view = View::Settings;
+                    } else if matches!(key.code, KeyCode::Char('q') | KeyCode::Char('й')) {
+                        return Ok(None);
                     }
                 }
             }
@@ -1246,8 +3155,7 @@ async fn run_loop(terminal: &mut DefaultTerminal, app_arc: Arc<Mutex<App>>) -> R
 fn handle_editing_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) {
     match key.code {
         KeyCode::Enter => {
-            let query = app.input.clone();
-            app.input.clear();
+            let query = app.input.submit();
             app.input_mode = InputMode::Normal;
             tokio::spawn(async_play_track(app_arc, query));
         }
@@ -1255,12 +3163,222 @@ fn handle_editing_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<A
             app.input_mode = InputMode::Normal;
             app.input.clear();
         }
-        KeyCode::Char(c) => app.input.push(c),
-        KeyCode::Backspace => { app.input.pop(); }
+        KeyCode::Char('v') if key.modifiers.contains(event::KeyModifiers::CONTROL) => { tokio::spawn(async_paste_clipboard(app_arc)); }
+        _ => edit_line(&mut app.input, key),
+    }
+}
+
+/// Cursor movement, deletion and history recall shared by the text inputs.
+fn edit_line(line: &mut LineInput, key: event::KeyEvent) {
+    let ctrl = key.modifiers.contains(event::KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(event::KeyModifiers::ALT);
+    match key.code {
+        KeyCode::Char('w') | KeyCode::Char('h') if ctrl => line.delete_word_back(),
+        KeyCode::Char('u') if ctrl => line.delete_to_start(),
+        KeyCode::Char('k') if ctrl => line.delete_to_end(),
+        KeyCode::Char('a') if ctrl => line.home(),
+        KeyCode::Char('e') if ctrl => line.end(),
+        KeyCode::Char(c) => line.insert(c),
+        KeyCode::Backspace if ctrl || alt => line.delete_word_back(),
+        KeyCode::Backspace => line.backspace(),
+        KeyCode::Delete => line.delete(),
+        KeyCode::Left if ctrl || alt => line.word_left(),
+        KeyCode::Right if ctrl || alt => line.word_right(),
+        KeyCode::Left => line.left(),
+        KeyCode::Right => line.right(),
+        KeyCode::Home => line.home(),
+        KeyCode::End => line.end(),
+        KeyCode::Up => line.history_prev(),
+        KeyCode::Down => line.history_next(),
         _ => {}
     }
 }
 
+/// Names `:` accepts, for Tab completion and `:help`.
+const COMMANDS: [&str; 17] = [
+    "skip", "pause", "stop", "shuffle", "clear", "loop", "247", "filter", "seek", "volume", "play", "lyrics", "history",
+    "playlists", "settings", "help", "quit",
+];
+
+/// Typing a `:` command. Returns true when it asked to quit.
+fn handle_command_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) -> bool {
+    match key.code {
+        KeyCode::Enter => {
+            app.command_editing = false;
+            let line = app.command.submit();
+            match run_command(app, line.trim(), app_arc) {
+                Ok(quit) => return quit,
+                Err(e) => app.error_toast(format!("{:#}", e)),
+            }
+        }
+        KeyCode::Esc => {
+            app.command_editing = false;
+            app.command.clear();
+        }
+        // Backspace on an empty line leaves command mode, as in vim.
+        KeyCode::Backspace if app.command.text.is_empty() => app.command_editing = false,
+        KeyCode::Tab => {
+            let text = app.command.text.clone();
+            if !text.contains(' ') {
+                let matches: Vec<_> = COMMANDS.iter().filter(|c| c.starts_with(text.as_str())).collect();
+                if let [only] = matches[..] {
+                    app.command.set_text(format!("{} ", only));
+                }
+            }
+        }
+        KeyCode::Char('v') if key.modifiers.contains(event::KeyModifiers::CONTROL) => { tokio::spawn(async_paste_clipboard(app_arc)); }
+        _ => edit_line(&mut app.command, key),
+    }
+    false
+}
+
+/// Run one `:` command line, doing what the matching key or menu entry does.
+/// Returns true for `:quit`.
+fn run_command(app: &mut App, line: &str, app_arc: Arc<Mutex<App>>) -> Result<bool> {
+    let (name, arg) = line.split_once(' ').map_or((line, ""), |(n, a)| (n, a.trim()));
+    match name.to_lowercase().as_str() {
+        "" => {}
+        "skip" | "next" => send_simple_action(app, "skip", app_arc),
+        "pause" | "resume" => send_simple_action(app, "pause", app_arc),
+        "stop" => confirm_action(app, "stop", app_arc),
+        "shuffle" => send_simple_action(app, "shuffle", app_arc),
+        "clear" => confirm_action(app, "clear", app_arc),
+        "loop" => {
+            let mode = match arg {
+                "" => match app.loop_mode.as_str() {
+                    "off" => "track",
+                    "track" => "queue",
+                    _ => "off",
+                },
+                arg => parse_loop_mode(&Value::String(arg.to_string()))
+                    .with_context(|| t!("Unknown loop mode: {} (track, queue or off)", arg))?,
+            };
+            app.loop_mode = mode.to_string();
+            let payload = LoopPayload { action: "loop", guild_id: app.guild_id.clone(), user_id: app.user_id.clone(), loop_mode: mode.to_string() };
+            tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+        }
+        "247" => {
+            let enabled = match arg {
+                "" => None,
+                "on" => Some(true),
+                "off" => Some(false),
+                _ => bail!(t!("Usage: :247 [on|off]")),
+            };
+            let payload = TwentyFourSevenPayload { action: "247", guild_id: app.guild_id.clone(), user_id: app.user_id.clone(), enabled };
+            tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+        }
+        "filter" | "filters" => {
+            if arg.is_empty() {
+                open_filter_editor(app);
+                return Ok(false);
+            }
+            let name = filter_preset_names(app)
+                .into_iter()
+                .find(|n| n.eq_ignore_ascii_case(arg))
+                .with_context(|| t!("Unknown filter: {}", arg))?;
+            app.filter_draft = FilterDraft::from_filters(&filter_preset(app, &name));
+            preview_filters(app);
+            if let Some(payload) = app.take_pending_filters() {
+                tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+            }
+        }
+        "seek" => {
+            let (sign, pos) = match arg.chars().next() {
+                Some(c @ ('+' | '-')) => (Some(c), &arg[1..]),
+                _ => (None, arg),
+            };
+            let ms = parse_position(pos).with_context(|| t!("Usage: :seek 1:30, +10 or -0:15"))?;
+            app.scrub_ms = Some(match sign {
+                Some('+') => app.elapsed_ms.saturating_add(ms),
+                Some(_) => app.elapsed_ms.saturating_sub(ms),
+                None => ms,
+            });
+            if app.duration_ms > 0 {
+                app.scrub_ms = app.scrub_ms.map(|ms| ms.min(app.duration_ms));
+            }
+            commit_scrub(app, app_arc);
+        }
+        "volume" | "vol" => {
+            let volume: u16 = arg.trim_end_matches('%').parse().with_context(|| t!("Usage: :volume 0-{}", MAX_VOLUME))?;
+            app.volume = volume.min(MAX_VOLUME);
+            app.filters_changed_at = Some(Instant::now());
+        }
+        "play" if !arg.is_empty() => {
+            tokio::spawn(async_play_track(app_arc, arg.to_string()));
+        }
+        "play" => {
+            app.input_mode = InputMode::Editing;
+        }
+        "lyrics" => {
+            tokio::spawn(async_fetch_lyrics(app_arc, true));
+            app.view = View::Lyrics;
+        }
+        "history" => {
+            app.history_state.select(Some(0));
+            app.view = View::History;
+            tokio::spawn(async_fetch_history(app_arc));
+        }
+        "playlists" => open_playlists(app),
+        "settings" => {
+            app.settings_input = app.base_url.clone();
+            app.view = View::Settings;
+        }
+        "help" => app.toast(t!("Commands: {}", COMMANDS.join(", "))),
+        "quit" | "q" => return Ok(true),
+        other => bail!(t!("Unknown command: {}", other)),
+    }
+    Ok(false)
+}
+
+/// `90`, `1:30` or `1:02:03` in milliseconds. `None` past what fits in a u64.
+fn parse_position(text: &str) -> Option<u64> {
+    if text.is_empty() {
+        return None;
+    }
+    text.split(':')
+        .try_fold(0u64, |total, part| total.checked_mul(60)?.checked_add(part.parse::<u64>().ok()?))?
+        .checked_mul(1000)
+}
+
+/// Insert pasted text into whichever text field is active. On the player view
+/// a paste opens the Play / Search popup with it, so a URL can go straight in.
+fn handle_paste(app: &mut App, text: &str, app_arc: Arc<Mutex<App>>) {
+    // Inputs are single-line; a copied URL often carries a trailing newline.
+    let text = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return;
+    }
+    if app.input_mode == InputMode::Editing {
+        app.input.insert_str(&text);
+        return;
+    }
+    let typed = text.chars().map(|c| event::KeyEvent::new(KeyCode::Char(c), event::KeyModifiers::NONE));
+    if app.is_settings_editing {
+        typed.for_each(|key| handle_settings_keys(app, key, app_arc.clone()));
+    } else if app.preset_name_input.is_some() {
+        typed.for_each(|key| handle_filter_menu_keys(app, key, app_arc.clone()));
+    } else if app.queue_search_editing {
+        typed.for_each(|key| handle_queue_search_keys(app, key));
+    } else if app.command_editing {
+        app.command.insert_str(&text);
+    } else if app.view == View::Main && app.pending_remove.is_none() && app.pending_action.is_none() {
+        app.input_mode = InputMode::Editing;
+        app.input.insert_str(&text);
+    }
+}
+
+/// Ctrl+V: paste the system clipboard, for terminals that don't do it
+/// themselves. The clipboard tool runs on a blocking thread without the App
+/// lock, so a slow one doesn't freeze the interface.
+async fn async_paste_clipboard(app_arc: Arc<Mutex<App>>) {
+    let text = tokio::task::spawn_blocking(crate::clipboard::paste).await.unwrap_or_else(|e| Err(e.into()));
+    let mut app = app_arc.lock().await;
+    match text {
+        Ok(text) => handle_paste(&mut app, &text, app_arc.clone()),
+        Err(e) => app.log(format!("Paste failed: {}", e)),
+    }
+}
+
 fn handle_update_keys(app: &mut App, key: event::KeyEvent) -> Option<(String, Vec<api::GiteaAsset>)> {
     match key.code {
         KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Char('н') | KeyCode::Char('Н') => {
@@ -1275,12 +3393,68 @@ fn handle_update_keys(app: &mut App, key: event::KeyEvent) -> Option<(String, Ve
 }
 
 fn handle_player_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) {
+    let confirmed = matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Char('н') | KeyCode::Char('Н'));
+    if let Some(index) = app.pending_remove.take() {
+        if confirmed {
+            remove_queue_track(app, index, app_arc);
+        }
+        return;
+    }
+    if let Some(action) = app.pending_action.take() {
+        if confirmed {
+            send_simple_action(app, action, app_arc);
+        }
+        return;
+    }
     match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(event::KeyModifiers::ALT) => toggle_panel(app, c),
+        KeyCode::Char(':') => app.command_editing = true,
         KeyCode::Char('r') | KeyCode::Char('к') => {
             tokio::spawn(async_fetch_queue(app_arc));
         }
+        KeyCode::Char('R') | KeyCode::Char('К') => {
+            app.needs_reconnect = true;
+            app.toast(i18n::tr("Reconnecting WebSocket..."));
+        }
         KeyCode::Tab => app.view = View::Menu,
-        KeyCode::Enter => app.input_mode = InputMode::Editing,
+        KeyCode::Enter if app.scrub_ms.is_some() => commit_scrub(app, app_arc),
+        KeyCode::Enter => match app.queue_state.selected() {
+            Some(index) => jump_to_queue_track(app, index, app_arc),
+            None => app.input_mode = InputMode::Editing,
+        },
+        KeyCode::Down if key.modifiers.contains(event::KeyModifiers::SHIFT) => move_queue_track(app, 1, app_arc),
+        KeyCode::Up if key.modifiers.contains(event::KeyModifiers::SHIFT) => move_queue_track(app, -1, app_arc),
+        KeyCode::Right | KeyCode::Left => {
+            let step = if key.modifiers.contains(event::KeyModifiers::SHIFT) { 30_000 } else { 5_000 };
+            scrub_by(app, if key.code == KeyCode::Left { -step } else { step });
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-') if app.browsing_queue() => {
+            app.volume = if key.code == KeyCode::Char('-') {
+                app.volume.saturating_sub(5)
+            } else {
+                (app.volume + 5).min(MAX_VOLUME)
+            };
+            app.filters_changed_at = Some(Instant::now());
+        }
+        KeyCode::Down => {
+            app.move_queue_selection(1);
+            load_more_queue(app, app_arc);
+        }
+        KeyCode::Up => app.move_queue_selection(-1),
+        KeyCode::PageDown => {
+            app.move_queue_selection(app.queue_page as isize);
+            load_more_queue(app, app_arc);
+        }
+        KeyCode::PageUp => app.move_queue_selection(-(app.queue_page as isize)),
+        KeyCode::Esc if app.scrub_ms.is_some() => app.scrub_ms = None,
+        KeyCode::Esc if app.queue_search.is_some() => app.queue_search = None,
+        KeyCode::Esc => app.queue_state.select(None),
+        KeyCode::Char('/') | KeyCode::Char('.') if app.browsing_queue() => {
+            app.queue_search = Some(String::new());
+            app.queue_search_editing = true;
+        }
+        KeyCode::Char('n') | KeyCode::Char('т') if app.queue_search.is_some() => app.jump_to_match(true),
+        KeyCode::Char('N') | KeyCode::Char('Т') if app.queue_search.is_some() => app.jump_to_match(false),
         KeyCode::Char('l') | KeyCode::Char('д') => {
             let new_mode = match app.loop_mode.as_str() {
                 "off" => "track",
@@ -1297,35 +3471,244 @@ fn handle_player_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<Ap
         KeyCode::Char('p') | KeyCode::Char('з') => {
             tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), SimplePayload { action: "pause", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() }));
         }
-        KeyCode::Char('w') | KeyCode::Char('ц') => {
-            tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), SimplePayload { action: "stop", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() }));
-        }
-        KeyCode::Char('c') | KeyCode::Char('с') => {
-            tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), SimplePayload { action: "clear", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() }));
-        }
+        KeyCode::Char('w') | KeyCode::Char('ц') => confirm_action(app, "stop", app_arc),
+        KeyCode::Char('c') | KeyCode::Char('с') => confirm_action(app, "clear", app_arc),
         KeyCode::Char('i') | KeyCode::Char('ш') => {
+            app.details_index = app.queue_state.selected();
+            app.view = View::TrackDetails;
+        }
+        KeyCode::Char('I') | KeyCode::Char('Ш') => {
             app.view = View::AppInfo;
         }
+        KeyCode::Char('y') | KeyCode::Char('н') if app.browsing_queue() => copy_track_url(app, app_arc),
+        KeyCode::Char('v') if key.modifiers.contains(event::KeyModifiers::CONTROL) => { tokio::spawn(async_paste_clipboard(app_arc)); }
+        KeyCode::Char('v') | KeyCode::Char('м') if app.browsing_queue() => {
+            app.viz_style = cycle_name(&VIZ_STYLES, &app.viz_style, 1).to_string();
+            save_app_settings(app);
+        }
         KeyCode::Char('d') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
             app.view = View::Debug;
         }
+        // Letter shortcuts on the selection only apply while one is highlighted,
+        // so typing a search that starts with them still works.
+        KeyCode::Delete | KeyCode::Char('d') | KeyCode::Char('в') if let Some(index) = app.queue_state.selected() => {
+            if app.confirm_destructive {
+                app.pending_remove = Some(index);
+            } else {
+                remove_queue_track(app, index, app_arc);
+            }
+        }
+        KeyCode::Char('J') | KeyCode::Char('О') if app.queue_state.selected().is_some() => move_queue_track(app, 1, app_arc),
+        KeyCode::Char('K') | KeyCode::Char('Л') if app.queue_state.selected().is_some() => move_queue_track(app, -1, app_arc),
         KeyCode::Char(c) => {
             app.input_mode = InputMode::Editing;
-            app.input.push(c);
+            app.input.insert(c);
+        }
+        _ => {}
+    }
+}
+
+/// `y`: copy the highlighted track's URL, or the current one's.
+fn copy_track_url(app: &mut App, app_arc: Arc<Mutex<App>>) {
+    let url = match app.queue_state.selected().and_then(|i| app.queue.get(i)) {
+        Some(track) => track.info.uri.clone(),
+        None => app.current_uri.clone(),
+    };
+    let Some(url) = url else {
+        app.toast(i18n::tr("This track has no URL"));
+        return;
+    };
+    tokio::spawn(async_copy_url(app_arc, url));
+}
+
+/// Like `async_paste_clipboard`, the copy tool runs on a blocking thread
+/// without the App lock.
+async fn async_copy_url(app_arc: Arc<Mutex<App>>, url: String) {
+    let text = url.clone();
+    let tool = tokio::task::spawn_blocking(move || crate::clipboard::copy_with_tool(&text)).await.ok().flatten();
+    let mut app = app_arc.lock().await;
+    if let Some(tool) = tool {
+        app.log(format!("Clipboard set with {}", tool));
+        app.toast(t!("Copied {}", url));
+        return;
+    }
+    // Written under the lock the draw loop holds, so the escape sequence
+    // can't land in the middle of a frame.
+    match crate::clipboard::copy_to_terminal(&url) {
+        Ok(()) => app.toast(t!("Sent {} to the terminal clipboard", url)),
+        Err(e) => app.error_toast(t!("Copy failed: {}", e)),
+    }
+}
+
+/// Alt+V / Alt+L / Alt+Q: show or hide the visualizer, logo or queue panel.
+fn toggle_panel(app: &mut App, c: char) {
+    let (shown, name) = match c {
+        'v' | 'V' | 'м' | 'М' => (&mut app.show_visualizer, "Visualizer"),
+        'l' | 'L' | 'д' | 'Д' => (&mut app.show_logo, "Logo"),
+        'q' | 'Q' | 'й' | 'Й' => (&mut app.show_queue, "Queue"),
+        _ => return,
+    };
+    *shown = !*shown;
+    let message = format!("{} panel {}", name, if *shown { "shown" } else { "hidden" });
+    app.log(message);
+    save_app_settings(app);
+}
+
+fn send_simple_action(app: &mut App, action: &'static str, app_arc: Arc<Mutex<App>>) {
+    let payload = SimplePayload { action, guild_id: app.guild_id.clone(), user_id: app.user_id.clone() };
+    tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+}
+
+/// Send `action` now, or ask first when confirmations are on.
+fn confirm_action(app: &mut App, action: &'static str, app_arc: Arc<Mutex<App>>) {
+    if app.confirm_destructive {
+        app.pending_action = Some(action);
+    } else {
+        send_simple_action(app, action, app_arc);
+    }
+}
+
+fn remove_queue_track(app: &mut App, index: usize, app_arc: Arc<Mutex<App>>) {
+    if index >= app.queue.len() {
+        return;
+    }
+    let removed = app.queue.remove(index);
+    app.queue_validator = None;
+    app.log(format!("Removing queue #{}: {}", index + 1, removed.label));
+    if let Some(i) = app.queue_state.selected() {
+        app.queue_state.select(app.queue.len().checked_sub(1).map(|last| i.min(last)));
+    }
+    let payload = QueueItemPayload {
+        action: "remove",
+        guild_id: app.guild_id.clone(),
+        user_id: app.user_id.clone(),
+        position: index + 1,
+    };
+    tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+}
+
+/// Play the track at `index` now. The skipped-over entries are dropped
+/// locally straight away; the next WS state update confirms or corrects it.
+fn jump_to_queue_track(app: &mut App, index: usize, app_arc: Arc<Mutex<App>>) {
+    if index >= app.queue.len() {
+        return;
+    }
+    let mut rest = app.queue.split_off(index);
+    let next = rest.remove(0);
+    app.current_track = Some(next.label);
+    app.current_uri = next.info.uri.clone();
+    app.current_info = Some(next.info);
+    app.queue = rest;
+    app.queue_validator = None;
+    app.queue_state.select(None);
+    app.elapsed_ms = 0;
+    app.duration_ms = 0;
+    app.last_state_update = Instant::now();
+    let payload = QueueItemPayload {
+        action: "jump",
+        guild_id: app.guild_id.clone(),
+        user_id: app.user_id.clone(),
+        position: index + 1,
+    };
+    tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+}
+
+/// Move the seek bar's cursor without seeking yet.
+fn scrub_by(app: &mut App, delta_ms: i64) {
+    if app.current_track.is_none() {
+        return;
+    }
+    let mut target = app.scrub_ms.unwrap_or(app.elapsed_ms).saturating_add_signed(delta_ms);
+    if app.duration_ms > 0 {
+        target = target.min(app.duration_ms);
+    }
+    app.scrub_ms = Some(target);
+}
+
+/// Put the seek bar's cursor under column `x`, for mouse clicks and drags.
+fn scrub_to_column(app: &mut App, x: u16) {
+    let bar = app.seek_bar;
+    if app.current_track.is_none() || app.duration_ms == 0 || bar.width == 0 {
+        return;
+    }
+    let offset = x.clamp(bar.x, bar.right() - 1) - bar.x;
+    let ratio = offset as f64 / bar.width.saturating_sub(1).max(1) as f64;
+    app.scrub_ms = Some((app.duration_ms as f64 * ratio) as u64);
+}
+
+fn handle_mouse(app: &mut App, mouse: event::MouseEvent) {
+    if app.view != View::Main || app.input_mode != InputMode::Normal {
+        return;
+    }
+    let bar = app.seek_bar;
+    match mouse.kind {
+        event::MouseEventKind::Down(event::MouseButton::Left)
+            if mouse.row == bar.y && (bar.x..bar.right()).contains(&mouse.column) =>
+        {
+            scrub_to_column(app, mouse.column);
+        }
+        // Keep following a drag that started on the bar, wherever the pointer goes.
+        event::MouseEventKind::Drag(event::MouseButton::Left) if app.scrub_ms.is_some() => {
+            scrub_to_column(app, mouse.column);
         }
         _ => {}
     }
 }
 
+/// Seek to where the seek bar's cursor was left, moving the local clock
+/// right away so the progress bar doesn't wait for the server.
+fn commit_scrub(app: &mut App, app_arc: Arc<Mutex<App>>) {
+    let Some(target) = app.scrub_ms.take() else {
+        return;
+    };
+    if app.current_track.is_none() {
+        return;
+    }
+    app.elapsed_ms = target;
+    app.last_state_update = Instant::now();
+    let payload = SeekPayload {
+        action: "seek",
+        guild_id: app.guild_id.clone(),
+        user_id: app.user_id.clone(),
+        position_ms: target,
+    };
+    tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+}
+
+/// Shift the highlighted track by one slot, locally first so the list
+/// reacts immediately, then on the server.
+fn move_queue_track(app: &mut App, delta: isize, app_arc: Arc<Mutex<App>>) {
+    let Some(from) = app.queue_state.selected() else {
+        return;
+    };
+    let Some(to) = from.checked_add_signed(delta).filter(|to| *to < app.queue.len()) else {
+        return;
+    };
+    app.queue.swap(from, to);
+    app.queue_validator = None;
+    app.queue_state.select(Some(to));
+    let payload = MovePayload {
+        action: "move",
+        guild_id: app.guild_id.clone(),
+        user_id: app.user_id.clone(),
+        from: from + 1,
+        to: to + 1,
+    };
+    tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
+}
+
 fn handle_lyrics_keys(app: &mut App, key: event::KeyEvent) {
     match key.code {
         KeyCode::Esc | KeyCode::Backspace => app.view = View::Main,
         KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('о') => {
+            app.lyrics_follow = false;
             app.lyrics_scroll = app.lyrics_scroll.saturating_add(1);
         },
         KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('л') => {
+            app.lyrics_follow = false;
             app.lyrics_scroll = app.lyrics_scroll.saturating_sub(1);
         },
+        KeyCode::Char('f') | KeyCode::Char('а') => app.lyrics_follow = !app.lyrics_follow,
         _ => {}
     }
 }
@@ -1335,25 +3718,48 @@ fn handle_settings_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<
         match key.code {
             KeyCode::Enter | KeyCode::Esc => {
                 app.is_settings_editing = false;
+                let before = app.seeded_settings.clone();
                 save_app_settings(app);
                 // If host changed, we might need reconnect
                 if app.base_url != app.settings_input {
                     app.base_url = app.settings_input.clone();
                     app.needs_reconnect = true;
-                    tokio::spawn(async_fetch_queue(app_arc));
+                    tokio::spawn(async_fetch_queue(app_arc.clone()));
                 }
                 if let Ok(offset) = app.offset_input.parse::<i64>() {
                     app.visualizer_offset = offset;
                 }
+                // Default IDs that were changed, or cleared, also take effect
+                // for this session.
+                let typed = |input: &str| Some(input.to_string()).filter(|s| !s.is_empty());
+                let guild = typed(&app.guild_input);
+                if guild != before.default_guild_id {
+                    app.guild_id = guild;
+                    if app.guild_id.is_some() {
+                        tokio::spawn(async_fetch_queue(app_arc.clone()));
+                    } else {
+                        open_guild_picker(app, app_arc.clone());
+                    }
+                }
+                let user = typed(&app.user_input);
+                if user != before.default_user_id {
+                    app.user_id = user;
+                }
+                let channel = typed(&app.channel_input);
+                if channel != before.default_channel_id {
+                    app.channel_id = channel;
+                }
             }
             KeyCode::Char(c) => {
                 match app.settings_field {
                     SettingsField::Host => { app.settings_input.push(c); }
-                    SettingsField::Offset => { 
-                        if c.is_ascii_digit() || (c == '-' && app.offset_input.is_empty()) { 
-                            app.offset_input.push(c); 
-                        } 
+                    SettingsField::Offset if c.is_ascii_digit() || (c == '-' && app.offset_input.is_empty()) => {
+                        app.offset_input.push(c);
                     }
+                    SettingsField::GuildId if c.is_ascii_digit() => { app.guild_input.push(c); }
+                    SettingsField::UserId if c.is_ascii_digit() => { app.user_input.push(c); }
+                    SettingsField::ChannelId if c.is_ascii_digit() => { app.channel_input.push(c); }
+                    SettingsField::ExportDir => { app.export_dir_input.push(c); }
                     _ => {}
                 }
             }
@@ -1361,6 +3767,10 @@ fn handle_settings_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<
                 match app.settings_field {
                     SettingsField::Host => { app.settings_input.pop(); }
                     SettingsField::Offset => { app.offset_input.pop(); }
+                    SettingsField::GuildId => { app.guild_input.pop(); }
+                    SettingsField::UserId => { app.user_input.pop(); }
+                    SettingsField::ChannelId => { app.channel_input.pop(); }
+                    SettingsField::ExportDir => { app.export_dir_input.pop(); }
                     _ => {}
                 }
             }
@@ -1372,7 +3782,12 @@ fn handle_settings_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<
     match key.code {
         KeyCode::Enter => {
             match app.settings_field {
-                SettingsField::Host | SettingsField::Offset => {
+                SettingsField::Host
+                | SettingsField::Offset
+                | SettingsField::GuildId
+                | SettingsField::UserId
+                | SettingsField::ChannelId
+                | SettingsField::ExportDir => {
                     app.is_settings_editing = true;
                 }
                 _ => {
@@ -1390,40 +3805,46 @@ fn handle_settings_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<
                 SettingsField::Offset => SettingsField::Theme,
                 SettingsField::Theme => SettingsField::VizStyle,
                 SettingsField::VizStyle => SettingsField::Layout,
-                SettingsField::Layout => SettingsField::Host,
+                SettingsField::Layout => SettingsField::GuildId,
+                SettingsField::GuildId => SettingsField::UserId,
+                SettingsField::UserId => SettingsField::ChannelId,
+                SettingsField::ChannelId => SettingsField::Confirm,
+                SettingsField::Confirm => SettingsField::Compact,
+                SettingsField::Compact => SettingsField::Notify,
+                SettingsField::Notify => SettingsField::FrameRate,
+                SettingsField::FrameRate => SettingsField::Accessible,
+                SettingsField::Accessible => SettingsField::ExportDir,
+                SettingsField::ExportDir => SettingsField::Host,
             };
         }
         KeyCode::Up => {
             app.settings_field = match app.settings_field {
-                SettingsField::Host => SettingsField::Layout,
+                SettingsField::Host => SettingsField::ExportDir,
                 SettingsField::Offset => SettingsField::Host,
                 SettingsField::Theme => SettingsField::Offset,
                 SettingsField::VizStyle => SettingsField::Theme,
                 SettingsField::Layout => SettingsField::VizStyle,
+                SettingsField::GuildId => SettingsField::Layout,
+                SettingsField::UserId => SettingsField::GuildId,
+                SettingsField::ChannelId => SettingsField::UserId,
+                SettingsField::Confirm => SettingsField::ChannelId,
+                SettingsField::Compact => SettingsField::Confirm,
+                SettingsField::Notify => SettingsField::Compact,
+                SettingsField::FrameRate => SettingsField::Notify,
+                SettingsField::Accessible => SettingsField::FrameRate,
+                SettingsField::ExportDir => SettingsField::Accessible,
             };
         }
         KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('д') => {
             match app.settings_field {
                 SettingsField::Theme => {
-                    app.theme = match app.theme.as_str() {
-                        "Default" => "Midnight".to_string(),
-                        "Midnight" => "Emerald".to_string(),
-                        "Emerald" => "Ruby".to_string(),
-                        "Ruby" => "Ocean".to_string(),
-                        "Ocean" => "Synthwave".to_string(),
-                        "Synthwave" => "Sepia".to_string(),
-                        _ => "Default".to_string(),
-                    };
+                    let names = theme_names(app);
+                    let i = names.iter().position(|n| *n == app.theme).map_or(0, |i| (i + 1) % names.len());
+                    app.theme = names[i].clone();
                     save_app_settings(app);
                 }
                 SettingsField::VizStyle => {
-                    app.viz_style = match app.viz_style.as_str() {
-                        "Bars" => "Blocky".to_string(),
-                        "Blocky" => "Line".to_string(),
-                        "Line" => "Wave".to_string(),
-                        "Wave" => "Dots".to_string(),
-                        _ => "Bars".to_string(),
-                    };
+                    app.viz_style = cycle_name(&VIZ_STYLES, &app.viz_style, 1).to_string();
                     save_app_settings(app);
                 }
                 SettingsField::Layout => {
@@ -1431,43 +3852,53 @@ fn handle_settings_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<
                         "Standard" => "Sidebar".to_string(),
                         "Sidebar" => "Studio".to_string(),
                         "Studio" => "Zen".to_string(),
-                        "Zen" => "Standard".to_string(),
+                        "Zen" => "Lyrics".to_string(),
+                        "Lyrics" => "Standard".to_string(),
                         _ => "Standard".to_string(),
                     };
                     save_app_settings(app);
                 }
+                SettingsField::Confirm => {
+                    app.confirm_destructive = !app.confirm_destructive;
+                    save_app_settings(app);
+                }
+                SettingsField::Notify => {
+                    app.notify_track_start = !app.notify_track_start;
+                    save_app_settings(app);
+                }
+                SettingsField::Accessible => {
+                    app.accessible = !app.accessible;
+                    app.art = None;
+                    app.art_loaded_url = None;
+                    save_app_settings(app);
+                }
+                SettingsField::Compact => {
+                    app.compact_mode = cycle_name(&COMPACT_MODES, &app.compact_mode, 1).to_string();
+                    save_app_settings(app);
+                }
+                SettingsField::FrameRate => {
+                    app.frame_rate = cycle_frame_rate(app.frame_rate, 1);
+                    save_app_settings(app);
+                }
                 _ => {}
             }
         }
         KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('р') => {
             match app.settings_field {
                 SettingsField::Theme => {
-                    app.theme = match app.theme.as_str() {
-                        "Default" => "Sepia".to_string(),
-                        "Midnight" => "Default".to_string(),
-                        "Emerald" => "Midnight".to_string(),
-                        "Ruby" => "Emerald".to_string(),
-                        "Ocean" => "Ruby".to_string(),
-                        "Synthwave" => "Ocean".to_string(),
-                        "Sepia" => "Synthwave".to_string(),
-                        _ => "Default".to_string(),
-                    };
+                    let names = theme_names(app);
+                    let i = names.iter().position(|n| *n == app.theme).map_or(0, |i| (i + names.len() - 1) % names.len());
+                    app.theme = names[i].clone();
                     save_app_settings(app);
                 }
                 SettingsField::VizStyle => {
-                    app.viz_style = match app.viz_style.as_str() {
-                        "Bars" => "Dots".to_string(),
-                        "Blocky" => "Bars".to_string(),
-                        "Line" => "Blocky".to_string(),
-                        "Wave" => "Line".to_string(),
-                        "Dots" => "Wave".to_string(),
-                        _ => "Bars".to_string(),
-                    };
+                    app.viz_style = cycle_name(&VIZ_STYLES, &app.viz_style, -1).to_string();
                     save_app_settings(app);
                 }
                 SettingsField::Layout => {
                     app.layout = match app.layout.as_str() {
-                        "Standard" => "Zen".to_string(),
+                        "Standard" => "Lyrics".to_string(),
+                        "Lyrics" => "Zen".to_string(),
                         "Sidebar" => "Standard".to_string(),
                         "Studio" => "Sidebar".to_string(),
                         "Zen" => "Studio".to_string(),
@@ -1475,6 +3906,28 @@ fn handle_settings_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<
                     };
                     save_app_settings(app);
                 }
+                SettingsField::Confirm => {
+                    app.confirm_destructive = !app.confirm_destructive;
+                    save_app_settings(app);
+                }
+                SettingsField::Notify => {
+                    app.notify_track_start = !app.notify_track_start;
+                    save_app_settings(app);
+                }
+                SettingsField::Accessible => {
+                    app.accessible = !app.accessible;
+                    app.art = None;
+                    app.art_loaded_url = None;
+                    save_app_settings(app);
+                }
+                SettingsField::Compact => {
+                    app.compact_mode = cycle_name(&COMPACT_MODES, &app.compact_mode, -1).to_string();
+                    save_app_settings(app);
+                }
+                SettingsField::FrameRate => {
+                    app.frame_rate = cycle_frame_rate(app.frame_rate, -1);
+                    save_app_settings(app);
+                }
                 _ => {}
             }
         }
@@ -1482,20 +3935,80 @@ fn handle_settings_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<
     }
 }
 
-fn save_app_settings(app: &App) {
-    let settings = api::Settings { 
+fn save_app_settings(app: &mut App) {
+    let edited = api::Settings {
         base_url: app.settings_input.clone(),
         visualizer_offset: app.offset_input.parse().unwrap_or(app.visualizer_offset),
         theme: app.theme.clone(),
         visualizer_style: app.viz_style.clone(),
         layout: app.layout.clone(),
+        default_guild_id: Some(app.guild_input.clone()).filter(|s| !s.is_empty()),
+        default_user_id: Some(app.user_input.clone()).filter(|s| !s.is_empty()),
+        default_channel_id: Some(app.channel_input.clone()).filter(|s| !s.is_empty()),
+        export_dir: Some(app.export_dir_input.clone()).filter(|s| !s.is_empty()),
+        confirm_destructive: app.confirm_destructive,
+        notify_track_start: app.notify_track_start,
+        frame_rate: app.frame_rate,
+        accessible: app.accessible,
+        filter_presets: app.filter_presets.clone(),
+        show_visualizer: app.show_visualizer,
+        show_logo: app.show_logo,
+        show_queue: app.show_queue,
+        compact_mode: app.compact_mode.clone(),
+        ..app.seeded_settings.clone()
+    };
+    let (Ok(Value::Object(changed)), Ok(Value::Object(seeded))) =
+        (serde_json::to_value(&edited), serde_json::to_value(&app.seeded_settings))
+    else {
+        return;
     };
-    let _ = api::save_settings(&settings);
+    // Everything else stays as it is on disk.
+    let Ok(Value::Object(mut on_disk)) = serde_json::to_value(api::load_settings()) else {
+        return;
+    };
+    for (key, value) in changed {
+        if seeded.get(&key) != Some(&value) {
+            on_disk.insert(key, value);
+        }
+    }
+    if let Ok(settings) = serde_json::from_value(Value::Object(on_disk)) {
+        let _ = api::save_settings(&settings);
+    }
+    app.seeded_settings = edited;
 }
 
 fn handle_debug_keys(app: &mut App, key: event::KeyEvent) {
+    if app.debug_filter_editing {
+        match key.code {
+            KeyCode::Char(c) => app.debug_filter.push(c),
+            KeyCode::Backspace => { app.debug_filter.pop(); }
+            KeyCode::Esc => {
+                app.debug_filter.clear();
+                app.debug_filter_editing = false;
+            }
+            KeyCode::Enter => app.debug_filter_editing = false,
+            _ => return,
+        }
+        app.debug_scroll = 0;
+        return;
+    }
+    let last = app.filtered_debug_logs().len().saturating_sub(1);
     match key.code {
-        KeyCode::Char('s') | KeyCode::Char('ы') => app.save_spectrogram(),
+        KeyCode::Char('s') | KeyCode::Char('ы') => app.save_spectrogram("json"),
+        KeyCode::Char('p') | KeyCode::Char('з') => app.save_spectrogram("png"),
+        KeyCode::Char('c') | KeyCode::Char('с') => app.save_spectrogram("csv"),
+        KeyCode::Char('e') | KeyCode::Char('у') => app.export_debug_log(),
+        KeyCode::Char('/') | KeyCode::Char('.') => app.debug_filter_editing = true,
+        KeyCode::Down => app.debug_scroll = app.debug_scroll.saturating_sub(1),
+        KeyCode::Up => app.debug_scroll = (app.debug_scroll + 1).min(last),
+        KeyCode::PageDown => app.debug_scroll = app.debug_scroll.saturating_sub(10),
+        KeyCode::PageUp => app.debug_scroll = (app.debug_scroll + 10).min(last),
+        KeyCode::End => app.debug_scroll = 0,
+        KeyCode::Home => app.debug_scroll = last,
+        KeyCode::Esc if !app.debug_filter.is_empty() => {
+            app.debug_filter.clear();
+            app.debug_scroll = 0;
+        }
         KeyCode::Esc | KeyCode::Backspace => {
             app.view = if app.token.is_none() { View::LoginRequired } else { View::Main };
         }
@@ -1525,16 +4038,24 @@ fn handle_menu_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>
                 let item = app.menu_items[idx].trim();
                 if item.contains("Skip") { tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), SimplePayload { action: "skip", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() })); }
                 else if item.contains("Pause/Resume") { tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), SimplePayload { action: "pause", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() })); }
-                else if item.contains("Stop") { tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), SimplePayload { action: "stop", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() })); }
+                else if item.contains("Stop") { confirm_action(app, "stop", app_arc.clone()); }
                 else if item.contains("Shuffle") { tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), SimplePayload { action: "shuffle", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() })); }
-                else if item.contains("Clear Queue") { tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), SimplePayload { action: "clear", guild_id: app.guild_id.clone(), user_id: app.user_id.clone() })); }
+                else if item.contains("Clear Queue") { confirm_action(app, "clear", app_arc.clone()); }
                 else if item.contains("Loop Track") { app.loop_mode = "track".to_string(); tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), LoopPayload { action: "loop", guild_id: app.guild_id.clone(), user_id: app.user_id.clone(), loop_mode: "track".to_string() })); }
                 else if item.contains("Loop Queue") { app.loop_mode = "queue".to_string(); tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), LoopPayload { action: "loop", guild_id: app.guild_id.clone(), user_id: app.user_id.clone(), loop_mode: "queue".to_string() })); }
                 else if item.contains("Loop Off") { app.loop_mode = "off".to_string(); tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), LoopPayload { action: "loop", guild_id: app.guild_id.clone(), user_id: app.user_id.clone(), loop_mode: "off".to_string() })); }
                 else if item.contains("24/7 Mode") { tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), TwentyFourSevenPayload { action: "247", guild_id: app.guild_id.clone(), user_id: app.user_id.clone(), enabled: None })); }
-                else if item.contains("Filters...") { app.view = View::FilterMenu; }
-                else if item.contains("Lyrics") { tokio::spawn(async_fetch_lyrics(app_arc.clone())); }
+                else if item.contains("Filters...") { open_filter_editor(app); }
+                else if item.contains("Lyrics") { tokio::spawn(async_fetch_lyrics(app_arc.clone(), true)); }
+                else if item.contains("Playlists") { open_playlists(app); }
+                else if item.contains("History") {
+                    app.history_state.select(Some(0));
+                    app.view = View::History;
+                    tokio::spawn(async_fetch_history(app_arc.clone()));
+                }
                 else if item.contains("Play Turip") { tokio::spawn(async_play_track(app_arc.clone(), "https://open.spotify.com/track/2RQWB4Asy1rjZL4IUcJ7kn".to_string())); }
+                else if item.contains("Guilds") { open_guild_picker(app, app_arc.clone()); }
+                else if item.contains("Profiles") { open_profiles(app); }
                 else if item.contains("Auth") { app.view = View::AuthMenu; }
                 else if item.contains("Settings") { 
                     app.settings_input = app.base_url.clone();
@@ -1542,7 +4063,7 @@ fn handle_menu_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>
                 }
                 else if item.contains("Exit TUI") { return Ok(true); }
 
-                if !item.contains("Filters...") && !item.contains("Lyrics") && !item.contains("Auth") && !item.contains("Settings") {
+                if !item.contains("Filters...") && !item.contains("Lyrics") && !item.contains("History") && !item.contains("Playlists") && !item.contains("Guilds") && !item.contains("Profiles") && !item.contains("Auth") && !item.contains("Settings") {
                     app.view = View::Main;
                 }
             }
@@ -1552,37 +4073,484 @@ fn handle_menu_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>
     Ok(false)
 }
 
+fn handle_history_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) {
+    let len = app.history.len();
+    match key.code {
+        KeyCode::Esc | KeyCode::Tab => app.view = View::Main,
+        KeyCode::Backspace => app.view = View::Menu,
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('о') if len > 0 => {
+            let i = app.history_state.selected().map_or(0, |i| (i + 1) % len);
+            app.history_state.select(Some(i));
+        }
+        KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('л') if len > 0 => {
+            let i = app.history_state.selected().map_or(0, |i| (i + len - 1) % len);
+            app.history_state.select(Some(i));
+        }
+        KeyCode::Enter => {
+            if let Some(entry) = app.history_state.selected().and_then(|i| app.history.get(i)) {
+                let query = entry.query.clone();
+                app.log(format!("Re-enqueueing {}", entry.label));
+                tokio::spawn(async_play_track(app_arc, query));
+                app.view = View::Main;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn open_playlists(app: &mut App) {
+    match playlist::load_all() {
+        Ok((playlists, skipped)) => {
+            app.playlists = playlists;
+            for e in skipped {
+                app.log(format!("Skipped a playlist: {:#}", e));
+            }
+        }
+        Err(e) => app.log(format!("Loading playlists failed: {:#}", e)),
+    }
+    app.playlist_state.select((!app.playlists.is_empty()).then_some(0));
+    app.playlist_track_state.select(None);
+    app.playlist_tracks_focused = false;
+    app.view = View::Playlists;
+}
+
+fn open_guild_picker(app: &mut App, app_arc: Arc<Mutex<App>>) {
+    app.picker_guilds.clear();
+    app.picker_guild_state.select(None);
+    app.picker_channels.clear();
+    app.picker_channel_state.select(None);
+    app.picker_channels_focused = false;
+    app.view = View::GuildPicker;
+    tokio::spawn(async_fetch_picker_guilds(app_arc));
+}
+
+/// Switch this tab to the picked guild, or go to the tab that has it, and
+/// to the voice channel when one was picked.
+fn apply_guild_pick(app: &mut App, app_arc: Arc<Mutex<App>>) {
+    let Some(guild) = app.picker_guild_state.selected().and_then(|i| app.picker_guilds.get(i)).cloned() else {
+        return;
+    };
+    let channel = match app.picker_channel_state.selected() {
+        Some(i) if app.picker_channels_focused && i > 0 => app.picker_channels.get(i - 1).cloned(),
+        _ => None,
+    };
+    app.toast(t!("Switched to {}", guild.name.as_deref().unwrap_or(&guild.id)));
+    app.channel_id = channel.map(|c| c.id);
+    // A guild another tab already has is switched to, not opened twice.
+    let open = (0..app.tabs.len()).find(|&i| i != app.active_tab && app.tab_guild(i) == Some(guild.id.as_str()));
+    if let Some(i) = open {
+        app.select_tab(i);
+    } else if app.guild_id.as_deref() != Some(guild.id.as_str()) {
+        app.guild_id = Some(guild.id);
+        app.guild_name = guild.name;
+        apply_guild_filters(app, &app_arc, app.guild_id.clone());
+        tokio::spawn(async_fetch_queue(app_arc));
+    }
+    app.view = View::Main;
+}
+
+fn handle_guild_picker_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) {
+    let (state, len) = if app.picker_channels_focused {
+        (&mut app.picker_channel_state, app.picker_channels.len() + 1)
+    } else {
+        (&mut app.picker_guild_state, app.picker_guilds.len())
+    };
+    match key.code {
+        // Without a pick the server goes on guessing from the voice state.
+        KeyCode::Esc | KeyCode::Tab => app.view = View::Main,
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('о') if len > 0 => {
+            state.select(Some(state.selected().map_or(0, |i| (i + 1) % len)));
+            if !app.picker_channels_focused {
+                app.picker_channels.clear();
+            }
+        }
+        KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('л') if len > 0 => {
+            state.select(Some(state.selected().map_or(0, |i| (i + len - 1) % len)));
+            if !app.picker_channels_focused {
+                app.picker_channels.clear();
+            }
+        }
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('р') | KeyCode::Backspace => app.picker_channels_focused = false,
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('д') | KeyCode::Enter if !app.picker_channels_focused => {
+            let Some(guild) = app.picker_guild_state.selected().and_then(|i| app.picker_guilds.get(i)) else {
+                return;
+            };
+            let guild_id = guild.id.clone();
+            app.picker_channels.clear();
+            app.picker_channel_state.select(Some(0));
+            app.picker_channels_focused = true;
+            tokio::spawn(async_fetch_picker_channels(app_arc, guild_id));
+        }
+        KeyCode::Enter => apply_guild_pick(app, app_arc),
+        _ => {}
+    }
+}
+
+fn open_profiles(app: &mut App) {
+    let store = api::load_auth_store();
+    app.profiles = store.profiles.into_iter().collect();
+    let active = app.profiles.iter().position(|(name, _)| *name == store.active);
+    app.profile_state.select(active.or((!app.profiles.is_empty()).then_some(0)));
+    app.active_profile = store.active;
+    app.view = View::Profiles;
+}
+
+/// Make the selected profile active, here and on disk like `auth switch`,
+/// then start over against its server: new WS connection, fresh state.
+fn switch_profile(app: &mut App, app_arc: Arc<Mutex<App>>) {
+    let Some((name, auth)) = app.profile_state.selected().and_then(|i| app.profiles.get(i)).cloned() else {
+        return;
+    };
+    app.view = View::Main;
+    if name == app.active_profile {
+        return;
+    }
+    let mut store = api::load_auth_store();
+    store.active = name.clone();
+    if let Err(e) = api::save_auth_store(&store) {
+        // Stateless mode: switch for this session only.
+        app.log(format!("Saving the active profile failed: {:#}", e));
+    }
+    app.active_profile = name.clone();
+    app.base_url = auth.base_url.unwrap_or_else(|| api::load_settings().base_url);
+    app.token = Some(auth.token);
+    app.username = auth.username;
+    app.reset_tabs();
+    app.art_loaded_url = None;
+    app.needs_reconnect = true;
+    for guild_id in app.tab_guilds() {
+        apply_guild_filters(app, &app_arc, guild_id.clone());
+        tokio::spawn(async_fetch_guild_queue(app_arc.clone(), guild_id));
+    }
+    app.toast(t!("Switched to profile {}", name));
+    tokio::spawn(async_check_token(app_arc));
+}
+
+fn handle_profile_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) {
+    let len = app.profiles.len();
+    match key.code {
+        KeyCode::Esc | KeyCode::Tab => app.view = View::Main,
+        KeyCode::Backspace => app.view = View::Menu,
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('о') if len > 0 => {
+            app.profile_state.select(Some(app.profile_state.selected().map_or(0, |i| (i + 1) % len)));
+        }
+        KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('л') if len > 0 => {
+            app.profile_state.select(Some(app.profile_state.selected().map_or(0, |i| (i + len - 1) % len)));
+        }
+        KeyCode::Enter => switch_profile(app, app_arc),
+        _ => {}
+    }
+}
+
+/// Enqueue `tracks` in order: in `play_bulk` chunks while the server takes
+/// them, then one after another once it says it doesn't know the action.
+/// Any other failure stops there, since that chunk may have gone in.
+fn enqueue_tracks(app: &mut App, name: &str, tracks: Vec<String>, app_arc: Arc<Mutex<App>>) {
+    app.log(format!("Enqueueing {} track(s) from {}", tracks.len(), name));
+    tokio::spawn(async move {
+        let mut done = 0;
+        if tracks.len() > 1 {
+            for chunk in tracks.chunks(api::BULK_PLAY_MAX) {
+                match async_play_bulk(app_arc.clone(), chunk).await {
+                    BulkReply::Queued => done += chunk.len(),
+                    BulkReply::Unsupported => break,
+                    BulkReply::Failed => {
+                        async_fetch_queue(app_arc).await;
+                        return;
+                    }
+                }
+            }
+        }
+        let bulk_only = done == tracks.len();
+        for query in tracks.into_iter().skip(done) {
+            async_play_track(app_arc.clone(), query).await;
+        }
+        if bulk_only {
+            async_fetch_queue(app_arc).await;
+        }
+    });
+}
+
+/// How the server took one `play_bulk` request.
+enum BulkReply {
+    Queued,
+    /// It doesn't know the action, so single plays are the way in.
+    Unsupported,
+    /// Anything else, already shown in an error toast.
+    Failed,
+}
+
+/// Send one `play_bulk` request over REST.
+async fn async_play_bulk(app_arc: Arc<Mutex<App>>, queries: &[String]) -> BulkReply {
+    let _redraw = RedrawOnDrop;
+    let (client, url, token, payload) = {
+        let app = app_arc.lock().await;
+        let single = PlayPayload {
+            action: "play",
+            guild_id: app.guild_id.clone(),
+            channel_id: app.play_channel(),
+            query: String::new(),
+            user_id: app.user_id.clone(),
+            requested_by: None,
+            avatar_url: None,
+        };
+        let queries: Vec<String> = queries.iter().map(|q| api::clean_query(q)).collect();
+        let url = api::build_url(&app.base_url, "/webhook/audio");
+        (app.client.clone(), url, app.token.clone(), BulkPlayPayload::like(&single, &queries))
+    };
+
+    let request_id = api::new_request_id();
+    let mut req = client.post(&url).header(api::REQUEST_ID_HEADER, &request_id).json(&payload);
+    if let Some(bearer) = &token {
+        req = req.bearer_auth(bearer);
+    }
+    let resp = match req.send().await {
+        Ok(resp) => resp,
+        Err(e) => {
+            app_arc.lock().await.error_toast(t!("Network error: {}", e));
+            return BulkReply::Failed;
+        }
+    };
+    let status = resp.status();
+    if status.is_success() {
+        return BulkReply::Queued;
+    }
+    let text = resp.text().await.unwrap_or_default();
+    let error = serde_json::from_str::<ErrorResponse>(&text).ok().map(|e| e.error);
+    if api::bulk_unsupported(status, error.as_deref()) {
+        return BulkReply::Unsupported;
+    }
+    let mut app = app_arc.lock().await;
+    app.log(format!("Bulk play failed with {} (request ID {})", status, request_id));
+    app.error_toast(t!("Adding tracks failed ({}); the rest were not sent", status));
+    BulkReply::Failed
+}
+
+fn handle_playlist_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) {
+    let Some(selected) = app.playlist_state.selected() else {
+        match key.code {
+            KeyCode::Backspace => app.view = View::Menu,
+            KeyCode::Esc | KeyCode::Tab | KeyCode::Enter => app.view = View::Main,
+            _ => {}
+        }
+        return;
+    };
+    let (name, tracks) = {
+        let list = &app.playlists[selected];
+        (list.name.clone(), list.tracks.clone())
+    };
+    let (state, len) = if app.playlist_tracks_focused {
+        (&mut app.playlist_track_state, tracks.len())
+    } else {
+        (&mut app.playlist_state, app.playlists.len())
+    };
+    match key.code {
+        KeyCode::Esc | KeyCode::Tab => app.view = View::Main,
+        KeyCode::Backspace => app.view = View::Menu,
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('о') if len > 0 => {
+            state.select(Some(state.selected().map_or(0, |i| (i + 1) % len)));
+            if !app.playlist_tracks_focused {
+                app.playlist_track_state.select(None);
+            }
+        }
+        KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('л') if len > 0 => {
+            state.select(Some(state.selected().map_or(0, |i| (i + len - 1) % len)));
+            if !app.playlist_tracks_focused {
+                app.playlist_track_state.select(None);
+            }
+        }
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('д') if !tracks.is_empty() => {
+            app.playlist_tracks_focused = true;
+            if app.playlist_track_state.selected().is_none() {
+                app.playlist_track_state.select(Some(0));
+            }
+        }
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('р') => app.playlist_tracks_focused = false,
+        KeyCode::Char('a') | KeyCode::Char('ф') => {
+            enqueue_tracks(app, &name, tracks, app_arc);
+            app.view = View::Main;
+        }
+        KeyCode::Enter => {
+            let tracks = match app.playlist_track_state.selected() {
+                Some(i) if app.playlist_tracks_focused => tracks.into_iter().skip(i).take(1).collect(),
+                _ => tracks,
+            };
+            enqueue_tracks(app, &name, tracks, app_arc);
+            app.view = View::Main;
+        }
+        _ => {}
+    }
+}
+
+fn open_filter_editor(app: &mut App) {
+    app.filter_draft = FilterDraft::from_filters(&app.filters);
+    app.filter_params_focused = false;
+    if app.filter_param_state.selected().is_none() {
+        app.filter_param_state.select(Some(0));
+    }
+    app.view = View::FilterMenu;
+}
+
+/// Send the `[guilds.<id>] filters` preference of `guild_id`'s tab, so the
+/// guild sounds the way it was configured from the moment the TUI opens it.
+fn apply_guild_filters(app: &mut App, app_arc: &Arc<Mutex<App>>, guild_id: Option<String>) {
+    let Some(name) = guild_id
+        .as_deref()
+        .and_then(|g| app.guild_overrides.get(g))
+        .and_then(|g| g.filters.clone())
+    else {
+        return;
+    };
+    let payload = app.with_guild(guild_id.as_deref(), |app| {
+        app.log(format!("Applying the guild's filters ({})", name));
+        app.filters = filter_preset(app, &name);
+        app.filters_changed_at = Some(Instant::now());
+        app.take_pending_filters()
+    });
+    if let Some(payload) = payload.flatten() {
+        tokio::spawn(async_simple_command(app_arc.clone(), "/webhook/audio".to_string(), payload));
+    }
+}
+
+/// Built-in presets followed by the saved ones.
+fn filter_preset_names(app: &App) -> Vec<String> {
+    app.filter_items.iter().map(|s| s.to_string()).chain(app.filter_presets.keys().cloned()).collect()
+}
+
+fn filter_preset(app: &App, name: &str) -> AudioFilters {
+    app.filter_presets.get(name).cloned().unwrap_or_else(|| get_filters_for_style(name))
+}
+
+/// Make the draft the live filters; the run loop sends them once they settle.
+fn preview_filters(app: &mut App) {
+    app.filters = app.filter_draft.to_filters();
+    app.filters_changed_at = Some(Instant::now());
+}
+
+/// Typing a `/` search: the queue narrows to matches, ↑↓ move between them,
+/// Enter keeps the term for `n`/`N`, Esc drops it.
+fn handle_queue_search_keys(app: &mut App, key: event::KeyEvent) {
+    let Some(term) = &mut app.queue_search else {
+        app.queue_search_editing = false;
+        return;
+    };
+    match key.code {
+        KeyCode::Char(c) => term.push(c),
+        KeyCode::Backspace if term.is_empty() => {
+            app.queue_search = None;
+            app.queue_search_editing = false;
+            return;
+        }
+        KeyCode::Backspace => { term.pop(); }
+        KeyCode::Esc => {
+            app.queue_search = None;
+            app.queue_search_editing = false;
+            return;
+        }
+        KeyCode::Enter => {
+            if term.is_empty() {
+                app.queue_search = None;
+            }
+            app.queue_search_editing = false;
+            return;
+        }
+        KeyCode::Down => return app.jump_to_match(true),
+        KeyCode::Up => return app.jump_to_match(false),
+        _ => return,
+    }
+    // Keep the highlight on a match as the term changes.
+    let matches = app.queue_matches();
+    if !app.queue_state.selected().is_some_and(|i| matches.contains(&i)) {
+        app.queue_state.select(matches.first().copied());
+    }
+}
+
 fn handle_filter_menu_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex<App>>) {
+    if let Some(name) = &mut app.preset_name_input {
+        match key.code {
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::Backspace => { name.pop(); }
+            KeyCode::Esc => app.preset_name_input = None,
+            KeyCode::Enter => {
+                let name = name.trim().to_string();
+                app.preset_name_input = None;
+                if app.filter_items.iter().any(|builtin| builtin.eq_ignore_ascii_case(&name)) {
+                    app.error_toast(t!("{} is a built-in style; pick another name", name));
+                } else if !name.is_empty() {
+                    app.log(format!("Saved filter preset {}", name));
+                    app.filter_presets.insert(name, app.filter_draft.to_filters());
+                    save_app_settings(app);
+                }
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    let presets = filter_preset_names(app);
+    let (state, len) = if app.filter_params_focused {
+        (&mut app.filter_param_state, FILTER_PARAMS.len())
+    } else {
+        (&mut app.filter_state, presets.len())
+    };
+    let coarse = key.modifiers.contains(event::KeyModifiers::SHIFT);
     match key.code {
         KeyCode::Esc => app.view = View::Main,
         KeyCode::Backspace => app.view = View::Menu,
+        KeyCode::Tab | KeyCode::BackTab => app.filter_params_focused = !app.filter_params_focused,
         KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('о') => {
-            let i = match app.filter_state.selected() {
-                Some(i) => if i >= app.filter_items.len() - 1 { 0 } else { i + 1 },
-                None => 0,
-            };
-            app.filter_state.select(Some(i));
+            state.select(Some(state.selected().map_or(0, |i| (i + 1) % len)));
         }
         KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('л') => {
-            let i = match app.filter_state.selected() {
-                Some(i) => if i == 0 { app.filter_items.len() - 1 } else { i - 1 },
-                None => 0,
-            };
-            app.filter_state.select(Some(i));
+            state.select(Some(state.selected().map_or(0, |i| (i + len - 1) % len)));
+        }
+        KeyCode::Left | KeyCode::Right | KeyCode::Char('h') | KeyCode::Char('l') | KeyCode::Char('р') | KeyCode::Char('д')
+            if app.filter_params_focused =>
+        {
+            if let Some(i) = app.filter_param_state.selected() {
+                let up = matches!(key.code, KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('д'));
+                let steps = if coarse { 5.0 } else { 1.0 };
+                app.filter_draft.adjust(i, if up { steps } else { -steps });
+                preview_filters(app);
+            }
+        }
+        KeyCode::Char('r') | KeyCode::Char('к') if app.filter_params_focused => {
+            if let Some(i) = app.filter_param_state.selected() {
+                app.filter_draft.reset(i);
+                preview_filters(app);
+            }
+        }
+        KeyCode::Char('s') | KeyCode::Char('ы') => {
+            let current = app.filter_state.selected().and_then(|i| presets.get(i));
+            let name = current.filter(|n| app.filter_presets.contains_key(*n)).cloned().unwrap_or_default();
+            app.preset_name_input = Some(name);
+        }
+        KeyCode::Delete | KeyCode::Char('x') | KeyCode::Char('ч') if !app.filter_params_focused => {
+            if let Some(name) = app.filter_state.selected().and_then(|i| presets.get(i))
+                && app.filter_presets.remove(name).is_some()
+            {
+                app.log(format!("Deleted filter preset {}", name));
+                save_app_settings(app);
+                // One fewer entry now; keep the highlight on the list.
+                let last = presets.len() - 2;
+                app.filter_state.select(app.filter_state.selected().map(|i| i.min(last)));
+            }
+        }
+        KeyCode::Enter if !app.filter_params_focused => {
+            if let Some(name) = app.filter_state.selected().and_then(|i| presets.get(i)) {
+                app.filter_draft = FilterDraft::from_filters(&filter_preset(app, name));
+                preview_filters(app);
+                app.filter_params_focused = true;
+            }
         }
         KeyCode::Enter => {
-            if let Some(idx) = app.filter_state.selected() {
-                let style = app.filter_items[idx];
-                let filters = get_filters_for_style(style);
-                let payload = FilterPayload {
-                    action: "filter",
-                    guild_id: app.guild_id.clone(),
-                    user_id: app.user_id.clone(),
-                    filters,
-                };
+            // Apply right away instead of waiting for the debounce.
+            if app.filters_changed_at.is_some()
+                && let Some(payload) = app.take_pending_filters()
+            {
                 tokio::spawn(async_simple_command(app_arc, "/webhook/audio".to_string(), payload));
-                app.view = View::Main;
             }
+            app.view = View::Main;
         }
         _ => {}
     }
@@ -1615,22 +4583,22 @@ fn handle_auth_menu_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex
                         if let Some(auth) = api::load_auth() {
                             let mut info = String::new();
                             if let Some(path) = api::config_file_path() {
-                                info.push_str(&format!("Auth file: {}\n", path.display()));
+                                info.push_str(&format!("{}\n", t!("Auth file: {}", path.display())));
                             }
-                            info.push_str(&format!("User: {}\n", auth.username.unwrap_or_else(|| "Unknown".to_string())));
+                            info.push_str(&format!("{}\n", t!("User: {}", auth.username.unwrap_or_else(|| i18n::tr("Unknown").to_string()))));
                             if let Some(avatar) = auth.avatar_url {
-                                info.push_str(&format!("Avatar: {}\n", avatar));
+                                info.push_str(&format!("{}\n", t!("Avatar: {}", avatar)));
                             }
                             let token_masked = if auth.token.len() > 8 {
                                 format!("{}...{}", &auth.token[0..4], &auth.token[auth.token.len() - 4..])
                             } else {
                                 auth.token
                             };
-                            info.push_str(&format!("Token: {}", token_masked));
+                            info.push_str(&t!("Token: {}", token_masked));
                             app.auth_info_text = Some(info);
                             app.view = View::AuthResult;
                         } else {
-                            app.auth_info_text = Some("Not authenticated. Run Login.".to_string());
+                            app.auth_info_text = Some(i18n::tr("Not authenticated. Run Login.").to_string());
                             app.view = View::AuthResult;
                         }
                     }
@@ -1642,6 +4610,150 @@ fn handle_auth_menu_keys(app: &mut App, key: event::KeyEvent, app_arc: Arc<Mutex
     }
 }
 
+/// One value the filter editor can adjust.
+struct FilterParam {
+    label: &'static str,
+    min: f32,
+    max: f32,
+    step: f32,
+    /// Where `r` resets to. The filter is left out while its strength
+    /// parameter sits here.
+    neutral: f32,
+}
+
+impl FilterParam {
+    /// Within half a step of neutral, so float drift from stepping doesn't count.
+    fn is_neutral(&self, value: f32) -> bool {
+        (value - self.neutral).abs() < self.step / 2.0
+    }
+}
+
+const fn param(label: &'static str, min: f32, max: f32, step: f32, neutral: f32) -> FilterParam {
+    FilterParam { label, min, max, step, neutral }
+}
+
+const fn eq(label: &'static str) -> FilterParam {
+    param(label, -0.25, 1.0, 0.05, 0.0)
+}
+
+const EQ_BANDS: usize = 15;
+const SPEED: usize = EQ_BANDS;
+const PITCH: usize = EQ_BANDS + 1;
+const RATE: usize = EQ_BANDS + 2;
+const ROTATION: usize = EQ_BANDS + 3;
+const TREMOLO_FREQ: usize = EQ_BANDS + 4;
+const TREMOLO_DEPTH: usize = EQ_BANDS + 5;
+const VIBRATO_FREQ: usize = EQ_BANDS + 6;
+const VIBRATO_DEPTH: usize = EQ_BANDS + 7;
+const LOW_PASS: usize = EQ_BANDS + 8;
+const KARAOKE: usize = EQ_BANDS + 9;
+
+const FILTER_PARAMS: [FilterParam; EQ_BANDS + 10] = [
+    eq("EQ 25 Hz"), eq("EQ 40 Hz"), eq("EQ 63 Hz"), eq("EQ 100 Hz"), eq("EQ 160 Hz"),
+    eq("EQ 250 Hz"), eq("EQ 400 Hz"), eq("EQ 630 Hz"), eq("EQ 1 kHz"), eq("EQ 1.6 kHz"),
+    eq("EQ 2.5 kHz"), eq("EQ 4 kHz"), eq("EQ 6.3 kHz"), eq("EQ 10 kHz"), eq("EQ 16 kHz"),
+    param("Speed", 0.5, 2.0, 0.05, 1.0),
+    param("Pitch", 0.5, 2.0, 0.05, 1.0),
+    param("Rate", 0.5, 2.0, 0.05, 1.0),
+    param("8D rotation Hz", 0.0, 2.0, 0.05, 0.0),
+    param("Tremolo Hz", 0.5, 10.0, 0.5, 2.0),
+    param("Tremolo depth", 0.0, 1.0, 0.05, 0.0),
+    param("Vibrato Hz", 0.5, 14.0, 0.5, 2.0),
+    param("Vibrato depth", 0.0, 1.0, 0.05, 0.0),
+    param("Low-pass", 1.0, 100.0, 1.0, 1.0),
+    param("Karaoke", 0.0, 1.0, 0.05, 0.0),
+];
+
+/// The filter editor's working copy: one value per `FILTER_PARAMS` entry,
+/// on top of the filters it was loaded from so sections the editor has no
+/// sliders for (distortion, channel mix) survive a round trip.
+struct FilterDraft {
+    values: [f32; FILTER_PARAMS.len()],
+    base: AudioFilters,
+}
+
+impl FilterDraft {
+    fn from_filters(filters: &AudioFilters) -> Self {
+        let mut values = FILTER_PARAMS.map(|p| p.neutral);
+        for band in filters.equalizer.iter().flatten() {
+            if let Ok(b) = usize::try_from(band.band)
+                && b < EQ_BANDS
+            {
+                values[b] = band.gain;
+            }
+        }
+        let mut set = |i: usize, value: Option<f32>| {
+            if let Some(value) = value {
+                values[i] = value;
+            }
+        };
+        if let Some(t) = &filters.timescale {
+            set(SPEED, t.speed);
+            set(PITCH, t.pitch);
+            set(RATE, t.rate);
+        }
+        if let Some(r) = &filters.rotation {
+            set(ROTATION, r.rotation_hz);
+        }
+        if let Some(t) = &filters.tremolo {
+            set(TREMOLO_FREQ, t.frequency);
+            set(TREMOLO_DEPTH, t.depth);
+        }
+        if let Some(v) = &filters.vibrato {
+            set(VIBRATO_FREQ, v.frequency);
+            set(VIBRATO_DEPTH, v.depth);
+        }
+        if let Some(l) = &filters.low_pass {
+            set(LOW_PASS, l.smoothing);
+        }
+        if let Some(k) = &filters.karaoke {
+            set(KARAOKE, k.level);
+        }
+        Self { values, base: filters.clone() }
+    }
+
+    fn to_filters(&self) -> AudioFilters {
+        let v = &self.values;
+        let changed = |i: usize| !FILTER_PARAMS[i].is_neutral(v[i]);
+        let bands: Vec<EqualizerBand> = (0..EQ_BANDS)
+            .filter(|&i| changed(i))
+            .map(|i| EqualizerBand { band: i as i32, gain: v[i] })
+            .collect();
+        AudioFilters {
+            equalizer: Some(bands).filter(|b| !b.is_empty()),
+            timescale: (changed(SPEED) || changed(PITCH) || changed(RATE))
+                .then(|| TimescaleOptions { speed: Some(v[SPEED]), pitch: Some(v[PITCH]), rate: Some(v[RATE]) }),
+            rotation: changed(ROTATION).then(|| RotationOptions { rotation_hz: Some(v[ROTATION]) }),
+            tremolo: changed(TREMOLO_DEPTH)
+                .then(|| TremoloOptions { frequency: Some(v[TREMOLO_FREQ]), depth: Some(v[TREMOLO_DEPTH]) }),
+            vibrato: changed(VIBRATO_DEPTH)
+                .then(|| VibratoOptions { frequency: Some(v[VIBRATO_FREQ]), depth: Some(v[VIBRATO_DEPTH]) }),
+            low_pass: changed(LOW_PASS).then(|| LowPassOptions { smoothing: Some(v[LOW_PASS]) }),
+            karaoke: changed(KARAOKE).then(|| {
+                let keep = self.base.karaoke.clone();
+                KaraokeOptions {
+                    level: Some(v[KARAOKE]),
+                    mono_level: keep.as_ref().and_then(|k| k.mono_level).or(Some(v[KARAOKE])),
+                    filter_band: keep.as_ref().and_then(|k| k.filter_band).or(Some(220.0)),
+                    filter_width: keep.as_ref().and_then(|k| k.filter_width).or(Some(100.0)),
+                }
+            }),
+            ..self.base.clone()
+        }
+    }
+
+    /// Move parameter `i` by `steps` of its step size, snapped to the step grid.
+    fn adjust(&mut self, i: usize, steps: f32) {
+        let p = &FILTER_PARAMS[i];
+        let value = ((self.values[i] + p.step * steps) / p.step).round() * p.step;
+        self.values[i] = value.clamp(p.min, p.max);
+    }
+
+    fn reset(&mut self, i: usize) {
+        self.values[i] = FILTER_PARAMS[i].neutral;
+    }
+}
+
 fn get_filters_for_style(style: &str) -> AudioFilters {
     match style.to_lowercase().as_str() {
         "clear" => AudioFilters::default(),
@@ -1689,8 +4801,14 @@ fn get_filters_for_style(style: &str) -> AudioFilters {
 }
 
 fn ui(f: &mut Frame, app: &mut App) {
-    let theme = get_theme(&app.theme);
+    let theme = get_theme(&app.theme, &app.custom_themes);
     
+    if app.screensaver {
+        render_screensaver(f, app, &theme);
+        return;
+    }
+    app.seek_bar = Rect::default();
+
     // Base background color for the entire UI
     f.render_widget(Block::default().bg(theme.bg), f.area());
 
@@ -1701,34 +4819,34 @@ fn ui(f: &mut Frame, app: &mut App) {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .title(" 🚀 Update Available ")
+            .title(format!(" {} ", i18n::tr("🚀 Update Available")))
             .title_alignment(Alignment::Center)
             .border_style(Style::default().fg(Color::Green));
 
-        let version = app.update_info.as_ref().map(|(v, _)| v.as_str()).unwrap_or("Unknown");
+        let version = app.update_info.as_ref().map(|(v, _)| v.as_str()).unwrap_or(i18n::tr("Unknown"));
         
         let text = vec![
             Line::from(""),
             Line::from(vec![
-                Span::raw("A new version "),
+                Span::raw(i18n::tr("A new version ")),
                 Span::styled(version, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-                Span::raw(" is available!"),
+                Span::raw(i18n::tr(" is available!")),
             ]),
             Line::from(""),
-            Line::from("Do you want to update now?"),
+            Line::from(i18n::tr("Do you want to update now?")),
             Line::from(""),
             Line::from(vec![
-                Span::raw("Press "),
+                Span::raw(i18n::tr("Press ")),
                 Span::styled(" y ", Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD)),
-                Span::raw(" to Update and Exit"),
+                Span::raw(i18n::tr(" to Update and Exit")),
             ]),
             Line::from(vec![
-                Span::raw("Press "),
+                Span::raw(i18n::tr("Press ")),
                 Span::styled(" n ", Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD)),
-                Span::raw(" to Skip for now"),
+                Span::raw(i18n::tr(" to Skip for now")),
             ]),
             Line::from(""),
-            Line::from(Span::styled("The update will be installed automatically upon exit.", Style::default().fg(theme.text_secondary))),
+            Line::from(Span::styled(i18n::tr("The update will be installed automatically upon exit."), Style::default().fg(theme.text_secondary))),
         ];
 
         let p = Paragraph::new(text)
@@ -1755,11 +4873,7 @@ fn ui(f: &mut Frame, app: &mut App) {
             ])
             .split(area);
 
-        // Logo
-        let art_text: Vec<Line> = ASCII_LOGO.iter().map(|s| Line::from(Span::styled(*s, Style::default().fg(theme.primary)))).collect();
-        let art_paragraph = Paragraph::new(art_text)
-            .alignment(Alignment::Center);
-        f.render_widget(art_paragraph, chunks[1]);
+        render_logo(f, app, &theme, chunks[1]);
 
         // Text
         let login_block = Block::default()
@@ -1768,31 +4882,31 @@ fn ui(f: &mut Frame, app: &mut App) {
             .border_style(Style::default().fg(theme.border))
             .padding(ratatui::widgets::Padding::uniform(1));
 
-        let text = if app.is_loading || (app.auth_info_text.is_some() && app.auth_info_text.as_deref() != Some("Initializing login...")) {
-             let status = app.auth_info_text.clone().unwrap_or_else(|| "Authenticating...".to_string());
+        let text = if app.is_loading || (app.auth_info_text.is_some() && app.auth_info_text.as_deref() != Some(i18n::tr("Initializing login..."))) {
+             let status = app.auth_info_text.clone().unwrap_or_else(|| i18n::tr("Authenticating...").to_string());
              vec![
-                Line::from(Span::styled(" AUTHENTICATING ", Style::default().add_modifier(Modifier::BOLD).bg(Color::Yellow).fg(Color::Black))),
+                Line::from(Span::styled(i18n::tr(" AUTHENTICATING "), Style::default().add_modifier(Modifier::BOLD).bg(Color::Yellow).fg(Color::Black))),
                 Line::from(""),
                 Line::from(status),
                 Line::from(""),
-                Line::from(Span::styled("Please wait while we connect to Discord...", Style::default().fg(theme.text_secondary))),
+                Line::from(Span::styled(i18n::tr("Please wait while we connect to Discord..."), Style::default().fg(theme.text_secondary))),
              ]
         } else {
              vec![
-                Line::from(Span::styled(" LOGIN REQUIRED ", Style::default().add_modifier(Modifier::BOLD).bg(Color::Red).fg(Color::White))),
+                Line::from(Span::styled(i18n::tr(" LOGIN REQUIRED "), Style::default().add_modifier(Modifier::BOLD).bg(Color::Red).fg(Color::White))),
                 Line::from(""),
-                Line::from("To use Jorik CLI, you must log in with your Discord account."),
-                Line::from("This allows us to access your voice channels and manage playback."),
+                Line::from(i18n::tr("To use Jorik CLI, you must log in with your Discord account.")),
+                Line::from(i18n::tr("This allows us to access your voice channels and manage playback.")),
                 Line::from(""),
                 Line::from(vec![
-                    Span::raw("Press "),
+                    Span::raw(i18n::tr("Press ")),
                     Span::styled(" ENTER ", Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD)),
-                    Span::raw(" to Login"),
+                    Span::raw(i18n::tr(" to Login")),
                 ]),
                 Line::from(vec![
-                    Span::raw("Press "),
+                    Span::raw(i18n::tr("Press ")),
                     Span::styled(" \\ ", Style::default().bg(theme.highlight).fg(Color::Black).add_modifier(Modifier::BOLD)),
-                    Span::raw(" to Change Host"),
+                    Span::raw(i18n::tr(" to Change Host")),
                 ]),
             ]
         };
@@ -1808,12 +4922,15 @@ fn ui(f: &mut Frame, app: &mut App) {
         return;
     }
 
+    let compact = is_compact(app, f.area());
+    // Compact mode drops the separator lines around the tabs and status bar.
+    let bar_height = if compact { 1 } else { 3 };
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),
+            Constraint::Length(if compact { 1 } else { 3 }),
             Constraint::Min(0),
-            Constraint::Length(3),
+            Constraint::Length(bar_height),
         ])
         .split(f.area());
 
@@ -1831,8 +4948,11 @@ fn ui(f: &mut Frame, app: &mut App) {
         _ => 0,
     };
 
+    let tab_titles = if compact { vec![" 1 ", " 2 ", " 3 ", " 4 "] } else { tab_titles };
+    // Title widths, their one-cell padding and the " | " dividers.
+    let titles_width = tab_titles.iter().map(|t| t.chars().count() as u16 + 2).sum::<u16>() + 3 * (tab_titles.len() as u16 - 1);
     let tabs = Tabs::new(tab_titles)
-        .block(Block::default().borders(Borders::BOTTOM).border_style(Style::default().fg(theme.border)))
+        .block(Block::default().borders(if compact { Borders::NONE } else { Borders::BOTTOM }).border_style(Style::default().fg(theme.border)))
         .select(selected_tab)
         .style(Style::default().fg(theme.text_secondary))
         .highlight_style(Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD))
@@ -1840,16 +4960,55 @@ fn ui(f: &mut Frame, app: &mut App) {
 
     f.render_widget(tabs, tabs_area);
 
+    let mut guild_width = 0;
+    if app.tabs.len() > 1 {
+        let guild_titles: Vec<Line> = (0..app.tabs.len())
+            .map(|i| {
+                let gid = app.tab_guild(i).unwrap_or("?");
+                let marker = if app.tab_playing(i) { "♪" } else { " " };
+                Line::from(format!(" {}:{}{} ", i + 1, guild_label(gid), marker))
+            })
+            .collect();
+        let width = guild_titles.iter().map(|l| l.width() as u16 + 3).sum::<u16>();
+        guild_width = width;
+        let guild_area = Rect {
+            x: tabs_area.right().saturating_sub(width),
+            width: width.min(tabs_area.width),
+            height: 1,
+            ..tabs_area
+        };
+        let guild_tabs = Tabs::new(guild_titles)
+            .select(app.active_tab)
+            .style(Style::default().fg(theme.text_secondary))
+            .highlight_style(Style::default().fg(theme.bg).bg(theme.primary).add_modifier(Modifier::BOLD))
+            .divider(Span::styled("|", Style::default().fg(theme.border)));
+        f.render_widget(guild_tabs, guild_area);
+    }
+
+    let indicators = player_indicators(app, &theme);
+    let indicators_width = indicators.width() as u16;
+    let free = tabs_area.width.saturating_sub(titles_width + guild_width);
+    if indicators_width + 2 <= free {
+        let indicators_area = Rect {
+            x: tabs_area.right() - guild_width - indicators_width - 1,
+            width: indicators_width,
+            height: 1,
+            ..tabs_area
+        };
+        f.render_widget(indicators, indicators_area);
+    }
+
     match app.view {
+        View::Lyrics if !app.lyrics_lines.is_empty() => render_synced_lyrics(f, app, &theme, top_section),
         View::Lyrics => {
             let block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .title(format!(" Lyrics {} ", if app.is_loading { " ⏳ " } else { "" }))
+                .title(format!(" {} {} ", i18n::tr("Lyrics"), if app.is_loading { " ⏳ " } else { "" }))
                 .title_alignment(Alignment::Center)
                 .border_style(Style::default().fg(theme.primary));
             
-            let text = app.lyrics_text.as_deref().unwrap_or("Loading...");
+            let text = app.lyrics_text.as_deref().unwrap_or(i18n::tr("Loading..."));
             let p = Paragraph::new(text)
                 .block(block)
                 .wrap(Wrap { trim: false })
@@ -1861,7 +5020,7 @@ fn ui(f: &mut Frame, app: &mut App) {
             let block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
-                .title(" Settings ")
+                .title(format!(" {} ", i18n::tr("Settings")))
                 .title_alignment(Alignment::Center)
                 .border_style(Style::default().fg(theme.primary));
             
@@ -1878,36 +5037,98 @@ fn ui(f: &mut Frame, app: &mut App) {
                 else { format!(" >> {}", l) }
             } else { format!("    {}", l) };
 
-            let p = Paragraph::new(vec![
-                Line::from("Configure your experience:"),
-                Line::from(""),
-                Line::from(vec![
-                    Span::styled(h_l(SettingsField::Host, "Webhook Host: "), h_s(SettingsField::Host)),
-                    Span::styled(&app.settings_input, h_s(SettingsField::Host)),
-                ]),
-                Line::from(vec![
-                    Span::styled(h_l(SettingsField::Offset, "Visualizer Offset (ms): "), h_s(SettingsField::Offset)),
+            let rows: Vec<(SettingsField, &str, Span)> = vec![
+                (
+                    SettingsField::Host,
+                    i18n::tr("Webhook Host: "),
+                    Span::styled(&app.settings_input, h_s(SettingsField::Host)),
+                ),
+                (
+                    SettingsField::Offset,
+                    i18n::tr("Visualizer Offset (ms): "),
                     Span::styled(&app.offset_input, h_s(SettingsField::Offset)),
-                ]),
-                Line::from(vec![
-                    Span::styled(h_l(SettingsField::Theme, "Color Theme: "), h_s(SettingsField::Theme)),
+                ),
+                (
+                    SettingsField::Theme,
+                    i18n::tr("Color Theme: "),
                     Span::styled(format!("< {} >", app.theme), h_s(SettingsField::Theme)),
-                ]),
-                Line::from(vec![
-                    Span::styled(h_l(SettingsField::VizStyle, "Visualizer Style: "), h_s(SettingsField::VizStyle)),
+                ),
+                (
+                    SettingsField::VizStyle,
+                    i18n::tr("Visualizer Style: "),
                     Span::styled(format!("< {} >", app.viz_style), h_s(SettingsField::VizStyle)),
-                ]),
-                Line::from(vec![
-                    Span::styled(h_l(SettingsField::Layout, "UI Layout: "), h_s(SettingsField::Layout)),
+                ),
+                (
+                    SettingsField::Layout,
+                    i18n::tr("UI Layout: "),
                     Span::styled(format!("< {} >", app.layout), h_s(SettingsField::Layout)),
-                ]),
-                Line::from(""),
-                Line::from(if is_ed {
-                    Span::styled("TYPE TO EDIT, ENTER TO FINISH", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-                } else {
-                    Span::styled("NAVIGATE WITH ARROWS/TAB, ENTER ON TEXT TO EDIT, ESC TO EXIT", Style::default().fg(theme.text_secondary))
-                }),
-            ])
+                ),
+                (
+                    SettingsField::GuildId,
+                    i18n::tr("Default Guild ID: "),
+                    Span::styled(&app.guild_input, h_s(SettingsField::GuildId)),
+                ),
+                (
+                    SettingsField::UserId,
+                    i18n::tr("Default User ID: "),
+                    Span::styled(&app.user_input, h_s(SettingsField::UserId)),
+                ),
+                (
+                    SettingsField::ChannelId,
+                    i18n::tr("Default Channel ID: "),
+                    Span::styled(&app.channel_input, h_s(SettingsField::ChannelId)),
+                ),
+                (
+                    SettingsField::Confirm,
+                    i18n::tr("Confirm Stop/Clear: "),
+                    Span::styled(format!("< {} >", if app.confirm_destructive { i18n::tr("On") } else { i18n::tr("Off") }), h_s(SettingsField::Confirm)),
+                ),
+                (
+                    SettingsField::Compact,
+                    i18n::tr("Compact Mode: "),
+                    Span::styled(format!("< {} >", i18n::tr(compact_mode_label(&app.compact_mode))), h_s(SettingsField::Compact)),
+                ),
+                (
+                    SettingsField::Notify,
+                    i18n::tr("Track Notifications: "),
+                    Span::styled(format!("< {} >", if app.notify_track_start { i18n::tr("On") } else { i18n::tr("Off") }), h_s(SettingsField::Notify)),
+                ),
+                (
+                    SettingsField::FrameRate,
+                    i18n::tr("Frame Rate: "),
+                    Span::styled(format!("< {} >", t!("{} fps", app.frame_rate)), h_s(SettingsField::FrameRate)),
+                ),
+                (
+                    SettingsField::Accessible,
+                    i18n::tr("Accessibility Mode: "),
+                    Span::styled(format!("< {} >", if app.accessible { i18n::tr("On") } else { i18n::tr("Off") }), h_s(SettingsField::Accessible)),
+                ),
+                (
+                    SettingsField::ExportDir,
+                    i18n::tr("Export Folder: "),
+                    if app.export_dir_input.is_empty() && !(is_ed && f_field == SettingsField::ExportDir) {
+                        Span::styled(i18n::tr("(Desktop)"), Style::default().fg(theme.text_secondary))
+                    } else {
+                        Span::styled(&app.export_dir_input, h_s(SettingsField::ExportDir))
+                    },
+                ),
+            ];
+            // The line each row is drawn on: below the border and the two
+            // lines above the rows.
+            let row_y = |field| {
+                rows.iter().position(|(f, _, _)| *f == field).map(|i| top_section.y + 3 + i as u16)
+            };
+            let mut lines = vec![Line::from(i18n::tr("Configure your experience:")), Line::from("")];
+            lines.extend(rows.iter().map(|(field, label, value)| {
+                Line::from(vec![Span::styled(h_l(*field, *label), h_s(*field)), value.clone()])
+            }));
+            lines.push(Line::from(""));
+            lines.push(Line::from(if is_ed {
+                Span::styled(i18n::tr("TYPE TO EDIT, ENTER TO FINISH"), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            } else {
+                Span::styled(i18n::tr("NAVIGATE WITH ARROWS/TAB, ENTER ON TEXT TO EDIT, ESC TO EXIT"), Style::default().fg(theme.text_secondary))
+            }));
+            let p = Paragraph::new(lines)
             .block(block)
             .alignment(Alignment::Left)
             .wrap(Wrap { trim: true });
@@ -1916,19 +5137,26 @@ fn ui(f: &mut Frame, app: &mut App) {
 
             // Show cursor when editing settings
             if is_ed {
-                let cursor_y = match f_field {
-                    SettingsField::Host => top_section.y + 3,
-                    SettingsField::Offset => top_section.y + 4,
-                    _ => 0,
-                };
-                let prefix_len = match f_field {
-                    SettingsField::Host => 27, // " >> [EDITING] Webhook Host: "
-                    SettingsField::Offset => 37, // " >> [EDITING] Visualizer Offset (ms): "
-                    _ => 0,
-                };
+                let text_field = matches!(
+                    f_field,
+                    SettingsField::Host
+                        | SettingsField::Offset
+                        | SettingsField::GuildId
+                        | SettingsField::UserId
+                        | SettingsField::ChannelId
+                        | SettingsField::ExportDir
+                );
+                let cursor_y = row_y(f_field).filter(|_| text_field).unwrap_or(0);
+                let label = rows.iter().find(|(f, _, _)| *f == f_field).map_or("", |(_, label, _)| *label);
+                // " >> [EDITING] " minus the border column, plus the (possibly translated) label
+                let prefix_len = 13 + label.chars().count() as u16;
                 let input_len = match f_field {
                     SettingsField::Host => app.settings_input.len(),
                     SettingsField::Offset => app.offset_input.len(),
+                    SettingsField::GuildId => app.guild_input.len(),
+                    SettingsField::UserId => app.user_input.len(),
+                    SettingsField::ChannelId => app.channel_input.len(),
+                    SettingsField::ExportDir => app.export_dir_input.chars().count(),
                     _ => 0,
                 };
                 if cursor_y > 0 {
@@ -1941,27 +5169,42 @@ fn ui(f: &mut Frame, app: &mut App) {
         }
         View::Debug => {
             let ws_status = if app.ws_connected {
-                Span::styled(" CONNECTED ", Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD))
+                Span::styled(i18n::tr(" CONNECTED "), Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD))
             } else if app.ws_connecting {
-                Span::styled(" CONNECTING... ", Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD))
+                Span::styled(i18n::tr(" CONNECTING... "), Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD))
             } else {
-                Span::styled(" DISCONNECTED ", Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD))
+                Span::styled(i18n::tr(" DISCONNECTED "), Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD))
             };
 
             let block = Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Thick)
                 .title(vec![
-                    Span::raw(" Debug Console "), 
+                    Span::raw(i18n::tr(" Debug Console ")), 
                     ws_status,
-                    Span::raw(" (Press 's' to Save Spectrogram) ")
+                    Span::raw(i18n::tr(" (Save Spectrogram: 's' JSON, 'p' PNG, 'c' CSV) "))
                 ])
                 .title_alignment(Alignment::Left)
                 .border_style(Style::default().fg(Color::Yellow));
-            
-            let log_lines: Vec<Line> = app.debug_logs.iter()
+
+            let logs = app.filtered_debug_logs();
+            let filter = if app.debug_filter_editing {
+                format!(" /{}▏ ", app.debug_filter)
+            } else if app.debug_filter.is_empty() {
+                format!(" {} ", i18n::tr("/ filter · ↑↓ scroll · e export"))
+            } else {
+                format!(" /{} ", app.debug_filter)
+            };
+            let block = block.title_bottom(Line::from(vec![
+                Span::styled(filter, Style::default().fg(theme.highlight)),
+                Span::styled(format!("{} ", t!("{} of {}", logs.len(), app.debug_logs.len())), Style::default().fg(theme.text_secondary)),
+            ]));
+
+            // Newest first, skipping what has been scrolled past.
+            let log_lines: Vec<Line> = logs.iter()
                 .rev()
-                .map(|l| Line::from(l.as_str()))
+                .skip(app.debug_scroll)
+                .map(|l| Line::from(*l))
                 .collect();
             
             let p = Paragraph::new(log_lines)
@@ -1975,9 +5218,40 @@ fn ui(f: &mut Frame, app: &mut App) {
         }
     }
 
-    if app.input_mode == InputMode::Normal && app.view == View::Main {
+    let status_block = Block::default()
+        .borders(if compact { Borders::NONE } else { Borders::TOP })
+        .border_style(Style::default().fg(theme.border));
+    let status_inner = status_block.inner(status_bar_area);
+    f.render_widget(status_block.style(Style::default().bg(theme.status_bg)), status_bar_area);
+    let status = status_segment(app, &theme);
+    let status_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(status.width() as u16)])
+        .split(status_inner);
+    f.render_widget(Paragraph::new(status), status_chunks[1]);
+
+    if app.command_editing {
+        let area = status_chunks[0];
+        // Keep the cursor in view on long lines, like the search popup.
+        let width = area.width.saturating_sub(2) as usize;
+        let scroll = (app.command.cursor_width() + 1).saturating_sub(width);
+        let line = Line::from(vec![
+            Span::styled(":", Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+            Span::styled(app.command.text.as_str(), Style::default().fg(Color::White)),
+        ]);
+        f.render_widget(Paragraph::new(line).scroll((0, scroll as u16)), area);
+        f.set_cursor_position((area.x + 1 + app.command.cursor_width().saturating_sub(scroll) as u16, area.y));
+    } else if app.input_mode == InputMode::Normal && app.view == View::Main && !compact {
         let keys = vec![
-            ("ENTER", "SEARCH"),
+            ("ENTER", if app.scrub_ms.is_some() { "SEEK" } else if app.queue_state.selected().is_some() { "PLAY NOW" } else { "SEARCH" }),
+            ("↑↓", "SELECT"),
+            ("/", "FIND"),
+            (":", "COMMAND"),
+            ("D", "REMOVE"),
+            ("J/K", "MOVE"),
+            ("←→", "SEEK"),
+            ("+/-", "VOLUME"),
+            ("V", "VISUALIZER"),
             ("TAB", "MENU"),
             ("S", "SKIP"),
             ("W", "STOP"),
@@ -1989,26 +5263,25 @@ fn ui(f: &mut Frame, app: &mut App) {
         
         let mut spans = Vec::new();
         spans.push(Span::styled(" >> ", Style::default().fg(theme.primary)));
-        spans.push(Span::styled("COMMANDS ", Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
+        spans.push(Span::styled(i18n::tr("COMMANDS "), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)));
         
         for (key, desc) in keys {
             spans.push(Span::styled(format!(" {} ", key), Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)));
-            spans.push(Span::styled(format!("{} ", desc), Style::default().fg(theme.text_secondary)));
+            spans.push(Span::styled(format!("{} ", i18n::tr(desc)), Style::default().fg(theme.text_secondary)));
             spans.push(Span::styled("|", Style::default().fg(theme.border)));
         }
 
         let version = env!("CARGO_PKG_VERSION");
         if version.chars().any(|c| c.is_ascii_lowercase()) {
             spans.push(Span::raw("   "));
-            spans.push(Span::styled(" ! DEV UNSTABLE BUILD ! ", Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD)));
+            spans.push(Span::styled(i18n::tr(" ! DEV UNSTABLE BUILD ! "), Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD)));
         }
 
         let p = Paragraph::new(Line::from(spans))
-            .style(Style::default().bg(theme.bg))
-            .alignment(Alignment::Left)
-            .block(Block::default().borders(Borders::TOP).border_style(Style::default().fg(theme.border)));
+            .style(Style::default().bg(theme.status_bg))
+            .alignment(Alignment::Left);
             
-        f.render_widget(p, status_bar_area);
+        f.render_widget(p, status_chunks[0]);
     }
 
     if app.input_mode == InputMode::Editing {
@@ -2019,23 +5292,39 @@ fn ui(f: &mut Frame, app: &mut App) {
         let input_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .title(format!(" Play / Search {} ", loading_text))
+            .title(format!(" {} {} ", i18n::tr("Play / Search"), loading_text))
             .title_alignment(Alignment::Center)
             .border_style(Style::default().fg(theme.highlight));
         
+        // Scroll sideways so the cursor stays inside the box on long URLs.
+        let width = area.width.saturating_sub(2).max(1) as usize;
+        let cursor = app.input.cursor_width();
+        let scroll = (cursor + 1).saturating_sub(width);
         let p = Paragraph::new(app.input.as_str())
             .block(input_block)
             .style(Style::default().fg(Color::White))
-            .wrap(Wrap { trim: true });
+            .scroll((0, scroll as u16));
         f.render_widget(p, area);
 
         // Show cursor in Search popup
         f.set_cursor_position((
-            area.x + 1 + app.input.len() as u16,
+            area.x + 1 + (cursor - scroll) as u16,
             area.y + 1,
         ));
     }
 
+    if let Some(action) = app.pending_action {
+        render_confirm(f, &theme, action);
+    }
+
+    if let Some(toast) = &app.toast {
+        if toast.shown_at.elapsed() < toast.duration() {
+            render_toast(f, &theme, toast, status_bar_area);
+        } else {
+            app.toast = None;
+        }
+    }
+
     if app.view == View::Menu {
         let area = centered_rect(40, 50, f.area());
         
@@ -2051,13 +5340,13 @@ fn ui(f: &mut Frame, app: &mut App) {
         let menu_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .title(format!(" Menu {} ", loading_text))
+            .title(format!(" {} {} ", i18n::tr("Menu"), loading_text))
             .title_alignment(Alignment::Center)
             .border_style(Style::default().fg(theme.primary));
         
         let items: Vec<ListItem> = app.menu_items
             .iter()
-            .map(|i| ListItem::new(format!("  {}  ", *i)))
+            .map(|i| ListItem::new(format!("  {}  ", i18n::tr((*i).trim()))))
             .collect();
             
         let list = List::new(items)
@@ -2068,9 +5357,9 @@ fn ui(f: &mut Frame, app: &mut App) {
         f.render_stateful_widget(list, area, &mut app.menu_state);
     }
 
-    if app.view == View::FilterMenu {
-        let area = centered_rect(40, 50, f.area());
-        
+    if app.view == View::History {
+        let area = centered_rect(60, 60, f.area());
+
         // Shadow
         let shadow_area = Rect { x: area.x + 1, y: area.y + 1, width: area.width, height: area.height };
         if shadow_area.right() < f.area().right() && shadow_area.bottom() < f.area().bottom() {
@@ -2078,26 +5367,130 @@ fn ui(f: &mut Frame, app: &mut App) {
         }
 
         f.render_widget(Clear, area);
-        
+
         let loading_text = if app.is_loading { " ⏳ " } else { "" };
-        let menu_block = Block::default()
+        let history_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .title(format!(" Select Filter {} ", loading_text))
+            .title(format!(" {} {} ", i18n::tr("Recently Played"), loading_text))
             .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(format!(" {} ", i18n::tr("ENTER play again · ESC close"))).centered())
             .border_style(Style::default().fg(theme.primary));
-        
-        let items: Vec<ListItem> = app.filter_items
-            .iter()
-            .map(|i| ListItem::new(format!("  {}  ", *i)))
-            .collect();
-            
-        let list = List::new(items)
-            .block(menu_block)
-            .highlight_style(Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD))
-            .highlight_symbol(" >> ");
-            
-        f.render_stateful_widget(list, area, &mut app.filter_state);
+
+        if app.history.is_empty() {
+            let p = Paragraph::new(i18n::tr("Nothing has been played yet"))
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.text_secondary))
+                .block(history_block);
+            f.render_widget(p, area);
+        } else {
+            let items: Vec<ListItem> = app.history
+                .iter()
+                .enumerate()
+                .map(|(i, h)| ListItem::new(format!(" {:>2}. {} ", i + 1, h.label)))
+                .collect();
+
+            let list = List::new(items)
+                .block(history_block)
+                .highlight_style(Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD))
+                .highlight_symbol(" >> ");
+
+            f.render_stateful_widget(list, area, &mut app.history_state);
+        }
+    }
+
+    if app.view == View::Playlists {
+        let area = centered_rect(70, 60, f.area());
+
+        // Shadow
+        let shadow_area = Rect { x: area.x + 1, y: area.y + 1, width: area.width, height: area.height };
+        if shadow_area.right() < f.area().right() && shadow_area.bottom() < f.area().bottom() {
+            f.render_widget(Block::default().bg(Color::Rgb(10, 10, 20)), shadow_area);
+        }
+
+        f.render_widget(Clear, area);
+        render_playlists(f, app, &theme, area);
+    }
+
+    if app.view == View::Profiles {
+        let area = centered_rect(60, 50, f.area());
+
+        // Shadow
+        let shadow_area = Rect { x: area.x + 1, y: area.y + 1, width: area.width, height: area.height };
+        if shadow_area.right() < f.area().right() && shadow_area.bottom() < f.area().bottom() {
+            f.render_widget(Block::default().bg(Color::Rgb(10, 10, 20)), shadow_area);
+        }
+
+        f.render_widget(Clear, area);
+
+        let profiles_block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(format!(" {} ", i18n::tr("Profiles")))
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(format!(" {} ", i18n::tr("ENTER switch · ESC close"))).centered())
+            .border_style(Style::default().fg(theme.primary));
+
+        if app.profiles.is_empty() {
+            let p = Paragraph::new(i18n::tr("No profiles saved. Run `jorik auth login --profile <name>` to create one."))
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.text_secondary))
+                .wrap(Wrap { trim: true })
+                .block(profiles_block);
+            f.render_widget(p, area);
+        } else {
+            let settings_host = i18n::tr("(settings host)");
+            let items: Vec<ListItem> = app.profiles
+                .iter()
+                .map(|(name, auth)| {
+                    let marker = if *name == app.active_profile { "*" } else { " " };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!(" {} {} ", marker, name), Style::default().add_modifier(Modifier::BOLD)),
+                        Span::styled(
+                            format!(
+                                "— {} @ {} ",
+                                auth.username.as_deref().unwrap_or(i18n::tr("Unknown")),
+                                auth.base_url.as_deref().unwrap_or(settings_host)
+                            ),
+                            Style::default().fg(theme.text_secondary),
+                        ),
+                    ]))
+                })
+                .collect();
+
+            let list = List::new(items)
+                .block(profiles_block)
+                .highlight_style(Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD))
+                .highlight_symbol(" >> ");
+
+            f.render_stateful_widget(list, area, &mut app.profile_state);
+        }
+    }
+
+    if app.view == View::GuildPicker {
+        let area = centered_rect(70, 60, f.area());
+
+        // Shadow
+        let shadow_area = Rect { x: area.x + 1, y: area.y + 1, width: area.width, height: area.height };
+        if shadow_area.right() < f.area().right() && shadow_area.bottom() < f.area().bottom() {
+            f.render_widget(Block::default().bg(Color::Rgb(10, 10, 20)), shadow_area);
+        }
+
+        f.render_widget(Clear, area);
+        render_guild_picker(f, app, &theme, area);
+    }
+
+    if app.view == View::FilterMenu {
+        let area = centered_rect(80, 70, f.area());
+
+        // Shadow
+        let shadow_area = Rect { x: area.x + 1, y: area.y + 1, width: area.width, height: area.height };
+        if shadow_area.right() < f.area().right() && shadow_area.bottom() < f.area().bottom() {
+            f.render_widget(Block::default().bg(Color::Rgb(10, 10, 20)), shadow_area);
+        }
+
+        f.render_widget(Clear, area);
+        render_filter_editor(f, app, &theme, area);
     }
 
     if app.view == View::AuthMenu {
@@ -2115,13 +5508,13 @@ fn ui(f: &mut Frame, app: &mut App) {
         let menu_block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .title(format!(" Auth {} ", loading_text))
+            .title(format!(" {} {} ", i18n::tr("Auth"), loading_text))
             .title_alignment(Alignment::Center)
             .border_style(Style::default().fg(theme.primary));
         
         let items: Vec<ListItem> = app.auth_menu_items
             .iter()
-            .map(|i| ListItem::new(format!("  {}  ", *i)))
+            .map(|i| ListItem::new(format!("  {}  ", i18n::tr((*i).trim()))))
             .collect();
             
         let list = List::new(items)
@@ -2139,185 +5532,990 @@ fn ui(f: &mut Frame, app: &mut App) {
         let block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .title(" Auth Info ")
+            .title(format!(" {} ", i18n::tr("Auth Info")))
             .title_alignment(Alignment::Center)
             .border_style(Style::default().fg(theme.primary));
         
-        let text = app.auth_info_text.as_deref().unwrap_or("No data.");
+        let text = app.auth_info_text.as_deref().unwrap_or(i18n::tr("No data."));
+        let p = Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: true });
+            
+        f.render_widget(p, area);
+    }
+
+    if app.view == View::TrackDetails {
+        render_track_details(f, app, &theme);
+    }
+
+    if app.view == View::AppInfo {
+        let area = centered_rect(60, 40, f.area());
+        f.render_widget(Clear, area);
+        
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(format!(" {} ", i18n::tr("Build Compatibility Info")))
+            .title_alignment(Alignment::Center)
+            .border_style(Style::default().fg(theme.highlight));
+        
+        let text = vec![
+            Line::from(Span::styled(i18n::tr("BUILD COMPATIBILITY"), Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow))),
+            Line::from(""),
+            Line::from(i18n::tr("This version of Jorik CLI is intended for use with")),
+            Line::from(vec![
+                Span::raw(i18n::tr("the ")),
+                Span::styled(i18n::tr("INTERNAL DEV VERSION"), Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+                Span::raw(i18n::tr(" of Jorik bot.")),
+            ]),
+            Line::from(""),
+            Line::from(i18n::tr("The production version will work, but with significantly")),
+            Line::from(i18n::tr("reduced functionality (limited real-time features).")),
+            Line::from(""),
+            Line::from(vec![
+                Span::raw(i18n::tr("Current Version: ")),
+                Span::styled(env!("CARGO_PKG_VERSION"), Style::default().fg(Color::White)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(i18n::tr("Press 'i' or Esc to close"), Style::default().fg(theme.text_secondary))),
+        ];
+
         let p = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .block(block)
+            .wrap(Wrap { trim: true });
+            
+        f.render_widget(p, area);
+    }
+
+    if let Some(msg) = &app.fatal_error {
+        let area = centered_rect(60, 25, f.area());
+        f.render_widget(Clear, area);
+        
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(format!(" {} ", i18n::tr("⚠ Connection Error")))
+            .title_alignment(Alignment::Center)
+            .style(Style::default())
+            .border_style(Style::default().fg(Color::Red));
+        
+        let p = Paragraph::new(msg.as_str())
             .block(block)
+            .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
             .wrap(Wrap { trim: true });
             
         f.render_widget(p, area);
     }
+}
+
+/// Karaoke-style lyrics: the line at `elapsed_ms` is highlighted and, while
+/// following, kept in the middle of the panel.
+fn render_synced_lyrics(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let current = app
+        .lyrics_lines
+        .partition_point(|(ts, _)| *ts <= app.elapsed_ms)
+        .checked_sub(1);
+
+    let title = if app.lyrics_follow {
+        format!(" {} ", i18n::tr("Lyrics"))
+    } else {
+        format!(" {} {} ", i18n::tr("Lyrics"), i18n::tr("[F] follow"))
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(title)
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme.primary));
+
+    if app.lyrics_follow {
+        let half = area.height.saturating_sub(2) / 2;
+        app.lyrics_scroll = (current.unwrap_or(0) as u16).saturating_sub(half);
+    }
+
+    let lines: Vec<Line> = app
+        .lyrics_lines
+        .iter()
+        .enumerate()
+        .map(|(i, (_, text))| {
+            let style = match current {
+                Some(c) if i == c => Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+                Some(c) if i < c => Style::default().fg(theme.text_secondary),
+                _ => Style::default().fg(Color::White),
+            };
+            Line::from(Span::styled(text.as_str(), style))
+        })
+        .collect();
+
+    let p = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .scroll((app.lyrics_scroll, 0));
+    f.render_widget(p, area);
+}
+
+/// ASCII stand-in for a glyph the TUI or its widgets draw, for accessibility
+/// mode. Text in any script is left alone; only drawing symbols are listed.
+fn ascii_symbol(symbol: &str) -> Option<&'static str> {
+    Some(match symbol {
+        "─" | "━" | "═" => "-",
+        "│" | "┃" | "║" | "▏" => "|",
+        "┌" | "┐" | "└" | "┘" | "┏" | "┓" | "┗" | "┛" | "╭" | "╮" | "╰" | "╯" | "├" | "┤" | "┬" | "┴" | "┼" | "┣" | "┫" | "┳" | "┻" | "╋" => "+",
+        "█" | "▉" | "▊" | "▋" | "▌" | "▍" | "▎" | "▐" | "▇" | "▆" | "▅" | "▄" | "▃" | "▂" | "▁" | "▀" => "#",
+        "░" | "▒" | "▓" => ".",
+        "·" | "•" | "—" | "–" => "-",
+        "●" | "★" | "♪" | "✓" | "🔊" => "*",
+        "▶" | "→" | "›" => ">",
+        "←" | "‹" => "<",
+        "↑" => "^",
+        "↓" => "v",
+        "»" => ">>",
+        "…" => "...",
+        "⏸" => "||",
+        "⏳" => "..",
+        "⚠" | "🚀" => "!",
+        _ => return None,
+    })
+}
+
+/// Swap every glyph `ascii_symbol` knows for plain ASCII after a frame is
+/// drawn, so borders, gauges and icons come out readable everywhere. A wide
+/// glyph's replacement may spill into the cells it covered.
+fn asciify(buf: &mut Buffer) {
+    let area = buf.area;
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x < area.right() {
+            let (ascii, width) = {
+                let symbol = buf[(x, y)].symbol();
+                (ascii_symbol(symbol), Span::raw(symbol).width().max(1) as u16)
+            };
+            if let Some(ascii) = ascii {
+                for (i, c) in ascii.chars().take(width as usize).enumerate() {
+                    if let Some(cell) = buf.cell_mut((x + i as u16, y)) {
+                        cell.set_char(c);
+                    }
+                }
+            }
+            x += width;
+        }
+    }
+}
+
+/// Guild IDs are long snowflakes; the last digits are enough to tell tabs apart.
+fn guild_label(guild_id: &str) -> &str {
+    let skip = guild_id.chars().count().saturating_sub(6);
+    guild_id.char_indices().nth(skip).map_or(guild_id, |(i, _)| &guild_id[i..])
+}
+
+/// `user │ guild │ WS state │ refresh time`, for the right end of the status bar.
+fn status_segment(app: &App, theme: &Theme) -> Line<'static> {
+    let dim = Style::default().fg(theme.text_secondary);
+    let sep = || Span::styled(" │ ", Style::default().fg(theme.border));
+    let user = app.username.clone().unwrap_or_else(|| i18n::tr("not logged in").to_string());
+    let guild = match (&app.guild_name, &app.guild_id) {
+        (Some(name), _) => name.clone(),
+        (None, Some(id)) => id.clone(),
+        (None, None) => i18n::tr("no guild").to_string(),
+    };
+    let (ws, ws_color) = if app.ws_connected {
+        (i18n::tr("connected"), Color::Green)
+    } else if app.ws_connecting {
+        (i18n::tr("connecting"), Color::Yellow)
+    } else {
+        (i18n::tr("down"), Color::Red)
+    };
+    let refreshed = app.refreshed_at.map_or_else(|| "--:--:--".to_string(), |t| t.format("%H:%M:%S").to_string());
+    Line::from(vec![
+        Span::styled(format!(" {}", user), Style::default().fg(theme.highlight)),
+        sep(),
+        Span::styled(guild, dim),
+        sep(),
+        Span::styled("● ", Style::default().fg(ws_color)),
+        Span::styled(format!("WS {}", ws), dim),
+        sep(),
+        Span::styled(if app.accessible { format!("{} ", t!("updated {}", refreshed)) } else { format!("⟳ {} ", refreshed) }, dim),
+    ])
+}
+
+/// Pause, loop, shuffle and 24/7 state for the header. Modes that are on are
+/// highlighted; shuffle and 24/7 are left out until the server reports them.
+fn player_indicators(app: &App, theme: &Theme) -> Line<'static> {
+    let on = Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD);
+    let off = Style::default().fg(theme.text_secondary);
+    let mut spans = Vec::new();
+    let looping = app.loop_mode != "off";
+    if app.accessible {
+        // Spelled out, since colour alone tells on from off here.
+        let state = |on_off: bool| if on_off { i18n::tr("ON") } else { i18n::tr("OFF") };
+        if app.current_track.is_some() {
+            spans.push(Span::styled(if app.paused { i18n::tr("PAUSED") } else { i18n::tr("PLAYING") }, on));
+        }
+        spans.push(Span::styled(t!("LOOP {}", app.loop_mode.to_uppercase()), if looping { on } else { off }));
+        if let Some(shuffle) = app.shuffle {
+            spans.push(Span::styled(t!("SHUFFLE {}", state(shuffle)), if shuffle { on } else { off }));
+        }
+        if let Some(always_on) = app.always_on {
+            spans.push(Span::styled(t!("24/7 {}", state(always_on)), if always_on { on } else { off }));
+        }
+    } else {
+        if app.current_track.is_some() {
+            spans.push(if app.paused { Span::styled("⏸", off) } else { Span::styled("▶", on) });
+        }
+        spans.push(Span::styled(format!("↻ {}", app.loop_mode.to_uppercase()), if looping { on } else { off }));
+        if let Some(shuffle) = app.shuffle {
+            spans.push(Span::styled("⤮", if shuffle { on } else { off }));
+        }
+        if let Some(always_on) = app.always_on {
+            spans.push(Span::styled("24/7", if always_on { on } else { off }));
+        }
+    }
+    let mut line = Vec::new();
+    for (i, span) in spans.into_iter().enumerate() {
+        if i > 0 {
+            line.push(Span::styled(" · ", Style::default().fg(theme.border)));
+        }
+        line.push(span);
+    }
+    Line::from(line)
+}
+
+/// Badge for the player title while the WebSocket is not connected, so a
+/// drop shows up without opening the Debug console.
+fn ws_badge(app: &App) -> Option<Span<'static>> {
+    if app.ws_connected {
+        None
+    } else if app.ws_connecting || app.needs_reconnect {
+        Some(Span::styled(
+            format!(" {} ", i18n::tr("WS CONNECTING...")),
+            Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD),
+        ))
+    } else {
+        Some(Span::styled(
+            format!(" {} ", i18n::tr("WS DOWN · Shift+R reconnect")),
+            Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD),
+        ))
+    }
+}
+
+/// Time left in the current track plus every loaded upcoming one, and
+/// whether that covers the whole queue.
+fn queue_remaining_ms(app: &App) -> (u64, bool) {
+    let current = app.duration_ms.saturating_sub(app.elapsed_ms);
+    let upcoming: u64 = app.queue.iter().filter_map(|t| t.info.duration_ms).sum();
+    let complete = app.queue_total.is_none_or(|total| app.queue.len() >= total);
+    (current + upcoming, complete)
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour up.
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Non-neutral filter settings as `Speed 1.25, Karaoke 1.00`; volume included.
+fn filter_summary(filters: &AudioFilters) -> String {
+    let draft = FilterDraft::from_filters(filters);
+    let mut parts: Vec<String> = FILTER_PARAMS
+        .iter()
+        .zip(draft.values)
+        .filter(|(p, v)| !p.is_neutral(*v))
+        .map(|(p, v)| format!("{} {:.2}", p.label, v))
+        .collect();
+    if let Some(volume) = filters.volume {
+        parts.insert(0, format!("Volume {}%", (volume * 100.0).round()));
+    }
+    if parts.is_empty() { i18n::tr("None").to_string() } else { parts.join(", ") }
+}
+
+fn render_track_details(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = centered_rect(70, 50, f.area());
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!(" {} ", i18n::tr("Track Details")))
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme.highlight))
+        .padding(ratatui::widgets::Padding::horizontal(1));
+
+    let no_info = Track::default();
+    let (label, info, position) = match app.details_index.and_then(|i| app.queue.get(i).map(|t| (i, t))) {
+        Some((i, track)) => (
+            track.label.as_str(),
+            &track.info,
+            t!("#{} of {}", i + 1, app.queue_total.unwrap_or(app.queue.len())),
+        ),
+        None => match &app.current_track {
+            Some(current) => (current.as_str(), app.current_info.as_ref().unwrap_or(&no_info), i18n::tr("Now playing").to_string()),
+            None => {
+                let p = Paragraph::new(i18n::tr("Nothing is playing")).block(block).alignment(Alignment::Center);
+                f.render_widget(p, area);
+                return;
+            }
+        },
+    };
+
+    let (title, artist) = label.split_once(" - ").unwrap_or((label, ""));
+    let unknown = || i18n::tr("Unknown").to_string();
+    let duration = match (app.details_index, info.duration_ms) {
+        (_, Some(ms)) => format_duration(ms),
+        (None, None) if app.duration_ms > 0 => format_duration(app.duration_ms),
+        _ => unknown(),
+    };
+    let requester = match (info.requester_name(), info.requester_avatar()) {
+        (Some(name), Some(avatar)) => format!("{} ({})", name, avatar),
+        (Some(name), None) => name.to_string(),
+        _ => unknown(),
+    };
+    let rows = [
+        (i18n::tr("Title"), title.to_string()),
+        (i18n::tr("Artist"), if artist.is_empty() { unknown() } else { artist.to_string() }),
+        (i18n::tr("Source"), info.uri.clone().unwrap_or_else(unknown)),
+        (i18n::tr("Requested by"), requester),
+        (i18n::tr("Duration"), duration),
+        (i18n::tr("Position"), position),
+        (i18n::tr("Filters"), filter_summary(&app.current_filters())),
+    ];
+    let label_width = rows.iter().map(|(l, _)| l.chars().count()).max().unwrap_or(0);
+    let mut lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(l, v)| {
+            Line::from(vec![
+                Span::styled(format!("{:<width$}  ", l, width = label_width), Style::default().fg(theme.text_secondary)),
+                Span::styled(v, Style::default().fg(Color::White)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(i18n::tr("ESC close"), Style::default().fg(theme.text_secondary))));
+    let p = Paragraph::new(lines).block(block).wrap(Wrap { trim: false });
+    f.render_widget(p, area);
+}
+
+/// A one-line box in the bottom-right corner, just above the status bar.
+fn render_toast(f: &mut Frame, theme: &Theme, toast: &Toast, status_bar: Rect) {
+    let msg = toast.message.as_str();
+    let screen = f.area();
+    // Server errors can be long: wrap them in a box at most two thirds wide.
+    let text_width = Span::raw(msg).width() as u16;
+    let width = (text_width + 4).min(screen.width * 2 / 3).max(20).min(screen.width);
+    let rows = text_width.div_ceil(width.saturating_sub(4).max(1)).clamp(1, 4);
+    let height = (rows + 2).min(screen.height);
+    let area = Rect {
+        x: screen.right().saturating_sub(width),
+        y: status_bar.y.saturating_sub(height),
+        width,
+        height,
+    };
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+    let block = if toast.error {
+        block
+            .border_style(Style::default().fg(Color::Red))
+            .title_bottom(Line::from(Span::styled(" Esc ", Style::default().fg(theme.text_secondary))).right_aligned())
+    } else {
+        block.border_style(Style::default().fg(theme.highlight))
+    };
+    let p = Paragraph::new(Span::styled(msg, Style::default().fg(Color::White)))
+        .block(block)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(p, area);
+}
+
+fn render_confirm(f: &mut Frame, theme: &Theme, action: &str) {
+    let question = match action {
+        "stop" => i18n::tr("Stop playback and clear the queue?"),
+        _ => i18n::tr("Clear the whole queue?"),
+    };
+    let full = f.area();
+    let width = 50.min(full.width);
+    let area = Rect {
+        x: full.x + (full.width - width) / 2,
+        y: full.y + full.height.saturating_sub(7) / 2,
+        width,
+        height: 7.min(full.height),
+    };
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!(" {} ", i18n::tr("Are you sure?")))
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(Color::Yellow))
+        .bg(theme.bg);
+    let text = vec![
+        Line::from(""),
+        Line::from(Span::styled(question, Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(" y ", Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD)),
+            Span::raw(" / "),
+            Span::styled(" n ", Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD)),
+        ]),
+    ];
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Center).block(block), area);
+}
+
+/// Presets on the left, one slider per filter parameter on the right.
+fn render_filter_editor(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let loading_text = if app.is_loading { " ⏳ " } else { "" };
+    let hint = match &app.preset_name_input {
+        Some(name) => t!("Save preset as: {}▏ (ENTER save · ESC cancel)", name),
+        None => i18n::tr("TAB switch · ←→ adjust · R reset · S save · X delete · ENTER apply · ESC close").to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!(" {} {} ", i18n::tr("Filters"), loading_text))
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(format!(" {} ", hint)).centered())
+        .border_style(Style::default().fg(theme.primary));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(inner);
+    let pane_block = |focused: bool| {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(if focused { theme.highlight } else { theme.border }))
+    };
+    let highlight = Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD);
+
+    let presets: Vec<ListItem> = filter_preset_names(app)
+        .into_iter()
+        .map(|name| {
+            let saved = if app.filter_presets.contains_key(&name) { "★ " } else { "" };
+            ListItem::new(format!(" {}{} ", saved, name))
+        })
+        .collect();
+    let presets = List::new(presets)
+        .block(pane_block(!app.filter_params_focused))
+        .highlight_style(highlight)
+        .highlight_symbol(" >> ");
+    f.render_stateful_widget(presets, panes[0], &mut app.filter_state);
+
+    const SLIDER: usize = 24;
+    let slot = |p: &FilterParam, v: f32| (((v - p.min) / (p.max - p.min)) * (SLIDER - 1) as f32).round() as usize;
+    let params: Vec<ListItem> = FILTER_PARAMS
+        .iter()
+        .zip(app.filter_draft.values)
+        .map(|(p, v)| {
+            let (at, neutral) = (slot(p, v), slot(p, p.neutral));
+            let slider: String = (0..SLIDER)
+                .map(|i| if i == at { '●' } else if i == neutral { '┼' } else { '─' })
+                .collect();
+            let color = if p.is_neutral(v) { theme.text_secondary } else { theme.highlight };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(" {:<15}", p.label)),
+                Span::styled(slider, Style::default().fg(color)),
+                Span::raw(format!(" {:>6.2} ", v)),
+            ]))
+        })
+        .collect();
+    let params = List::new(params)
+        .block(pane_block(app.filter_params_focused))
+        .highlight_style(highlight)
+        .highlight_symbol(" >> ");
+    f.render_stateful_widget(params, panes[1], &mut app.filter_param_state);
+}
+
+/// Playlist names on the left, the highlighted playlist's tracks on the right.
+fn render_playlists(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!(" {} ", i18n::tr("Playlists")))
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(format!(" {} ", i18n::tr("ENTER enqueue · A enqueue all · ←→ switch · ESC close"))).centered())
+        .border_style(Style::default().fg(theme.primary));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.playlists.is_empty() {
+        let dir = playlist::dir().map(|d| d.display().to_string()).unwrap_or_default();
+        let p = Paragraph::new(t!("No playlists yet. Add text files with one track or URL per line to {}", dir))
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.text_secondary))
+            .wrap(Wrap { trim: true });
+        f.render_widget(p, inner);
+        return;
+    }
 
-    if app.view == View::AppInfo {
-        let area = centered_rect(60, 40, f.area());
-        f.render_widget(Clear, area);
-        
-        let block = Block::default()
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(inner);
+    let pane_block = |focused: bool| {
+        Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Thick)
-            .title(" Build Compatibility Info ")
-            .title_alignment(Alignment::Center)
-            .border_style(Style::default().fg(theme.highlight));
-        
-        let text = vec![
-            Line::from(Span::styled("BUILD COMPATIBILITY", Style::default().add_modifier(Modifier::BOLD).fg(Color::Yellow))),
-            Line::from(""),
-            Line::from("This version of Jorik CLI is intended for use with"),
-            Line::from(vec![
-                Span::raw("the "),
-                Span::styled("INTERNAL DEV VERSION", Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
-                Span::raw(" of Jorik bot."),
-            ]),
-            Line::from(""),
-            Line::from("The production version will work, but with significantly"),
-            Line::from("reduced functionality (limited real-time features)."),
-            Line::from(""),
-            Line::from(vec![
-                Span::raw("Current Version: "),
-                Span::styled(env!("CARGO_PKG_VERSION"), Style::default().fg(Color::White)),
-            ]),
-            Line::from(""),
-            Line::from(Span::styled("Press 'i' or Esc to close", Style::default().fg(theme.text_secondary))),
-        ];
+            .border_style(Style::default().fg(if focused { theme.highlight } else { theme.border }))
+    };
+    let highlight = Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD);
+
+    let names: Vec<ListItem> = app.playlists
+        .iter()
+        .map(|p| ListItem::new(format!(" {} ({}) ", p.name, p.tracks.len())))
+        .collect();
+    let names = List::new(names)
+        .block(pane_block(!app.playlist_tracks_focused))
+        .highlight_style(highlight)
+        .highlight_symbol(" >> ");
+    f.render_stateful_widget(names, panes[0], &mut app.playlist_state);
+
+    let tracks: Vec<ListItem> = app.playlist_state.selected()
+        .and_then(|i| app.playlists.get(i))
+        .map(|p| p.tracks.iter().enumerate().map(|(i, t)| ListItem::new(format!(" {:>2}. {} ", i + 1, t))).collect())
+        .unwrap_or_default();
+    let tracks = List::new(tracks)
+        .block(pane_block(app.playlist_tracks_focused))
+        .highlight_style(highlight)
+        .highlight_symbol(" >> ");
+    f.render_stateful_widget(tracks, panes[1], &mut app.playlist_track_state);
+}
 
+fn render_guild_picker(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let loading_text = if app.is_loading { " ⏳ " } else { "" };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!(" {} {}", i18n::tr("Choose a Guild"), loading_text))
+        .title_alignment(Alignment::Center)
+        .title_bottom(Line::from(format!(" {} ", i18n::tr("ENTER select · ←→ switch · ESC let the server decide"))).centered())
+        .border_style(Style::default().fg(theme.primary));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if app.picker_guilds.is_empty() {
+        let text = app.picker_status.as_deref().unwrap_or(i18n::tr("Loading..."));
         let p = Paragraph::new(text)
             .alignment(Alignment::Center)
-            .block(block)
+            .style(Style::default().fg(theme.text_secondary))
             .wrap(Wrap { trim: true });
-            
-        f.render_widget(p, area);
+        f.render_widget(p, inner);
+        return;
     }
 
-    if let Some(msg) = &app.fatal_error {
-        let area = centered_rect(60, 25, f.area());
-        f.render_widget(Clear, area);
-        
-        let block = Block::default()
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    let pane_block = |focused: bool, title: &str| {
+        Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Thick)
-            .title(" ⚠ Connection Error ")
-            .title_alignment(Alignment::Center)
-            .style(Style::default())
-            .border_style(Style::default().fg(Color::Red));
-        
-        let p = Paragraph::new(msg.as_str())
-            .block(block)
-            .style(Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+            .title(format!(" {} ", title))
+            .border_style(Style::default().fg(if focused { theme.highlight } else { theme.border }))
+    };
+    let highlight = Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD);
+
+    let guilds: Vec<ListItem> = app.picker_guilds
+        .iter()
+        .map(|g| {
+            let current = app.guild_id.as_deref() == Some(g.id.as_str());
+            ListItem::new(Line::from(vec![
+                Span::raw(format!(" {} ", g.name.as_deref().unwrap_or(&g.id))),
+                Span::styled(if current { i18n::tr("(current)") } else { "" }, Style::default().fg(theme.text_secondary)),
+            ]))
+        })
+        .collect();
+    let guilds = List::new(guilds)
+        .block(pane_block(!app.picker_channels_focused, i18n::tr("Guilds")))
+        .highlight_style(highlight)
+        .highlight_symbol(" >> ");
+    f.render_stateful_widget(guilds, panes[0], &mut app.picker_guild_state);
+
+    if !app.picker_channels_focused {
+        let p = Paragraph::new(i18n::tr("ENTER to list voice channels"))
             .alignment(Alignment::Center)
-            .wrap(Wrap { trim: true });
-            
-        f.render_widget(p, area);
+            .style(Style::default().fg(theme.text_secondary))
+            .block(pane_block(false, i18n::tr("Voice Channels")));
+        f.render_widget(p, panes[1]);
+        return;
+    }
+    let mut channels = vec![ListItem::new(format!(" {} ", i18n::tr("The channel I'm in")))];
+    channels.extend(app.picker_channels.iter().map(|c| {
+        let members = c.members.filter(|n| *n > 0).map_or_else(String::new, |n| format!(" ({})", n));
+        ListItem::new(format!(" 🔊 {}{} ", c.name.as_deref().unwrap_or(&c.id), members))
+    }));
+    let channels = List::new(channels)
+        .block(pane_block(true, i18n::tr("Voice Channels")))
+        .highlight_style(highlight)
+        .highlight_symbol(" >> ");
+    f.render_stateful_widget(channels, panes[1], &mut app.picker_channel_state);
+}
+
+/// Whether to use the compact single-column layout for a terminal of `area`.
+fn is_compact(app: &App, area: Rect) -> bool {
+    match app.compact_mode.as_str() {
+        "On" => true,
+        "Off" => false,
+        _ => area.width < COMPACT_WIDTH || area.height < COMPACT_HEIGHT,
+    }
+}
+
+fn compact_mode_label(mode: &str) -> &'static str {
+    match mode {
+        "On" => "On",
+        "Off" => "Off",
+        _ => "Auto",
     }
 }
 
 fn render_player_ui(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    if is_compact(app, f.area()) {
+        return render_compact_layout(f, app, theme, area);
+    }
     match app.layout.as_str() {
         "Sidebar" => render_sidebar_layout(f, app, theme, area),
         "Studio" => render_studio_layout(f, app, theme, area),
         "Zen" => render_zen_layout(f, app, theme, area),
+        "Lyrics" => render_lyrics_layout(f, app, theme, area),
         _ => render_standard_layout(f, app, theme, area),
     }
 }
 
+/// Split `area` among the parts that are shown, in order; hidden parts get `None`.
+fn split_shown(area: Rect, direction: Direction, parts: &[(bool, Constraint)]) -> Vec<Option<Rect>> {
+    let shown: Vec<Constraint> = parts.iter().filter(|(show, _)| *show).map(|(_, c)| *c).collect();
+    let mut rects = Layout::default().direction(direction).constraints(shown).split(area).to_vec().into_iter();
+    parts.iter().map(|(show, _)| if *show { rects.next() } else { None }).collect()
+}
+
 fn render_standard_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let show_art = app.show_logo && app.art.is_some();
+    let show_right = app.visualizer_shown() || show_art;
+    let content_chunks = split_shown(area, Direction::Horizontal, &[
+        (true, if show_right { Constraint::Percentage(60) } else { Constraint::Min(0) }),
+        (show_right, Constraint::Percentage(40)),
+    ]);
+
+    if let Some(right) = content_chunks[1] {
+        let right = split_shown(right, Direction::Vertical, &[
+            (show_art, if app.visualizer_shown() { Constraint::Length(14) } else { Constraint::Min(0) }),
+            (app.visualizer_shown(), Constraint::Min(0)),
+        ]);
+        if let Some(art_area) = right[0] {
+            render_album_art(f, app, theme, art_area);
+        }
+        if let Some(spectrogram_area) = right[1] {
+            render_visualizer(f, app, theme, spectrogram_area);
+        }
+    }
+
+    let left_chunks = split_shown(content_chunks[0].unwrap_or(area), Direction::Vertical, &[
+        (app.show_logo, Constraint::Length(11)),
+        (true, Constraint::Length(6)),
+        (app.show_queue, Constraint::Min(0)),
+    ]);
+
+    if let Some(logo_area) = left_chunks[0] {
+        render_logo(f, app, theme, logo_area);
+    }
+    if let Some(now_playing_area) = left_chunks[1] {
+        render_now_playing(f, app, theme, now_playing_area);
+    }
+    if let Some(queue_area) = left_chunks[2] {
+        render_queue(f, app, theme, queue_area);
+    }
+}
+
+/// Standard's left column, with the lyrics taking the visualizer's place on the right.
+fn render_lyrics_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(60),
-            Constraint::Percentage(40),
-        ])
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
         .split(area);
 
-    let left_side = content_chunks[0];
-    let spectrogram_area = content_chunks[1];
+    let show_art = app.show_logo && app.art.is_some();
+    let right = split_shown(content_chunks[1], Direction::Vertical, &[
+        (show_art, Constraint::Length(14)),
+        (true, Constraint::Min(0)),
+    ]);
+    if let Some(art_area) = right[0] {
+        render_album_art(f, app, theme, art_area);
+    }
+    if let Some(lyrics_area) = right[1] {
+        render_lyrics_pane(f, app, theme, lyrics_area);
+    }
 
-    let left_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(11),
-            Constraint::Length(6),
-            Constraint::Min(0),
-        ])
-        .split(left_side);
+    let left_chunks = split_shown(content_chunks[0], Direction::Vertical, &[
+        (app.show_logo, Constraint::Length(11)),
+        (true, Constraint::Length(6)),
+        (app.show_queue, Constraint::Min(0)),
+    ]);
+    if let Some(logo_area) = left_chunks[0] {
+        render_logo(f, app, theme, logo_area);
+    }
+    if let Some(now_playing_area) = left_chunks[1] {
+        render_now_playing(f, app, theme, now_playing_area);
+    }
+    if let Some(queue_area) = left_chunks[2] {
+        render_queue(f, app, theme, queue_area);
+    }
+}
 
-    render_logo(f, theme, left_chunks[0]);
-    render_now_playing(f, app, theme, left_chunks[1]);
-    render_queue(f, app, theme, left_chunks[2]);
-    render_visualizer(f, app, theme, spectrogram_area);
+/// Synced lyrics when the server has them; plain lyrics scroll through the
+/// panel in step with the track instead.
+fn render_lyrics_pane(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    if app.current_track.is_some() && !app.lyrics_lines.is_empty() {
+        return render_synced_lyrics(f, app, theme, area);
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .title(format!(" {} ", i18n::tr("Lyrics")))
+        .title_alignment(Alignment::Center)
+        .border_style(Style::default().fg(theme.primary));
+    let text = match (&app.current_track, &app.lyrics_text) {
+        (None, _) => i18n::tr("Nothing playing"),
+        (Some(_), Some(text)) => text.as_str(),
+        (Some(_), None) => i18n::tr("Loading..."),
+    };
+    let overflow = (text.lines().count() as u64).saturating_sub(area.height.saturating_sub(2) as u64);
+    let scroll = (overflow * app.elapsed_ms.min(app.duration_ms)).checked_div(app.duration_ms).unwrap_or(0) as u16;
+    let style = if app.lyrics_text.is_some() && app.current_track.is_some() {
+        Style::default().fg(Color::White)
+    } else {
+        Style::default().fg(theme.text_secondary)
+    };
+    let p = Paragraph::new(text)
+        .block(block)
+        .style(style)
+        .alignment(Alignment::Center)
+        .scroll((scroll, 0));
+    f.render_widget(p, area);
 }
 
 fn render_sidebar_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(70), // Bigger Viz
-            Constraint::Percentage(30),
-        ])
-        .split(area);
+    // With the visualizer hidden, the queue moves into its place.
+    let queue_in_main = app.show_queue && !app.visualizer_shown();
+    let queue_in_sidebar = app.show_queue && app.visualizer_shown();
+    let show_sidebar = app.show_logo || queue_in_sidebar;
+    let chunks = split_shown(area, Direction::Horizontal, &[
+        (true, if show_sidebar { Constraint::Percentage(70) } else { Constraint::Min(0) }), // Bigger Viz
+        (show_sidebar, Constraint::Percentage(30)),
+    ]);
+
+    let main_chunks = split_shown(chunks[0].unwrap_or(area), Direction::Vertical, &[
+        (app.visualizer_shown() || queue_in_main, Constraint::Min(0)),
+        (true, Constraint::Length(6)),
+    ]);
+
+    if let Some(main_area) = main_chunks[0] {
+        if app.visualizer_shown() {
+            render_visualizer(f, app, theme, main_area);
+        } else {
+            render_queue(f, app, theme, main_area);
+        }
+    }
+    if let Some(now_playing_area) = main_chunks[1] {
+        render_now_playing(f, app, theme, now_playing_area);
+    }
 
-    let main_side = chunks[0];
-    let sidebar = chunks[1];
+    if let Some(sidebar) = chunks[1] {
+        let sidebar_chunks = split_shown(sidebar, Direction::Vertical, &[
+            (app.show_logo, if queue_in_sidebar { Constraint::Length(11) } else { Constraint::Min(0) }),
+            (queue_in_sidebar, Constraint::Min(0)),
+        ]);
+        if let Some(logo_area) = sidebar_chunks[0] {
+            if app.art.is_some() {
+                render_album_art(f, app, theme, logo_area);
+            } else {
+                render_logo(f, app, theme, logo_area);
+            }
+        }
+        if let Some(queue_area) = sidebar_chunks[1] {
+            render_queue(f, app, theme, queue_area);
+        }
+    }
+}
 
-    let main_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Min(0),
-            Constraint::Length(6),
-        ])
-        .split(main_side);
+fn render_studio_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let chunks = split_shown(area, Direction::Vertical, &[
+        (true, Constraint::Length(if app.show_logo { 11 } else { 6 })),
+        (app.visualizer_shown(), Constraint::Min(0)),
+        (app.show_queue, if app.visualizer_shown() { Constraint::Length(8) } else { Constraint::Min(0) }),
+    ]);
+
+    let top_row = split_shown(chunks[0].unwrap_or(area), Direction::Horizontal, &[
+        (app.show_logo, Constraint::Percentage(40)),
+        (true, if app.show_logo { Constraint::Percentage(60) } else { Constraint::Min(0) }),
+    ]);
+
+    if let Some(now_playing_area) = top_row[1] {
+        render_now_playing(f, app, theme, now_playing_area);
+    }
+    if let Some(logo_area) = top_row[0] {
+        if app.art.is_some() {
+            render_album_art(f, app, theme, logo_area);
+        } else {
+            render_logo(f, app, theme, logo_area);
+        }
+    }
+    if let Some(spectrogram_area) = chunks[1] {
+        render_visualizer(f, app, theme, spectrogram_area);
+    }
+    if let Some(queue_area) = chunks[2] {
+        render_queue(f, app, theme, queue_area);
+    }
+}
 
-    let sidebar_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(11),
-            Constraint::Min(0),
-        ])
-        .split(sidebar);
+fn render_zen_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let chunks = split_shown(area, Direction::Vertical, &[
+        (app.visualizer_shown(), Constraint::Min(0)),
+        (true, Constraint::Length(6)),
+    ]);
 
-    render_visualizer(f, app, theme, main_chunks[0]);
-    render_now_playing(f, app, theme, main_chunks[1]);
-    render_logo(f, theme, sidebar_chunks[0]);
-    render_queue(f, app, theme, sidebar_chunks[1]);
+    if let Some(spectrogram_area) = chunks[0] {
+        render_visualizer(f, app, theme, spectrogram_area);
+    }
+    if let Some(now_playing_area) = chunks[1] {
+        render_now_playing(f, app, theme, now_playing_area);
+    }
 }
 
-fn render_studio_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+/// Small terminals: a one-line now playing strip over the queue, nothing else.
+fn render_compact_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(11),
-            Constraint::Min(0),
-            Constraint::Length(8),
-        ])
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
         .split(area);
 
-    let top_row = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-        .split(chunks[0]);
+    let block = Block::default()
+        .borders(Borders::TOP | Borders::BOTTOM)
+        .border_style(Style::default().fg(theme.border));
+    let block = match ws_badge(app) {
+        Some(badge) => block.title_top(Line::from(badge).right_aligned()),
+        None => block,
+    };
+    let inner = block.inner(chunks[0]);
+    f.render_widget(block, chunks[0]);
+    match &app.current_track {
+        Some(current) => {
+            let rows = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(24)])
+                .split(inner);
+            let icon = if app.paused { paused_banner(app, theme) } else { Span::styled("▶", Style::default().fg(theme.primary)) };
+            f.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::raw(" "),
+                    icon,
+                    Span::raw(" "),
+                    Span::styled(current.as_str(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+                    requester_span(app.current_info.as_ref().and_then(Track::requester_name), theme),
+                ])),
+                rows[0],
+            );
+            // m:ss/m:ss plus a short bar in place of the full gauge.
+            let shown = app.scrub_ms.unwrap_or(app.elapsed_ms);
+            let time = format!(
+                "{}:{:02}/{}:{:02}",
+                shown / 60000,
+                (shown % 60000) / 1000,
+                app.duration_ms / 60000,
+                (app.duration_ms % 60000) / 1000,
+            );
+            let ratio = if app.duration_ms > 0 { (shown as f64 / app.duration_ms as f64).min(1.0) } else { 0.0 };
+            let (fill, label) = if app.scrub_ms.is_some() {
+                (theme.highlight, theme.highlight)
+            } else if app.paused {
+                (theme.text_secondary, theme.text_secondary)
+            } else {
+                (theme.primary, theme.text_secondary)
+            };
+            // The bar starts one column after the label.
+            let label_width = time.chars().count() as u16 + 1;
+            if app.duration_ms > 0 {
+                app.seek_bar = Rect {
+                    x: rows[1].x + label_width,
+                    width: rows[1].width.saturating_sub(label_width),
+                    ..rows[1]
+                };
+            }
+            let gauge = LineGauge::default()
+                .filled_style(Style::default().fg(fill))
+                .unfilled_style(Style::default().fg(theme.border))
+                .ratio(ratio)
+                .label(Span::styled(time, Style::default().fg(label)));
+            f.render_widget(gauge, rows[1]);
+        }
+        None => f.render_widget(
+            Paragraph::new(Span::styled(i18n::tr("Nothing is playing"), Style::default().fg(theme.text_secondary))),
+            inner,
+        ),
+    }
 
-    render_logo(f, theme, top_row[0]);
-    render_now_playing(f, app, theme, top_row[1]);
-    render_visualizer(f, app, theme, chunks[1]);
-    render_queue(f, app, theme, chunks[2]);
+    render_queue(f, app, theme, chunks[1]);
 }
 
-fn render_zen_layout(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Min(0),
-            Constraint::Length(6),
-        ])
-        .split(area);
+/// Dimmed logo, clock and last track on black, drifting around the screen
+/// one cell a second so nothing stays lit in the same place.
+fn render_screensaver(f: &mut Frame, app: &App, theme: &Theme) {
+    let area = f.area();
+    f.render_widget(Block::default().bg(Color::Black), area);
+
+    let dim = Style::default().fg(theme.border);
+    let mut lines: Vec<Line> = ASCII_LOGO.iter().map(|s| Line::from(Span::styled(*s, dim))).collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        chrono::Local::now().format("%H:%M").to_string(),
+        Style::default().fg(theme.text_secondary).add_modifier(Modifier::BOLD),
+    )));
+    if let Some(track) = &app.last_track {
+        lines.push(Line::from(Span::styled(t!("Last played: {}", track), dim)));
+    }
+
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let height = lines.len() as u16;
+    let secs = app.idle_since.elapsed().as_secs();
+    let bounce = |range: u16| {
+        if range == 0 {
+            return 0;
+        }
+        let range = range as u64;
+        let p = secs % (2 * range);
+        (if p < range { p } else { 2 * range - p }) as u16
+    };
+    let x = area.x + bounce(area.width.saturating_sub(width));
+    let y = area.y + bounce(area.height.saturating_sub(height));
+    let rect = Rect::new(x, y, width, height).intersection(area);
+    f.render_widget(Paragraph::new(lines).alignment(Alignment::Center), rect);
+}
 
-    render_visualizer(f, app, theme, chunks[0]);
-    render_now_playing(f, app, theme, chunks[1]);
+fn render_album_art(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Thick)
+        .border_style(Style::default().fg(theme.border));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if let Some(art) = app.art.as_mut() {
+        f.render_stateful_widget(StatefulImage::default(), inner, art);
+    }
 }
 
-fn render_logo(f: &mut Frame, theme: &Theme, area: Rect) {
+fn render_logo(f: &mut Frame, app: &App, theme: &Theme, area: Rect) {
+    if app.accessible {
+        // The shaded letters turn to noise once made ASCII; plain text instead.
+        let top = area.height.saturating_sub(1) / 2;
+        let name = Paragraph::new(Span::styled("JORIK CLI", Style::default().fg(theme.primary).add_modifier(Modifier::BOLD)))
+            .alignment(Alignment::Center);
+        f.render_widget(name, Rect { y: area.y + top, height: area.height.min(1), ..area });
+        return;
+    }
     let art_text: Vec<Line> = ASCII_LOGO.iter().map(|s| Line::from(Span::styled(*s, Style::default().fg(theme.primary)))).collect();
     let art_paragraph = Paragraph::new(art_text)
         .alignment(Alignment::Center)
@@ -2330,24 +6528,34 @@ fn render_now_playing(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
         .borders(Borders::ALL)
         .border_type(BorderType::Thick)
         .border_style(Style::default().fg(theme.border))
-        .title(" Now Playing ")
+        .title(format!(" {} ", i18n::tr("Now Playing")))
         .title_style(Style::default().fg(theme.primary).add_modifier(Modifier::BOLD));
+    let playing_block = match ws_badge(app) {
+        Some(badge) => playing_block.title_top(Line::from(badge).right_aligned()),
+        None => playing_block,
+    };
 
     if let Some(current) = &app.current_track {
         let (title, artist) = if let Some((t, a)) = current.split_once(" - ") {
             (t, a)
         } else {
-            (current.as_str(), "Unknown Artist")
+            (current.as_str(), i18n::tr("Unknown Artist"))
         };
 
+        let mut title_line = vec![
+            Span::styled(" > ", Style::default().fg(theme.primary)),
+            Span::styled(title, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+        ];
+        if app.paused {
+            title_line.push(Span::raw("  "));
+            title_line.push(paused_banner(app, theme));
+        }
         let play_info = vec![
+            Line::from(title_line),
             Line::from(vec![
-                Span::styled(" > ", Style::default().fg(theme.primary)),
-                Span::styled(title, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-            ]),
-            Line::from(vec![
-                Span::styled("   by ", Style::default().fg(theme.text_secondary)),
+                Span::styled(i18n::tr("   by "), Style::default().fg(theme.text_secondary)),
                 Span::styled(artist, Style::default().fg(theme.highlight)),
+                requester_span(app.current_info.as_ref().and_then(Track::requester_name), theme),
             ]),
         ];
 
@@ -2355,38 +6563,97 @@ fn render_now_playing(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
         f.render_widget(p, area);
 
         if app.duration_ms > 0 {
-            let ratio = (app.elapsed_ms as f64 / app.duration_ms as f64).min(1.0);
+            // While scrubbing the bar shows the picked position instead of playback.
+            let shown = app.scrub_ms.unwrap_or(app.elapsed_ms);
+            let ratio = (shown as f64 / app.duration_ms as f64).min(1.0);
             let time_str = format!(
                 " {:02}:{:02} / {:02}:{:02} ",
-                app.elapsed_ms / 60000,
-                (app.elapsed_ms % 60000) / 1000,
+                shown / 60000,
+                (shown % 60000) / 1000,
                 app.duration_ms / 60000,
                 (app.duration_ms % 60000) / 1000,
             );
+            let (fill, label) = match app.scrub_ms {
+                Some(_) => (
+                    theme.highlight,
+                    Span::styled(
+                        format!("»{}· {} ", time_str, i18n::tr("ENTER seek · ESC cancel")),
+                        Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD),
+                    ),
+                ),
+                None if app.paused => (theme.text_secondary, Span::raw(time_str)),
+                None => (theme.primary, Span::raw(time_str)),
+            };
 
             let gauge = Gauge::default()
                 .block(Block::default().padding(ratatui::widgets::Padding::horizontal(2)))
-                .gauge_style(Style::default().fg(theme.primary).bg(Color::Rgb(30, 30, 40)))
+                .gauge_style(Style::default().fg(fill).bg(Color::Rgb(30, 30, 40)))
                 .ratio(ratio)
-                .label(time_str)
+                .label(label)
                 .use_unicode(true);
             
             let gauge_area = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Length(3), Constraint::Length(1), Constraint::Min(0)])
                 .split(area)[1];
+            app.seek_bar = Rect {
+                x: gauge_area.x + 2,
+                width: gauge_area.width.saturating_sub(4),
+                ..gauge_area
+            };
             
             f.render_widget(gauge, gauge_area);
         }
     } else {
-        f.render_widget(Paragraph::new("Nothing is playing").block(playing_block).alignment(Alignment::Center), area);
+        f.render_widget(Paragraph::new(i18n::tr("Nothing is playing")).block(playing_block).alignment(Alignment::Center), area);
     }
+
+    let volume_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Length(1), Constraint::Min(0)])
+        .split(area)[1];
+    let volume_color = if app.filters_changed_at.is_some() { theme.highlight } else { theme.text_secondary };
+    let volume = LineGauge::default()
+        .block(Block::default().padding(ratatui::widgets::Padding::horizontal(2)))
+        .filled_style(Style::default().fg(volume_color))
+        .unfilled_style(Style::default().fg(theme.border))
+        .ratio(app.volume as f64 / MAX_VOLUME as f64)
+        .label(Span::styled(t!("VOL {}%", app.volume), Style::default().fg(volume_color)));
+    f.render_widget(volume, volume_area);
+}
+
+/// `⏸ PAUSED`, blinking every half second from the moment playback went idle.
+fn paused_banner(app: &App, theme: &Theme) -> Span<'static> {
+    // Steady in accessibility mode; a blinking label keeps screen readers talking.
+    let lit = app.accessible || (app.idle_since.elapsed().as_millis() / 500).is_multiple_of(2);
+    let color = if lit { theme.highlight } else { theme.text_secondary };
+    Span::styled(
+        if app.accessible { i18n::tr("PAUSED").to_string() } else { format!("⏸ {}", i18n::tr("PAUSED")) },
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    )
 }
 
 fn render_queue(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
     let loop_status = app.loop_mode.to_uppercase();
     let loading_indicator = if app.is_loading { " [L] " } else { " " };
-    let title = format!(" Queue ({}){} ", loop_status, loading_indicator);
+    let total = app.queue_total.unwrap_or(app.queue.len());
+    let position = match app.queue_state.selected() {
+        Some(i) if total > 0 => format!("{} ", t!("{} of {}", i + 1, total)),
+        _ => String::new(),
+    };
+    let summary = if total > 0 || app.current_track.is_some() {
+        let (remaining, complete) = queue_remaining_ms(app);
+        // Durations of pages not loaded yet are unknown, so the sum is a lower bound.
+        let remaining = format!("{}{}", format_duration(remaining), if complete { "" } else { "+" });
+        if total == 1 {
+            t!("Queue: 1 track · {} remaining", remaining)
+        } else {
+            t!("Queue: {} tracks · {} remaining", total, remaining)
+        }
+    } else {
+        i18n::tr("Queue").to_string()
+    };
+    let title = format!(" {} ({}){}{}", summary, loop_status, loading_indicator, position);
     
     let content_block = Block::default()
         .borders(Borders::ALL)
@@ -2394,137 +6661,332 @@ fn render_queue(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
         .border_style(Style::default().fg(theme.border))
         .title_style(Style::default().fg(theme.primary).add_modifier(Modifier::BOLD))
         .title(title);
-
-    if let Some(err) = &app.error_message {
-        let p = Paragraph::new(format!("! {}", err))
-            .style(Style::default().fg(Color::Red))
-            .block(content_block)
-            .wrap(Wrap { trim: true });
-        f.render_widget(p, area);
-    } else {
-        let mut items = Vec::new();
-        if !app.queue.is_empty() {
-             for (i, track) in app.queue.iter().enumerate() {
-                items.push(ListItem::new(Line::from(vec![
-                    Span::styled(format!(" {:2}. ", i + 1), Style::default().fg(theme.primary)),
-                    Span::styled(track, Style::default().fg(theme.text_secondary)),
-                ])));
+    let content_block = match app.pending_remove.and_then(|i| app.queue.get(i)) {
+        Some(track) => content_block.title_bottom(Line::from(vec![
+            Span::styled(format!(" {} ", t!("Remove {}?", track.label)), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::styled(" y ", Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD)),
+            Span::raw(" / "),
+            Span::styled(" n ", Style::default().bg(Color::Red).fg(Color::White).add_modifier(Modifier::BOLD)),
+            Span::raw(" "),
+        ])),
+        None => match &app.queue_search {
+            Some(term) => {
+                let count = app.queue_matches().len();
+                let hint = if app.queue_search_editing {
+                    format!(" /{}▏ ", term)
+                } else {
+                    format!(" /{} · {} ", term, i18n::tr("n/N next/prev"))
+                };
+                content_block.title_bottom(Line::from(vec![
+                    Span::styled(hint, Style::default().fg(theme.highlight).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!("{} ", t!("{} found", count)), Style::default().fg(theme.text_secondary)),
+                ]))
             }
-        } else {
-             items.push(ListItem::new(Span::styled("   Queue is empty", Style::default().fg(Color::DarkGray))));
+            None => content_block,
+        },
+    };
+
+    let matches = app.queue_matches();
+    // While the term is typed only matches are listed; afterwards they are just marked.
+    let narrowed = app.queue_search_editing && app.queue_search.as_deref().is_some_and(|t| !t.is_empty());
+    let rows: Vec<usize> = if narrowed { matches.clone() } else { (0..app.queue.len()).collect() };
+    let mut items = Vec::new();
+    if !rows.is_empty() {
+         for &i in &rows {
+            let color = if !narrowed && matches.contains(&i) { theme.highlight } else { theme.text_secondary };
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(format!(" {:2}. ", i + 1), Style::default().fg(theme.primary)),
+                Span::styled(&app.queue[i].label, Style::default().fg(color)),
+                requester_span(app.queue[i].requester.as_deref(), theme),
+            ])));
         }
+    } else if narrowed {
+         items.push(ListItem::new(Span::styled(i18n::tr("   No matches"), Style::default().fg(Color::DarkGray))));
+    } else {
+         items.push(ListItem::new(Span::styled(i18n::tr("   Queue is empty"), Style::default().fg(Color::DarkGray))));
+    }
 
-        let list = List::new(items).block(content_block);
-        f.render_widget(list, area);
+    app.queue_page = area.height.saturating_sub(2).max(1) as usize;
+    let list = List::new(items)
+        .block(content_block)
+        .highlight_style(Style::default().bg(theme.primary).fg(Color::Black).add_modifier(Modifier::BOLD));
+    if narrowed {
+        let mut state = ListState::default()
+            .with_selected(app.queue_state.selected().and_then(|sel| rows.iter().position(|&i| i == sel)));
+        f.render_stateful_widget(list, area, &mut state);
+    } else {
+        f.render_stateful_widget(list, area, &mut app.queue_state);
     }
 }
 
 fn render_visualizer(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let title = if app.paused && app.current_track.is_some() {
+        format!(" {} · ⏸ ", i18n::tr("Visualizer"))
+    } else {
+        format!(" {} ", i18n::tr("Visualizer"))
+    };
     let spec_block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Thick)
         .border_style(Style::default().fg(theme.border))
-        .title(" Visualizer ")
+        .title(title)
         .title_style(Style::default().fg(theme.primary).add_modifier(Modifier::BOLD));
 
-    if app.current_track.is_some() {
-        let (b_w, b_g) = match app.viz_style.as_str() {
-            "Blocky" => (area.width / 64, 0),
-            "Line" => (1, 0),
-            "Wave" => (1, 0),
-            "Dots" => (1, 1),
-            _ => (2, 1),
-        };
+    if app.current_track.is_none() {
+        f.render_widget(Paragraph::new(i18n::tr("Idle (No Track)")).block(spec_block).alignment(Alignment::Center), area);
+        return;
+    }
 
-        let num_bars = if app.viz_style == "Wave" || app.viz_style == "Dots" {
-            (area.width as usize).min(128)
-        } else {
-            ((area.width / (b_w + b_g)) as usize).min(64)
-        };
+    let inner = spec_block.inner(area);
+    f.render_widget(spec_block, area);
+    match app.viz_style.as_str() {
+        "Mirror" => render_mirror_bars(f, app, theme, inner),
+        "Braille" => render_braille_spectrum(f, app, theme, inner),
+        "Waveform" => render_waveform(f, app, theme, inner),
+        _ => render_bar_chart(f, app, theme, inner),
+    }
+    // The spectrum freezes on pause; greying it out says why.
+    if app.paused {
+        f.buffer_mut().set_style(inner, Style::default().fg(theme.border).add_modifier(Modifier::DIM));
+    }
+}
 
-        let mut bar_items = Vec::with_capacity(num_bars);
-
-        if num_bars > 0 {
-            let start_bin = 3.0;
-            let end_bin = 61.0;
-            let bins_to_show = end_bin - start_bin;
-            let bins_per_bar = bins_to_show / num_bars as f32;
-
-            for j in 0..num_bars {
-                let start_f = start_bin + j as f32 * bins_per_bar;
-                let end_f = start_bin + (j + 1) as f32 * bins_per_bar;
-                let mut sum = 0.0;
-                let mut weight = 0.0;
-                for i in 0..64 {
-                    let overlap = ((i + 1) as f32).min(end_f) - (i as f32).max(start_f);
-                    if overlap > 0.0 {
-                        sum += app.smoothed_bars[i] * overlap;
-                        weight += overlap;
-                    }
-                }
-                bar_items.push((if weight > 0.0 { sum / weight } else { 0.0 }) as u64);
+/// `smoothed_bars` resampled to `n` columns, leaving out the sub-bass rumble
+/// and the top bins. Values are 0-100.
+fn spectrum_levels(app: &App, n: usize) -> Vec<u64> {
+    let mut levels = Vec::with_capacity(n);
+    if n == 0 {
+        return levels;
+    }
+    let start_bin = 3.0;
+    let end_bin = 61.0;
+    let bins_to_show = end_bin - start_bin;
+    let bins_per_bar = bins_to_show / n as f32;
+
+    for j in 0..n {
+        let start_f = start_bin + j as f32 * bins_per_bar;
+        let end_f = start_bin + (j + 1) as f32 * bins_per_bar;
+        let mut sum = 0.0;
+        let mut weight = 0.0;
+        for i in 0..64 {
+            let overlap = ((i + 1) as f32).min(end_f) - (i as f32).max(start_f);
+            if overlap > 0.0 {
+                sum += app.smoothed_bars[i] * overlap;
+                weight += overlap;
             }
         }
+        levels.push((if weight > 0.0 { sum / weight } else { 0.0 }) as u64);
+    }
+    levels
+}
 
-        let bars: Vec<Bar> = bar_items.iter().enumerate()
-            .map(|(i, &v)| {
-                let color = match app.viz_style.as_str() {
-                    "Blocky" | "Wave" => {
-                        if i < num_bars / 3 { theme.primary }
-                        else if i < 2 * num_bars / 3 { theme.highlight }
-                        else { Color::Rgb(200, 200, 255) }
-                    },
-                    "Line" => theme.highlight,
-                    _ => { // Bars (Gradient)
-                        if i < num_bars / 4 { theme.primary }
-                        else if i < num_bars / 2 { theme.highlight }
-                        else { Color::Rgb(200, 200, 255) }
-                    }
-                };
+/// Low, mid or peak colour for column `i` of `n`, split in thirds.
+fn column_color(theme: &Theme, i: usize, n: usize) -> Color {
+    if i < n / 3 { theme.viz_low }
+    else if i < 2 * n / 3 { theme.viz_mid }
+    else { theme.viz_peak }
+}
+
+/// Colour for a cell at `level` (0.0 bottom - 1.0 top) of the visualizer,
+/// blending low -> mid -> peak. Named terminal colours can't be blended, so
+/// those step between the three instead.
+fn level_color(theme: &Theme, level: f64) -> Color {
+    let level = level.clamp(0.0, 1.0);
+    let (from, to, t) = if level < 0.5 {
+        (theme.viz_low, theme.viz_mid, level * 2.0)
+    } else {
+        (theme.viz_mid, theme.viz_peak, level * 2.0 - 1.0)
+    };
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ if level < 1.0 / 3.0 => theme.viz_low,
+        _ if level < 2.0 / 3.0 => theme.viz_mid,
+        _ => theme.viz_peak,
+    }
+}
 
-                let label = if app.viz_style == "Line" || app.viz_style == "Wave" { String::new() } else { format!("{:2}", v.min(99)) };
+/// Recolour the bar cells already drawn in `area` by their height.
+fn shade_by_height(f: &mut Frame, theme: &Theme, area: Rect) {
+    const BAR_SYMBOLS: [&str; 8] = ["█", "▇", "▆", "▅", "▄", "▃", "▂", "▁"];
+    let buf = f.buffer_mut();
+    for y in area.top()..area.bottom() {
+        let level = (area.bottom() - y) as f64 / area.height.max(1) as f64;
+        let color = level_color(theme, level);
+        for x in area.left()..area.right() {
+            if let Some(cell) = buf.cell_mut((x, y))
+                && BAR_SYMBOLS.contains(&cell.symbol())
+            {
+                cell.set_fg(color);
+            }
+        }
+    }
+}
 
-                Bar::default()
-                    .value(v)
-                    .label(Span::from(label))
-                    .style(Style::default().fg(color))
-                    .text_value(String::new())
-            })
-            .collect();
-        
-        let bar_group = BarGroup::default().bars(&bars);
-        let spec_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Min(0), Constraint::Length(1)])
-            .split(spec_block.inner(area));
-
-        let barchart = BarChart::default()
-            .data(bar_group)
-            .bar_width(b_w.max(1))
-            .bar_gap(b_g)
-            .max(100) 
-            .label_style(Style::default().fg(theme.text_secondary));
-        
-        f.render_widget(spec_block, area);
-        f.render_widget(barchart, spec_chunks[0]);
-
-        if app.viz_style != "Wave" && app.viz_style != "Dots" {
-            let labels = ["40", "100", "500", "1k", "5k", "10k", "16k"];
-            let mut label_spans = Vec::new();
-            let total_w = spec_chunks[1].width as usize;
-            if total_w > 10 {
-                for (i, &l) in labels.iter().enumerate() {
-                    let pos = (i as f32 / (labels.len() - 1) as f32 * (total_w - l.len()) as f32) as usize;
-                    let current_len: usize = label_spans.iter().map(|s: &Span| s.content.len()).sum();
-                    if pos > current_len { label_spans.push(Span::raw(" ".repeat(pos - current_len))); }
-                    label_spans.push(Span::styled(l, Style::default().fg(theme.text_secondary)));
+/// The Bars, Blocky, Line, Wave and Dots styles, all drawn as a bar chart.
+fn render_bar_chart(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let (b_w, b_g) = match app.viz_style.as_str() {
+        "Blocky" => (area.width / 64, 0),
+        "Line" => (1, 0),
+        "Wave" => (1, 0),
+        "Dots" => (1, 1),
+        _ => (2, 1),
+    };
+
+    let num_bars = if app.viz_style == "Wave" || app.viz_style == "Dots" {
+        (area.width as usize).min(128)
+    } else {
+        ((area.width / (b_w.max(1) + b_g)) as usize).min(64)
+    };
+
+    let bar_items = spectrum_levels(app, num_bars);
+
+    let bars: Vec<Bar> = bar_items.iter().enumerate()
+        .map(|(i, &v)| {
+            let color = match app.viz_style.as_str() {
+                "Blocky" | "Wave" => column_color(theme, i, num_bars),
+                "Line" => theme.viz_mid,
+                _ => { // Bars (Gradient)
+                    if i < num_bars / 4 { theme.viz_low }
+                    else if i < num_bars / 2 { theme.viz_mid }
+                    else { theme.viz_peak }
+                }
+            };
+
+            let label = if app.viz_style == "Line" || app.viz_style == "Wave" { String::new() } else { format!("{:2}", v.min(99)) };
+
+            Bar::default()
+                .value(v)
+                .label(Span::from(label))
+                .style(Style::default().fg(color))
+                .text_value(String::new())
+        })
+        .collect();
+
+    let bar_group = BarGroup::default().bars(&bars);
+    let spec_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+
+    let barchart = BarChart::default()
+        .data(bar_group)
+        .bar_width(b_w.max(1))
+        .bar_gap(b_g)
+        .max(100)
+        .label_style(Style::default().fg(theme.text_secondary));
+
+    f.render_widget(barchart, spec_chunks[0]);
+    if theme.viz_gradient {
+        shade_by_height(f, theme, spec_chunks[0]);
+    }
+
+    if app.viz_style != "Wave" && app.viz_style != "Dots" {
+        let labels = ["40", "100", "500", "1k", "5k", "10k", "16k"];
+        let mut label_spans = Vec::new();
+        let total_w = spec_chunks[1].width as usize;
+        if total_w > 10 {
+            for (i, &l) in labels.iter().enumerate() {
+                let pos = (i as f32 / (labels.len() - 1) as f32 * (total_w - l.len()) as f32) as usize;
+                let current_len: usize = label_spans.iter().map(|s: &Span| s.content.len()).sum();
+                if pos > current_len { label_spans.push(Span::raw(" ".repeat(pos - current_len))); }
+                label_spans.push(Span::styled(l, Style::default().fg(theme.text_secondary)));
+            }
+            f.render_widget(Paragraph::new(Line::from(label_spans)), spec_chunks[1]);
+        }
+    }
+}
+
+/// Bars growing up and down from the middle of the panel.
+fn render_mirror_bars(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let levels = spectrum_levels(app, (area.width as usize / 2).min(64));
+    let half = area.height / 2;
+    let mid = area.y + half;
+    let buf = f.buffer_mut();
+    for (i, &v) in levels.iter().enumerate() {
+        let height = (v.min(100) * half as u64 / 100) as u16;
+        let column = column_color(theme, i, levels.len());
+        let x = area.x + i as u16 * 2;
+        for dy in 0..height {
+            let color = if theme.viz_gradient {
+                level_color(theme, (dy + 1) as f64 / half.max(1) as f64)
+            } else {
+                column
+            };
+            for y in [mid - 1 - dy, mid + dy] {
+                if let Some(cell) = buf.cell_mut((x, y)) {
+                    cell.set_symbol("█").set_fg(color);
                 }
-                f.render_widget(Paragraph::new(Line::from(label_spans)), spec_chunks[1]);
             }
         }
+    }
+}
+
+/// A dot-matrix spectrum: each column is filled with braille dots up to its level.
+fn render_braille_spectrum(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    // Two braille dots per cell horizontally; leave every other dot column empty.
+    let n = (area.width as usize).min(128);
+    let levels = spectrum_levels(app, n);
+    let step = 100.0 / (area.height.max(1) as f64 * 4.0);
+    // Points are drawn in colour groups: thirds of the spectrum, or height
+    // bands when the theme shades by height.
+    const BANDS: usize = 8;
+    let colors: Vec<Color> = if theme.viz_gradient {
+        (0..BANDS).map(|b| level_color(theme, (b as f64 + 0.5) / BANDS as f64)).collect()
     } else {
-        f.render_widget(Paragraph::new("Idle (No Track)").block(spec_block).alignment(Alignment::Center), area);
+        vec![theme.viz_low, theme.viz_mid, theme.viz_peak]
+    };
+    let mut groups: Vec<Vec<(f64, f64)>> = vec![Vec::new(); colors.len()];
+    for (i, &v) in levels.iter().enumerate() {
+        let mut y = 0.0;
+        while y <= v as f64 {
+            let group = if theme.viz_gradient {
+                ((y / 100.0 * BANDS as f64) as usize).min(BANDS - 1)
+            } else {
+                (i * 3 / n.max(1)).min(2)
+            };
+            groups[group].push((i as f64, y));
+            y += step;
+        }
     }
+    let canvas = Canvas::default()
+        .marker(Marker::Braille)
+        .x_bounds([0.0, n.max(1) as f64])
+        .y_bounds([0.0, 100.0])
+        .paint(|ctx| {
+            for (coords, &color) in groups.iter().zip(&colors) {
+                ctx.draw(&Points { coords, color });
+            }
+        });
+    f.render_widget(canvas, area);
+}
+
+/// An oscilloscope-style trace whose swing at each point follows the spectrum.
+fn render_waveform(f: &mut Frame, app: &mut App, theme: &Theme, area: Rect) {
+    let n = (area.width as usize * 2).min(256);
+    let levels = spectrum_levels(app, n);
+    let points: Vec<(f64, f64)> = levels
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (i as f64, if i % 2 == 0 { v as f64 } else { -(v as f64) }))
+        .collect();
+    let canvas = Canvas::default()
+        .marker(Marker::Braille)
+        .x_bounds([0.0, n.saturating_sub(1).max(1) as f64])
+        .y_bounds([-100.0, 100.0])
+        .paint(|ctx| {
+            for (i, pair) in points.windows(2).enumerate() {
+                let color = if theme.viz_gradient {
+                    level_color(theme, pair[0].1.abs().max(pair[1].1.abs()) / 100.0)
+                } else {
+                    column_color(theme, i, points.len())
+                };
+                ctx.draw(&CanvasLine::new(pair[0].0, pair[0].1, pair[1].0, pair[1].1, color));
+            }
+        });
+    f.render_widget(canvas, area);
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -0,0 +1,119 @@
+//! YouTube playlists read on this side, so `jorik play --pick` can offer
+//! their videos one by one: the YouTube Data API, with the key under
+//! `[youtube]`.
+
+use crate::api::YoutubeSettings;
+use anyhow::{Context, Result, bail};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+
+pub const API: &str = "https://www.googleapis.com/youtube/v3";
+
+/// The `list` of a youtube.com, music.youtube.com or youtu.be link.
+pub fn playlist_id(query: &str) -> Option<String> {
+    let url = url::Url::parse(query.trim()).ok()?;
+    let host = url.host_str()?.trim_start_matches("www.").trim_start_matches("m.");
+    if !matches!(host, "youtube.com" | "music.youtube.com" | "youtu.be") {
+        return None;
+    }
+    url.query_pairs()
+        .find(|(k, _)| k == "list")
+        .map(|(_, v)| v.into_owned())
+        .filter(|id| !id.is_empty())
+}
+
+/// One entry of a playlist.
+pub struct Video {
+    pub id: String,
+    pub title: String,
+    /// Who uploaded it
+    pub channel: String,
+}
+
+impl Video {
+    /// What the server is asked to play.
+    pub fn url(&self) -> String {
+        format!("https://www.youtube.com/watch?v={}", self.id)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Page {
+    #[serde(default)]
+    items: Vec<Item>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    snippet: Snippet,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Snippet {
+    #[serde(default)]
+    title: String,
+    /// Left out for deleted and private videos
+    video_owner_channel_title: Option<String>,
+    resource_id: ResourceId,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceId {
+    video_id: Option<String>,
+}
+
+/// The videos of playlist `id` in order, less those that are deleted or
+/// private and so can't be played.
+pub async fn playlist(client: &Client, settings: &YoutubeSettings, id: &str) -> Result<Vec<Video>> {
+    let Some(key) = &settings.api_key else {
+        bail!("no YouTube API key under [youtube]");
+    };
+    let api = settings.api_url.as_deref().unwrap_or(API).trim_end_matches('/');
+    let url = format!("{api}/playlistItems");
+    let mut videos = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let mut req = client
+            .get(&url)
+            .query(&[("part", "snippet"), ("maxResults", "50"), ("playlistId", id), ("key", key)]);
+        if let Some(token) = &page_token {
+            req = req.query(&[("pageToken", token)]);
+        }
+        // reqwest's errors name the URL, key and all.
+        let resp = req
+            .send()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .with_context(|| format!("GET {url}"))?;
+        match resp.status() {
+            StatusCode::NOT_FOUND => bail!("YouTube has no such playlist, or it is private"),
+            StatusCode::BAD_REQUEST | StatusCode::FORBIDDEN => {
+                bail!("YouTube turned down the api_key under [youtube] (status {})", resp.status())
+            }
+            status if !status.is_success() => bail!("YouTube returned status {status} for the playlist"),
+            _ => {}
+        }
+        let page: Page = resp
+            .json()
+            .await
+            .map_err(reqwest::Error::without_url)
+            .context("parsing the playlist from YouTube")?;
+        videos.extend(page.items.into_iter().filter_map(|item| {
+            let snippet = item.snippet;
+            Some(Video {
+                id: snippet.resource_id.video_id?,
+                channel: snippet.video_owner_channel_title?,
+                title: snippet.title,
+            })
+        }));
+        page_token = page.next_page_token;
+        if page_token.is_none() {
+            break;
+        }
+    }
+    Ok(videos)
+}
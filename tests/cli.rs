@@ -0,0 +1,1110 @@
+//! Every subcommand end-to-end: the real binary against `MockServer`, each
+//! test with its own server and config directory. `tui` needs a terminal,
+//! which its one test borrows from util-linux `script`.
+
+use jorik_core::mock::{BAD_TOKEN, MockServer};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::net::TcpStream;
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// A port nothing listens on, for network failures.
+const UNREACHABLE: &str = "http://127.0.0.1:9";
+
+/// How long a test waits on a child process or socket before failing.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Harness {
+    server: MockServer,
+    config: TempDir,
+}
+
+impl Harness {
+    fn new() -> Self {
+        Self {
+            server: MockServer::start(),
+            config: tempfile::tempdir().expect("temp config dir"),
+        }
+    }
+
+    /// The binary against the mock, with a throwaway config and no prompts.
+    fn command(&self, base_url: &str) -> Command {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_jorik-cli"));
+        for (name, _) in std::env::vars() {
+            if name.starts_with("JORIK_") {
+                cmd.env_remove(name);
+            }
+        }
+        cmd.env("JORIK_LANG", "en")
+            .env("NO_COLOR", "1")
+            .args(["--base-url", base_url, "--no-update-check", "--yes"])
+            .arg("--config-dir")
+            .arg(self.config.path())
+            .stdin(Stdio::null());
+        cmd
+    }
+
+    /// Run with `--token test-token`.
+    fn run(&self, args: &[&str]) -> Output {
+        self.command(&self.server.url())
+            .args(["--token", "test-token"])
+            .args(args)
+            .output()
+            .expect("running jorik-cli")
+    }
+
+    /// Run with whatever token is saved in the config directory.
+    fn run_saved(&self, args: &[&str]) -> Output {
+        self.command(&self.server.url())
+            .args(args)
+            .output()
+            .expect("running jorik-cli")
+    }
+
+    fn config_file(&self, name: &str) -> std::path::PathBuf {
+        self.config.path().join(name)
+    }
+
+    /// Save a login as `auth login` would.
+    fn log_in(&self, token: &str) {
+        let auth = json!({
+            "active": "default",
+            "profiles": {"default": {"token": token, "username": "mockuser"}},
+        });
+        std::fs::write(self.config_file("auth.json"), auth.to_string()).unwrap();
+    }
+
+    fn auth_json(&self) -> Value {
+        let text = std::fs::read_to_string(self.config_file("auth.json")).expect("auth.json");
+        serde_json::from_str(&text).unwrap()
+    }
+
+    /// Start a long-running command and wait for the first line it prints,
+    /// which for a server is where it listens. stderr is kept for the end.
+    fn spawn_listening(&self, args: &[&str]) -> (Child, String) {
+        let mut child = self
+            .command(&self.server.url())
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("running jorik-cli");
+        let stdout = child.stdout.take().unwrap();
+        let (line_tx, line_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            let _ = BufReader::new(stdout).read_line(&mut line);
+            let _ = line_tx.send(line);
+        });
+        match line_rx.recv_timeout(TIMEOUT) {
+            Ok(line) if !line.is_empty() => (child, line),
+            _ => {
+                child.kill().ok();
+                panic!("{args:?} printed nothing within {TIMEOUT:?}");
+            }
+        }
+    }
+
+    /// The only audio request with `action`.
+    fn sent(&self, action: &str) -> Value {
+        let sent = self.server.actions(action);
+        assert_eq!(sent.len(), 1, "expected one {action} request, got {sent:?}");
+        sent[0].body.clone()
+    }
+}
+
+/// A connection to something a test started, that fails rather than hangs.
+fn connect(addr: &str) -> TcpStream {
+    let stream = TcpStream::connect(addr).unwrap();
+    stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+    stream.set_write_timeout(Some(TIMEOUT)).unwrap();
+    stream
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[track_caller]
+fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "exit {:?}\nstdout:\n{}\nstderr:\n{}",
+        output.status.code(),
+        stdout(output),
+        stderr(output)
+    );
+}
+
+#[track_caller]
+fn assert_exit(output: &Output, code: i32) {
+    assert_eq!(
+        output.status.code(),
+        Some(code),
+        "stdout:\n{}\nstderr:\n{}",
+        stdout(output),
+        stderr(output)
+    );
+}
+
+#[test]
+fn health() {
+    let h = Harness::new();
+    let out = h.run(&["health"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("Server is healthy"));
+
+    h.server.reply("/health", 503, json!({}));
+    assert_exit(&h.run(&["health"]), 5);
+}
+
+#[test]
+fn ping() {
+    let h = Harness::new();
+    let out = h.run(&["ping", "--count", "2", "--guild-id", "1"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("2 samples"), "{}", stdout(&out));
+    assert!(h.server.requests().iter().any(|r| r.body["type"] == "subscribe"));
+}
+
+#[test]
+fn events() {
+    let h = Harness::new();
+    let (mut child, line) =
+        h.spawn_listening(&["--token", "test-token", "events", "--guild-id", "1", "--type", "track_start"]);
+    child.kill().ok();
+    child.wait().ok();
+    let event: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(event["type"], "track_start");
+    assert_eq!(event["guildId"], "1");
+
+    let out = h
+        .command(&h.server.url())
+        .args(["--token", BAD_TOKEN, "events", "--guild-id", "1", "--no-reconnect"])
+        .output()
+        .unwrap();
+    assert_exit(&out, 3);
+
+    assert_exit(&h.run(&["events"]), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn hooks_run_on_events() {
+    let h = Harness::new();
+    let log = h.config_file("hook.log");
+    let config = format!(
+        "[hooks]\non_track_start = 'echo \"$JORIK_EVENT|$JORIK_GUILD_ID|$JORIK_TITLE\" >> {}'\n",
+        log.display()
+    );
+    std::fs::write(h.config_file("config.toml"), config).unwrap();
+    let (mut child, _) =
+        h.spawn_listening(&["--token", "test-token", "events", "--guild-id", "1", "--type", "track_start"]);
+    // The hook runs in the background; give it a moment.
+    let deadline = Instant::now() + TIMEOUT;
+    while !log.exists() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    child.kill().ok();
+    child.wait().ok();
+    assert_eq!(std::fs::read_to_string(&log).unwrap().trim(), "track_start|1|Current Song");
+}
+
+#[test]
+fn play() {
+    let h = Harness::new();
+    let out = h.run(&["play", "--guild-id", "1", "--channel-id", "2", "some", "song"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("Added some song by Mock Artist to queue"), "{}", stdout(&out));
+    let body = h.sent("play");
+    assert_eq!(body["query"], "some song");
+    assert_eq!(body["guild_id"], "1");
+    assert_eq!(body["channel_id"], "2");
+    let request = &h.server.requests()[0];
+    assert_eq!(request.authorization.as_deref(), Some("Bearer test-token"));
+    assert!(request.request_id.is_some());
+}
+
+#[test]
+fn play_from_file_in_bulk() {
+    let h = Harness::new();
+    let list = h.config.path().join("list.txt");
+    std::fs::write(&list, "first\n# a comment\n\nsecond\n").unwrap();
+    let out = h.run(&["play", "--guild-id", "1", "--from", list.to_str().unwrap()]);
+    assert_success(&out);
+    assert_eq!(h.sent("play_bulk")["queries"], json!(["first", "second"]));
+    assert!(h.server.actions("play").is_empty());
+}
+
+#[test]
+fn play_from_stdin_falls_back_to_single_plays() {
+    let h = Harness::new();
+    h.server.reply("play_bulk", 400, json!({"error": "unknown_action"}));
+    let mut child = h
+        .command(&h.server.url())
+        .args(["--token", "test-token", "play", "--guild-id", "1", "--from", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"first\nsecond\n").unwrap();
+    assert_success(&child.wait_with_output().unwrap());
+    let queries: Vec<Value> = h.server.actions("play").iter().map(|r| r.body["query"].clone()).collect();
+    assert_eq!(queries, [json!("first"), json!("second")]);
+}
+
+#[test]
+fn play_expands_spotify_albums() {
+    let h = Harness::new();
+    let url = h.server.url();
+    let config = format!("[spotify]\nclient_id = \"id\"\nclient_secret = \"secret\"\napi_url = \"{url}\"\naccounts_url = \"{url}\"\n");
+    std::fs::write(h.config_file("config.toml"), config).unwrap();
+    h.server.reply("/api/token", 200, json!({"access_token": "spotify-token"}));
+    h.server.reply(
+        "/albums/4aawyAB9vmqN3uQ7FjRGTy/tracks",
+        200,
+        json!({"items": [
+            {"name": "First", "artists": [{"name": "Band"}, {"name": "Guest"}]},
+            {"name": "Second", "artists": [{"name": "Band"}]},
+        ], "next": null}),
+    );
+    let link = "https://open.spotify.com/intl-de/album/4aawyAB9vmqN3uQ7FjRGTy?si=abc";
+    assert_success(&h.run(&["play", "--guild-id", "1", "--expand", link]));
+    assert_eq!(h.sent("play_bulk")["queries"], json!(["Band, Guest - First", "Band - Second"]));
+    let requests = h.server.requests();
+    let listing = requests.iter().find(|r| r.path.starts_with("/albums/")).unwrap();
+    assert_eq!(listing.authorization.as_deref(), Some("Bearer spotify-token"));
+
+    // The secret moves to auth.json and stays out of what prints settings.
+    let config = std::fs::read_to_string(h.config_file("config.toml")).unwrap();
+    assert!(!config.contains("client_secret =") && config.contains("client_id ="), "{config}");
+    assert!(h.auth_json()["keys"]["spotify.client_secret"] == "secret");
+    for args in [&["config", "show"][..], &["config", "get", "spotify"], &["config", "sources"]] {
+        assert!(!stdout(&h.run(args)).contains("\"secret\""), "{args:?}");
+    }
+
+    // Single tracks go to the server as they are.
+    assert_success(&h.run(&["play", "--guild-id", "1", "--expand", "https://open.spotify.com/track/2RQWB4Asy1rjZL4IUcJ7kn"]));
+    assert_eq!(h.sent("play")["query"], "https://open.spotify.com/track/2RQWB4Asy1rjZL4IUcJ7kn");
+
+    std::fs::write(h.config_file("config.toml"), "").unwrap();
+    let out = h.run(&["play", "--guild-id", "1", "--expand", link]);
+    assert_exit(&out, 2);
+    assert!(stderr(&out).contains("[spotify]"), "{}", stderr(&out));
+}
+
+#[test]
+fn play_picks_from_youtube_playlists() {
+    let h = Harness::new();
+    let config = format!("[youtube]\napi_key = \"yt-key\"\napi_url = \"{}\"\n", h.server.url());
+    std::fs::write(h.config_file("config.toml"), config).unwrap();
+    let video = |id: &str, title: &str| {
+        json!({"snippet": {"title": title, "videoOwnerChannelTitle": "Uploader", "resourceId": {"videoId": id}}})
+    };
+    h.server.reply(
+        "/playlistItems",
+        200,
+        json!({"items": [
+            video("aaa", "One"),
+            {"snippet": {"title": "Deleted video", "resourceId": {"videoId": "gone"}}},
+            video("bbb", "Two"),
+            video("ccc", "Three"),
+        ]}),
+    );
+    let mut child = h
+        .command(&h.server.url())
+        .args(["--token", "test-token", "play", "--guild-id", "1", "--pick"])
+        .arg("https://www.youtube.com/playlist?list=PLxyz")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"1,3\n").unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert_success(&out);
+    assert!(stderr(&out).contains("  2. Two — Uploader"), "{}", stderr(&out));
+    assert_eq!(
+        h.sent("play_bulk")["queries"],
+        json!(["https://www.youtube.com/watch?v=aaa", "https://www.youtube.com/watch?v=ccc"])
+    );
+    let listing = h.server.requests().into_iter().find(|r| r.path.starts_with("/playlistItems")).unwrap();
+    assert!(listing.path.contains("playlistId=PLxyz"), "{}", listing.path);
+
+    // The key moves to auth.json and stays out of what prints settings.
+    let config = std::fs::read_to_string(h.config_file("config.toml")).unwrap();
+    assert!(!config.contains("yt-key"), "{config}");
+    assert!(h.auth_json()["keys"]["youtube.api_key"] == "yt-key");
+    for args in [&["config", "show"][..], &["config", "get", "youtube"], &["config", "sources"]] {
+        assert!(!stdout(&h.run(args)).contains("yt-key"), "{args:?}");
+    }
+
+    let out = h.run(&["play", "--guild-id", "1", "--pick", "some song"]);
+    assert_exit(&out, 2);
+}
+
+#[test]
+fn turip() {
+    let h = Harness::new();
+    assert_success(&h.run(&["turip", "--guild-id", "1"]));
+    let query = h.sent("play")["query"].as_str().unwrap().to_string();
+    assert!(query.starts_with("https://open.spotify.com/track/"), "{query}");
+}
+
+#[test]
+fn radio() {
+    let h = Harness::new();
+    std::fs::write(h.config_file("config.toml"), "[radio]\nMine = \"https://example.com/live.mp3\"\n").unwrap();
+    let list = stdout(&h.run(&["radio", "--list"]));
+    assert!(list.contains("groovesalad") && list.contains("https://example.com/live.mp3"), "{list}");
+
+    assert_success(&h.run(&["radio", "mine", "--no-check", "--stay", "--guild-id", "1"]));
+    assert_eq!(h.sent("play")["query"], "https://example.com/live.mp3");
+    assert_eq!(h.sent("247")["enabled"], true);
+
+    assert_exit(&h.run(&["radio", "no-such-station", "--guild-id", "1"]), 2);
+    // JSON is not audio.
+    let out = h.run(&["radio", &format!("{}/health", h.server.url()), "--guild-id", "1"]);
+    assert_exit(&out, 2);
+    assert!(stderr(&out).contains("not an audio stream"), "{}", stderr(&out));
+    // A station that doesn't answer is a network failure.
+    assert_exit(&h.run(&["radio", &format!("{UNREACHABLE}/live.mp3"), "--guild-id", "1"]), 4);
+    assert_eq!(h.server.actions("play").len(), 1);
+}
+
+#[test]
+fn playback_controls() {
+    let h = Harness::new();
+    for (args, action, expected) in [
+        (&["skip"][..], "skip", "Skipped"),
+        (&["stop"], "stop", "Playback stopped"),
+        (&["pause"], "pause", "Playback paused"),
+        (&["shuffle"], "shuffle", "Queue shuffled"),
+        (&["clear"], "clear", "Cleared 2 tracks"),
+    ] {
+        let out = h.run(&[args, &["--guild-id", "1"]].concat());
+        assert_success(&out);
+        assert!(stdout(&out).contains(expected), "{action}: {}", stdout(&out));
+        assert_eq!(h.sent(action)["guild_id"], "1");
+    }
+}
+
+#[test]
+fn loop_mode() {
+    let h = Harness::new();
+    assert_success(&h.run(&["loop", "queue", "--guild-id", "1"]));
+    assert_eq!(h.sent("loop")["loop_mode"], "queue");
+}
+
+#[test]
+fn twenty_four_seven() {
+    let h = Harness::new();
+    assert_success(&h.run(&["247", "on", "--guild-id", "1"]));
+    assert_eq!(h.sent("247")["enabled"], true);
+}
+
+#[test]
+fn filter() {
+    let h = Harness::new();
+    assert_success(&h.run(&["filter", "nightcore", "--guild-id", "1"]));
+    assert!(h.sent("filter")["filters"].is_object());
+
+    assert_exit(&h.run(&["filter", "no-such-style", "--guild-id", "1"]), 2);
+}
+
+#[test]
+fn queue() {
+    let h = Harness::new();
+    let out = h.run(&["queue", "--guild-id", "1"]);
+    assert_success(&out);
+    let text = stdout(&out);
+    assert!(text.contains("Current Song") && text.contains("Next Song"), "{text}");
+
+    let out = h.run(&["queue", "--guild-id", "1", "--output", "tsv"]);
+    assert_success(&out);
+    let text = stdout(&out);
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("position\ttitle\tauthor\tduration_ms\turi\trequester"));
+    assert!(lines.next().unwrap().starts_with("0\tCurrent Song\t"));
+    assert!(lines.next().unwrap().starts_with("1\tNext Song\t"));
+
+    // The global --format, on either side of the subcommand.
+    for args in [
+        ["--format", "{title}", "queue", "--guild-id", "1", "--output", "tsv"],
+        ["queue", "--guild-id", "1", "--output", "tsv", "--format", "{title}"],
+    ] {
+        assert_exit(&h.run(&args), 2);
+    }
+}
+
+#[test]
+fn queue_all_pages_through_everything() {
+    let h = Harness::new();
+    h.server.set_queue((1..=250).map(|n| format!("Track {n}")));
+    let out = h.run(&["queue", "--guild-id", "1", "--all", "--output", "tsv"]);
+    assert_success(&out);
+    let text = stdout(&out);
+    assert_eq!(text.lines().count(), 252, "{text}");
+    assert!(text.lines().last().unwrap().starts_with("250\tTrack 250\t"), "{text}");
+    let offsets: Vec<_> = h.server.actions("queue").iter().map(|r| r.body["offset"].clone()).collect();
+    assert_eq!(offsets, [json!(0), json!(100), json!(200)]);
+
+    assert_exit(&h.run(&["queue", "--guild-id", "1", "--all", "--limit", "5"]), 2);
+
+    // No total, and the same short page whatever the offset: it still ends,
+    // with each track once.
+    let page = json!({"action": "queue", "upcoming": [{"title": "Only"}, {"title": "Tracks"}]});
+    h.server.reply("queue", 200, page);
+    let out = h.run(&["queue", "--guild-id", "1", "--all", "--output", "tsv"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out).lines().count(), 3, "{}", stdout(&out));
+}
+
+#[test]
+fn queue_with_a_malformed_reply() {
+    let h = Harness::new();
+    h.server.reply("queue", 200, json!({"action": "queue", "upcoming": "nope"}));
+    let out = h.run(&["queue", "--guild-id", "1"]);
+    assert_exit(&out, 1);
+    assert!(stderr(&out).contains("`upcoming`: invalid type: string"), "{}", stderr(&out));
+
+    h.server.reply(
+        "queue",
+        200,
+        json!({"action": "queue", "upcoming": [{"title": "Fine"}, {"title": "Bad", "durationMs": "3:00"}]}),
+    );
+    let out = h.run(&["queue", "--guild-id", "1"]);
+    assert_exit(&out, 1);
+    assert!(stderr(&out).contains("`upcoming[1].durationMs`"), "{}", stderr(&out));
+
+    h.server.reply("queue", 200, json!({"action": "queue"}));
+    assert_exit(&h.run(&["queue", "--guild-id", "1"]), 1);
+}
+
+#[test]
+fn now_playing_with_a_malformed_reply() {
+    let h = Harness::new();
+    h.server.reply(
+        "nowplaying",
+        200,
+        json!({"action": "nowplaying", "now_playing": {"track": {"title": ["x"]}, "elapsedMs": 5}}),
+    );
+    let out = h.run(&["now-playing", "--guild-id", "1"]);
+    assert_exit(&out, 1);
+    let err = stderr(&out);
+    assert!(err.contains("`now_playing.track.title`"), "{err}");
+    assert!(err.contains("update whichever is older"), "{err}");
+}
+
+#[test]
+fn history() {
+    let h = Harness::new();
+    let out = h.run(&["history", "--guild-id", "1"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("Old Song"), "{}", stdout(&out));
+}
+
+#[test]
+fn now_playing() {
+    let h = Harness::new();
+    let out = h.run(&["now-playing", "--guild-id", "1"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("Current Song"), "{}", stdout(&out));
+
+    let out = h.run(&["--format", "{title}|{elapsed}", "now-playing", "--guild-id", "1"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out).trim(), "Current Song|01:00");
+}
+
+#[test]
+fn daemon() {
+    let h = Harness::new();
+    h.log_in("test-token");
+    let (mut child, line) = h.spawn_listening(&["daemon", "--guild-id", "1", "--listen", "127.0.0.1:0"]);
+    let addr = line.trim().rsplit("http://").next().unwrap().to_string();
+
+    let request = |head: &str, body: &str| {
+        let mut stream = connect(&addr);
+        write!(stream, "{head}\r\nHost: {addr}\r\nContent-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        let mut reply = String::new();
+        std::io::Read::read_to_string(&mut stream, &mut reply).unwrap();
+        let (status, body) = reply.split_once("\r\n\r\n").unwrap();
+        let status: u16 = status.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str::<Value>(body).unwrap())
+    };
+    let (status, body) = request("GET /now-playing HTTP/1.1", "");
+    assert_eq!(status, 200);
+    assert_eq!(body["now_playing"]["track"]["title"], "Current Song");
+    let (status, _) = request("POST /play HTTP/1.1", r#"{"query": "some song"}"#);
+    assert_eq!(status, 200);
+    assert_eq!(request("POST /skip?guild_id=5 HTTP/1.1", "").0, 200);
+    assert_eq!(request("GET /skip HTTP/1.1", "").0, 405);
+    assert_eq!(request("POST /play HTTP/1.1\r\nOrigin: https://example.com", "{}").0, 403);
+    child.kill().ok();
+    child.wait().ok();
+
+    let play = h.sent("play");
+    assert_eq!(play["query"], "some song");
+    assert_eq!(play["guild_id"], "1");
+    assert_eq!(h.sent("skip")["guild_id"], "5");
+    // The saved login, without the scripts sending one.
+    let requests = h.server.requests();
+    assert!(requests.iter().all(|r| r.authorization.as_deref() == Some("Bearer test-token")), "{requests:?}");
+}
+
+#[test]
+fn overlay() {
+    let h = Harness::new();
+    h.log_in("test-token");
+    let (mut child, line) = h.spawn_listening(&["overlay", "--guild-id", "1", "--port", "0"]);
+    let addr = line.trim().rsplit("http://").next().unwrap().trim_end_matches('/').to_string();
+
+    let get = |path: &str| {
+        let mut stream = connect(&addr);
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: {addr}\r\n\r\n").unwrap();
+        let mut reply = String::new();
+        std::io::Read::read_to_string(&mut stream, &mut reply).unwrap();
+        let (head, body) = reply.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), body.to_string())
+    };
+    let (head, page) = get("/");
+    assert!(head.contains("text/html"), "{head}");
+    assert!(page.contains("fetch(\"/state\""), "{page}");
+    // The state arrives with the event stream, a moment after startup.
+    let mut state = Value::Null;
+    for _ in 0..50 {
+        state = serde_json::from_str(&get("/state").1).unwrap();
+        if state["playing"] == true {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    assert_eq!(state["title"], "Current Song", "{state}");
+    assert!(get("/nope").0.starts_with("HTTP/1.1 404"));
+    child.kill().ok();
+    child.wait().ok();
+}
+
+#[test]
+fn mpd_bridge() {
+    let h = Harness::new();
+    let (mut child, line) =
+        h.spawn_listening(&["--token", "test-token", "mpd-bridge", "--guild-id", "1", "--listen", "127.0.0.1:0"]);
+    let addr = line.trim().rsplit(' ').next().unwrap().to_string();
+
+    let stream = connect(&addr);
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut greeting = String::new();
+    reader.read_line(&mut greeting).unwrap();
+    assert!(greeting.starts_with("OK MPD "), "{greeting}");
+    let mut writer = stream;
+    let mut send = |command: &str| {
+        writeln!(writer, "{command}").unwrap();
+        let mut reply = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let done = line.starts_with("OK") || line.starts_with("ACK");
+            reply.push(line.trim_end().to_string());
+            if done {
+                return reply;
+            }
+        }
+    };
+    let status = send("status");
+    assert!(status.contains(&"state: play".to_string()), "{status:?}");
+    let song = send("currentsong");
+    assert!(song.contains(&"Title: Current Song".to_string()), "{song:?}");
+    assert_eq!(send("next").last().unwrap(), "OK");
+    assert!(send("previous").last().unwrap().starts_with("ACK [5@0] {previous}"));
+    for time in ["inf", "nan", "+-5", "-inf"] {
+        let reply = send(&format!("seekcur {time}"));
+        assert!(reply.last().unwrap().starts_with("ACK [2@0] {seekcur}"), "{time}: {reply:?}");
+    }
+    h.server.reply("seek", 200, json!({"action": "seek"}));
+    assert_eq!(send("seekcur 30").last().unwrap(), "OK");
+    child.kill().ok();
+    child.wait().ok();
+
+    assert_eq!(h.server.actions("skip").len(), 1);
+    assert_eq!(h.sent("seek")["position_ms"], 30_000);
+    assert_exit(&h.run(&["mpd-bridge"]), 2);
+}
+
+#[test]
+fn status_bar() {
+    let h = Harness::new();
+    let out = h.run(&["status", "--bar", "waybar", "--guild-id", "1"]);
+    assert_success(&out);
+    let line: Value = serde_json::from_str(stdout(&out).trim()).unwrap();
+    assert_eq!(line["class"], "playing");
+    assert!(line["text"].as_str().unwrap().contains("Current Song"), "{line}");
+
+    let out = h.run(&["status", "--bar", "i3blocks", "--guild-id", "1"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out).lines().count(), 2, "{}", stdout(&out));
+
+    h.server.reply("nowplaying", 200, json!({"action": "nowplaying", "now_playing": null}));
+    let out = h.run(&["status", "--bar", "polybar", "--guild-id", "1"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out), "\n");
+
+    assert_exit(&h.run(&["status", "--bar", "lemonbar"]), 2);
+
+    let (mut child, line) =
+        h.spawn_listening(&["--token", "test-token", "status", "--bar", "polybar", "--follow", "--guild-id", "1"]);
+    child.kill().ok();
+    child.wait().ok();
+    assert!(line.contains("Current Song"), "{line}");
+}
+
+#[test]
+fn lyrics() {
+    let h = Harness::new();
+    let out = h.run(&["lyrics", "--guild-id", "1"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("Mock lyrics line one"), "{}", stdout(&out));
+}
+
+#[test]
+fn whoami() {
+    let h = Harness::new();
+    let out = h.run(&["whoami"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("mockuser"), "{}", stdout(&out));
+}
+
+#[test]
+fn auth_login_manual() {
+    let h = Harness::new();
+    let mut child = h
+        .command(&h.server.url())
+        .args(["auth", "login", "--manual"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"pasted-token\n").unwrap();
+    assert_success(&child.wait_with_output().unwrap());
+    let auth = h.auth_json();
+    assert_eq!(auth["profiles"]["default"]["token"], "pasted-token");
+    assert_eq!(auth["profiles"]["default"]["username"], "mockuser");
+}
+
+#[test]
+fn auth_login_device() {
+    let h = Harness::new();
+    let out = h.run_saved(&["auth", "login", "--device"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("MOCK-1234"), "{}", stdout(&out));
+    assert_eq!(h.auth_json()["profiles"]["default"]["token"], "device-token");
+}
+
+#[test]
+fn auth_login_callback_waits_for_its_state() {
+    let h = Harness::new();
+    let mut child = h
+        .command(&h.server.url())
+        .args(["auth", "login", "--browser", "--bind", "0.0.0.0"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let (line_tx, line_rx) = std::sync::mpsc::channel();
+    let lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    std::thread::spawn(move || lines.map_while(Result::ok).for_each(|line| drop(line_tx.send(line))));
+    let callback = loop {
+        let line = line_rx.recv_timeout(TIMEOUT).expect("no callback URL printed");
+        if let Some((_, url)) = line.split_once("Local callback URL: ") {
+            break url.trim().to_string();
+        }
+    };
+    let (port, path) = callback.trim_start_matches("http://0.0.0.0:").split_once('/').unwrap();
+    let call = |path: &str| {
+        let mut stream = connect(&format!("127.0.0.1:{port}"));
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut reply = String::new();
+        let _ = std::io::Read::read_to_string(&mut stream, &mut reply);
+        reply
+    };
+
+    // Someone else's callback is turned away without ending the login.
+    let reply = call("/oauth-callback?state=guess&token=other-token");
+    assert!(reply.starts_with("HTTP/1.1 403"), "{reply}");
+    let reply = call(&format!("/{path}&token=browser-token"));
+    assert!(reply.starts_with("HTTP/1.1 200"), "{reply}");
+
+    let deadline = Instant::now() + TIMEOUT;
+    while child.try_wait().unwrap().is_none() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let _ = child.kill();
+    assert_eq!(h.auth_json()["profiles"]["default"]["token"], "browser-token");
+}
+
+#[test]
+fn auth_info_token_and_refresh() {
+    let h = Harness::new();
+    h.log_in("saved-token");
+
+    let out = h.run_saved(&["auth", "info"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("mockuser"), "{}", stdout(&out));
+
+    assert_exit(&h.run_saved(&["auth", "token"]), 2);
+    let out = h.run_saved(&["auth", "token", "--show"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out).trim(), "saved-token");
+
+    // A --token one is handed back rather than saved over the profile.
+    let out = h.run(&["auth", "refresh"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out).trim(), "refreshed-token");
+    assert_eq!(h.auth_json()["profiles"]["default"]["token"], "saved-token");
+    let out = Harness::new().run(&["auth", "refresh"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out).trim(), "refreshed-token");
+
+    assert_success(&h.run_saved(&["auth", "refresh"]));
+    assert_eq!(h.auth_json()["profiles"]["default"]["token"], "refreshed-token");
+}
+
+#[test]
+fn auth_profiles_switch_and_signout() {
+    let h = Harness::new();
+    let auth = json!({
+        "active": "home",
+        "profiles": {
+            "home": {"token": "home-token", "username": "mockuser"},
+            "work": {"token": "work-token", "username": "other"},
+        },
+    });
+    std::fs::write(h.config_file("auth.json"), auth.to_string()).unwrap();
+
+    let out = h.run_saved(&["auth", "profiles"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("home") && stdout(&out).contains("work"));
+
+    assert_success(&h.run_saved(&["auth", "switch", "work"]));
+    assert_eq!(h.auth_json()["active"], "work");
+    assert_exit(&h.run_saved(&["auth", "switch", "nope"]), 2);
+
+    assert_success(&h.run_saved(&["auth", "signout"]));
+    let auth = h.auth_json();
+    assert!(auth["profiles"].get("work").is_none(), "{auth}");
+    assert_eq!(auth["active"], "home");
+}
+
+#[test]
+fn auth_json_broken_or_pinned() {
+    let h = Harness::new();
+    // A file that doesn't parse is reported and never saved over.
+    std::fs::write(h.config_file("auth.json"), "{\"active\": ").unwrap();
+    let mut child = h
+        .command(&h.server.url())
+        .args(["auth", "login", "--manual"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"pasted-token\n").unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert_exit(&out, 1);
+    assert!(stderr(&out).contains("Ignoring the saved logins"), "{}", stderr(&out));
+    assert_eq!(std::fs::read_to_string(h.config_file("auth.json")).unwrap(), "{\"active\": ");
+
+    // A --base-url beats the profile's server, even when it is the default.
+    let auth = json!({"active": "p", "profiles": {"p": {"token": "t", "base_url": h.server.url()}}});
+    std::fs::write(h.config_file("auth.json"), auth.to_string()).unwrap();
+    let out = h
+        .command(jorik_core::DEFAULT_BASE_URL)
+        .args(["--dry-run", "skip", "--guild-id", "1"])
+        .output()
+        .unwrap();
+    assert_success(&out);
+    assert!(stdout(&out).contains(jorik_core::DEFAULT_BASE_URL), "{}", stdout(&out));
+}
+
+#[test]
+fn config_round_trip() {
+    let h = Harness::new();
+    assert_success(&h.run(&["config", "set", "default_guild_id", "77"]));
+    let out = h.run(&["config", "get", "default_guild_id"]);
+    assert_success(&out);
+    assert_eq!(stdout(&out).trim(), "77");
+
+    // The default guild fills in a missing --guild-id.
+    assert_success(&h.run(&["skip"]));
+    assert_eq!(h.sent("skip")["guild_id"], "77");
+
+    let out = h.run(&["config", "sources"]);
+    assert_success(&out);
+    let sources = stdout(&out);
+    let line = sources.lines().find(|l| l.starts_with("default_guild_id")).unwrap();
+    assert!(line.contains("\"77\"") && line.contains("config.toml"), "{line}");
+    assert_success(&h.run(&["config", "show"]));
+
+    let bundle = h.config.path().join("bundle.json");
+    assert_success(&h.run(&["config", "export", bundle.to_str().unwrap(), "--no-secrets"]));
+    assert_success(&h.run(&["config", "unset", "default_guild_id"]));
+    assert_exit(&h.run(&["config", "get", "no_such_key"]), 2);
+    assert_success(&h.run(&["config", "import", bundle.to_str().unwrap()]));
+    assert_eq!(stdout(&h.run(&["config", "get", "default_guild_id"])).trim(), "77");
+
+    // Logins go out in a private file, and don't replace ones saved since.
+    h.log_in("old-token");
+    let bundle = h.config.path().join("logins.toml");
+    assert_success(&h.run(&["config", "export", bundle.to_str().unwrap()]));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&bundle).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    h.log_in("new-token");
+    let out = h.run(&["config", "import", bundle.to_str().unwrap()]);
+    assert_success(&out);
+    assert!(stderr(&out).contains("Kept the saved login 'default'"), "{}", stderr(&out));
+    assert_eq!(h.auth_json()["profiles"]["default"]["token"], "new-token");
+}
+
+/// A JORIK_* override is for one run: changing something else in the TUI
+/// saves that, not the override.
+#[cfg(target_os = "linux")]
+#[test]
+fn tui_saves_only_what_was_changed() {
+    let h = Harness::new();
+    h.server.set_queue(["Queued"]);
+    std::fs::write(h.config_file("config.toml"), "theme = \"Default\"\n").unwrap();
+    let tui = format!(
+        "stty rows 40 cols 120; '{}' --config-dir '{}' --no-update-check --token test-token tui --guild-id 1",
+        env!("CARGO_BIN_EXE_jorik-cli"),
+        h.config.path().display()
+    );
+    let mut script = Command::new("script");
+    for (name, _) in std::env::vars() {
+        if name.starts_with("JORIK_") {
+            script.env_remove(name);
+        }
+    }
+    let mut child = script
+        .args(["-qec", &tui, "/dev/null"])
+        // No graphics queries a pseudo-terminal would never answer.
+        .env("TERM", "linux")
+        .env("JORIK_LANG", "en")
+        .env("JORIK_THEME", "Nord")
+        .env("JORIK_BASE_URL", h.server.url())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Keys once the queue is on screen: highlight a track so one-key
+    // shortcuts apply, cycle the visualizer style, quit.
+    let mut screen = child.stdout.take().unwrap();
+    let (drawn, up) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut seen = Vec::new();
+        let mut buf = [0; 4096];
+        while let Ok(n @ 1..) = std::io::Read::read(&mut screen, &mut buf) {
+            seen.extend_from_slice(&buf[..n]);
+            if seen.windows(6).any(|w| w == b"Queued") {
+                let _ = drawn.send(());
+            }
+        }
+    });
+    let drew = up.recv_timeout(TIMEOUT);
+    if drew.is_ok() {
+        child.stdin.take().unwrap().write_all(b"\x1b[Bvq").unwrap();
+    }
+    let deadline = Instant::now() + TIMEOUT;
+    while child.try_wait().unwrap().is_none() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let _ = child.kill();
+    assert!(drew.is_ok(), "the TUI never drew");
+
+    let config = std::fs::read_to_string(h.config_file("config.toml")).unwrap();
+    assert!(config.contains("theme = \"Default\""), "{config}");
+    assert!(!config.contains("\"Nord\"") && !config.contains(&h.server.url()), "{config}");
+    assert!(config.contains("visualizer_style = \"Blocky\""), "{config}");
+}
+
+#[test]
+fn scrobble_enable_status_disable() {
+    let h = Harness::new();
+    let enable = |token: &str| {
+        let mut child = h
+            .command(&h.server.url())
+            .args(["scrobble", "enable", "listenbrainz", "--api-url", &h.server.url()])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(format!("{token}\n").as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    };
+    h.server.reply("/1/validate-token", 200, json!({"valid": false, "message": "Token invalid."}));
+    assert_exit(&enable("wrong"), 3);
+    assert!(stdout(&h.run(&["scrobble", "status"])).contains("Scrobbling is off"));
+
+    h.server.reply("/1/validate-token", 200, json!({"valid": true, "user_name": "lbuser"}));
+    let out = enable("lb-token");
+    assert_success(&out);
+    assert!(stdout(&out).contains("Scrobbling to ListenBrainz as lbuser"), "{}", stdout(&out));
+    let checked = h.server.requests().into_iter().rfind(|r| r.path == "/1/validate-token").unwrap();
+    assert_eq!(checked.authorization.as_deref(), Some("Token lb-token"));
+    let status = stdout(&h.run(&["scrobble", "status"]));
+    assert!(status.contains("Scrobbling to ListenBrainz") && status.contains("lbuser"), "{status}");
+
+    // The token is kept with the logins, out of config.toml and what prints it.
+    let config = std::fs::read_to_string(h.config_file("config.toml")).unwrap();
+    assert!(!config.contains("lb-token"), "{config}");
+    assert!(h.auth_json()["keys"]["scrobble.listenbrainz_token"] == "lb-token");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(h.config_file("auth.json")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    for args in [&["config", "show"][..], &["config", "get", "scrobble"], &["config", "sources"]] {
+        let out = h.run(args);
+        assert_success(&out);
+        assert!(!stdout(&out).contains("lb-token"), "{args:?}: {}", stdout(&out));
+    }
+    assert!(stdout(&h.run(&["config", "export", "-"])).contains("lb-token"));
+    let out = h.run(&["config", "export", "-", "--no-secrets"]);
+    assert!(!stdout(&out).contains("lb-token"), "{}", stdout(&out));
+
+    assert_success(&h.run(&["scrobble", "disable"]));
+    assert!(h.auth_json().get("keys").is_none(), "{}", h.auth_json());
+    assert!(stdout(&h.run(&["scrobble", "status"])).contains("Scrobbling is off"));
+
+    // A token written into config.toml by hand is moved over on the next run.
+    std::fs::write(
+        h.config_file("config.toml"),
+        "[scrobble]\nservice = \"listenbrainz\"\nlistenbrainz_token = \"hand-token\"\n",
+    )
+    .unwrap();
+    assert!(!stdout(&h.run(&["config", "get", "scrobble"])).contains("hand-token"));
+    let config = std::fs::read_to_string(h.config_file("config.toml")).unwrap();
+    assert!(!config.contains("hand-token") && config.contains("listenbrainz"), "{config}");
+    assert!(h.auth_json()["keys"]["scrobble.listenbrainz_token"] == "hand-token");
+}
+
+#[test]
+fn pending_spool_and_flush() {
+    let h = Harness::new();
+    let spool = |query: &str| {
+        h.command(UNREACHABLE)
+            .env("JORIK_OFFLINE_QUEUE", "true")
+            .args(["--token", "test-token", "play", "--guild-id", "1", query])
+            .output()
+            .unwrap()
+    };
+    assert_success(&spool("first"));
+    assert_success(&spool("second"));
+    let pending = std::fs::read_to_string(h.config_file("pending.jsonl")).unwrap();
+    assert_eq!(pending.lines().count(), 2);
+
+    // The journal is kept per server; point it at the mock before flushing.
+    // A line that no longer parses is set aside rather than lost.
+    let pending = pending.replace(UNREACHABLE, &h.server.url()) + "{not json\n";
+    std::fs::write(h.config_file("pending.jsonl"), pending).unwrap();
+    let out = h.run(&["pending", "list"]);
+    assert_success(&out);
+    assert!(stdout(&out).contains("first") && stdout(&out).contains("second"));
+
+    let out = h.run(&["pending", "flush"]);
+    assert_success(&out);
+    assert!(stderr(&out).contains("pending.jsonl.bad"), "{}", stderr(&out));
+    let queries: Vec<Value> = h.server.actions("play").iter().map(|r| r.body["query"].clone()).collect();
+    assert_eq!(queries, [json!("first"), json!("second")]);
+    assert!(!h.config_file("pending.jsonl").exists());
+    assert_eq!(std::fs::read_to_string(h.config_file("pending.jsonl.bad")).unwrap(), "{not json\n");
+
+    assert_success(&spool("third"));
+    assert_success(&h.run(&["pending", "clear"]));
+    assert!(!h.config_file("pending.jsonl").exists());
+}
+
+#[test]
+fn exit_codes() {
+    let h = Harness::new();
+    let out = h
+        .command(&h.server.url())
+        .args(["--token", BAD_TOKEN, "skip", "--guild-id", "1"])
+        .output()
+        .unwrap();
+    assert_exit(&out, 3);
+
+    h.server.reply("stop", 400, json!({"error": "bad_request", "message": "nope"}));
+    let out = h.run(&["stop", "--guild-id", "1"]);
+    assert_exit(&out, 5);
+    assert!(stderr(&out).contains("Request ID") || stdout(&out).contains("nope"));
+
+    let out = h
+        .command(UNREACHABLE)
+        .args(["--token", "test-token", "skip", "--guild-id", "1"])
+        .output()
+        .unwrap();
+    assert_exit(&out, 4);
+
+    // A saved login that has stopped working, with no terminal to log in again.
+    h.log_in(BAD_TOKEN);
+    assert_exit(&h.run_saved(&["skip", "--guild-id", "1"]), 6);
+}
+
+#[test]
+fn dry_run_sends_nothing() {
+    let h = Harness::new();
+    let out = h.run(&["--dry-run", "play", "--guild-id", "1", "x"]);
+    assert_success(&out);
+    let request: Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(request["body"]["query"], "x");
+    assert!(request["headers"].get("authorization").is_none());
+    assert!(h.server.requests().is_empty());
+
+    // Not even a saved login is lost to a dry run of signing out.
+    h.log_in("saved-token");
+    assert_success(&h.run_saved(&["--dry-run", "auth", "signout"]));
+    assert_eq!(h.auth_json()["profiles"]["default"]["token"], "saved-token");
+    assert_exit(&h.run(&["--dry-run", "ping"]), 2);
+    assert_exit(&h.run(&["--dry-run", "tui"]), 2);
+    assert!(h.server.requests().is_empty());
+}
+
+#[test]
+fn stateless_mode_writes_nothing() {
+    let h = Harness::new();
+    assert_success(&h.run(&["--no-config", "skip", "--guild-id", "1"]));
+    assert_exit(&h.run(&["--no-config", "config", "set", "retries", "2"]), 1);
+    assert!(std::fs::read_dir(h.config.path()).unwrap().next().is_none());
+    assert!(!Path::new(&h.config_file("config.toml")).exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn ctrl_c_exits_with_its_own_code() {
+    let h = Harness::new();
+    // Wait for the first event so the signal handler is in place.
+    let (child, _) = h.spawn_listening(&["--token", "test-token", "events", "--guild-id", "1"]);
+    let killed = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    let out = child.wait_with_output().unwrap();
+    assert_exit(&out, 130);
+    assert!(stderr(&out).contains("Interrupted"), "{}", stderr(&out));
+}